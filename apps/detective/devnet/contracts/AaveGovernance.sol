@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: GPL-3.0
+pragma solidity ^0.8.19;
+
+/// Aave governance v2 as read by the aave handlers, with the executor and voting
+/// strategy reduced to the getters the quorum is computed from.
+contract AaveGovernance {
+    struct Proposal {
+        uint256 id;
+        address creator;
+        address executor;
+        address[] targets;
+        uint256[] values;
+        string[] signatures;
+        bytes[] calldatas;
+        bool[] withDelegatecalls;
+        uint256 startBlock;
+        uint256 endBlock;
+        uint256 executionTime;
+        uint256 forVotes;
+        uint256 againstVotes;
+        bool executed;
+        bool canceled;
+        address strategy;
+        bytes32 ipfsHash;
+    }
+
+    event ProposalCreated(
+        uint256 id,
+        address indexed creator,
+        address indexed executor,
+        address[] targets,
+        uint256[] values,
+        string[] signatures,
+        bytes[] calldatas,
+        bool[] withDelegatecalls,
+        uint256 startBlock,
+        uint256 endBlock,
+        address strategy,
+        bytes32 ipfsHash
+    );
+    event VoteEmitted(uint256 id, address indexed voter, bool support, uint256 votingPower);
+    event ProposalCanceled(uint256 id);
+
+    uint256 public constant votingDelay = 1;
+    uint256 public constant votingDuration = 20;
+
+    uint256 private _proposalsCount;
+
+    mapping(uint256 => Proposal) private _proposals;
+    mapping(uint256 => mapping(address => bool)) public hasVoted;
+    mapping(address => uint256) public weights;
+
+    function setWeight(address voter, uint256 weight) external {
+        weights[voter] = weight;
+    }
+
+    function getProposalsCount() external view returns (uint256) {
+        return _proposalsCount;
+    }
+
+    function create(
+        address executor,
+        address[] memory targets,
+        uint256[] memory values,
+        string[] memory signatures,
+        bytes[] memory calldatas,
+        bool[] memory withDelegatecalls,
+        address strategy,
+        bytes32 ipfsHash
+    ) external returns (uint256) {
+        uint256 id = _proposalsCount;
+
+        Proposal storage p = _proposals[id];
+        p.id = id;
+        p.creator = msg.sender;
+        p.executor = executor;
+        p.targets = targets;
+        p.values = values;
+        p.signatures = signatures;
+        p.calldatas = calldatas;
+        p.withDelegatecalls = withDelegatecalls;
+        p.startBlock = block.number + votingDelay;
+        p.endBlock = p.startBlock + votingDuration;
+        p.strategy = strategy;
+        p.ipfsHash = ipfsHash;
+
+        _proposalsCount++;
+
+        emit ProposalCreated(
+            id,
+            msg.sender,
+            executor,
+            targets,
+            values,
+            signatures,
+            calldatas,
+            withDelegatecalls,
+            p.startBlock,
+            p.endBlock,
+            strategy,
+            ipfsHash
+        );
+
+        return id;
+    }
+
+    function submitVote(uint256 proposalId, bool support) external {
+        require(getProposalState(proposalId) == 2, "VOTING_CLOSED");
+        require(!hasVoted[proposalId][msg.sender], "VOTE_ALREADY_SUBMITTED");
+
+        Proposal storage p = _proposals[proposalId];
+        uint256 votingPower = weights[msg.sender];
+
+        if (support) {
+            p.forVotes += votingPower;
+        } else {
+            p.againstVotes += votingPower;
+        }
+
+        hasVoted[proposalId][msg.sender] = true;
+
+        emit VoteEmitted(proposalId, msg.sender, support, votingPower);
+    }
+
+    function cancel(uint256 proposalId) external {
+        _proposals[proposalId].canceled = true;
+
+        emit ProposalCanceled(proposalId);
+    }
+
+    function getProposalById(uint256 proposalId) external view returns (Proposal memory) {
+        return _proposals[proposalId];
+    }
+
+    /// Pending, Canceled, Active, Failed, Succeeded, Queued, Expired, Executed
+    function getProposalState(uint256 proposalId) public view returns (uint8) {
+        require(_proposalsCount > proposalId, "INVALID_PROPOSAL_ID");
+
+        Proposal storage p = _proposals[proposalId];
+        AaveExecutor executor = AaveExecutor(p.executor);
+
+        if (p.canceled) {
+            return 1;
+        } else if (block.number <= p.startBlock) {
+            return 0;
+        } else if (block.number <= p.endBlock) {
+            return 2;
+        } else if (!executor.isProposalPassed(p.strategy, p.forVotes, p.againstVotes)) {
+            return 3;
+        } else if (p.executed) {
+            return 7;
+        }
+
+        return 4;
+    }
+}
+
+contract AaveExecutor {
+    uint256 public constant ONE_HUNDRED_WITH_PRECISION = 10000;
+    uint256 public constant MINIMUM_QUORUM = 200;
+
+    function isProposalPassed(address strategy, uint256 forVotes, uint256 againstVotes)
+        external
+        view
+        returns (bool)
+    {
+        uint256 supply = AaveStrategy(strategy).getTotalVotingSupplyAt(block.number);
+
+        return forVotes * ONE_HUNDRED_WITH_PRECISION >= supply * MINIMUM_QUORUM
+            && forVotes > againstVotes;
+    }
+}
+
+contract AaveStrategy {
+    uint256 private _supply;
+
+    constructor(uint256 supply) {
+        _supply = supply;
+    }
+
+    function getTotalVotingSupplyAt(uint256) external view returns (uint256) {
+        return _supply;
+    }
+}
@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: GPL-3.0
+pragma solidity ^0.8.19;
+
+/// GovernorBravo's proposal and vote surface, as read by the compound, uniswap and
+/// interest protocol handlers. Voting weight comes from a table instead of a token.
+contract BravoGovernor {
+    struct Proposal {
+        uint256 id;
+        address proposer;
+        uint256 eta;
+        uint256 startBlock;
+        uint256 endBlock;
+        uint256 forVotes;
+        uint256 againstVotes;
+        uint256 abstainVotes;
+        bool canceled;
+        bool executed;
+    }
+
+    event ProposalCreated(
+        uint256 id,
+        address proposer,
+        address[] targets,
+        uint256[] values,
+        string[] signatures,
+        bytes[] calldatas,
+        uint256 startBlock,
+        uint256 endBlock,
+        string description
+    );
+    event VoteCast(
+        address indexed voter,
+        uint256 proposalId,
+        uint8 support,
+        uint256 votes,
+        string reason
+    );
+    event ProposalCanceled(uint256 id);
+
+    uint256 public constant votingDelay = 1;
+    uint256 public constant votingPeriod = 20;
+
+    uint256 public quorumVotes;
+    uint256 public proposalCount;
+
+    mapping(uint256 => Proposal) public proposals;
+    mapping(uint256 => mapping(address => bool)) public hasVoted;
+    mapping(address => uint256) public weights;
+
+    constructor(uint256 quorum) {
+        quorumVotes = quorum;
+    }
+
+    function setWeight(address voter, uint256 weight) external {
+        weights[voter] = weight;
+    }
+
+    function propose(
+        address[] memory targets,
+        uint256[] memory values,
+        string[] memory signatures,
+        bytes[] memory calldatas,
+        string memory description
+    ) external returns (uint256) {
+        proposalCount++;
+
+        Proposal storage p = proposals[proposalCount];
+        p.id = proposalCount;
+        p.proposer = msg.sender;
+        p.startBlock = block.number + votingDelay;
+        p.endBlock = p.startBlock + votingPeriod;
+
+        emit ProposalCreated(
+            p.id,
+            msg.sender,
+            targets,
+            values,
+            signatures,
+            calldatas,
+            p.startBlock,
+            p.endBlock,
+            description
+        );
+
+        return p.id;
+    }
+
+    function castVoteWithReason(uint256 proposalId, uint8 support, string calldata reason)
+        external
+    {
+        require(state(proposalId) == 1, "voting is closed");
+        require(!hasVoted[proposalId][msg.sender], "voter already voted");
+
+        Proposal storage p = proposals[proposalId];
+        uint256 votes = weights[msg.sender];
+
+        if (support == 0) {
+            p.againstVotes += votes;
+        } else if (support == 1) {
+            p.forVotes += votes;
+        } else if (support == 2) {
+            p.abstainVotes += votes;
+        } else {
+            revert("invalid vote type");
+        }
+
+        hasVoted[proposalId][msg.sender] = true;
+
+        emit VoteCast(msg.sender, proposalId, support, votes, reason);
+    }
+
+    function cancel(uint256 proposalId) external {
+        proposals[proposalId].canceled = true;
+
+        emit ProposalCanceled(proposalId);
+    }
+
+    /// Pending, Active, Canceled, Defeated, Succeeded, Queued, Expired, Executed
+    function state(uint256 proposalId) public view returns (uint8) {
+        Proposal storage p = proposals[proposalId];
+        require(p.id != 0, "invalid proposal id");
+
+        if (p.canceled) {
+            return 2;
+        } else if (block.number <= p.startBlock) {
+            return 0;
+        } else if (block.number <= p.endBlock) {
+            return 1;
+        } else if (p.forVotes <= p.againstVotes || p.forVotes < quorumVotes) {
+            return 3;
+        } else if (p.executed) {
+            return 7;
+        }
+
+        return 4;
+    }
+}
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: GPL-3.0
+pragma solidity ^0.8.19;
+
+/// The voting half of DSChief: slates of spells and the anonymous `LogNote` the
+/// maker executive handler reads votes from. Approvals and MKR locking are left out.
+contract MakerChief {
+    event LogNote(
+        bytes4 indexed sig,
+        address indexed guy,
+        bytes32 indexed foo,
+        bytes32 indexed bar,
+        uint256 wad,
+        bytes fax
+    ) anonymous;
+
+    mapping(bytes32 => address[]) public slates;
+    mapping(address => bytes32) public votes;
+
+    modifier note() {
+        bytes32 foo;
+        bytes32 bar;
+
+        assembly {
+            foo := calldataload(4)
+            bar := calldataload(36)
+        }
+
+        emit LogNote(msg.sig, msg.sender, foo, bar, 0, msg.data);
+
+        _;
+    }
+
+    function etch(address[] memory yays) public note returns (bytes32 slate) {
+        for (uint256 i = 1; i < yays.length; i++) {
+            require(uint160(yays[i - 1]) < uint160(yays[i]), "ds-chief-yays-not-ordered");
+        }
+
+        slate = keccak256(abi.encodePacked(yays));
+        slates[slate] = yays;
+    }
+
+    function vote(address[] memory yays) public returns (bytes32) {
+        bytes32 slate = etch(yays);
+        vote(slate);
+        return slate;
+    }
+
+    function vote(bytes32 slate) public note {
+        require(
+            slates[slate].length > 0 || slate == keccak256(abi.encodePacked(new address[](0))),
+            "ds-chief-invalid-slate"
+        );
+
+        votes[msg.sender] = slate;
+    }
+}
@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: GPL-3.0
+pragma solidity ^0.8.19;
+
+/// OpenZeppelin Governor with GovernorCountingSimple, as read by the ens, hop and
+/// generic handlers. Proposal ids are the OZ hash, voting weight comes from a table.
+contract OzGovernor {
+    struct Proposal {
+        uint256 voteStart;
+        uint256 voteEnd;
+        uint256 againstVotes;
+        uint256 forVotes;
+        uint256 abstainVotes;
+        bool canceled;
+    }
+
+    event ProposalCreated(
+        uint256 proposalId,
+        address proposer,
+        address[] targets,
+        uint256[] values,
+        string[] signatures,
+        bytes[] calldatas,
+        uint256 startBlock,
+        uint256 endBlock,
+        string description
+    );
+    event VoteCast(
+        address indexed voter,
+        uint256 proposalId,
+        uint8 support,
+        uint256 weight,
+        string reason
+    );
+    event ProposalCanceled(uint256 proposalId);
+
+    uint256 public constant votingDelay = 1;
+    uint256 public constant votingPeriod = 20;
+
+    uint256 private _quorum;
+
+    mapping(uint256 => Proposal) private _proposals;
+    mapping(uint256 => mapping(address => bool)) public hasVoted;
+    mapping(address => uint256) public weights;
+
+    constructor(uint256 quorum_) {
+        _quorum = quorum_;
+    }
+
+    function COUNTING_MODE() external pure returns (string memory) {
+        return "support=bravo&quorum=for,abstain";
+    }
+
+    function setWeight(address voter, uint256 weight) external {
+        weights[voter] = weight;
+    }
+
+    function quorum(uint256) external view returns (uint256) {
+        return _quorum;
+    }
+
+    function hashProposal(
+        address[] memory targets,
+        uint256[] memory values,
+        bytes[] memory calldatas,
+        bytes32 descriptionHash
+    ) public pure returns (uint256) {
+        return uint256(keccak256(abi.encode(targets, values, calldatas, descriptionHash)));
+    }
+
+    function propose(
+        address[] memory targets,
+        uint256[] memory values,
+        bytes[] memory calldatas,
+        string memory description
+    ) external returns (uint256) {
+        uint256 proposalId =
+            hashProposal(targets, values, calldatas, keccak256(bytes(description)));
+
+        Proposal storage p = _proposals[proposalId];
+        require(p.voteStart == 0, "proposal already exists");
+
+        p.voteStart = block.number + votingDelay;
+        p.voteEnd = p.voteStart + votingPeriod;
+
+        emit ProposalCreated(
+            proposalId,
+            msg.sender,
+            targets,
+            values,
+            new string[](targets.length),
+            calldatas,
+            p.voteStart,
+            p.voteEnd,
+            description
+        );
+
+        return proposalId;
+    }
+
+    function castVoteWithReason(uint256 proposalId, uint8 support, string calldata reason)
+        external
+        returns (uint256)
+    {
+        require(state(proposalId) == 1, "vote not currently active");
+        require(!hasVoted[proposalId][msg.sender], "vote already cast");
+
+        Proposal storage p = _proposals[proposalId];
+        uint256 weight = weights[msg.sender];
+
+        if (support == 0) {
+            p.againstVotes += weight;
+        } else if (support == 1) {
+            p.forVotes += weight;
+        } else if (support == 2) {
+            p.abstainVotes += weight;
+        } else {
+            revert("invalid value for enum VoteType");
+        }
+
+        hasVoted[proposalId][msg.sender] = true;
+
+        emit VoteCast(msg.sender, proposalId, support, weight, reason);
+
+        return weight;
+    }
+
+    function proposalVotes(uint256 proposalId)
+        external
+        view
+        returns (uint256 againstVotes, uint256 forVotes, uint256 abstainVotes)
+    {
+        Proposal storage p = _proposals[proposalId];
+        return (p.againstVotes, p.forVotes, p.abstainVotes);
+    }
+
+    /// Pending, Active, Canceled, Defeated, Succeeded, Queued, Expired, Executed
+    function state(uint256 proposalId) public view returns (uint8) {
+        Proposal storage p = _proposals[proposalId];
+        require(p.voteStart != 0, "unknown proposal id");
+
+        if (p.canceled) {
+            return 2;
+        } else if (p.voteStart >= block.number) {
+            return 0;
+        } else if (p.voteEnd >= block.number) {
+            return 1;
+        } else if (p.forVotes + p.abstainVotes >= _quorum && p.forVotes > p.againstVotes) {
+            return 4;
+        }
+
+        return 3;
+    }
+}
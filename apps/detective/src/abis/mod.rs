@@ -0,0 +1,384 @@
+use std::{
+    collections::HashMap,
+    env,
+    fs,
+    path::Path,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use anyhow::{bail, Result};
+use ethers::{
+    abi::{Abi, Event},
+    types::H256,
+};
+use serde_json::Value;
+use tracing::{event, instrument, Level};
+
+use crate::{
+    prisma::{contractabi, DaoHandlerType, PrismaClient},
+    Context,
+};
+
+const RELOAD_INTERVAL_MINUTES: u64 = 5;
+
+/// Abis shipped with the binary, the same files the bindings in `contracts` are
+/// generated from.
+const BUNDLED: [(&str, &str); 26] = [
+    ("aaveexecutor", include_str!("../../abi/aaveexecutor.json")),
+    ("aavegov", include_str!("../../abi/aavegov.json")),
+    ("aavestrategy", include_str!("../../abi/aavestrategy.json")),
+    (
+        "aragonmultisig",
+        include_str!("../../abi/aragonmultisig.json"),
+    ),
+    (
+        "aragontokenvoting",
+        include_str!("../../abi/aragontokenvoting.json"),
+    ),
+    ("arbitrumcore", include_str!("../../abi/arbitrumcore.json")),
+    (
+        "arbitrumtreasury",
+        include_str!("../../abi/arbitrumtreasury.json"),
+    ),
+    ("compoundgov", include_str!("../../abi/compoundgov.json")),
+    ("dydxexecutor", include_str!("../../abi/dydxexecutor.json")),
+    ("dydxgov", include_str!("../../abi/dydxgov.json")),
+    ("dydxstrategy", include_str!("../../abi/dydxstrategy.json")),
+    ("ensgov", include_str!("../../abi/ensgov.json")),
+    ("gitcoingov", include_str!("../../abi/gitcoingov.json")),
+    ("hopgov", include_str!("../../abi/hopgov.json")),
+    (
+        "interestprotocolgov",
+        include_str!("../../abi/interestprotocolgov.json"),
+    ),
+    (
+        "makerexecutive",
+        include_str!("../../abi/makerexecutive.json"),
+    ),
+    (
+        "makerpollcreate",
+        include_str!("../../abi/makerpollcreate.json"),
+    ),
+    (
+        "makerpollvote",
+        include_str!("../../abi/makerpollvote.json"),
+    ),
+    (
+        "makerpollvotearbitrum",
+        include_str!("../../abi/makerpollvotearbitrum.json"),
+    ),
+    ("nounsgov", include_str!("../../abi/nounsgov.json")),
+    ("optimismgov", include_str!("../../abi/optimismgov.json")),
+    (
+        "optimismvotemodule_54a8f",
+        include_str!("../../abi/optimismvotemodule_54a8f.json"),
+    ),
+    ("safe", include_str!("../../abi/safe.json")),
+    ("uniswapgov", include_str!("../../abi/uniswapgov.json")),
+    (
+        "zeroxstakingproxy",
+        include_str!("../../abi/zeroxstakingproxy.json"),
+    ),
+    (
+        "zeroxtreasury",
+        include_str!("../../abi/zeroxtreasury.json"),
+    ),
+];
+
+/// Registered abis by name. Starts with the bundled set, `reload` layers `$ABI_DIR`
+/// and the `contractabi` table on top.
+fn registry() -> &'static RwLock<HashMap<String, Abi>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, Abi>>> = OnceLock::new();
+
+    REGISTRY.get_or_init(|| RwLock::new(bundled()))
+}
+
+/// The bundled abis are compiled in, one that does not parse is a broken build.
+fn bundled() -> HashMap<String, Abi> {
+    BUNDLED
+        .iter()
+        .map(|(name, json)| {
+            let abi = serde_json::from_str::<Abi>(json)
+                .unwrap_or_else(|e| panic!("bundled abi {} does not parse: {}", name, e));
+            (name.to_string(), abi)
+        })
+        .collect()
+}
+
+fn is_bundled(name: &str) -> bool {
+    BUNDLED.iter().any(|(n, _)| *n == name)
+}
+
+/// Abi names a handler decodes with. `GENERIC_CHAIN` handlers name theirs in the
+/// decoder, either `"abi": "name"` or `"abi": ["name", ...]`.
+pub fn abi_names(handler_type: DaoHandlerType, decoder: &Value) -> Vec<String> {
+    let names: &[&str] = match handler_type {
+        DaoHandlerType::AaveChain => &["aavegov"],
+        DaoHandlerType::CompoundChain => &["compoundgov"],
+        DaoHandlerType::UniswapChain => &["uniswapgov"],
+        DaoHandlerType::EnsChain => &["ensgov"],
+        DaoHandlerType::GitcoinChain => &["gitcoingov"],
+        DaoHandlerType::HopChain => &["hopgov"],
+        DaoHandlerType::DydxChain => &["dydxgov"],
+        DaoHandlerType::MakerExecutive => &["makerexecutive"],
+        DaoHandlerType::MakerPoll => &["makerpollcreate", "makerpollvote"],
+        DaoHandlerType::MakerPollArbitrum => &["makerpollvotearbitrum"],
+        DaoHandlerType::InterestProtocolChain => &["interestprotocolgov"],
+        DaoHandlerType::ZeroxProtocolChain => &["zeroxtreasury"],
+        DaoHandlerType::OptimismChain => &["optimismgov", "optimismvotemodule_54a8f"],
+        DaoHandlerType::ArbitrumCoreChain => &["arbitrumcore"],
+        DaoHandlerType::ArbitrumTreasuryChain => &["arbitrumtreasury"],
+        DaoHandlerType::NounsChain => &["nounsgov"],
+        DaoHandlerType::AragonTokenVoting => &["aragontokenvoting"],
+        DaoHandlerType::AragonMultisig => &["aragonmultisig"],
+        DaoHandlerType::SafeMultisig => &["safe"],
+        DaoHandlerType::GenericChain => {
+            return match decoder.get("abi") {
+                Some(Value::String(name)) => vec![name.clone()],
+                Some(Value::Array(names)) => names
+                    .iter()
+                    .filter_map(|n| n.as_str().map(|n| n.to_string()))
+                    .collect(),
+                _ => vec![],
+            }
+        }
+        DaoHandlerType::Snapshot => &[],
+    };
+
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+/// Reads an abi from its json, either the plain array or a build artifact with an
+/// `abi` field.
+pub fn parse_abi(value: &Value) -> Result<Abi> {
+    let abi = match value.get("abi") {
+        Some(inner) => inner.clone(),
+        None => value.clone(),
+    };
+
+    if !abi.is_array() {
+        bail!("abi is not a json array");
+    }
+
+    Ok(serde_json::from_value(abi)?)
+}
+
+pub fn get(name: &str) -> Option<Abi> {
+    registry().read().unwrap().get(name).cloned()
+}
+
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = registry().read().unwrap().keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// One abi with the functions and events of every named abi. Names that are not
+/// registered are skipped.
+pub fn merged(names: &[String]) -> Abi {
+    let registry = registry().read().unwrap();
+    let mut merged = Abi::default();
+
+    for abi in names.iter().filter_map(|n| registry.get(n)) {
+        for (name, functions) in abi.functions.iter() {
+            merged
+                .functions
+                .entry(name.clone())
+                .or_default()
+                .extend(functions.iter().cloned());
+        }
+        for (name, events) in abi.events.iter() {
+            merged
+                .events
+                .entry(name.clone())
+                .or_default()
+                .extend(events.iter().cloned());
+        }
+    }
+
+    merged
+}
+
+/// Events of the named abis keyed by topic0.
+pub fn events(names: &[String]) -> HashMap<H256, Event> {
+    merged(names)
+        .events()
+        .filter(|e| !e.anonymous)
+        .map(|e| (e.signature(), e.clone()))
+        .collect()
+}
+
+fn load_dir(dir: &Path) -> Result<HashMap<String, Abi>> {
+    let mut abis = HashMap::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.extension().map_or(true, |e| e != "json") {
+            continue;
+        }
+
+        let name = match path.file_stem().and_then(|s| s.to_str()) {
+            Some(n) => n.to_string(),
+            None => continue,
+        };
+
+        let parsed = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| parse_abi(&serde_json::from_str(&json)?));
+
+        match parsed {
+            Ok(abi) => {
+                abis.insert(name, abi);
+            }
+            Err(e) => event!(
+                Level::WARN,
+                name = name,
+                err = e.to_string(),
+                "bad abi file"
+            ),
+        }
+    }
+
+    Ok(abis)
+}
+
+/// Rebuilds the registry from the bundled abis, `$ABI_DIR` and the `contractabi`
+/// table, later sources replacing earlier ones with the same name. Neither a file nor
+/// a stored abi ever replaces a bundled one.
+#[instrument(skip_all)]
+pub async fn reload(db: &Arc<PrismaClient>) -> Result<usize> {
+    let mut abis = bundled();
+
+    if let Ok(dir) = env::var("ABI_DIR") {
+        for (name, abi) in load_dir(Path::new(&dir))? {
+            if is_bundled(&name) {
+                event!(Level::WARN, name = name, "abi file shadows a bundled one");
+                continue;
+            }
+
+            abis.insert(name, abi);
+        }
+    }
+
+    for row in db.contractabi().find_many(vec![]).exec().await? {
+        if is_bundled(&row.name) {
+            event!(
+                Level::WARN,
+                name = row.name,
+                "stored abi shadows a bundled one"
+            );
+            continue;
+        }
+
+        match parse_abi(&row.abi) {
+            Ok(abi) => {
+                abis.insert(row.name, abi);
+            }
+            Err(e) => event!(
+                Level::WARN,
+                name = row.name,
+                err = e.to_string(),
+                "bad stored abi"
+            ),
+        }
+    }
+
+    let count = abis.len();
+    *registry().write().unwrap() = abis;
+
+    event!(Level::DEBUG, count = count, "reloaded abis");
+
+    Ok(count)
+}
+
+/// Stores an abi under `name` and makes it available right away. The bundled names are
+/// taken, the built-in handlers decode with them.
+#[instrument(skip(db, value))]
+pub async fn register(db: &Arc<PrismaClient>, name: &str, value: Value) -> Result<Abi> {
+    if is_bundled(name) {
+        bail!("{} is a bundled abi", name);
+    }
+
+    let abi = parse_abi(&value)?;
+
+    db.contractabi()
+        .upsert(
+            contractabi::name::equals(name.to_string()),
+            contractabi::create(name.to_string(), value.clone(), vec![]),
+            vec![contractabi::abi::set(value)],
+        )
+        .exec()
+        .await?;
+
+    registry()
+        .write()
+        .unwrap()
+        .insert(name.to_string(), abi.clone());
+
+    Ok(abi)
+}
+
+pub async fn run_schedule(ctx: Context) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        60 * RELOAD_INTERVAL_MINUTES,
+    ));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = reload(&ctx.db).await {
+            event!(Level::WARN, err = e.to_string(), "abi reload error");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{abi_names, bundled, events, parse_abi, BUNDLED};
+    use crate::prisma::DaoHandlerType;
+
+    #[test]
+    fn reads_artifacts_and_plain_abis() {
+        let event = json!({
+            "type": "event",
+            "name": "ProposalCanceled",
+            "anonymous": false,
+            "inputs": [{"name": "proposalId", "type": "uint256", "indexed": false}]
+        });
+
+        assert!(parse_abi(&json!([event.clone()])).is_ok());
+        assert!(parse_abi(&json!({"contractName": "Governor", "abi": [event]})).is_ok());
+        assert!(parse_abi(&json!({"contractName": "Governor"})).is_err());
+    }
+
+    #[test]
+    fn names_generic_abis_in_decoder() {
+        assert_eq!(
+            abi_names(
+                DaoHandlerType::GenericChain,
+                &json!({"abi": ["gov", "module"]})
+            ),
+            vec!["gov", "module"]
+        );
+        assert_eq!(
+            abi_names(DaoHandlerType::OptimismChain, &json!({"abi": "ignored"})),
+            vec!["optimismgov", "optimismvotemodule_54a8f"]
+        );
+    }
+
+    #[test]
+    fn skips_unregistered_names() {
+        let events = events(&["compoundgov".to_string(), "missing".to_string()]);
+
+        assert!(events.values().any(|e| e.name == "ProposalCreated"));
+        assert!(events.values().any(|e| e.name == "VoteCast"));
+    }
+
+    #[test]
+    fn parses_every_bundled_abi() {
+        assert_eq!(bundled().len(), BUNDLED.len());
+    }
+}
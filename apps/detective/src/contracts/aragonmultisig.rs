@@ -0,0 +1,420 @@
+pub use aragonmultisig::*;
+/// This module was auto-generated with ethers-rs Abigen.
+/// More information at: <https://github.com/gakonst/ethers-rs>
+#[allow(
+    clippy::enum_variant_names,
+    clippy::too_many_arguments,
+    clippy::upper_case_acronyms,
+    clippy::type_complexity,
+    dead_code,
+    non_camel_case_types,
+)]
+pub mod aragonmultisig {
+    pub use super::super::shared_types::*;
+    #[rustfmt::skip]
+    const __ABI: &str = "[\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"indexed\": true,\n        \"internalType\": \"uint256\",\n        \"name\": \"proposalId\",\n        \"type\": \"uint256\"\n      },\n      {\n        \"indexed\": true,\n        \"internalType\": \"address\",\n        \"name\": \"approver\",\n        \"type\": \"address\"\n      }\n    ],\n    \"name\": \"Approved\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"indexed\": true,\n        \"internalType\": \"uint256\",\n        \"name\": \"proposalId\",\n        \"type\": \"uint256\"\n      },\n      {\n        \"indexed\": true,\n        \"internalType\": \"address\",\n        \"name\": \"creator\",\n        \"type\": \"address\"\n      },\n      {\n        \"indexed\": false,\n        \"internalType\": \"uint64\",\n        \"name\": \"startDate\",\n        \"type\": \"uint64\"\n      },\n      {\n        \"indexed\": false,\n        \"internalType\": \"uint64\",\n        \"name\": \"endDate\",\n        \"type\": \"uint64\"\n      },\n      {\n        \"indexed\": false,\n        \"internalType\": \"bytes\",\n        \"name\": \"metadata\",\n        \"type\": \"bytes\"\n      },\n      {\n        \"components\": [\n          {\n            \"internalType\": \"address\",\n            \"name\": \"to\",\n            \"type\": \"address\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"value\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"bytes\",\n            \"name\": \"data\",\n            \"type\": \"bytes\"\n          }\n        ],\n        \"indexed\": false,\n        \"internalType\": \"struct IDAO.Action[]\",\n        \"name\": \"actions\",\n        \"type\": \"tuple[]\"\n      },\n      {\n        \"indexed\": false,\n        \"internalType\": \"uint256\",\n        \"name\": \"allowFailureMap\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"ProposalCreated\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"indexed\": true,\n        \"internalType\": \"uint256\",\n        \"name\": \"proposalId\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"ProposalExecuted\",\n    \"type\": \"event\"\n  },\n  {\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"_proposalId\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"canExecute\",\n    \"outputs\": [\n      {\n        \"internalType\": \"bool\",\n        \"name\": \"\",\n        \"type\": \"bool\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"_proposalId\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"getProposal\",\n    \"outputs\": [\n      {\n        \"internalType\": \"bool\",\n        \"name\": \"executed\",\n        \"type\": \"bool\"\n      },\n      {\n        \"internalType\": \"uint16\",\n        \"name\": \"approvals\",\n        \"type\": \"uint16\"\n      },\n      {\n        \"components\": [\n          {\n            \"internalType\": \"uint16\",\n            \"name\": \"minApprovals\",\n            \"type\": \"uint16\"\n          },\n          {\n            \"internalType\": \"uint64\",\n            \"name\": \"snapshotBlock\",\n            \"type\": \"uint64\"\n          },\n          {\n            \"internalType\": \"uint64\",\n            \"name\": \"startDate\",\n            \"type\": \"uint64\"\n          },\n          {\n            \"internalType\": \"uint64\",\n            \"name\": \"endDate\",\n            \"type\": \"uint64\"\n          }\n        ],\n        \"internalType\": \"struct Multisig.ProposalParameters\",\n        \"name\": \"parameters\",\n        \"type\": \"tuple\"\n      },\n      {\n        \"components\": [\n          {\n            \"internalType\": \"address\",\n            \"name\": \"to\",\n            \"type\": \"address\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"value\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"bytes\",\n            \"name\": \"data\",\n            \"type\": \"bytes\"\n          }\n        ],\n        \"internalType\": \"struct IDAO.Action[]\",\n        \"name\": \"actions\",\n        \"type\": \"tuple[]\"\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"allowFailureMap\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [],\n    \"name\": \"multisigSettings\",\n    \"outputs\": [\n      {\n        \"internalType\": \"bool\",\n        \"name\": \"onlyListed\",\n        \"type\": \"bool\"\n      },\n      {\n        \"internalType\": \"uint16\",\n        \"name\": \"minApprovals\",\n        \"type\": \"uint16\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  }\n]";
+    ///The parsed JSON ABI of the contract.
+    pub static ARAGONMULTISIG_ABI: ::ethers::contract::Lazy<::ethers::core::abi::Abi> = ::ethers::contract::Lazy::new(||
+    ::ethers::core::utils::__serde_json::from_str(__ABI).expect("ABI is always valid"));
+    pub struct aragonmultisig<M>(::ethers::contract::Contract<M>);
+    impl<M> ::core::clone::Clone for aragonmultisig<M> {
+        fn clone(&self) -> Self {
+            Self(::core::clone::Clone::clone(&self.0))
+        }
+    }
+    impl<M> ::core::ops::Deref for aragonmultisig<M> {
+        type Target = ::ethers::contract::Contract<M>;
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+    impl<M> ::core::ops::DerefMut for aragonmultisig<M> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+    impl<M> ::core::fmt::Debug for aragonmultisig<M> {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            f.debug_tuple(stringify!(aragonmultisig)).field(&self.address()).finish()
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> aragonmultisig<M> {
+        /// Creates a new contract instance with the specified `ethers` client at
+        /// `address`. The contract derefs to a `ethers::Contract` object.
+        pub fn new<T: Into<::ethers::core::types::Address>>(
+            address: T,
+            client: ::std::sync::Arc<M>,
+        ) -> Self {
+            Self(
+                ::ethers::contract::Contract::new(
+                    address.into(),
+                    ARAGONMULTISIG_ABI.clone(),
+                    client,
+                ),
+            )
+        }
+        ///Calls the contract's `canExecute` (0xcc63604a) function
+        pub fn can_execute(
+            &self,
+            proposal_id: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, bool> {
+            self.0
+                .method_hash([204, 99, 96, 74], proposal_id)
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `getProposal` (0xc7f758a8) function
+        pub fn get_proposal(
+            &self,
+            proposal_id: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<
+            M,
+            (
+                bool,
+                u16,
+                ProposalParameters,
+                ::std::vec::Vec<Action>,
+                ::ethers::core::types::U256,
+            ),
+        > {
+            self.0
+                .method_hash([199, 247, 88, 168], proposal_id)
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `multisigSettings` (0xe097b3ff) function
+        pub fn multisig_settings(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, (bool, u16)> {
+            self.0
+                .method_hash([224, 151, 179, 255], ())
+                .expect("method not found (this should never happen)")
+        }
+        ///Gets the contract's `Approved` event
+        pub fn approved_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ApprovedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ProposalCreated` event
+        pub fn proposal_created_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalCreatedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ProposalExecuted` event
+        pub fn proposal_executed_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalExecutedFilter,
+        > {
+            self.0.event()
+        }
+        /// Returns an `Event` builder for all the events of this contract.
+        pub fn events(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            aragonmultisigEvents,
+        > {
+            self.0.event_with_filter(::core::default::Default::default())
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> From<::ethers::contract::Contract<M>>
+    for aragonmultisig<M> {
+        fn from(contract: ::ethers::contract::Contract<M>) -> Self {
+            Self::new(contract.address(), contract.client())
+        }
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "Approved", abi = "Approved(uint256,address)")]
+    pub struct ApprovedFilter {
+        #[ethevent(indexed)]
+        pub proposal_id: ::ethers::core::types::U256,
+        #[ethevent(indexed)]
+        pub approver: ::ethers::core::types::Address,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(
+        name = "ProposalCreated",
+        abi = "ProposalCreated(uint256,address,uint64,uint64,bytes,(address,uint256,bytes)[],uint256)"
+    )]
+    pub struct ProposalCreatedFilter {
+        #[ethevent(indexed)]
+        pub proposal_id: ::ethers::core::types::U256,
+        #[ethevent(indexed)]
+        pub creator: ::ethers::core::types::Address,
+        pub start_date: u64,
+        pub end_date: u64,
+        pub metadata: ::ethers::core::types::Bytes,
+        pub actions: ::std::vec::Vec<Action>,
+        pub allow_failure_map: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "ProposalExecuted", abi = "ProposalExecuted(uint256)")]
+    pub struct ProposalExecutedFilter {
+        #[ethevent(indexed)]
+        pub proposal_id: ::ethers::core::types::U256,
+    }
+    ///Container type for all of the contract's events
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum aragonmultisigEvents {
+        ApprovedFilter(ApprovedFilter),
+        ProposalCreatedFilter(ProposalCreatedFilter),
+        ProposalExecutedFilter(ProposalExecutedFilter),
+    }
+    impl ::ethers::contract::EthLogDecode for aragonmultisigEvents {
+        fn decode_log(
+            log: &::ethers::core::abi::RawLog,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::Error> {
+            if let Ok(decoded) = ApprovedFilter::decode_log(log) {
+                return Ok(aragonmultisigEvents::ApprovedFilter(decoded));
+            }
+            if let Ok(decoded) = ProposalCreatedFilter::decode_log(log) {
+                return Ok(aragonmultisigEvents::ProposalCreatedFilter(decoded));
+            }
+            if let Ok(decoded) = ProposalExecutedFilter::decode_log(log) {
+                return Ok(aragonmultisigEvents::ProposalExecutedFilter(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData)
+        }
+    }
+    impl ::core::fmt::Display for aragonmultisigEvents {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::ApprovedFilter(element) => ::core::fmt::Display::fmt(element, f),
+                Self::ProposalCreatedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ProposalExecutedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+            }
+        }
+    }
+    impl ::core::convert::From<ApprovedFilter> for aragonmultisigEvents {
+        fn from(value: ApprovedFilter) -> Self {
+            Self::ApprovedFilter(value)
+        }
+    }
+    impl ::core::convert::From<ProposalCreatedFilter> for aragonmultisigEvents {
+        fn from(value: ProposalCreatedFilter) -> Self {
+            Self::ProposalCreatedFilter(value)
+        }
+    }
+    impl ::core::convert::From<ProposalExecutedFilter> for aragonmultisigEvents {
+        fn from(value: ProposalExecutedFilter) -> Self {
+            Self::ProposalExecutedFilter(value)
+        }
+    }
+    ///Container type for all input parameters for the `canExecute` function with signature `canExecute(uint256)` and selector `0xcc63604a`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "canExecute", abi = "canExecute(uint256)")]
+    pub struct CanExecuteCall {
+        pub proposal_id: ::ethers::core::types::U256,
+    }
+    ///Container type for all input parameters for the `getProposal` function with signature `getProposal(uint256)` and selector `0xc7f758a8`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "getProposal", abi = "getProposal(uint256)")]
+    pub struct GetProposalCall {
+        pub proposal_id: ::ethers::core::types::U256,
+    }
+    ///Container type for all input parameters for the `multisigSettings` function with signature `multisigSettings()` and selector `0xe097b3ff`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "multisigSettings", abi = "multisigSettings()")]
+    pub struct MultisigSettingsCall;
+    ///Container type for all of the contract's call
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum aragonmultisigCalls {
+        CanExecute(CanExecuteCall),
+        GetProposal(GetProposalCall),
+        MultisigSettings(MultisigSettingsCall),
+    }
+    impl ::ethers::core::abi::AbiDecode for aragonmultisigCalls {
+        fn decode(
+            data: impl AsRef<[u8]>,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::AbiError> {
+            let data = data.as_ref();
+            if let Ok(decoded) = <CanExecuteCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::CanExecute(decoded));
+            }
+            if let Ok(decoded) = <GetProposalCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::GetProposal(decoded));
+            }
+            if let Ok(decoded) = <MultisigSettingsCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::MultisigSettings(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData.into())
+        }
+    }
+    impl ::ethers::core::abi::AbiEncode for aragonmultisigCalls {
+        fn encode(self) -> Vec<u8> {
+            match self {
+                Self::CanExecute(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::GetProposal(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::MultisigSettings(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+            }
+        }
+    }
+    impl ::core::fmt::Display for aragonmultisigCalls {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::CanExecute(element) => ::core::fmt::Display::fmt(element, f),
+                Self::GetProposal(element) => ::core::fmt::Display::fmt(element, f),
+                Self::MultisigSettings(element) => ::core::fmt::Display::fmt(element, f),
+            }
+        }
+    }
+    impl ::core::convert::From<CanExecuteCall> for aragonmultisigCalls {
+        fn from(value: CanExecuteCall) -> Self {
+            Self::CanExecute(value)
+        }
+    }
+    impl ::core::convert::From<GetProposalCall> for aragonmultisigCalls {
+        fn from(value: GetProposalCall) -> Self {
+            Self::GetProposal(value)
+        }
+    }
+    impl ::core::convert::From<MultisigSettingsCall> for aragonmultisigCalls {
+        fn from(value: MultisigSettingsCall) -> Self {
+            Self::MultisigSettings(value)
+        }
+    }
+    ///Container type for all return fields from the `canExecute` function with signature `canExecute(uint256)` and selector `0xcc63604a`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct CanExecuteReturn(pub bool);
+    ///Container type for all return fields from the `getProposal` function with signature `getProposal(uint256)` and selector `0xc7f758a8`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct GetProposalReturn {
+        pub executed: bool,
+        pub approvals: u16,
+        pub parameters: ProposalParameters,
+        pub actions: ::std::vec::Vec<Action>,
+        pub allow_failure_map: ::ethers::core::types::U256,
+    }
+    ///Container type for all return fields from the `multisigSettings` function with signature `multisigSettings()` and selector `0xe097b3ff`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct MultisigSettingsReturn {
+        pub only_listed: bool,
+        pub min_approvals: u16,
+    }
+    ///`ProposalParameters(uint16,uint64,uint64,uint64)`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct ProposalParameters {
+        pub min_approvals: u16,
+        pub snapshot_block: u64,
+        pub start_date: u64,
+        pub end_date: u64,
+    }
+}
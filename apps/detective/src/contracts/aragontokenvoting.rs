@@ -0,0 +1,542 @@
+pub use aragontokenvoting::*;
+/// This module was auto-generated with ethers-rs Abigen.
+/// More information at: <https://github.com/gakonst/ethers-rs>
+#[allow(
+    clippy::enum_variant_names,
+    clippy::too_many_arguments,
+    clippy::upper_case_acronyms,
+    clippy::type_complexity,
+    dead_code,
+    non_camel_case_types,
+)]
+pub mod aragontokenvoting {
+    pub use super::super::shared_types::*;
+    #[rustfmt::skip]
+    const __ABI: &str = "[\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"indexed\": true,\n        \"internalType\": \"uint256\",\n        \"name\": \"proposalId\",\n        \"type\": \"uint256\"\n      },\n      {\n        \"indexed\": true,\n        \"internalType\": \"address\",\n        \"name\": \"creator\",\n        \"type\": \"address\"\n      },\n      {\n        \"indexed\": false,\n        \"internalType\": \"uint64\",\n        \"name\": \"startDate\",\n        \"type\": \"uint64\"\n      },\n      {\n        \"indexed\": false,\n        \"internalType\": \"uint64\",\n        \"name\": \"endDate\",\n        \"type\": \"uint64\"\n      },\n      {\n        \"indexed\": false,\n        \"internalType\": \"bytes\",\n        \"name\": \"metadata\",\n        \"type\": \"bytes\"\n      },\n      {\n        \"components\": [\n          {\n            \"internalType\": \"address\",\n            \"name\": \"to\",\n            \"type\": \"address\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"value\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"bytes\",\n            \"name\": \"data\",\n            \"type\": \"bytes\"\n          }\n        ],\n        \"indexed\": false,\n        \"internalType\": \"struct IDAO.Action[]\",\n        \"name\": \"actions\",\n        \"type\": \"tuple[]\"\n      },\n      {\n        \"indexed\": false,\n        \"internalType\": \"uint256\",\n        \"name\": \"allowFailureMap\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"ProposalCreated\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"indexed\": true,\n        \"internalType\": \"uint256\",\n        \"name\": \"proposalId\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"ProposalExecuted\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"indexed\": true,\n        \"internalType\": \"uint256\",\n        \"name\": \"proposalId\",\n        \"type\": \"uint256\"\n      },\n      {\n        \"indexed\": true,\n        \"internalType\": \"address\",\n        \"name\": \"voter\",\n        \"type\": \"address\"\n      },\n      {\n        \"indexed\": false,\n        \"internalType\": \"enum IMajorityVoting.VoteOption\",\n        \"name\": \"voteOption\",\n        \"type\": \"uint8\"\n      },\n      {\n        \"indexed\": false,\n        \"internalType\": \"uint256\",\n        \"name\": \"votingPower\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"VoteCast\",\n    \"type\": \"event\"\n  },\n  {\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"_proposalId\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"canExecute\",\n    \"outputs\": [\n      {\n        \"internalType\": \"bool\",\n        \"name\": \"\",\n        \"type\": \"bool\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"_proposalId\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"getProposal\",\n    \"outputs\": [\n      {\n        \"internalType\": \"bool\",\n        \"name\": \"open\",\n        \"type\": \"bool\"\n      },\n      {\n        \"internalType\": \"bool\",\n        \"name\": \"executed\",\n        \"type\": \"bool\"\n      },\n      {\n        \"components\": [\n          {\n            \"internalType\": \"enum MajorityVotingBase.VotingMode\",\n            \"name\": \"votingMode\",\n            \"type\": \"uint8\"\n          },\n          {\n            \"internalType\": \"uint32\",\n            \"name\": \"supportThreshold\",\n            \"type\": \"uint32\"\n          },\n          {\n            \"internalType\": \"uint64\",\n            \"name\": \"startDate\",\n            \"type\": \"uint64\"\n          },\n          {\n            \"internalType\": \"uint64\",\n            \"name\": \"endDate\",\n            \"type\": \"uint64\"\n          },\n          {\n            \"internalType\": \"uint64\",\n            \"name\": \"snapshotBlock\",\n            \"type\": \"uint64\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"minVotingPower\",\n            \"type\": \"uint256\"\n          }\n        ],\n        \"internalType\": \"struct MajorityVotingBase.ProposalParameters\",\n        \"name\": \"parameters\",\n        \"type\": \"tuple\"\n      },\n      {\n        \"components\": [\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"abstain\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"yes\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"no\",\n            \"type\": \"uint256\"\n          }\n        ],\n        \"internalType\": \"struct MajorityVotingBase.Tally\",\n        \"name\": \"tally\",\n        \"type\": \"tuple\"\n      },\n      {\n        \"components\": [\n          {\n            \"internalType\": \"address\",\n            \"name\": \"to\",\n            \"type\": \"address\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"value\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"bytes\",\n            \"name\": \"data\",\n            \"type\": \"bytes\"\n          }\n        ],\n        \"internalType\": \"struct IDAO.Action[]\",\n        \"name\": \"actions\",\n        \"type\": \"tuple[]\"\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"allowFailureMap\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [],\n    \"name\": \"minParticipation\",\n    \"outputs\": [\n      {\n        \"internalType\": \"uint32\",\n        \"name\": \"\",\n        \"type\": \"uint32\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [],\n    \"name\": \"supportThreshold\",\n    \"outputs\": [\n      {\n        \"internalType\": \"uint32\",\n        \"name\": \"\",\n        \"type\": \"uint32\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"_blockNumber\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"totalVotingPower\",\n    \"outputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  }\n]";
+    ///The parsed JSON ABI of the contract.
+    pub static ARAGONTOKENVOTING_ABI: ::ethers::contract::Lazy<
+        ::ethers::core::abi::Abi,
+    > = ::ethers::contract::Lazy::new(|| {
+        ::ethers::core::utils::__serde_json::from_str(__ABI)
+            .expect("ABI is always valid")
+    });
+    pub struct aragontokenvoting<M>(::ethers::contract::Contract<M>);
+    impl<M> ::core::clone::Clone for aragontokenvoting<M> {
+        fn clone(&self) -> Self {
+            Self(::core::clone::Clone::clone(&self.0))
+        }
+    }
+    impl<M> ::core::ops::Deref for aragontokenvoting<M> {
+        type Target = ::ethers::contract::Contract<M>;
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+    impl<M> ::core::ops::DerefMut for aragontokenvoting<M> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+    impl<M> ::core::fmt::Debug for aragontokenvoting<M> {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            f.debug_tuple(stringify!(aragontokenvoting)).field(&self.address()).finish()
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> aragontokenvoting<M> {
+        /// Creates a new contract instance with the specified `ethers` client at
+        /// `address`. The contract derefs to a `ethers::Contract` object.
+        pub fn new<T: Into<::ethers::core::types::Address>>(
+            address: T,
+            client: ::std::sync::Arc<M>,
+        ) -> Self {
+            Self(
+                ::ethers::contract::Contract::new(
+                    address.into(),
+                    ARAGONTOKENVOTING_ABI.clone(),
+                    client,
+                ),
+            )
+        }
+        ///Calls the contract's `canExecute` (0xcc63604a) function
+        pub fn can_execute(
+            &self,
+            proposal_id: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, bool> {
+            self.0
+                .method_hash([204, 99, 96, 74], proposal_id)
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `getProposal` (0xc7f758a8) function
+        pub fn get_proposal(
+            &self,
+            proposal_id: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<
+            M,
+            (
+                bool,
+                bool,
+                ProposalParameters,
+                Tally,
+                ::std::vec::Vec<Action>,
+                ::ethers::core::types::U256,
+            ),
+        > {
+            self.0
+                .method_hash([199, 247, 88, 168], proposal_id)
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `minParticipation` (0x054fd2c2) function
+        pub fn min_participation(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, u32> {
+            self.0
+                .method_hash([5, 79, 210, 194], ())
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `supportThreshold` (0x7c36e8e8) function
+        pub fn support_threshold(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, u32> {
+            self.0
+                .method_hash([124, 54, 232, 232], ())
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `totalVotingPower` (0x536f9f42) function
+        pub fn total_voting_power(
+            &self,
+            block_number: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
+            self.0
+                .method_hash([83, 111, 159, 66], block_number)
+                .expect("method not found (this should never happen)")
+        }
+        ///Gets the contract's `ProposalCreated` event
+        pub fn proposal_created_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalCreatedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ProposalExecuted` event
+        pub fn proposal_executed_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalExecutedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `VoteCast` event
+        pub fn vote_cast_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            VoteCastFilter,
+        > {
+            self.0.event()
+        }
+        /// Returns an `Event` builder for all the events of this contract.
+        pub fn events(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            aragontokenvotingEvents,
+        > {
+            self.0.event_with_filter(::core::default::Default::default())
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> From<::ethers::contract::Contract<M>>
+    for aragontokenvoting<M> {
+        fn from(contract: ::ethers::contract::Contract<M>) -> Self {
+            Self::new(contract.address(), contract.client())
+        }
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(
+        name = "ProposalCreated",
+        abi = "ProposalCreated(uint256,address,uint64,uint64,bytes,(address,uint256,bytes)[],uint256)"
+    )]
+    pub struct ProposalCreatedFilter {
+        #[ethevent(indexed)]
+        pub proposal_id: ::ethers::core::types::U256,
+        #[ethevent(indexed)]
+        pub creator: ::ethers::core::types::Address,
+        pub start_date: u64,
+        pub end_date: u64,
+        pub metadata: ::ethers::core::types::Bytes,
+        pub actions: ::std::vec::Vec<Action>,
+        pub allow_failure_map: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "ProposalExecuted", abi = "ProposalExecuted(uint256)")]
+    pub struct ProposalExecutedFilter {
+        #[ethevent(indexed)]
+        pub proposal_id: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "VoteCast", abi = "VoteCast(uint256,address,uint8,uint256)")]
+    pub struct VoteCastFilter {
+        #[ethevent(indexed)]
+        pub proposal_id: ::ethers::core::types::U256,
+        #[ethevent(indexed)]
+        pub voter: ::ethers::core::types::Address,
+        pub vote_option: u8,
+        pub voting_power: ::ethers::core::types::U256,
+    }
+    ///Container type for all of the contract's events
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum aragontokenvotingEvents {
+        ProposalCreatedFilter(ProposalCreatedFilter),
+        ProposalExecutedFilter(ProposalExecutedFilter),
+        VoteCastFilter(VoteCastFilter),
+    }
+    impl ::ethers::contract::EthLogDecode for aragontokenvotingEvents {
+        fn decode_log(
+            log: &::ethers::core::abi::RawLog,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::Error> {
+            if let Ok(decoded) = ProposalCreatedFilter::decode_log(log) {
+                return Ok(aragontokenvotingEvents::ProposalCreatedFilter(decoded));
+            }
+            if let Ok(decoded) = ProposalExecutedFilter::decode_log(log) {
+                return Ok(aragontokenvotingEvents::ProposalExecutedFilter(decoded));
+            }
+            if let Ok(decoded) = VoteCastFilter::decode_log(log) {
+                return Ok(aragontokenvotingEvents::VoteCastFilter(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData)
+        }
+    }
+    impl ::core::fmt::Display for aragontokenvotingEvents {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::ProposalCreatedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ProposalExecutedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::VoteCastFilter(element) => ::core::fmt::Display::fmt(element, f),
+            }
+        }
+    }
+    impl ::core::convert::From<ProposalCreatedFilter> for aragontokenvotingEvents {
+        fn from(value: ProposalCreatedFilter) -> Self {
+            Self::ProposalCreatedFilter(value)
+        }
+    }
+    impl ::core::convert::From<ProposalExecutedFilter> for aragontokenvotingEvents {
+        fn from(value: ProposalExecutedFilter) -> Self {
+            Self::ProposalExecutedFilter(value)
+        }
+    }
+    impl ::core::convert::From<VoteCastFilter> for aragontokenvotingEvents {
+        fn from(value: VoteCastFilter) -> Self {
+            Self::VoteCastFilter(value)
+        }
+    }
+    ///Container type for all input parameters for the `canExecute` function with signature `canExecute(uint256)` and selector `0xcc63604a`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "canExecute", abi = "canExecute(uint256)")]
+    pub struct CanExecuteCall {
+        pub proposal_id: ::ethers::core::types::U256,
+    }
+    ///Container type for all input parameters for the `getProposal` function with signature `getProposal(uint256)` and selector `0xc7f758a8`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "getProposal", abi = "getProposal(uint256)")]
+    pub struct GetProposalCall {
+        pub proposal_id: ::ethers::core::types::U256,
+    }
+    ///Container type for all input parameters for the `minParticipation` function with signature `minParticipation()` and selector `0x054fd2c2`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "minParticipation", abi = "minParticipation()")]
+    pub struct MinParticipationCall;
+    ///Container type for all input parameters for the `supportThreshold` function with signature `supportThreshold()` and selector `0x7c36e8e8`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "supportThreshold", abi = "supportThreshold()")]
+    pub struct SupportThresholdCall;
+    ///Container type for all input parameters for the `totalVotingPower` function with signature `totalVotingPower(uint256)` and selector `0x536f9f42`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "totalVotingPower", abi = "totalVotingPower(uint256)")]
+    pub struct TotalVotingPowerCall {
+        pub block_number: ::ethers::core::types::U256,
+    }
+    ///Container type for all of the contract's call
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum aragontokenvotingCalls {
+        CanExecute(CanExecuteCall),
+        GetProposal(GetProposalCall),
+        MinParticipation(MinParticipationCall),
+        SupportThreshold(SupportThresholdCall),
+        TotalVotingPower(TotalVotingPowerCall),
+    }
+    impl ::ethers::core::abi::AbiDecode for aragontokenvotingCalls {
+        fn decode(
+            data: impl AsRef<[u8]>,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::AbiError> {
+            let data = data.as_ref();
+            if let Ok(decoded) = <CanExecuteCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::CanExecute(decoded));
+            }
+            if let Ok(decoded) = <GetProposalCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::GetProposal(decoded));
+            }
+            if let Ok(decoded) = <MinParticipationCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::MinParticipation(decoded));
+            }
+            if let Ok(decoded) = <SupportThresholdCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::SupportThreshold(decoded));
+            }
+            if let Ok(decoded) = <TotalVotingPowerCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::TotalVotingPower(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData.into())
+        }
+    }
+    impl ::ethers::core::abi::AbiEncode for aragontokenvotingCalls {
+        fn encode(self) -> Vec<u8> {
+            match self {
+                Self::CanExecute(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::GetProposal(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::MinParticipation(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::SupportThreshold(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::TotalVotingPower(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+            }
+        }
+    }
+    impl ::core::fmt::Display for aragontokenvotingCalls {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::CanExecute(element) => ::core::fmt::Display::fmt(element, f),
+                Self::GetProposal(element) => ::core::fmt::Display::fmt(element, f),
+                Self::MinParticipation(element) => ::core::fmt::Display::fmt(element, f),
+                Self::SupportThreshold(element) => ::core::fmt::Display::fmt(element, f),
+                Self::TotalVotingPower(element) => ::core::fmt::Display::fmt(element, f),
+            }
+        }
+    }
+    impl ::core::convert::From<CanExecuteCall> for aragontokenvotingCalls {
+        fn from(value: CanExecuteCall) -> Self {
+            Self::CanExecute(value)
+        }
+    }
+    impl ::core::convert::From<GetProposalCall> for aragontokenvotingCalls {
+        fn from(value: GetProposalCall) -> Self {
+            Self::GetProposal(value)
+        }
+    }
+    impl ::core::convert::From<MinParticipationCall> for aragontokenvotingCalls {
+        fn from(value: MinParticipationCall) -> Self {
+            Self::MinParticipation(value)
+        }
+    }
+    impl ::core::convert::From<SupportThresholdCall> for aragontokenvotingCalls {
+        fn from(value: SupportThresholdCall) -> Self {
+            Self::SupportThreshold(value)
+        }
+    }
+    impl ::core::convert::From<TotalVotingPowerCall> for aragontokenvotingCalls {
+        fn from(value: TotalVotingPowerCall) -> Self {
+            Self::TotalVotingPower(value)
+        }
+    }
+    ///Container type for all return fields from the `canExecute` function with signature `canExecute(uint256)` and selector `0xcc63604a`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct CanExecuteReturn(pub bool);
+    ///Container type for all return fields from the `getProposal` function with signature `getProposal(uint256)` and selector `0xc7f758a8`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct GetProposalReturn {
+        pub open: bool,
+        pub executed: bool,
+        pub parameters: ProposalParameters,
+        pub tally: Tally,
+        pub actions: ::std::vec::Vec<Action>,
+        pub allow_failure_map: ::ethers::core::types::U256,
+    }
+    ///Container type for all return fields from the `minParticipation` function with signature `minParticipation()` and selector `0x054fd2c2`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct MinParticipationReturn(pub u32);
+    ///Container type for all return fields from the `supportThreshold` function with signature `supportThreshold()` and selector `0x7c36e8e8`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct SupportThresholdReturn(pub u32);
+    ///Container type for all return fields from the `totalVotingPower` function with signature `totalVotingPower(uint256)` and selector `0x536f9f42`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct TotalVotingPowerReturn(pub ::ethers::core::types::U256);
+    ///`ProposalParameters(uint8,uint32,uint64,uint64,uint64,uint256)`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct ProposalParameters {
+        pub voting_mode: u8,
+        pub support_threshold: u32,
+        pub start_date: u64,
+        pub end_date: u64,
+        pub snapshot_block: u64,
+        pub min_voting_power: ::ethers::core::types::U256,
+    }
+    ///`Tally(uint256,uint256,uint256)`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct Tally {
+        pub abstain: ::ethers::core::types::U256,
+        pub yes: ::ethers::core::types::U256,
+        pub no: ::ethers::core::types::U256,
+    }
+}
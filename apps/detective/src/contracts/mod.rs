@@ -6,6 +6,8 @@
 pub mod aaveexecutor;
 pub mod aavegov;
 pub mod aavestrategy;
+pub mod aragonmultisig;
+pub mod aragontokenvoting;
 pub mod arbitrumcore;
 pub mod arbitrumtreasury;
 pub mod compoundgov;
@@ -20,8 +22,10 @@ pub mod makerexecutive;
 pub mod makerpollcreate;
 pub mod makerpollvote;
 pub mod makerpollvotearbitrum;
+pub mod nounsgov;
 pub mod optimismgov;
 pub mod optimismvotemodule_5_4a_8f;
+pub mod safe;
 pub mod shared_types;
 pub mod uniswapgov;
 pub mod zeroxstakingproxy;
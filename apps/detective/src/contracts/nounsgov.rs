@@ -0,0 +1,757 @@
+pub use nounsgov::*;
+/// This module was auto-generated with ethers-rs Abigen.
+/// More information at: <https://github.com/gakonst/ethers-rs>
+#[allow(
+    clippy::enum_variant_names,
+    clippy::too_many_arguments,
+    clippy::upper_case_acronyms,
+    clippy::type_complexity,
+    dead_code,
+    non_camel_case_types,
+)]
+pub mod nounsgov {
+    #[rustfmt::skip]
+    const __ABI: &str = "[\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"id\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ProposalCanceled\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"id\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"address\",\n        \"name\": \"proposer\",\n        \"type\": \"address\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"address[]\",\n        \"name\": \"targets\",\n        \"type\": \"address[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"uint256[]\",\n        \"name\": \"values\",\n        \"type\": \"uint256[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"string[]\",\n        \"name\": \"signatures\",\n        \"type\": \"string[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"bytes[]\",\n        \"name\": \"calldatas\",\n        \"type\": \"bytes[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"startBlock\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"endBlock\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"string\",\n        \"name\": \"description\",\n        \"type\": \"string\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ProposalCreated\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"id\",\n        \"type\": \"uint256\",\n        \"indexed\": true\n      },\n      {\n        \"internalType\": \"address\",\n        \"name\": \"proposer\",\n        \"type\": \"address\",\n        \"indexed\": true\n      },\n      {\n        \"internalType\": \"string\",\n        \"name\": \"description\",\n        \"type\": \"string\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"string\",\n        \"name\": \"updateMessage\",\n        \"type\": \"string\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ProposalDescriptionUpdated\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"id\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ProposalExecuted\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"id\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"eta\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ProposalQueued\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"id\",\n        \"type\": \"uint256\",\n        \"indexed\": true\n      },\n      {\n        \"internalType\": \"address\",\n        \"name\": \"proposer\",\n        \"type\": \"address\",\n        \"indexed\": true\n      },\n      {\n        \"internalType\": \"address[]\",\n        \"name\": \"targets\",\n        \"type\": \"address[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"uint256[]\",\n        \"name\": \"values\",\n        \"type\": \"uint256[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"string[]\",\n        \"name\": \"signatures\",\n        \"type\": \"string[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"bytes[]\",\n        \"name\": \"calldatas\",\n        \"type\": \"bytes[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"string\",\n        \"name\": \"updateMessage\",\n        \"type\": \"string\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ProposalTransactionsUpdated\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"id\",\n        \"type\": \"uint256\",\n        \"indexed\": true\n      },\n      {\n        \"internalType\": \"address\",\n        \"name\": \"proposer\",\n        \"type\": \"address\",\n        \"indexed\": true\n      },\n      {\n        \"internalType\": \"address[]\",\n        \"name\": \"targets\",\n        \"type\": \"address[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"uint256[]\",\n        \"name\": \"values\",\n        \"type\": \"uint256[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"string[]\",\n        \"name\": \"signatures\",\n        \"type\": \"string[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"bytes[]\",\n        \"name\": \"calldatas\",\n        \"type\": \"bytes[]\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"string\",\n        \"name\": \"description\",\n        \"type\": \"string\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"string\",\n        \"name\": \"updateMessage\",\n        \"type\": \"string\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ProposalUpdated\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"id\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ProposalVetoed\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"address\",\n        \"name\": \"voter\",\n        \"type\": \"address\",\n        \"indexed\": true\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"refundAmount\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"bool\",\n        \"name\": \"refundSent\",\n        \"type\": \"bool\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"RefundableVote\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"address\",\n        \"name\": \"voter\",\n        \"type\": \"address\",\n        \"indexed\": true\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"proposalId\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"uint8\",\n        \"name\": \"support\",\n        \"type\": \"uint8\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"votes\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"string\",\n        \"name\": \"reason\",\n        \"type\": \"string\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"VoteCast\",\n    \"type\": \"event\"\n  },\n  {\n    \"inputs\": [],\n    \"name\": \"objectionPeriodDurationInBlocks\",\n    \"outputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"proposalId\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"proposals\",\n    \"outputs\": [\n      {\n        \"internalType\": \"struct NounsDAOStorageV3.ProposalCondensed\",\n        \"name\": \"\",\n        \"type\": \"tuple\",\n        \"components\": [\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"id\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"address\",\n            \"name\": \"proposer\",\n            \"type\": \"address\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"proposalThreshold\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"quorumVotes\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"eta\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"startBlock\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"endBlock\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"forVotes\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"againstVotes\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"abstainVotes\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"bool\",\n            \"name\": \"canceled\",\n            \"type\": \"bool\"\n          },\n          {\n            \"internalType\": \"bool\",\n            \"name\": \"vetoed\",\n            \"type\": \"bool\"\n          },\n          {\n            \"internalType\": \"bool\",\n            \"name\": \"executed\",\n            \"type\": \"bool\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"totalSupply\",\n            \"type\": \"uint256\"\n          },\n          {\n            \"internalType\": \"uint256\",\n            \"name\": \"creationBlock\",\n            \"type\": \"uint256\"\n          }\n        ]\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"proposalId\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"quorumVotes\",\n    \"outputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"proposalId\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"name\": \"state\",\n    \"outputs\": [\n      {\n        \"internalType\": \"enum NounsDAOStorageV3.ProposalState\",\n        \"name\": \"\",\n        \"type\": \"uint8\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  }\n]";
+    ///The parsed JSON ABI of the contract.
+    pub static NOUNSGOV_ABI: ::ethers::contract::Lazy<::ethers::core::abi::Abi> = ::ethers::contract::Lazy::new(||
+    ::ethers::core::utils::__serde_json::from_str(__ABI).expect("ABI is always valid"));
+    pub struct nounsgov<M>(::ethers::contract::Contract<M>);
+    impl<M> ::core::clone::Clone for nounsgov<M> {
+        fn clone(&self) -> Self {
+            Self(::core::clone::Clone::clone(&self.0))
+        }
+    }
+    impl<M> ::core::ops::Deref for nounsgov<M> {
+        type Target = ::ethers::contract::Contract<M>;
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+    impl<M> ::core::ops::DerefMut for nounsgov<M> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+    impl<M> ::core::fmt::Debug for nounsgov<M> {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            f.debug_tuple(stringify!(nounsgov)).field(&self.address()).finish()
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> nounsgov<M> {
+        /// Creates a new contract instance with the specified `ethers` client at
+        /// `address`. The contract derefs to a `ethers::Contract` object.
+        pub fn new<T: Into<::ethers::core::types::Address>>(
+            address: T,
+            client: ::std::sync::Arc<M>,
+        ) -> Self {
+            Self(
+                ::ethers::contract::Contract::new(
+                    address.into(),
+                    NOUNSGOV_ABI.clone(),
+                    client,
+                ),
+            )
+        }
+        ///Calls the contract's `objectionPeriodDurationInBlocks` (0x705cb335) function
+        pub fn objection_period_duration_in_blocks(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
+            self.0
+                .method_hash([112, 92, 179, 53], ())
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `proposals` (0x013cf08b) function
+        pub fn proposals(
+            &self,
+            proposal_id: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, ProposalCondensed> {
+            self.0
+                .method_hash([1, 60, 240, 139], proposal_id)
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `quorumVotes` (0x0f7b1f08) function
+        pub fn quorum_votes(
+            &self,
+            proposal_id: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
+            self.0
+                .method_hash([15, 123, 31, 8], proposal_id)
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `state` (0x3e4f49e6) function
+        pub fn state(
+            &self,
+            proposal_id: ::ethers::core::types::U256,
+        ) -> ::ethers::contract::builders::ContractCall<M, u8> {
+            self.0
+                .method_hash([62, 79, 73, 230], proposal_id)
+                .expect("method not found (this should never happen)")
+        }
+        ///Gets the contract's `ProposalCanceled` event
+        pub fn proposal_canceled_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalCanceledFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ProposalCreated` event
+        pub fn proposal_created_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalCreatedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ProposalDescriptionUpdated` event
+        pub fn proposal_description_updated_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalDescriptionUpdatedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ProposalExecuted` event
+        pub fn proposal_executed_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalExecutedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ProposalQueued` event
+        pub fn proposal_queued_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalQueuedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ProposalTransactionsUpdated` event
+        pub fn proposal_transactions_updated_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalTransactionsUpdatedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ProposalUpdated` event
+        pub fn proposal_updated_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalUpdatedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ProposalVetoed` event
+        pub fn proposal_vetoed_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ProposalVetoedFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `RefundableVote` event
+        pub fn refundable_vote_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            RefundableVoteFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `VoteCast` event
+        pub fn vote_cast_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            VoteCastFilter,
+        > {
+            self.0.event()
+        }
+        /// Returns an `Event` builder for all the events of this contract.
+        pub fn events(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            nounsgovEvents,
+        > {
+            self.0.event_with_filter(::core::default::Default::default())
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> From<::ethers::contract::Contract<M>>
+    for nounsgov<M> {
+        fn from(contract: ::ethers::contract::Contract<M>) -> Self {
+            Self::new(contract.address(), contract.client())
+        }
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "ProposalCanceled", abi = "ProposalCanceled(uint256)")]
+    pub struct ProposalCanceledFilter {
+        pub id: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(
+        name = "ProposalCreated",
+        abi = "ProposalCreated(uint256,address,address[],uint256[],string[],bytes[],uint256,uint256,string)"
+    )]
+    pub struct ProposalCreatedFilter {
+        pub id: ::ethers::core::types::U256,
+        pub proposer: ::ethers::core::types::Address,
+        pub targets: ::std::vec::Vec<::ethers::core::types::Address>,
+        pub values: ::std::vec::Vec<::ethers::core::types::U256>,
+        pub signatures: ::std::vec::Vec<::std::string::String>,
+        pub calldatas: ::std::vec::Vec<::ethers::core::types::Bytes>,
+        pub start_block: ::ethers::core::types::U256,
+        pub end_block: ::ethers::core::types::U256,
+        pub description: ::std::string::String,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(
+        name = "ProposalDescriptionUpdated",
+        abi = "ProposalDescriptionUpdated(uint256,address,string,string)"
+    )]
+    pub struct ProposalDescriptionUpdatedFilter {
+        #[ethevent(indexed)]
+        pub id: ::ethers::core::types::U256,
+        #[ethevent(indexed)]
+        pub proposer: ::ethers::core::types::Address,
+        pub description: ::std::string::String,
+        pub update_message: ::std::string::String,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "ProposalExecuted", abi = "ProposalExecuted(uint256)")]
+    pub struct ProposalExecutedFilter {
+        pub id: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "ProposalQueued", abi = "ProposalQueued(uint256,uint256)")]
+    pub struct ProposalQueuedFilter {
+        pub id: ::ethers::core::types::U256,
+        pub eta: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(
+        name = "ProposalTransactionsUpdated",
+        abi = "ProposalTransactionsUpdated(uint256,address,address[],uint256[],string[],bytes[],string)"
+    )]
+    pub struct ProposalTransactionsUpdatedFilter {
+        #[ethevent(indexed)]
+        pub id: ::ethers::core::types::U256,
+        #[ethevent(indexed)]
+        pub proposer: ::ethers::core::types::Address,
+        pub targets: ::std::vec::Vec<::ethers::core::types::Address>,
+        pub values: ::std::vec::Vec<::ethers::core::types::U256>,
+        pub signatures: ::std::vec::Vec<::std::string::String>,
+        pub calldatas: ::std::vec::Vec<::ethers::core::types::Bytes>,
+        pub update_message: ::std::string::String,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(
+        name = "ProposalUpdated",
+        abi = "ProposalUpdated(uint256,address,address[],uint256[],string[],bytes[],string,string)"
+    )]
+    pub struct ProposalUpdatedFilter {
+        #[ethevent(indexed)]
+        pub id: ::ethers::core::types::U256,
+        #[ethevent(indexed)]
+        pub proposer: ::ethers::core::types::Address,
+        pub targets: ::std::vec::Vec<::ethers::core::types::Address>,
+        pub values: ::std::vec::Vec<::ethers::core::types::U256>,
+        pub signatures: ::std::vec::Vec<::std::string::String>,
+        pub calldatas: ::std::vec::Vec<::ethers::core::types::Bytes>,
+        pub description: ::std::string::String,
+        pub update_message: ::std::string::String,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "ProposalVetoed", abi = "ProposalVetoed(uint256)")]
+    pub struct ProposalVetoedFilter {
+        pub id: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "RefundableVote", abi = "RefundableVote(address,uint256,bool)")]
+    pub struct RefundableVoteFilter {
+        #[ethevent(indexed)]
+        pub voter: ::ethers::core::types::Address,
+        pub refund_amount: ::ethers::core::types::U256,
+        pub refund_sent: bool,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(
+        name = "VoteCast",
+        abi = "VoteCast(address,uint256,uint8,uint256,string)"
+    )]
+    pub struct VoteCastFilter {
+        #[ethevent(indexed)]
+        pub voter: ::ethers::core::types::Address,
+        pub proposal_id: ::ethers::core::types::U256,
+        pub support: u8,
+        pub votes: ::ethers::core::types::U256,
+        pub reason: ::std::string::String,
+    }
+    ///Container type for all of the contract's events
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum nounsgovEvents {
+        ProposalCanceledFilter(ProposalCanceledFilter),
+        ProposalCreatedFilter(ProposalCreatedFilter),
+        ProposalDescriptionUpdatedFilter(ProposalDescriptionUpdatedFilter),
+        ProposalExecutedFilter(ProposalExecutedFilter),
+        ProposalQueuedFilter(ProposalQueuedFilter),
+        ProposalTransactionsUpdatedFilter(ProposalTransactionsUpdatedFilter),
+        ProposalUpdatedFilter(ProposalUpdatedFilter),
+        ProposalVetoedFilter(ProposalVetoedFilter),
+        RefundableVoteFilter(RefundableVoteFilter),
+        VoteCastFilter(VoteCastFilter),
+    }
+    impl ::ethers::contract::EthLogDecode for nounsgovEvents {
+        fn decode_log(
+            log: &::ethers::core::abi::RawLog,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::Error> {
+            if let Ok(decoded) = ProposalCanceledFilter::decode_log(log) {
+                return Ok(nounsgovEvents::ProposalCanceledFilter(decoded));
+            }
+            if let Ok(decoded) = ProposalCreatedFilter::decode_log(log) {
+                return Ok(nounsgovEvents::ProposalCreatedFilter(decoded));
+            }
+            if let Ok(decoded) = ProposalDescriptionUpdatedFilter::decode_log(log) {
+                return Ok(nounsgovEvents::ProposalDescriptionUpdatedFilter(decoded));
+            }
+            if let Ok(decoded) = ProposalExecutedFilter::decode_log(log) {
+                return Ok(nounsgovEvents::ProposalExecutedFilter(decoded));
+            }
+            if let Ok(decoded) = ProposalQueuedFilter::decode_log(log) {
+                return Ok(nounsgovEvents::ProposalQueuedFilter(decoded));
+            }
+            if let Ok(decoded) = ProposalTransactionsUpdatedFilter::decode_log(log) {
+                return Ok(nounsgovEvents::ProposalTransactionsUpdatedFilter(decoded));
+            }
+            if let Ok(decoded) = ProposalUpdatedFilter::decode_log(log) {
+                return Ok(nounsgovEvents::ProposalUpdatedFilter(decoded));
+            }
+            if let Ok(decoded) = ProposalVetoedFilter::decode_log(log) {
+                return Ok(nounsgovEvents::ProposalVetoedFilter(decoded));
+            }
+            if let Ok(decoded) = RefundableVoteFilter::decode_log(log) {
+                return Ok(nounsgovEvents::RefundableVoteFilter(decoded));
+            }
+            if let Ok(decoded) = VoteCastFilter::decode_log(log) {
+                return Ok(nounsgovEvents::VoteCastFilter(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData)
+        }
+    }
+    impl ::core::fmt::Display for nounsgovEvents {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::ProposalCanceledFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ProposalCreatedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ProposalDescriptionUpdatedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ProposalExecutedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ProposalQueuedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ProposalTransactionsUpdatedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ProposalUpdatedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ProposalVetoedFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::RefundableVoteFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::VoteCastFilter(element) => ::core::fmt::Display::fmt(element, f),
+            }
+        }
+    }
+    impl ::core::convert::From<ProposalCanceledFilter> for nounsgovEvents {
+        fn from(value: ProposalCanceledFilter) -> Self {
+            Self::ProposalCanceledFilter(value)
+        }
+    }
+    impl ::core::convert::From<ProposalCreatedFilter> for nounsgovEvents {
+        fn from(value: ProposalCreatedFilter) -> Self {
+            Self::ProposalCreatedFilter(value)
+        }
+    }
+    impl ::core::convert::From<ProposalDescriptionUpdatedFilter> for nounsgovEvents {
+        fn from(value: ProposalDescriptionUpdatedFilter) -> Self {
+            Self::ProposalDescriptionUpdatedFilter(value)
+        }
+    }
+    impl ::core::convert::From<ProposalExecutedFilter> for nounsgovEvents {
+        fn from(value: ProposalExecutedFilter) -> Self {
+            Self::ProposalExecutedFilter(value)
+        }
+    }
+    impl ::core::convert::From<ProposalQueuedFilter> for nounsgovEvents {
+        fn from(value: ProposalQueuedFilter) -> Self {
+            Self::ProposalQueuedFilter(value)
+        }
+    }
+    impl ::core::convert::From<ProposalTransactionsUpdatedFilter> for nounsgovEvents {
+        fn from(value: ProposalTransactionsUpdatedFilter) -> Self {
+            Self::ProposalTransactionsUpdatedFilter(value)
+        }
+    }
+    impl ::core::convert::From<ProposalUpdatedFilter> for nounsgovEvents {
+        fn from(value: ProposalUpdatedFilter) -> Self {
+            Self::ProposalUpdatedFilter(value)
+        }
+    }
+    impl ::core::convert::From<ProposalVetoedFilter> for nounsgovEvents {
+        fn from(value: ProposalVetoedFilter) -> Self {
+            Self::ProposalVetoedFilter(value)
+        }
+    }
+    impl ::core::convert::From<RefundableVoteFilter> for nounsgovEvents {
+        fn from(value: RefundableVoteFilter) -> Self {
+            Self::RefundableVoteFilter(value)
+        }
+    }
+    impl ::core::convert::From<VoteCastFilter> for nounsgovEvents {
+        fn from(value: VoteCastFilter) -> Self {
+            Self::VoteCastFilter(value)
+        }
+    }
+    ///Container type for all input parameters for the `objectionPeriodDurationInBlocks` function with signature `objectionPeriodDurationInBlocks()` and selector `0x705cb335`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(
+        name = "objectionPeriodDurationInBlocks",
+        abi = "objectionPeriodDurationInBlocks()"
+    )]
+    pub struct ObjectionPeriodDurationInBlocksCall;
+    ///Container type for all input parameters for the `proposals` function with signature `proposals(uint256)` and selector `0x013cf08b`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "proposals", abi = "proposals(uint256)")]
+    pub struct ProposalsCall {
+        pub proposal_id: ::ethers::core::types::U256,
+    }
+    ///Container type for all input parameters for the `quorumVotes` function with signature `quorumVotes(uint256)` and selector `0x0f7b1f08`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "quorumVotes", abi = "quorumVotes(uint256)")]
+    pub struct QuorumVotesCall {
+        pub proposal_id: ::ethers::core::types::U256,
+    }
+    ///Container type for all input parameters for the `state` function with signature `state(uint256)` and selector `0x3e4f49e6`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "state", abi = "state(uint256)")]
+    pub struct StateCall {
+        pub proposal_id: ::ethers::core::types::U256,
+    }
+    ///Container type for all of the contract's call
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum nounsgovCalls {
+        ObjectionPeriodDurationInBlocks(ObjectionPeriodDurationInBlocksCall),
+        Proposals(ProposalsCall),
+        QuorumVotes(QuorumVotesCall),
+        State(StateCall),
+    }
+    impl ::ethers::core::abi::AbiDecode for nounsgovCalls {
+        fn decode(
+            data: impl AsRef<[u8]>,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::AbiError> {
+            let data = data.as_ref();
+            if let Ok(decoded) = <ObjectionPeriodDurationInBlocksCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::ObjectionPeriodDurationInBlocks(decoded));
+            }
+            if let Ok(decoded) = <ProposalsCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::Proposals(decoded));
+            }
+            if let Ok(decoded) = <QuorumVotesCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::QuorumVotes(decoded));
+            }
+            if let Ok(decoded) = <StateCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::State(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData.into())
+        }
+    }
+    impl ::ethers::core::abi::AbiEncode for nounsgovCalls {
+        fn encode(self) -> Vec<u8> {
+            match self {
+                Self::ObjectionPeriodDurationInBlocks(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::Proposals(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::QuorumVotes(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::State(element) => ::ethers::core::abi::AbiEncode::encode(element),
+            }
+        }
+    }
+    impl ::core::fmt::Display for nounsgovCalls {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::ObjectionPeriodDurationInBlocks(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::Proposals(element) => ::core::fmt::Display::fmt(element, f),
+                Self::QuorumVotes(element) => ::core::fmt::Display::fmt(element, f),
+                Self::State(element) => ::core::fmt::Display::fmt(element, f),
+            }
+        }
+    }
+    impl ::core::convert::From<ObjectionPeriodDurationInBlocksCall> for nounsgovCalls {
+        fn from(value: ObjectionPeriodDurationInBlocksCall) -> Self {
+            Self::ObjectionPeriodDurationInBlocks(value)
+        }
+    }
+    impl ::core::convert::From<ProposalsCall> for nounsgovCalls {
+        fn from(value: ProposalsCall) -> Self {
+            Self::Proposals(value)
+        }
+    }
+    impl ::core::convert::From<QuorumVotesCall> for nounsgovCalls {
+        fn from(value: QuorumVotesCall) -> Self {
+            Self::QuorumVotes(value)
+        }
+    }
+    impl ::core::convert::From<StateCall> for nounsgovCalls {
+        fn from(value: StateCall) -> Self {
+            Self::State(value)
+        }
+    }
+    ///Container type for all return fields from the `objectionPeriodDurationInBlocks` function with signature `objectionPeriodDurationInBlocks()` and selector `0x705cb335`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct ObjectionPeriodDurationInBlocksReturn(pub ::ethers::core::types::U256);
+    ///Container type for all return fields from the `proposals` function with signature `proposals(uint256)` and selector `0x013cf08b`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct ProposalsReturn(pub ProposalCondensed);
+    ///Container type for all return fields from the `quorumVotes` function with signature `quorumVotes(uint256)` and selector `0x0f7b1f08`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct QuorumVotesReturn(pub ::ethers::core::types::U256);
+    ///Container type for all return fields from the `state` function with signature `state(uint256)` and selector `0x3e4f49e6`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct StateReturn(pub u8);
+    ///`ProposalCondensed(uint256,address,uint256,uint256,uint256,uint256,uint256,uint256,uint256,uint256,bool,bool,bool,uint256,uint256)`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct ProposalCondensed {
+        pub id: ::ethers::core::types::U256,
+        pub proposer: ::ethers::core::types::Address,
+        pub proposal_threshold: ::ethers::core::types::U256,
+        pub quorum_votes: ::ethers::core::types::U256,
+        pub eta: ::ethers::core::types::U256,
+        pub start_block: ::ethers::core::types::U256,
+        pub end_block: ::ethers::core::types::U256,
+        pub for_votes: ::ethers::core::types::U256,
+        pub against_votes: ::ethers::core::types::U256,
+        pub abstain_votes: ::ethers::core::types::U256,
+        pub canceled: bool,
+        pub vetoed: bool,
+        pub executed: bool,
+        pub total_supply: ::ethers::core::types::U256,
+        pub creation_block: ::ethers::core::types::U256,
+    }
+}
@@ -0,0 +1,605 @@
+pub use safe::*;
+/// This module was auto-generated with ethers-rs Abigen.
+/// More information at: <https://github.com/gakonst/ethers-rs>
+#[allow(
+    clippy::enum_variant_names,
+    clippy::too_many_arguments,
+    clippy::upper_case_acronyms,
+    clippy::type_complexity,
+    dead_code,
+    non_camel_case_types,
+)]
+pub mod safe {
+    #[rustfmt::skip]
+    const __ABI: &str = "[\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"address\",\n        \"name\": \"owner\",\n        \"type\": \"address\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"AddedOwner\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"bytes32\",\n        \"name\": \"approvedHash\",\n        \"type\": \"bytes32\",\n        \"indexed\": true\n      },\n      {\n        \"internalType\": \"address\",\n        \"name\": \"owner\",\n        \"type\": \"address\",\n        \"indexed\": true\n      }\n    ],\n    \"name\": \"ApproveHash\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"threshold\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ChangedThreshold\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"bytes32\",\n        \"name\": \"txHash\",\n        \"type\": \"bytes32\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"payment\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ExecutionFailure\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"bytes32\",\n        \"name\": \"txHash\",\n        \"type\": \"bytes32\",\n        \"indexed\": false\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"payment\",\n        \"type\": \"uint256\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"ExecutionSuccess\",\n    \"type\": \"event\"\n  },\n  {\n    \"anonymous\": false,\n    \"inputs\": [\n      {\n        \"internalType\": \"address\",\n        \"name\": \"owner\",\n        \"type\": \"address\",\n        \"indexed\": false\n      }\n    ],\n    \"name\": \"RemovedOwner\",\n    \"type\": \"event\"\n  },\n  {\n    \"inputs\": [],\n    \"name\": \"VERSION\",\n    \"outputs\": [\n      {\n        \"internalType\": \"string\",\n        \"name\": \"\",\n        \"type\": \"string\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [\n      {\n        \"internalType\": \"address\",\n        \"name\": \"to\",\n        \"type\": \"address\"\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"value\",\n        \"type\": \"uint256\"\n      },\n      {\n        \"internalType\": \"bytes\",\n        \"name\": \"data\",\n        \"type\": \"bytes\"\n      },\n      {\n        \"internalType\": \"enum Enum.Operation\",\n        \"name\": \"operation\",\n        \"type\": \"uint8\"\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"safeTxGas\",\n        \"type\": \"uint256\"\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"baseGas\",\n        \"type\": \"uint256\"\n      },\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"gasPrice\",\n        \"type\": \"uint256\"\n      },\n      {\n        \"internalType\": \"address\",\n        \"name\": \"gasToken\",\n        \"type\": \"address\"\n      },\n      {\n        \"internalType\": \"address\",\n        \"name\": \"refundReceiver\",\n        \"type\": \"address\"\n      },\n      {\n        \"internalType\": \"bytes\",\n        \"name\": \"signatures\",\n        \"type\": \"bytes\"\n      }\n    ],\n    \"name\": \"execTransaction\",\n    \"outputs\": [\n      {\n        \"internalType\": \"bool\",\n        \"name\": \"success\",\n        \"type\": \"bool\"\n      }\n    ],\n    \"stateMutability\": \"payable\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [],\n    \"name\": \"getOwners\",\n    \"outputs\": [\n      {\n        \"internalType\": \"address[]\",\n        \"name\": \"\",\n        \"type\": \"address[]\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [],\n    \"name\": \"getThreshold\",\n    \"outputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  },\n  {\n    \"inputs\": [],\n    \"name\": \"nonce\",\n    \"outputs\": [\n      {\n        \"internalType\": \"uint256\",\n        \"name\": \"\",\n        \"type\": \"uint256\"\n      }\n    ],\n    \"stateMutability\": \"view\",\n    \"type\": \"function\"\n  }\n]";
+    ///The parsed JSON ABI of the contract.
+    pub static SAFE_ABI: ::ethers::contract::Lazy<::ethers::core::abi::Abi> = ::ethers::contract::Lazy::new(||
+    ::ethers::core::utils::__serde_json::from_str(__ABI).expect("ABI is always valid"));
+    pub struct safe<M>(::ethers::contract::Contract<M>);
+    impl<M> ::core::clone::Clone for safe<M> {
+        fn clone(&self) -> Self {
+            Self(::core::clone::Clone::clone(&self.0))
+        }
+    }
+    impl<M> ::core::ops::Deref for safe<M> {
+        type Target = ::ethers::contract::Contract<M>;
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+    impl<M> ::core::ops::DerefMut for safe<M> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+    impl<M> ::core::fmt::Debug for safe<M> {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            f.debug_tuple(stringify!(safe)).field(&self.address()).finish()
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> safe<M> {
+        /// Creates a new contract instance with the specified `ethers` client at
+        /// `address`. The contract derefs to a `ethers::Contract` object.
+        pub fn new<T: Into<::ethers::core::types::Address>>(
+            address: T,
+            client: ::std::sync::Arc<M>,
+        ) -> Self {
+            Self(
+                ::ethers::contract::Contract::new(
+                    address.into(),
+                    SAFE_ABI.clone(),
+                    client,
+                ),
+            )
+        }
+        ///Calls the contract's `VERSION` (0xffa1ad74) function
+        pub fn version(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::std::string::String> {
+            self.0
+                .method_hash([255, 161, 173, 116], ())
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `execTransaction` (0x6a761202) function
+        pub fn exec_transaction(
+            &self,
+            to: ::ethers::core::types::Address,
+            value: ::ethers::core::types::U256,
+            data: ::ethers::core::types::Bytes,
+            operation: u8,
+            safe_tx_gas: ::ethers::core::types::U256,
+            base_gas: ::ethers::core::types::U256,
+            gas_price: ::ethers::core::types::U256,
+            gas_token: ::ethers::core::types::Address,
+            refund_receiver: ::ethers::core::types::Address,
+            signatures: ::ethers::core::types::Bytes,
+        ) -> ::ethers::contract::builders::ContractCall<M, bool> {
+            self.0
+                .method_hash(
+                    [106, 118, 18, 2],
+                    (
+                        to,
+                        value,
+                        data,
+                        operation,
+                        safe_tx_gas,
+                        base_gas,
+                        gas_price,
+                        gas_token,
+                        refund_receiver,
+                        signatures,
+                    ),
+                )
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `getOwners` (0xa0e67e2b) function
+        pub fn get_owners(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<
+            M,
+            ::std::vec::Vec<::ethers::core::types::Address>,
+        > {
+            self.0
+                .method_hash([160, 230, 126, 43], ())
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `getThreshold` (0xe75235b8) function
+        pub fn get_threshold(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
+            self.0
+                .method_hash([231, 82, 53, 184], ())
+                .expect("method not found (this should never happen)")
+        }
+        ///Calls the contract's `nonce` (0xaffed0e0) function
+        pub fn nonce(
+            &self,
+        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
+            self.0
+                .method_hash([175, 254, 208, 224], ())
+                .expect("method not found (this should never happen)")
+        }
+        ///Gets the contract's `AddedOwner` event
+        pub fn added_owner_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            AddedOwnerFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ApproveHash` event
+        pub fn approve_hash_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ApproveHashFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ChangedThreshold` event
+        pub fn changed_threshold_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ChangedThresholdFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ExecutionFailure` event
+        pub fn execution_failure_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ExecutionFailureFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `ExecutionSuccess` event
+        pub fn execution_success_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            ExecutionSuccessFilter,
+        > {
+            self.0.event()
+        }
+        ///Gets the contract's `RemovedOwner` event
+        pub fn removed_owner_filter(
+            &self,
+        ) -> ::ethers::contract::builders::Event<
+            ::std::sync::Arc<M>,
+            M,
+            RemovedOwnerFilter,
+        > {
+            self.0.event()
+        }
+        /// Returns an `Event` builder for all the events of this contract.
+        pub fn events(
+            &self,
+        ) -> ::ethers::contract::builders::Event<::std::sync::Arc<M>, M, safeEvents> {
+            self.0.event_with_filter(::core::default::Default::default())
+        }
+    }
+    impl<M: ::ethers::providers::Middleware> From<::ethers::contract::Contract<M>>
+    for safe<M> {
+        fn from(contract: ::ethers::contract::Contract<M>) -> Self {
+            Self::new(contract.address(), contract.client())
+        }
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "AddedOwner", abi = "AddedOwner(address)")]
+    pub struct AddedOwnerFilter {
+        pub owner: ::ethers::core::types::Address,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "ApproveHash", abi = "ApproveHash(bytes32,address)")]
+    pub struct ApproveHashFilter {
+        #[ethevent(indexed)]
+        pub approved_hash: [u8; 32],
+        #[ethevent(indexed)]
+        pub owner: ::ethers::core::types::Address,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "ChangedThreshold", abi = "ChangedThreshold(uint256)")]
+    pub struct ChangedThresholdFilter {
+        pub threshold: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "ExecutionFailure", abi = "ExecutionFailure(bytes32,uint256)")]
+    pub struct ExecutionFailureFilter {
+        pub tx_hash: [u8; 32],
+        pub payment: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "ExecutionSuccess", abi = "ExecutionSuccess(bytes32,uint256)")]
+    pub struct ExecutionSuccessFilter {
+        pub tx_hash: [u8; 32],
+        pub payment: ::ethers::core::types::U256,
+    }
+    #[derive(
+        Clone,
+        ::ethers::contract::EthEvent,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethevent(name = "RemovedOwner", abi = "RemovedOwner(address)")]
+    pub struct RemovedOwnerFilter {
+        pub owner: ::ethers::core::types::Address,
+    }
+    ///Container type for all of the contract's events
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum safeEvents {
+        AddedOwnerFilter(AddedOwnerFilter),
+        ApproveHashFilter(ApproveHashFilter),
+        ChangedThresholdFilter(ChangedThresholdFilter),
+        ExecutionFailureFilter(ExecutionFailureFilter),
+        ExecutionSuccessFilter(ExecutionSuccessFilter),
+        RemovedOwnerFilter(RemovedOwnerFilter),
+    }
+    impl ::ethers::contract::EthLogDecode for safeEvents {
+        fn decode_log(
+            log: &::ethers::core::abi::RawLog,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::Error> {
+            if let Ok(decoded) = AddedOwnerFilter::decode_log(log) {
+                return Ok(safeEvents::AddedOwnerFilter(decoded));
+            }
+            if let Ok(decoded) = ApproveHashFilter::decode_log(log) {
+                return Ok(safeEvents::ApproveHashFilter(decoded));
+            }
+            if let Ok(decoded) = ChangedThresholdFilter::decode_log(log) {
+                return Ok(safeEvents::ChangedThresholdFilter(decoded));
+            }
+            if let Ok(decoded) = ExecutionFailureFilter::decode_log(log) {
+                return Ok(safeEvents::ExecutionFailureFilter(decoded));
+            }
+            if let Ok(decoded) = ExecutionSuccessFilter::decode_log(log) {
+                return Ok(safeEvents::ExecutionSuccessFilter(decoded));
+            }
+            if let Ok(decoded) = RemovedOwnerFilter::decode_log(log) {
+                return Ok(safeEvents::RemovedOwnerFilter(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData)
+        }
+    }
+    impl ::core::fmt::Display for safeEvents {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::AddedOwnerFilter(element) => ::core::fmt::Display::fmt(element, f),
+                Self::ApproveHashFilter(element) => ::core::fmt::Display::fmt(element, f),
+                Self::ChangedThresholdFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ExecutionFailureFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::ExecutionSuccessFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+                Self::RemovedOwnerFilter(element) => {
+                    ::core::fmt::Display::fmt(element, f)
+                }
+            }
+        }
+    }
+    impl ::core::convert::From<AddedOwnerFilter> for safeEvents {
+        fn from(value: AddedOwnerFilter) -> Self {
+            Self::AddedOwnerFilter(value)
+        }
+    }
+    impl ::core::convert::From<ApproveHashFilter> for safeEvents {
+        fn from(value: ApproveHashFilter) -> Self {
+            Self::ApproveHashFilter(value)
+        }
+    }
+    impl ::core::convert::From<ChangedThresholdFilter> for safeEvents {
+        fn from(value: ChangedThresholdFilter) -> Self {
+            Self::ChangedThresholdFilter(value)
+        }
+    }
+    impl ::core::convert::From<ExecutionFailureFilter> for safeEvents {
+        fn from(value: ExecutionFailureFilter) -> Self {
+            Self::ExecutionFailureFilter(value)
+        }
+    }
+    impl ::core::convert::From<ExecutionSuccessFilter> for safeEvents {
+        fn from(value: ExecutionSuccessFilter) -> Self {
+            Self::ExecutionSuccessFilter(value)
+        }
+    }
+    impl ::core::convert::From<RemovedOwnerFilter> for safeEvents {
+        fn from(value: RemovedOwnerFilter) -> Self {
+            Self::RemovedOwnerFilter(value)
+        }
+    }
+    ///Container type for all input parameters for the `VERSION` function with signature `VERSION()` and selector `0xffa1ad74`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "VERSION", abi = "VERSION()")]
+    pub struct VersionCall;
+    ///Container type for all input parameters for the `execTransaction` function with signature `execTransaction(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,bytes)` and selector `0x6a761202`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(
+        name = "execTransaction",
+        abi = "execTransaction(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,bytes)"
+    )]
+    pub struct ExecTransactionCall {
+        pub to: ::ethers::core::types::Address,
+        pub value: ::ethers::core::types::U256,
+        pub data: ::ethers::core::types::Bytes,
+        pub operation: u8,
+        pub safe_tx_gas: ::ethers::core::types::U256,
+        pub base_gas: ::ethers::core::types::U256,
+        pub gas_price: ::ethers::core::types::U256,
+        pub gas_token: ::ethers::core::types::Address,
+        pub refund_receiver: ::ethers::core::types::Address,
+        pub signatures: ::ethers::core::types::Bytes,
+    }
+    ///Container type for all input parameters for the `getOwners` function with signature `getOwners()` and selector `0xa0e67e2b`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "getOwners", abi = "getOwners()")]
+    pub struct GetOwnersCall;
+    ///Container type for all input parameters for the `getThreshold` function with signature `getThreshold()` and selector `0xe75235b8`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "getThreshold", abi = "getThreshold()")]
+    pub struct GetThresholdCall;
+    ///Container type for all input parameters for the `nonce` function with signature `nonce()` and selector `0xaffed0e0`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthCall,
+        ::ethers::contract::EthDisplay,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    #[ethcall(name = "nonce", abi = "nonce()")]
+    pub struct NonceCall;
+    ///Container type for all of the contract's call
+    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
+    pub enum safeCalls {
+        Version(VersionCall),
+        ExecTransaction(ExecTransactionCall),
+        GetOwners(GetOwnersCall),
+        GetThreshold(GetThresholdCall),
+        Nonce(NonceCall),
+    }
+    impl ::ethers::core::abi::AbiDecode for safeCalls {
+        fn decode(
+            data: impl AsRef<[u8]>,
+        ) -> ::core::result::Result<Self, ::ethers::core::abi::AbiError> {
+            let data = data.as_ref();
+            if let Ok(decoded) = <VersionCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::Version(decoded));
+            }
+            if let Ok(decoded) = <ExecTransactionCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::ExecTransaction(decoded));
+            }
+            if let Ok(decoded) = <GetOwnersCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::GetOwners(decoded));
+            }
+            if let Ok(decoded) = <GetThresholdCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::GetThreshold(decoded));
+            }
+            if let Ok(decoded) = <NonceCall as ::ethers::core::abi::AbiDecode>::decode(
+                data,
+            ) {
+                return Ok(Self::Nonce(decoded));
+            }
+            Err(::ethers::core::abi::Error::InvalidData.into())
+        }
+    }
+    impl ::ethers::core::abi::AbiEncode for safeCalls {
+        fn encode(self) -> Vec<u8> {
+            match self {
+                Self::Version(element) => ::ethers::core::abi::AbiEncode::encode(element),
+                Self::ExecTransaction(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::GetOwners(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::GetThreshold(element) => {
+                    ::ethers::core::abi::AbiEncode::encode(element)
+                }
+                Self::Nonce(element) => ::ethers::core::abi::AbiEncode::encode(element),
+            }
+        }
+    }
+    impl ::core::fmt::Display for safeCalls {
+        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            match self {
+                Self::Version(element) => ::core::fmt::Display::fmt(element, f),
+                Self::ExecTransaction(element) => ::core::fmt::Display::fmt(element, f),
+                Self::GetOwners(element) => ::core::fmt::Display::fmt(element, f),
+                Self::GetThreshold(element) => ::core::fmt::Display::fmt(element, f),
+                Self::Nonce(element) => ::core::fmt::Display::fmt(element, f),
+            }
+        }
+    }
+    impl ::core::convert::From<VersionCall> for safeCalls {
+        fn from(value: VersionCall) -> Self {
+            Self::Version(value)
+        }
+    }
+    impl ::core::convert::From<ExecTransactionCall> for safeCalls {
+        fn from(value: ExecTransactionCall) -> Self {
+            Self::ExecTransaction(value)
+        }
+    }
+    impl ::core::convert::From<GetOwnersCall> for safeCalls {
+        fn from(value: GetOwnersCall) -> Self {
+            Self::GetOwners(value)
+        }
+    }
+    impl ::core::convert::From<GetThresholdCall> for safeCalls {
+        fn from(value: GetThresholdCall) -> Self {
+            Self::GetThreshold(value)
+        }
+    }
+    impl ::core::convert::From<NonceCall> for safeCalls {
+        fn from(value: NonceCall) -> Self {
+            Self::Nonce(value)
+        }
+    }
+    ///Container type for all return fields from the `VERSION` function with signature `VERSION()` and selector `0xffa1ad74`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct VersionReturn(pub ::std::string::String);
+    ///Container type for all return fields from the `execTransaction` function with signature `execTransaction(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,bytes)` and selector `0x6a761202`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct ExecTransactionReturn {
+        pub success: bool,
+    }
+    ///Container type for all return fields from the `getOwners` function with signature `getOwners()` and selector `0xa0e67e2b`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct GetOwnersReturn(pub ::std::vec::Vec<::ethers::core::types::Address>);
+    ///Container type for all return fields from the `getThreshold` function with signature `getThreshold()` and selector `0xe75235b8`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct GetThresholdReturn(pub ::ethers::core::types::U256);
+    ///Container type for all return fields from the `nonce` function with signature `nonce()` and selector `0xaffed0e0`
+    #[derive(
+        Clone,
+        ::ethers::contract::EthAbiType,
+        ::ethers::contract::EthAbiCodec,
+        Default,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash
+    )]
+    pub struct NonceReturn(pub ::ethers::core::types::U256);
+}
@@ -14,3 +14,19 @@ pub struct Receipt {
     pub support: u8,
     pub votes: u128,
 }
+///`Action(address,uint256,bytes)`
+#[derive(
+    Clone,
+    ::ethers::contract::EthAbiType,
+    ::ethers::contract::EthAbiCodec,
+    Default,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash
+)]
+pub struct Action {
+    pub to: ::ethers::core::types::Address,
+    pub value: ::ethers::core::types::U256,
+    pub data: ::ethers::core::types::Bytes,
+}
@@ -0,0 +1,353 @@
+//! Handler integration tests against a local anvil chain.
+//!
+//! The test governors in `devnet/contracts` emit the same events and answer the same
+//! getters as the real ones, so the handlers run unchanged: contracts are deployed and
+//! driven from the tests, the refresh routes run against them and the rows they write
+//! are checked in a disposable MySQL.
+//!
+//! The tests are ignored by default. `devnet/run.sh` starts the database, pushes the
+//! schema, builds the contracts and runs them; by hand it is
+//!
+//! ```sh
+//! forge build --root devnet
+//! DEVNET_DATABASE_URL=mysql://... cargo test devnet -- --ignored
+//! ```
+
+mod scenarios;
+
+use std::{
+    env,
+    fs,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
+
+use anyhow::{Context as _, Result};
+use ethers::{
+    abi::{Abi, Tokenize},
+    contract::{Contract, ContractFactory},
+    middleware::SignerMiddleware,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Bytes, TransactionReceipt, U256},
+    utils::{to_checksum, Anvil, AnvilInstance},
+};
+use prisma_client_rust::{chrono::Utc, Direction};
+use rocket::{http::ContentType, local::asynchronous::Client};
+use serde_json::{json, Value};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::{
+    abis,
+    app,
+    prisma::{self, contentcache, daohandler, proposal, vote, voter, DaoHandlerType},
+    Context,
+};
+
+pub type Signed = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// One anvil chain and a rocket client over it. Holding a `Devnet` holds the devnet
+/// lock, the tests share the database and the rpc env vars.
+pub struct Devnet {
+    pub anvil: AnvilInstance,
+    pub ctx: Context,
+    pub client: Client,
+    _lock: MutexGuard<'static, ()>,
+}
+
+fn lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn artifacts_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("devnet/out")
+}
+
+/// Forge build artifact of `contract` in `devnet/contracts/<file>.sol`.
+fn artifact_json(file: &str, contract: &str) -> Result<Value> {
+    let path = artifacts_dir()
+        .join(format!("{}.sol", file))
+        .join(format!("{}.json", contract));
+
+    let json = fs::read_to_string(&path)
+        .with_context(|| format!("{} missing, run forge build", path.display()))?;
+
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Abi and creation bytecode of a contract.
+fn artifact(file: &str, contract: &str) -> Result<(Abi, Bytes)> {
+    let json = artifact_json(file, contract)?;
+
+    let bytecode = serde_json::from_value(json["bytecode"]["object"].clone())?;
+
+    Ok((abis::parse_abi(&json)?, bytecode))
+}
+
+impl Devnet {
+    pub async fn start() -> Result<Devnet> {
+        let lock = lock().lock().await;
+
+        let database_url =
+            env::var("DEVNET_DATABASE_URL").context("$DEVNET_DATABASE_URL is not set")?;
+
+        let anvil = Anvil::new().spawn();
+
+        // estimate_timestamp reads past blocks from here
+        env::set_var("ALCHEMY_NODE_URL", anvil.endpoint());
+        env::set_var("ETHERSCAN_API_KEY", "devnet");
+
+        let rpc = Arc::new(Provider::<Http>::try_from(anvil.endpoint())?);
+        let db = Arc::new(prisma::new_client_with_url(&database_url).await?);
+
+        let ctx = Context {
+            db,
+            eth_rpc: rpc.clone(),
+            arbitrum_rpc: rpc.clone(),
+            optimism_rpc: rpc,
+        };
+
+        let client = Client::tracked(app(ctx.clone()))
+            .await
+            .expect("devnet rocket failed to ignite");
+
+        Ok(Devnet {
+            anvil,
+            ctx,
+            client,
+            _lock: lock,
+        })
+    }
+
+    /// Client signing with the anvil account at `index`. Account 0 deploys.
+    pub fn signer(&self, index: usize) -> Arc<Signed> {
+        let wallet: LocalWallet = self.anvil.keys()[index].clone().into();
+
+        Arc::new(SignerMiddleware::new(
+            self.ctx.eth_rpc.as_ref().clone(),
+            wallet.with_chain_id(self.anvil.chain_id()),
+        ))
+    }
+
+    /// Checksummed address of the anvil account at `index`.
+    pub fn address(&self, index: usize) -> String {
+        to_checksum(&self.anvil.addresses()[index], None)
+    }
+
+    pub async fn deploy<T: Tokenize>(
+        &self,
+        file: &str,
+        contract: &str,
+        args: T,
+    ) -> Result<Contract<Signed>> {
+        let (abi, bytecode) = artifact(file, contract)?;
+
+        let deployed = ContractFactory::new(abi, bytecode, self.signer(0))
+            .deploy(args)?
+            .send()
+            .await?;
+
+        Ok(deployed)
+    }
+
+    pub async fn mine(&self, blocks: u64) -> Result<()> {
+        self.ctx
+            .eth_rpc
+            .request::<_, ()>("anvil_mine", [U256::from(blocks)])
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn dao_handler(
+        &self,
+        name: &str,
+        handler_type: DaoHandlerType,
+        decoder: Value,
+    ) -> Result<daohandler::Data> {
+        // one dao per run, the database outlives the chain
+        let dao = self
+            .ctx
+            .db
+            .dao()
+            .create(
+                format!("{} {}", name, Utc::now().timestamp_millis()),
+                "".to_string(),
+                vec![],
+            )
+            .exec()
+            .await?;
+
+        let handler = self
+            .ctx
+            .db
+            .daohandler()
+            .create_unchecked(handler_type, decoder, dao.id, vec![])
+            .exec()
+            .await?;
+
+        Ok(handler)
+    }
+
+    pub async fn subscribe(&self, dao_handler: &daohandler::Data, address: &str) -> Result<()> {
+        let voter = self
+            .ctx
+            .db
+            .voter()
+            .upsert(
+                voter::address::equals(address.to_string()),
+                voter::create(address.to_string(), vec![]),
+                vec![],
+            )
+            .exec()
+            .await?;
+
+        self.ctx
+            .db
+            .voterhandler()
+            .create_unchecked(dao_handler.id.clone(), voter.id, vec![])
+            .exec()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Seeds the content cache so handlers resolve `uri` without fetching it.
+    pub async fn cache_content(&self, uri: &str, content: &str) -> Result<()> {
+        self.ctx
+            .db
+            .contentcache()
+            .upsert(
+                contentcache::uri::equals(uri.to_string()),
+                contentcache::create(
+                    uri.to_string(),
+                    content.to_string(),
+                    vec![contentcache::verified::set(true)],
+                ),
+                vec![contentcache::content::set(content.to_string())],
+            )
+            .exec()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Runs `/proposals/chain_proposals` over everything mined so far. The route stays
+    /// 10 blocks behind the head.
+    pub async fn refresh_proposals(&self, dao_handler: &daohandler::Data) -> Result<bool> {
+        self.mine(10).await?;
+
+        let response = self
+            .client
+            .post("/proposals/chain_proposals")
+            .header(ContentType::JSON)
+            .body(json!({"daoHandlerId": dao_handler.id, "refreshspeed": 1_000_000}).to_string())
+            .dispatch()
+            .await
+            .into_string()
+            .await
+            .unwrap_or_default();
+
+        let response: Value = serde_json::from_str(&response)?;
+
+        Ok(response["success"] == json!(true))
+    }
+
+    pub async fn refresh_votes(
+        &self,
+        dao_handler: &daohandler::Data,
+        voters: &[String],
+    ) -> Result<bool> {
+        let response = self
+            .client
+            .post("/votes/chain_votes")
+            .header(ContentType::JSON)
+            .body(
+                json!({
+                    "daoHandlerId": dao_handler.id,
+                    "voters": voters,
+                    "refreshspeed": 1_000_000
+                })
+                .to_string(),
+            )
+            .dispatch()
+            .await
+            .into_string()
+            .await
+            .unwrap_or_default();
+
+        let response: Vec<Value> = serde_json::from_str(&response)?;
+
+        Ok(response.iter().all(|r| r["success"] == json!(true)))
+    }
+
+    pub async fn proposals(&self, dao_handler: &daohandler::Data) -> Result<Vec<proposal::Data>> {
+        Ok(self
+            .ctx
+            .db
+            .proposal()
+            .find_many(vec![proposal::daohandlerid::equals(dao_handler.id.clone())])
+            .order_by(proposal::blockcreated::order(Direction::Asc))
+            .exec()
+            .await?)
+    }
+
+    /// Votes of `address` with the external id of the proposal each is on.
+    pub async fn votes(
+        &self,
+        dao_handler: &daohandler::Data,
+        address: &str,
+    ) -> Result<Vec<(String, vote::Data)>> {
+        let proposals = self.proposals(dao_handler).await?;
+
+        let votes = self
+            .ctx
+            .db
+            .vote()
+            .find_many(vec![
+                vote::daohandlerid::equals(dao_handler.id.clone()),
+                vote::voteraddress::equals(address.to_string()),
+            ])
+            .exec()
+            .await?;
+
+        let mut votes: Vec<(String, vote::Data)> = votes
+            .into_iter()
+            .filter_map(|v| {
+                let p = proposals.iter().find(|p| p.id == v.proposalid)?;
+                Some((p.externalid.clone(), v))
+            })
+            .collect();
+        votes.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(votes)
+    }
+}
+
+/// Sends a transaction and waits for it to be mined.
+pub async fn send<T: Tokenize>(
+    contract: &Contract<Signed>,
+    function: &str,
+    args: T,
+) -> Result<TransactionReceipt> {
+    let call = contract.method::<_, ()>(function, args)?;
+
+    let receipt = call.send().await?.await?;
+
+    receipt.context("transaction dropped")
+}
+
+/// `send` for overloaded functions, which `method` can not pick between.
+pub async fn send_selector<T: Tokenize>(
+    contract: &Contract<Signed>,
+    selector: [u8; 4],
+    args: T,
+) -> Result<TransactionReceipt> {
+    let call = contract.method_hash::<_, ()>(selector, args)?;
+
+    let receipt = call.send().await?.await?;
+
+    receipt.context("transaction dropped")
+}
@@ -0,0 +1,514 @@
+use anyhow::Result;
+use ethers::{
+    types::{Address, Bytes, H256, U256},
+    utils::keccak256,
+};
+use prisma_client_rust::chrono::{FixedOffset, Utc};
+use serde_json::json;
+
+use super::{artifact_json, send, send_selector, Devnet};
+use crate::{
+    abis,
+    prisma::{DaoHandlerType, ProposalState},
+    utils::content::Cid,
+};
+
+const VOTE_MULTIPLE_ACTIONS: [u8; 4] = [0xed, 0x08, 0x13, 0x29];
+const VOTE_SINGLE_ACTION: [u8; 4] = [0xa6, 0x9b, 0xea, 0xba];
+
+const SPELL_A: &str = "0x1111111111111111111111111111111111111111";
+const SPELL_B: &str = "0x2222222222222222222222222222222222222222";
+
+/// A proposal calling back into the governor, the handlers only read its events.
+fn actions(target: Address) -> (Vec<Address>, Vec<U256>, Vec<Bytes>) {
+    (vec![target], vec![U256::zero()], vec![Bytes::default()])
+}
+
+#[tokio::test]
+#[ignore]
+async fn bravo_proposals_and_votes() -> Result<()> {
+    let devnet = Devnet::start().await?;
+    let (alice, bob) = (devnet.address(1), devnet.address(2));
+
+    let gov = devnet
+        .deploy("BravoGovernor", "BravoGovernor", (U256::from(100),))
+        .await?;
+
+    send(
+        &gov,
+        "setWeight",
+        (alice.parse::<Address>()?, U256::from(300)),
+    )
+    .await?;
+    send(&gov, "setWeight", (bob.parse::<Address>()?, U256::from(50))).await?;
+
+    let (targets, values, calldatas) = actions(gov.address());
+
+    send(
+        &gov,
+        "propose",
+        (
+            targets.clone(),
+            values.clone(),
+            vec![String::new()],
+            calldatas.clone(),
+            "# Raise the reserve factor\n\nTo fund the devnet.".to_string(),
+        ),
+    )
+    .await?;
+    devnet.mine(1).await?;
+
+    send(
+        &gov.connect(devnet.signer(1)),
+        "castVoteWithReason",
+        (U256::from(1), 1u8, "for".to_string()),
+    )
+    .await?;
+    send(
+        &gov.connect(devnet.signer(2)),
+        "castVoteWithReason",
+        (U256::from(1), 2u8, "abstain".to_string()),
+    )
+    .await?;
+
+    send(
+        &gov,
+        "propose",
+        (
+            targets,
+            values,
+            vec![String::new()],
+            calldatas,
+            "Withdrawn".to_string(),
+        ),
+    )
+    .await?;
+    send(&gov, "cancel", (U256::from(2),)).await?;
+
+    devnet.mine(25).await?;
+
+    let handler = devnet
+        .dao_handler(
+            "Bravo",
+            DaoHandlerType::CompoundChain,
+            json!({
+                "address": format!("{:#x}", gov.address()),
+                "proposalUrl": "https://devnet/bravo/"
+            }),
+        )
+        .await?;
+    devnet.subscribe(&handler, &alice).await?;
+    devnet.subscribe(&handler, &bob).await?;
+
+    assert!(devnet.refresh_proposals(&handler).await?);
+
+    let proposals = devnet.proposals(&handler).await?;
+    assert_eq!(proposals.len(), 2);
+
+    let p = &proposals[0];
+    assert_eq!(p.externalid, "1");
+    assert_eq!(p.name, "Raise the reserve factor");
+    assert_eq!(p.url, "https://devnet/bravo/1");
+    assert_eq!(p.choices, json!(["For", "Against", "Abstain"]));
+    assert_eq!(p.scores, json!([300, 0, 50]));
+    assert_eq!(p.scorestotal, json!(350));
+    assert_eq!(p.quorum, json!(100));
+    assert_eq!(p.state, ProposalState::Succeeded);
+    assert!(p.timestart < p.timeend);
+
+    assert_eq!(proposals[1].externalid, "2");
+    assert_eq!(proposals[1].state, ProposalState::Canceled);
+
+    assert!(
+        devnet
+            .refresh_votes(&handler, &[alice.clone(), bob.clone()])
+            .await?
+    );
+
+    let votes = devnet.votes(&handler, &alice).await?;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].0, "1");
+    assert_eq!(votes[0].1.choice, json!(1));
+    assert_eq!(votes[0].1.votingpower, json!(300));
+
+    let votes = devnet.votes(&handler, &bob).await?;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].1.choice, json!(3));
+    assert_eq!(votes[0].1.votingpower, json!(50));
+
+    Ok(())
+}
+
+/// Deploys an OZ governor with one proposal, alice voting with 300 and bob with 50.
+async fn oz_governor(devnet: &Devnet, bob_support: u8) -> Result<(Address, U256)> {
+    let (alice, bob) = (devnet.address(1), devnet.address(2));
+
+    let gov = devnet
+        .deploy("OzGovernor", "OzGovernor", (U256::from(200),))
+        .await?;
+
+    send(
+        &gov,
+        "setWeight",
+        (alice.parse::<Address>()?, U256::from(300)),
+    )
+    .await?;
+    send(&gov, "setWeight", (bob.parse::<Address>()?, U256::from(50))).await?;
+
+    let (targets, values, calldatas) = actions(gov.address());
+    let description = "Fund the devnet working group";
+
+    let id = gov
+        .method::<_, U256>(
+            "hashProposal",
+            (
+                targets.clone(),
+                values.clone(),
+                calldatas.clone(),
+                H256::from(keccak256(description)),
+            ),
+        )?
+        .call()
+        .await?;
+
+    send(
+        &gov,
+        "propose",
+        (targets, values, calldatas, description.to_string()),
+    )
+    .await?;
+    devnet.mine(1).await?;
+
+    send(
+        &gov.connect(devnet.signer(1)),
+        "castVoteWithReason",
+        (id, 1u8, String::new()),
+    )
+    .await?;
+    send(
+        &gov.connect(devnet.signer(2)),
+        "castVoteWithReason",
+        (id, bob_support, String::new()),
+    )
+    .await?;
+
+    devnet.mine(25).await?;
+
+    Ok((gov.address(), id))
+}
+
+#[tokio::test]
+#[ignore]
+async fn oz_proposals_and_votes() -> Result<()> {
+    let devnet = Devnet::start().await?;
+    let (alice, bob) = (devnet.address(1), devnet.address(2));
+
+    let (gov, id) = oz_governor(&devnet, 2).await?;
+
+    let handler = devnet
+        .dao_handler(
+            "OZ",
+            DaoHandlerType::EnsChain,
+            json!({
+                "address": format!("{:#x}", gov),
+                "proposalUrl": "https://devnet/oz/"
+            }),
+        )
+        .await?;
+    devnet.subscribe(&handler, &alice).await?;
+    devnet.subscribe(&handler, &bob).await?;
+
+    assert!(devnet.refresh_proposals(&handler).await?);
+
+    let proposals = devnet.proposals(&handler).await?;
+    assert_eq!(proposals.len(), 1);
+
+    let p = &proposals[0];
+    assert_eq!(p.externalid, id.to_string());
+    assert_eq!(p.name, "Fund the devnet working group");
+    assert_eq!(p.choices, json!(["Against", "For", "Abstain"]));
+    assert_eq!(p.scores, json!([0, 300, 50]));
+    assert_eq!(p.quorum, json!(200));
+    assert_eq!(p.state, ProposalState::Succeeded);
+
+    assert!(
+        devnet
+            .refresh_votes(&handler, &[alice.clone(), bob.clone()])
+            .await?
+    );
+
+    let votes = devnet.votes(&handler, &alice).await?;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].0, id.to_string());
+    assert_eq!(votes[0].1.votingpower, json!(300));
+
+    let votes = devnet.votes(&handler, &bob).await?;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].1.votingpower, json!(50));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn generic_proposals_and_votes() -> Result<()> {
+    let devnet = Devnet::start().await?;
+    let (alice, bob) = (devnet.address(1), devnet.address(2));
+
+    abis::register(
+        &devnet.ctx.db,
+        "devnetozgovernor",
+        artifact_json("OzGovernor", "OzGovernor")?,
+    )
+    .await?;
+
+    let (gov, id) = oz_governor(&devnet, 0).await?;
+
+    let handler = devnet
+        .dao_handler(
+            "Generic",
+            DaoHandlerType::GenericChain,
+            json!({
+                "address": format!("{:#x}", gov),
+                "abi": "devnetozgovernor",
+                "proposalUrl": "https://devnet/generic/"
+            }),
+        )
+        .await?;
+    devnet.subscribe(&handler, &alice).await?;
+    devnet.subscribe(&handler, &bob).await?;
+
+    assert!(devnet.refresh_proposals(&handler).await?);
+
+    let proposals = devnet.proposals(&handler).await?;
+    assert_eq!(proposals.len(), 1);
+
+    let p = &proposals[0];
+    assert_eq!(p.externalid, id.to_string());
+    assert_eq!(p.choices, json!(["For", "Against", "Abstain"]));
+    assert_eq!(p.scores, json!([300, 50, 0]));
+    assert_eq!(p.quorum, json!(200));
+    assert_eq!(p.state, ProposalState::Succeeded);
+
+    assert!(
+        devnet
+            .refresh_votes(&handler, &[alice.clone(), bob.clone()])
+            .await?
+    );
+
+    let votes = devnet.votes(&handler, &alice).await?;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].1.choice, json!(1));
+    assert_eq!(votes[0].1.votingpower, json!(300));
+
+    let votes = devnet.votes(&handler, &bob).await?;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].1.choice, json!(2));
+    assert_eq!(votes[0].1.votingpower, json!(50));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn aave_proposals_and_votes() -> Result<()> {
+    let devnet = Devnet::start().await?;
+    let (alice, bob) = (devnet.address(1), devnet.address(2));
+
+    let strategy = devnet
+        .deploy("AaveGovernance", "AaveStrategy", (U256::from(10000),))
+        .await?;
+    let executor = devnet.deploy("AaveGovernance", "AaveExecutor", ()).await?;
+    let gov = devnet
+        .deploy("AaveGovernance", "AaveGovernance", ())
+        .await?;
+
+    send(
+        &gov,
+        "setWeight",
+        (alice.parse::<Address>()?, U256::from(300)),
+    )
+    .await?;
+    send(&gov, "setWeight", (bob.parse::<Address>()?, U256::from(50))).await?;
+
+    // the title comes from ipfs, the cache answers for it
+    let ipfs_hash = H256::from(keccak256("devnet aave proposal"));
+    devnet
+        .cache_content(
+            &format!(
+                "ipfs://{}",
+                Cid::from_bytes32(ipfs_hash.as_bytes())?.to_v1()
+            ),
+            &json!({"title": "Onboard the devnet asset"}).to_string(),
+        )
+        .await?;
+
+    let (targets, values, calldatas) = actions(gov.address());
+
+    send(
+        &gov,
+        "create",
+        (
+            executor.address(),
+            targets,
+            values,
+            vec![String::new()],
+            calldatas,
+            vec![false],
+            strategy.address(),
+            ipfs_hash,
+        ),
+    )
+    .await?;
+    devnet.mine(1).await?;
+
+    send(
+        &gov.connect(devnet.signer(1)),
+        "submitVote",
+        (U256::zero(), true),
+    )
+    .await?;
+    send(
+        &gov.connect(devnet.signer(2)),
+        "submitVote",
+        (U256::zero(), false),
+    )
+    .await?;
+
+    devnet.mine(25).await?;
+
+    let handler = devnet
+        .dao_handler(
+            "Aave",
+            DaoHandlerType::AaveChain,
+            json!({
+                "address": format!("{:#x}", gov.address()),
+                "proposalUrl": "https://devnet/aave/"
+            }),
+        )
+        .await?;
+    devnet.subscribe(&handler, &alice).await?;
+    devnet.subscribe(&handler, &bob).await?;
+
+    assert!(devnet.refresh_proposals(&handler).await?);
+
+    let proposals = devnet.proposals(&handler).await?;
+    assert_eq!(proposals.len(), 1);
+
+    let p = &proposals[0];
+    assert_eq!(p.externalid, "0");
+    assert_eq!(p.name, "Onboard the devnet asset");
+    assert_eq!(p.choices, json!(["For", "Against"]));
+    assert_eq!(p.scores, json!([300, 50]));
+    // 2% of the 10000 voting supply
+    assert_eq!(p.quorum, json!(200));
+    assert_eq!(p.state, ProposalState::Succeeded);
+
+    assert!(
+        devnet
+            .refresh_votes(&handler, &[alice.clone(), bob.clone()])
+            .await?
+    );
+
+    let votes = devnet.votes(&handler, &alice).await?;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].1.choice, json!(1));
+    assert_eq!(votes[0].1.votingpower, json!(300));
+
+    let votes = devnet.votes(&handler, &bob).await?;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].1.choice, json!(2));
+    assert_eq!(votes[0].1.votingpower, json!(50));
+
+    Ok(())
+}
+
+/// Executive proposals come from the maker api, only the votes are read on chain. The
+/// spells are seeded the way the proposals refresh stores them.
+#[tokio::test]
+#[ignore]
+async fn maker_executive_votes() -> Result<()> {
+    let devnet = Devnet::start().await?;
+    let (alice, bob) = (devnet.address(1), devnet.address(2));
+
+    let chief = devnet.deploy("MakerChief", "MakerChief", ()).await?;
+
+    let handler = devnet
+        .dao_handler(
+            "Maker",
+            DaoHandlerType::MakerExecutive,
+            json!({
+                "address": format!("{:#x}", chief.address()),
+                "proposalUrl": "https://devnet/executive/"
+            }),
+        )
+        .await?;
+    devnet.subscribe(&handler, &alice).await?;
+    devnet.subscribe(&handler, &bob).await?;
+
+    let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+    for spell in [SPELL_A, SPELL_B] {
+        devnet
+            .ctx
+            .db
+            .proposal()
+            .create_unchecked(
+                format!("Spell {}", spell),
+                spell.to_string(),
+                json!(["Yes"]),
+                json!([0]),
+                json!(0),
+                json!(0),
+                ProposalState::Executed,
+                now,
+                now,
+                now,
+                format!("https://devnet/executive/{}", spell),
+                handler.id.clone(),
+                handler.daoid.clone(),
+                vec![],
+            )
+            .exec()
+            .await?;
+    }
+
+    let (spell_a, spell_b) = (SPELL_A.parse::<Address>()?, SPELL_B.parse::<Address>()?);
+
+    // alice votes for a new slate, bob etches his own and votes for it by hash
+    send_selector(
+        &chief.connect(devnet.signer(1)),
+        VOTE_MULTIPLE_ACTIONS,
+        (vec![spell_a, spell_b],),
+    )
+    .await?;
+
+    let bob_chief = chief.connect(devnet.signer(2));
+    let slate = bob_chief
+        .method::<_, H256>("etch", (vec![spell_a],))?
+        .call()
+        .await?;
+    send(&bob_chief, "etch", (vec![spell_a],)).await?;
+    send_selector(&bob_chief, VOTE_SINGLE_ACTION, (slate,)).await?;
+
+    assert!(
+        devnet
+            .refresh_votes(&handler, &[alice.clone(), bob.clone()])
+            .await?
+    );
+
+    let votes = devnet.votes(&handler, &alice).await?;
+    assert_eq!(
+        votes.iter().map(|v| v.0.as_str()).collect::<Vec<&str>>(),
+        vec![SPELL_A, SPELL_B]
+    );
+    assert!(votes.iter().all(|v| v.1.choice == json!(1)));
+
+    let votes = devnet.votes(&handler, &bob).await?;
+    assert_eq!(
+        votes.iter().map(|v| v.0.as_str()).collect::<Vec<&str>>(),
+        vec![SPELL_A]
+    );
+
+    Ok(())
+}
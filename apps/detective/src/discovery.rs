@@ -0,0 +1,512 @@
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use ethers::{
+    abi::{self, ParamType, Token},
+    providers::{Http, Middleware, Provider},
+    types::{Address, BlockId, BlockNumber, Bytes, TransactionRequest, H256},
+    utils::id,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::{event, instrument, Level};
+
+use crate::{prisma::DaoHandlerType, Chain};
+
+// keccak256("eip1967.proxy.implementation") - 1
+const EIP1967_IMPLEMENTATION_SLOT: &str =
+    "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+
+// the pre-4.6 IGovernor function set, every OZ governor since 4.4 still
+// answers supportsInterface for it
+const IGOVERNOR_FUNCTIONS: [&str; 17] = [
+    "name()",
+    "version()",
+    "COUNTING_MODE()",
+    "hashProposal(address[],uint256[],bytes[],bytes32)",
+    "state(uint256)",
+    "proposalSnapshot(uint256)",
+    "proposalDeadline(uint256)",
+    "votingDelay()",
+    "votingPeriod()",
+    "quorum(uint256)",
+    "getVotes(address,uint256)",
+    "hasVoted(uint256,address)",
+    "propose(address[],uint256[],bytes[],string)",
+    "execute(address[],uint256[],bytes[],bytes32)",
+    "castVote(uint256,uint8)",
+    "castVoteWithReason(uint256,uint8,string)",
+    "castVoteBySig(uint256,uint8,uint8,bytes32,bytes32)",
+];
+
+const TOKEN_GETTERS: [&str; 6] = ["token()", "comp()", "uni()", "gtc()", "ipt()", "GOV()"];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(crate = "rocket::serde")]
+pub enum GovernorKind {
+    OzGovernor,
+    GovernorBravo,
+    GovernorAlpha,
+    NounsGovernor,
+    AragonTokenVoting,
+    AragonMultisig,
+    Safe,
+    AaveGovernanceV2,
+    MakerChief,
+    MakerPollingEmitter,
+    ZeroExTreasury,
+    Unknown,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct DiscoveryReport {
+    pub chain: Chain,
+    pub address: String,
+    pub implementation: Option<String>,
+    pub kind: GovernorKind,
+    pub handler_type: Option<DaoHandlerType>,
+    pub compatible_handler_types: Vec<DaoHandlerType>,
+    pub name: Option<String>,
+    pub supports_igovernor: bool,
+    pub counting_mode: Option<String>,
+    pub clock_mode: Option<String>,
+    pub token: Option<String>,
+    pub timelock: Option<String>,
+    pub deployment_block: Option<i64>,
+    pub warnings: Vec<String>,
+    pub handler_config: Option<Value>,
+}
+
+struct Probe<'a> {
+    rpc: &'a Arc<Provider<Http>>,
+    address: Address,
+    code: Bytes,
+}
+
+impl<'a> Probe<'a> {
+    // solidity dispatchers compare against PUSH4 selectors, so a selector
+    // showing up in the runtime code is a good enough signal
+    fn has(&self, signature: &str) -> bool {
+        let selector = id(signature);
+        self.code.windows(4).any(|w| w == selector)
+    }
+
+    fn has_all(&self, signatures: &[&str]) -> bool {
+        signatures.iter().all(|s| self.has(s))
+    }
+
+    async fn call(&self, signature: &str, args: &[Token]) -> Option<Bytes> {
+        let mut data = id(signature).to_vec();
+        data.extend(abi::encode(args));
+
+        let tx = TransactionRequest::new().to(self.address).data(data);
+
+        match self.rpc.call(&tx.into(), None).await {
+            Ok(result) if !result.is_empty() => Some(result),
+            _ => None,
+        }
+    }
+
+    async fn call_string(&self, signature: &str) -> Option<String> {
+        let result = self.call(signature, &[]).await?;
+        match abi::decode(&[ParamType::String], &result).ok()?.pop()? {
+            Token::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    async fn call_address(&self, signature: &str) -> Option<Address> {
+        let result = self.call(signature, &[]).await?;
+        match abi::decode(&[ParamType::Address], &result).ok()?.pop()? {
+            Token::Address(a) if !a.is_zero() => Some(a),
+            _ => None,
+        }
+    }
+
+    async fn supports_interface(&self, interface_id: [u8; 4]) -> bool {
+        let result = match self
+            .call(
+                "supportsInterface(bytes4)",
+                &[Token::FixedBytes(interface_id.to_vec())],
+            )
+            .await
+        {
+            Some(r) => r,
+            None => return false,
+        };
+
+        matches!(
+            abi::decode(&[ParamType::Bool], &result)
+                .ok()
+                .and_then(|mut t| t.pop()),
+            Some(Token::Bool(true))
+        )
+    }
+}
+
+pub fn igovernor_interface_id() -> [u8; 4] {
+    IGOVERNOR_FUNCTIONS.iter().fold([0u8; 4], |acc, f| {
+        let selector = id(f);
+        [
+            acc[0] ^ selector[0],
+            acc[1] ^ selector[1],
+            acc[2] ^ selector[2],
+            acc[3] ^ selector[3],
+        ]
+    })
+}
+
+#[instrument(skip(rpc))]
+pub async fn discover(
+    rpc: &Arc<Provider<Http>>,
+    chain: Chain,
+    address: Address,
+) -> Result<DiscoveryReport> {
+    let proxy_code = rpc.get_code(address, None).await?;
+    if proxy_code.is_empty() {
+        bail!("{:?} has no code on {:?}", address, chain);
+    }
+
+    let proxy = Probe {
+        rpc,
+        address,
+        code: proxy_code,
+    };
+
+    let implementation = find_implementation(&proxy).await;
+
+    // selectors live in the implementation, calls still go through the proxy
+    let code = match implementation {
+        Some(i) => rpc.get_code(i, None).await?,
+        None => proxy.code.clone(),
+    };
+
+    let probe = Probe { rpc, address, code };
+
+    let (kind, compatible_handler_types) = classify(&probe);
+    // a generic handler reads any chain, prefer it over a type bound to another one
+    let handler_type = compatible_handler_types
+        .iter()
+        .find(|t| Chain::for_handler(**t) == chain || **t == DaoHandlerType::GenericChain)
+        .or(compatible_handler_types.first())
+        .copied();
+
+    let supports_igovernor = probe.supports_interface(igovernor_interface_id()).await;
+    let counting_mode = probe.call_string("COUNTING_MODE()").await;
+    let clock_mode = match probe.has("clock()") {
+        true => probe.call_string("CLOCK_MODE()").await,
+        false => None,
+    };
+
+    let mut token = None;
+    for getter in TOKEN_GETTERS {
+        if probe.has(getter) {
+            token = probe.call_address(getter).await;
+            if token.is_some() {
+                break;
+            }
+        }
+    }
+
+    let timelock = probe.call_address("timelock()").await;
+    let name = probe.call_string("name()").await;
+
+    let deployment_block = match find_deployment_block(rpc, address).await {
+        Ok(b) => Some(b),
+        Err(e) => {
+            event!(
+                Level::WARN,
+                err = e.to_string(),
+                "deployment block not found"
+            );
+            None
+        }
+    };
+
+    let mut warnings = vec![];
+
+    if let Some(t) = handler_type {
+        if t != DaoHandlerType::GenericChain && Chain::for_handler(t) != chain {
+            warnings.push(format!(
+                "{} handlers are read from {:?}, not {:?}",
+                t.to_string(),
+                Chain::for_handler(t),
+                chain
+            ));
+        }
+    }
+
+    if kind == GovernorKind::OzGovernor && !supports_igovernor {
+        warnings.push("governor does not report IGovernor over ERC-165".to_string());
+    }
+
+    if clock_mode
+        .as_ref()
+        .map_or(false, |m| m.contains("mode=timestamp"))
+    {
+        warnings.push("governor uses timestamps for proposal snapshots and deadlines".to_string());
+    }
+
+    if handler_type == Some(DaoHandlerType::MakerPoll) {
+        warnings.push("MAKER_POLL also needs address_vote for the vote handler".to_string());
+    }
+
+    let stakingproxy = match kind {
+        GovernorKind::ZeroExTreasury => probe.call_address("stakingProxy()").await,
+        _ => None,
+    };
+
+    let handler_config = handler_type.map(|t| {
+        json!({
+            "type": t,
+            "decoder": decoder_for(t, chain, address, stakingproxy),
+            "chainindex": deployment_block.unwrap_or(0),
+        })
+    });
+
+    Ok(DiscoveryReport {
+        chain,
+        address: format!("{:?}", address),
+        implementation: implementation.map(|i| format!("{:?}", i)),
+        kind,
+        handler_type,
+        compatible_handler_types,
+        name,
+        supports_igovernor,
+        counting_mode,
+        clock_mode,
+        token: token.map(|t| format!("{:?}", t)),
+        timelock: timelock.map(|t| format!("{:?}", t)),
+        deployment_block,
+        warnings,
+        handler_config,
+    })
+}
+
+fn classify(probe: &Probe) -> (GovernorKind, Vec<DaoHandlerType>) {
+    if probe.has_all(&["getGovernanceStrategy()", "getProposalById(uint256)"]) {
+        return match probe.has("hasRole(bytes32,address)") {
+            true => (
+                GovernorKind::AaveGovernanceV2,
+                vec![DaoHandlerType::DydxChain],
+            ),
+            false => (
+                GovernorKind::AaveGovernanceV2,
+                vec![DaoHandlerType::AaveChain],
+            ),
+        };
+    }
+
+    if probe.has_all(&["hat()", "GOV()", "IOU()"]) {
+        return (
+            GovernorKind::MakerChief,
+            vec![DaoHandlerType::MakerExecutive],
+        );
+    }
+
+    if probe.has_all(&["npoll()", "withdrawPoll(uint256)"]) {
+        return match probe.has("DOMAIN_SEPARATOR()") {
+            true => (
+                GovernorKind::MakerPollingEmitter,
+                vec![DaoHandlerType::MakerPollArbitrum],
+            ),
+            false => (
+                GovernorKind::MakerPollingEmitter,
+                vec![DaoHandlerType::MakerPoll],
+            ),
+        };
+    }
+
+    if probe.has_all(&["stakingProxy()", "quorumThreshold()"]) {
+        return (
+            GovernorKind::ZeroExTreasury,
+            vec![DaoHandlerType::ZeroxProtocolChain],
+        );
+    }
+
+    if probe.has_all(&[
+        "supportThreshold()",
+        "minParticipation()",
+        "totalVotingPower(uint256)",
+    ]) {
+        return (
+            GovernorKind::AragonTokenVoting,
+            vec![DaoHandlerType::AragonTokenVoting],
+        );
+    }
+
+    if probe.has_all(&["multisigSettings()", "approve(uint256,bool)"]) {
+        return (
+            GovernorKind::AragonMultisig,
+            vec![DaoHandlerType::AragonMultisig],
+        );
+    }
+
+    if probe.has_all(&["getThreshold()", "getOwners()", "nonce()"]) {
+        return (GovernorKind::Safe, vec![DaoHandlerType::SafeMultisig]);
+    }
+
+    if probe.has("COUNTING_MODE()") {
+        if probe.has_all(&["manager()", "cancelWithModule(address,bytes,bytes32)"]) {
+            return (
+                GovernorKind::OzGovernor,
+                vec![DaoHandlerType::OptimismChain],
+            );
+        }
+
+        if probe.has("EXCLUDE_ADDRESS()") {
+            return (
+                GovernorKind::OzGovernor,
+                vec![
+                    DaoHandlerType::ArbitrumCoreChain,
+                    DaoHandlerType::ArbitrumTreasuryChain,
+                ],
+            );
+        }
+
+        return (
+            GovernorKind::OzGovernor,
+            vec![
+                DaoHandlerType::EnsChain,
+                DaoHandlerType::HopChain,
+                DaoHandlerType::GenericChain,
+            ],
+        );
+    }
+
+    if probe.has_all(&["vetoer()", "objectionPeriodDurationInBlocks()"]) {
+        return (
+            GovernorKind::NounsGovernor,
+            vec![DaoHandlerType::NounsChain],
+        );
+    }
+
+    if probe.has_all(&["initialProposalId()", "proposalCount()"]) {
+        return match probe.has("optimisticQuorumVotes()") {
+            true => (
+                GovernorKind::GovernorBravo,
+                vec![DaoHandlerType::InterestProtocolChain],
+            ),
+            false => (
+                GovernorKind::GovernorBravo,
+                vec![DaoHandlerType::CompoundChain, DaoHandlerType::UniswapChain],
+            ),
+        };
+    }
+
+    if probe.has_all(&["proposalCount()", "quorumVotes()", "proposals(uint256)"]) {
+        return (
+            GovernorKind::GovernorAlpha,
+            vec![DaoHandlerType::GitcoinChain],
+        );
+    }
+
+    (GovernorKind::Unknown, vec![])
+}
+
+fn decoder_for(
+    handler_type: DaoHandlerType,
+    chain: Chain,
+    address: Address,
+    stakingproxy: Option<Address>,
+) -> Value {
+    let address = format!("{:?}", address);
+
+    match handler_type {
+        DaoHandlerType::MakerPoll => json!({
+            "address_create": address,
+            "address_vote": "",
+            "proposalUrl": "",
+            "governancePortal": "",
+        }),
+        DaoHandlerType::MakerPollArbitrum => json!({
+            "address_vote": address,
+            "governancePortal": "",
+        }),
+        DaoHandlerType::SafeMultisig => json!({
+            "address": address,
+            "transactionService": null,
+            "proposalUrl": "",
+            "governancePortal": "",
+        }),
+        DaoHandlerType::GenericChain => json!({
+            "address": address,
+            "abi": "",
+            "chain": chain.name(),
+            "proposalUrl": "",
+            "governancePortal": "",
+        }),
+        DaoHandlerType::ZeroxProtocolChain => json!({
+            "address": address,
+            "stakingProxy": stakingproxy.map(|s| format!("{:?}", s)).unwrap_or_default(),
+            "proposalUrl": "",
+            "governancePortal": "",
+        }),
+        _ => json!({
+            "address": address,
+            "proposalUrl": "",
+            "governancePortal": "",
+        }),
+    }
+}
+
+async fn find_implementation(proxy: &Probe<'_>) -> Option<Address> {
+    let slot = EIP1967_IMPLEMENTATION_SLOT.parse::<H256>().ok()?;
+
+    if let Ok(value) = proxy.rpc.get_storage_at(proxy.address, slot, None).await {
+        let implementation = Address::from_slice(&value.as_bytes()[12..]);
+        if !implementation.is_zero() {
+            return Some(implementation);
+        }
+    }
+
+    // compound style delegators expose it as a getter
+    if proxy.has("implementation()") {
+        return proxy.call_address("implementation()").await;
+    }
+
+    // safe proxies answer masterCopy() from their fallback
+    match proxy.has("masterCopy()") {
+        true => proxy.call_address("masterCopy()").await,
+        false => None,
+    }
+}
+
+// binary search for the first block with code at the address,
+// needs an archive node
+async fn find_deployment_block(rpc: &Arc<Provider<Http>>, address: Address) -> Result<i64> {
+    let mut high = rpc.get_block_number().await?.as_u64();
+    let mut low = 0;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        let code = rpc
+            .get_code(
+                address,
+                Some(BlockId::Number(BlockNumber::Number(mid.into()))),
+            )
+            .await?;
+
+        if code.is_empty() {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    Ok(low as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::utils::hex;
+
+    use super::igovernor_interface_id;
+
+    #[test]
+    fn igovernor_interface() {
+        assert_eq!(hex::encode(igovernor_interface_id()), "bf26d897");
+    }
+}
@@ -0,0 +1,178 @@
+use anyhow::Result;
+use ethers::{
+    providers::{Http, Middleware, Provider, ProviderError},
+    types::Address,
+};
+use metrics::counter;
+use prisma_client_rust::{
+    chrono::{DateTime, Duration, FixedOffset, Utc},
+    Direction,
+};
+use tracing::{event, instrument, Level};
+
+use crate::{
+    prisma::{voter, PrismaClient},
+    Context,
+};
+
+/// Resolved names, and the lack of one, are trusted for this long before being looked up again.
+pub const ENS_TTL_HOURS: i64 = 24;
+
+/// A failed lookup is tried again after this long. Reverting resolvers look like transport
+/// errors, they would otherwise come first in every batch.
+const ENS_RETRY_HOURS: i64 = 1;
+
+const BATCH_SIZE: i64 = 50;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnsProfile {
+    pub name: Option<String>,
+    pub avatar: Option<String>,
+}
+
+pub fn is_stale(updated: Option<DateTime<FixedOffset>>, now: DateTime<Utc>) -> bool {
+    match updated {
+        Some(t) => now - t.with_timezone(&Utc) >= Duration::hours(ENS_TTL_HOURS),
+        None => true,
+    }
+}
+
+/// Primary ENS name and avatar of an address on mainnet.
+///
+/// `lookup_address` resolves the reverse record and forward-resolves the name again, a name
+/// is only returned when it points back at the same address.
+pub async fn lookup(rpc: &Provider<Http>, address: Address) -> Result<EnsProfile> {
+    let name = match rpc.lookup_address(address).await {
+        Ok(name) if !name.is_empty() => name,
+        Ok(_) => return Ok(EnsProfile::default()),
+        // transport errors are retried after `ENS_RETRY_HOURS`, anything else means no name
+        Err(e @ ProviderError::JsonRpcClientError(_)) | Err(e @ ProviderError::HTTPError(_)) => {
+            return Err(e.into())
+        }
+        Err(_) => return Ok(EnsProfile::default()),
+    };
+
+    let avatar = rpc
+        .resolve_avatar(&name)
+        .await
+        .ok()
+        .map(|url| url.to_string());
+
+    Ok(EnsProfile {
+        name: Some(name),
+        avatar,
+    })
+}
+
+#[instrument(skip(db, rpc))]
+pub async fn refresh_voter(
+    db: &PrismaClient,
+    rpc: &Provider<Http>,
+    address: &str,
+) -> Result<voter::Data> {
+    let profile = match address.parse::<Address>() {
+        Ok(a) => lookup(rpc, a).await?,
+        Err(_) => EnsProfile::default(),
+    };
+
+    let updated = db
+        .voter()
+        .update(
+            voter::address::equals(address.to_string()),
+            vec![
+                voter::ensname::set(profile.name),
+                voter::ensavatar::set(profile.avatar),
+                voter::ensupdatedat::set(Some(Utc::now().into())),
+            ],
+        )
+        .exec()
+        .await?;
+
+    Ok(updated)
+}
+
+/// Voters that were never resolved go first, then the ones with the oldest lookups.
+pub async fn refresh_stale_voters(ctx: &Context) -> Result<usize> {
+    let cutoff = Utc::now() - Duration::hours(ENS_TTL_HOURS);
+
+    let voters = ctx
+        .db
+        .voter()
+        .find_many(vec![voter::or(vec![
+            voter::ensupdatedat::equals(None),
+            voter::ensupdatedat::lt(cutoff.into()),
+        ])])
+        .order_by(voter::ensupdatedat::order(Direction::Asc))
+        .take(BATCH_SIZE)
+        .exec()
+        .await?;
+
+    let mut refreshed = 0;
+
+    for voter in voters {
+        match refresh_voter(&ctx.db, &ctx.eth_rpc, &voter.address).await {
+            Ok(_) => refreshed += 1,
+            Err(e) => {
+                counter!("detective_ens_errors", 1);
+                event!(
+                    Level::WARN,
+                    voter = voter.address,
+                    err = e.to_string(),
+                    "ens lookup error"
+                );
+
+                // backdated so the voter is stale again in `ENS_RETRY_HOURS`
+                let retry_at =
+                    Utc::now() - Duration::hours(ENS_TTL_HOURS) + Duration::hours(ENS_RETRY_HOURS);
+
+                ctx.db
+                    .voter()
+                    .update(
+                        voter::address::equals(voter.address.clone()),
+                        vec![voter::ensupdatedat::set(Some(retry_at.into()))],
+                    )
+                    .exec()
+                    .await?;
+            }
+        }
+    }
+
+    Ok(refreshed)
+}
+
+pub async fn run_schedule(ctx: Context) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+
+    loop {
+        interval.tick().await;
+
+        match refresh_stale_voters(&ctx).await {
+            Ok(0) => {}
+            Ok(count) => event!(Level::INFO, count = count, "ens names refreshed"),
+            Err(e) => event!(Level::WARN, err = e.to_string(), "ens schedule error"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prisma_client_rust::chrono::{Duration, FixedOffset, Utc};
+
+    use super::{is_stale, ENS_TTL_HOURS};
+
+    #[test]
+    fn expires_after_ttl() {
+        let now = Utc::now();
+        let utc = FixedOffset::east_opt(0).unwrap();
+
+        assert!(is_stale(None, now));
+        assert!(!is_stale(
+            Some((now - Duration::hours(ENS_TTL_HOURS - 1)).with_timezone(&utc)),
+            now
+        ));
+        assert!(is_stale(
+            Some((now - Duration::hours(ENS_TTL_HOURS)).with_timezone(&utc)),
+            now
+        ));
+    }
+}
@@ -0,0 +1,427 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+
+use anyhow::Result;
+use prisma_client_rust::chrono::{Duration, Utc};
+use regex::Regex;
+use serde::Serialize;
+use tracing::{event, instrument, Level};
+
+use crate::{
+    prisma::{chainlog, proposal, proposalfamily, DaoHandlerType, PrismaClient},
+    proposal_with_dao,
+};
+
+// how long before the on-chain proposal its snapshot vote may have been created
+const MAX_GAP_DAYS: i64 = 120;
+const LOOKBACK_DAYS: i64 = 180;
+const TITLE_THRESHOLD: f64 = 0.75;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub enum FamilyMatch {
+    ExplicitId,
+    Link,
+    Title,
+}
+
+impl FamilyMatch {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FamilyMatch::ExplicitId => "explicit_id",
+            FamilyMatch::Link => "link",
+            FamilyMatch::Title => "title",
+        }
+    }
+}
+
+/// What the matcher knows about a proposal.
+#[derive(Debug, Clone, Default)]
+pub struct Candidate {
+    pub external_id: String,
+    pub title: String,
+    pub text: String,
+    pub links: Vec<String>,
+    pub created: i64,
+}
+
+/// Lowercased title without stage tags like `[ARFC]`, `Temp Check:` or `AIP-42:`.
+pub fn normalize_title(title: &str) -> String {
+    static BRACKETS: OnceLock<Regex> = OnceLock::new();
+    static PREFIX: OnceLock<Regex> = OnceLock::new();
+
+    let brackets = BRACKETS.get_or_init(|| Regex::new(r"\[[^\]]*\]|\([^)]*\)").unwrap());
+    let prefix = PREFIX.get_or_init(|| {
+        Regex::new(
+            r"^\s*((temp(erature)? check|arfc|arc|aip|ep|eip|snapshot|onchain|on-chain|proposal|vote)[\s\-#]*[\d.]*\s*[:\-|]\s*)+",
+        )
+        .unwrap()
+    });
+
+    let lower = title.to_lowercase();
+    let stripped = brackets.replace_all(&lower, " ");
+    let stripped = prefix.replace(&stripped, "");
+
+    stripped
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Sørensen–Dice coefficient over character bigrams, 1.0 for equal titles.
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let bigrams = |s: &str| -> HashMap<(char, char), usize> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut counts = HashMap::new();
+        for w in chars.windows(2) {
+            *counts.entry((w[0], w[1])).or_insert(0) += 1;
+        }
+        counts
+    };
+
+    let (a, b) = (bigrams(a), bigrams(b));
+    let total: usize = a.values().sum::<usize>() + b.values().sum::<usize>();
+
+    if total == 0 {
+        return 0.0;
+    }
+
+    let shared: usize = a
+        .iter()
+        .map(|(k, n)| (*n).min(*b.get(k).unwrap_or(&0)))
+        .sum();
+
+    2.0 * shared as f64 / total as f64
+}
+
+/// Links in a proposal description.
+pub fn extract_links(text: &str) -> Vec<String> {
+    static URL: OnceLock<Regex> = OnceLock::new();
+
+    let url = URL.get_or_init(|| Regex::new(r#"https?://[^\s)\]>"'<]+"#).unwrap());
+
+    url.find_iter(text)
+        .map(|m| normalize_link(m.as_str()))
+        .collect()
+}
+
+/// Comparable form of a link, discourse topics lose their post number.
+pub fn normalize_link(link: &str) -> String {
+    static DISCOURSE: OnceLock<Regex> = OnceLock::new();
+
+    let discourse = DISCOURSE.get_or_init(|| Regex::new(r"^(.*/t/[^/]+/\d+)(/\d+)?$").unwrap());
+
+    let lower = link.trim().to_lowercase();
+    let bare = lower
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.")
+        .trim_end_matches(|c| c == '/' || c == '.' || c == ',');
+
+    match discourse.captures(bare) {
+        Some(c) => c[1].to_string(),
+        None => bare.to_string(),
+    }
+}
+
+/// The snapshot vote an on-chain proposal continues, strongest evidence first.
+pub fn best_match(chain: &Candidate, snapshots: &[Candidate]) -> Option<(usize, FamilyMatch)> {
+    let eligible: Vec<(usize, &Candidate)> = snapshots
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| {
+            s.created <= chain.created
+                && chain.created - s.created <= Duration::days(MAX_GAP_DAYS).num_seconds()
+        })
+        .collect();
+
+    let text = chain.text.to_lowercase();
+
+    if let Some((i, _)) = eligible
+        .iter()
+        .find(|(_, s)| s.external_id.len() >= 10 && text.contains(&s.external_id.to_lowercase()))
+    {
+        return Some((*i, FamilyMatch::ExplicitId));
+    }
+
+    if let Some((i, _)) = eligible
+        .iter()
+        .find(|(_, s)| s.links.iter().any(|l| chain.links.contains(l)))
+    {
+        return Some((*i, FamilyMatch::Link));
+    }
+
+    let title = normalize_title(&chain.title);
+
+    eligible
+        .iter()
+        .map(|(i, s)| (*i, title_similarity(&title, &normalize_title(&s.title))))
+        .filter(|(_, score)| *score >= TITLE_THRESHOLD)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| (i, FamilyMatch::Title))
+}
+
+/// Links the recent on-chain proposals of a dao to their snapshot votes.
+#[instrument(skip(db))]
+pub async fn link_dao(db: &Arc<PrismaClient>, dao_id: &str) -> Result<usize> {
+    let proposals = db
+        .proposal()
+        .find_many(vec![
+            proposal::daoid::equals(dao_id.to_string()),
+            proposal::timecreated::gte((Utc::now() - Duration::days(LOOKBACK_DAYS)).into()),
+        ])
+        .include(proposal_with_dao::include())
+        .exec()
+        .await?;
+
+    let (snapshots, chains): (Vec<proposal_with_dao::Data>, Vec<proposal_with_dao::Data>) =
+        proposals
+            .into_iter()
+            .partition(|p| p.daohandler.r#type == DaoHandlerType::Snapshot);
+
+    let unlinked: Vec<&proposal_with_dao::Data> =
+        chains.iter().filter(|p| p.familyid.is_none()).collect();
+
+    if snapshots.is_empty() || unlinked.is_empty() {
+        return Ok(0);
+    }
+
+    // descriptions are only kept in the log store
+    let descriptions: HashMap<(String, String), String> = db
+        .chainlog()
+        .find_many(vec![
+            chainlog::daohandlerid::in_vec(
+                unlinked.iter().map(|p| p.daohandlerid.clone()).collect(),
+            ),
+            chainlog::event::equals("ProposalCreated".to_string()),
+        ])
+        .exec()
+        .await?
+        .into_iter()
+        .filter_map(|l| {
+            let id = l
+                .args
+                .get("id")
+                .or(l.args.get("proposalId"))?
+                .as_str()?
+                .to_string();
+            let description = l.args.get("description")?.as_str()?.to_string();
+            Some(((l.daohandlerid, id), description))
+        })
+        .collect();
+
+    let snapshot_candidates: Vec<Candidate> = snapshots
+        .iter()
+        .map(|s| Candidate {
+            external_id: s.externalid.clone(),
+            title: s.name.clone(),
+            text: String::new(),
+            links: [Some(s.url.clone()), s.discussion.clone()]
+                .into_iter()
+                .flatten()
+                .map(|l| normalize_link(&l))
+                .collect(),
+            created: s.timecreated.timestamp(),
+        })
+        .collect();
+
+    let mut linked = 0;
+
+    for chain in unlinked {
+        let text = descriptions
+            .get(&(chain.daohandlerid.clone(), chain.externalid.clone()))
+            .cloned()
+            .unwrap_or_default();
+
+        let candidate = Candidate {
+            external_id: chain.externalid.clone(),
+            title: chain.name.clone(),
+            links: extract_links(&text),
+            text,
+            created: chain.timecreated.timestamp(),
+        };
+
+        let (index, matched) = match best_match(&candidate, &snapshot_candidates) {
+            Some(m) => m,
+            None => continue,
+        };
+
+        let snapshot = &snapshots[index];
+
+        let family_id = match &snapshot.familyid {
+            Some(id) => id.clone(),
+            None => {
+                let family = db
+                    .proposalfamily()
+                    .create_unchecked(dao_id.to_string(), chain.name.clone(), vec![])
+                    .exec()
+                    .await?;
+
+                db.proposal()
+                    .update(
+                        proposal::id::equals(snapshot.id.clone()),
+                        vec![
+                            proposal::familyid::set(Some(family.id.clone())),
+                            proposal::familymatch::set(Some(matched.as_str().to_string())),
+                        ],
+                    )
+                    .exec()
+                    .await?;
+
+                family.id
+            }
+        };
+
+        db.proposal()
+            .update(
+                proposal::id::equals(chain.id.clone()),
+                vec![
+                    proposal::familyid::set(Some(family_id.clone())),
+                    proposal::familymatch::set(Some(matched.as_str().to_string())),
+                ],
+            )
+            .exec()
+            .await?;
+
+        // the family is named after its latest stage
+        db.proposalfamily()
+            .update(
+                proposalfamily::id::equals(family_id.clone()),
+                vec![proposalfamily::name::set(chain.name.clone())],
+            )
+            .exec()
+            .await?;
+
+        event!(
+            Level::INFO,
+            family_id = family_id,
+            snapshot_proposal_id = snapshot.id,
+            chain_proposal_id = chain.id,
+            matched = matched.as_str(),
+            "linked proposals"
+        );
+
+        linked += 1;
+    }
+
+    Ok(linked)
+}
+
+/// Links the proposals of a dao right after new ones are inserted, so the notifications
+/// of a new stage already know its family. A failed link is picked up by the next insert.
+pub async fn link_inserted(db: &Arc<PrismaClient>, dao_id: &str) {
+    if let Err(e) = link_dao(db, dao_id).await {
+        event!(
+            Level::WARN,
+            dao_id = dao_id,
+            err = e.to_string(),
+            "family link error"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_match, normalize_link, normalize_title, Candidate, FamilyMatch};
+
+    fn snapshot(id: &str, title: &str, links: &[&str], created: i64) -> Candidate {
+        Candidate {
+            external_id: id.into(),
+            title: title.into(),
+            links: links.iter().map(|l| normalize_link(l)).collect(),
+            created,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn strips_stage_tags() {
+        assert_eq!(
+            normalize_title("[ARFC] Add rETH to Aave V3 Ethereum"),
+            "add reth to aave v3 ethereum"
+        );
+        assert_eq!(
+            normalize_title("Temp Check: Add rETH to Aave V3 Ethereum"),
+            "add reth to aave v3 ethereum"
+        );
+        assert_eq!(normalize_title("EP 4.2: Fund the DAO"), "fund the dao");
+    }
+
+    #[test]
+    fn normalizes_discourse_posts() {
+        assert_eq!(
+            normalize_link("https://governance.aave.com/t/arfc-add-reth/12345/7"),
+            "governance.aave.com/t/arfc-add-reth/12345"
+        );
+        assert_eq!(
+            normalize_link("https://www.tally.xyz/gov/ens/proposal/42/"),
+            "tally.xyz/gov/ens/proposal/42"
+        );
+    }
+
+    #[test]
+    fn prefers_explicit_ids_over_titles() {
+        let id = "0x8b1d3c7d9a6f1c2f5b4e8d0a3c9e7f6b5a4d3c2b1a0f9e8d7c6b5a4f3e2d1c0b";
+        let snapshots = vec![
+            snapshot("0xaaaa00000000", "Add rETH to Aave V3", &[], 100),
+            snapshot(id, "Something else", &[], 200),
+        ];
+
+        let chain = Candidate {
+            title: "Add rETH to Aave V3".into(),
+            text: format!("Snapshot: https://snapshot.org/#/aave.eth/proposal/{}", id),
+            created: 300,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            best_match(&chain, &snapshots),
+            Some((1, FamilyMatch::ExplicitId))
+        );
+    }
+
+    #[test]
+    fn matches_shared_discussion_and_titles() {
+        let snapshots = vec![snapshot(
+            "0xbbbb00000000",
+            "[TEMP CHECK] Onboard cbETH",
+            &["https://governance.aave.com/t/onboard-cbeth/555"],
+            100,
+        )];
+
+        let by_link = Candidate {
+            title: "Unrelated title".into(),
+            links: vec![normalize_link(
+                "https://governance.aave.com/t/onboard-cbeth/555/12",
+            )],
+            created: 200,
+            ..Default::default()
+        };
+        assert_eq!(
+            best_match(&by_link, &snapshots),
+            Some((0, FamilyMatch::Link))
+        );
+
+        let by_title = Candidate {
+            title: "AIP-301: Onboard cbETH".into(),
+            created: 200,
+            ..Default::default()
+        };
+        assert_eq!(
+            best_match(&by_title, &snapshots),
+            Some((0, FamilyMatch::Title))
+        );
+
+        // a snapshot created after the on-chain proposal is not its predecessor
+        let earlier = Candidate {
+            created: 50,
+            ..by_title
+        };
+        assert_eq!(best_match(&earlier, &snapshots), None);
+    }
+}
@@ -1,34 +1,44 @@
 pub mod proposals {
     pub mod aave;
+    pub mod aragon_multisig;
+    pub mod aragon_token_voting;
     pub mod arbitrum_core;
     pub mod arbitrum_treasury;
     pub mod compound;
     pub mod dydx;
     pub mod ens;
+    pub mod generic;
     pub mod gitcoin;
     pub mod hop;
     pub mod interest_protocol;
     pub mod maker_executive;
     pub mod maker_poll;
+    pub mod nouns;
     pub mod optimism;
+    pub mod safe;
     pub mod uniswap;
     pub mod zeroxtreasury;
 }
 
 pub mod votes {
     pub mod aave;
+    pub mod aragon_multisig;
+    pub mod aragon_token_voting;
     pub mod arbitrum_core;
     pub mod arbitrum_treasury;
     pub mod compound;
     pub mod dydx;
     pub mod ens;
+    pub mod generic;
     pub mod gitcoin;
     pub mod hop;
     pub mod interest_protocol;
     pub mod maker_executive;
     pub mod maker_poll;
     pub mod maker_poll_arbitrum;
+    pub mod nouns;
     pub mod optimism;
+    pub mod safe;
     pub mod uniswap;
     pub mod zeroxtreasury;
 }
@@ -1,23 +1,17 @@
-use std::{str, sync::Arc, time::Duration};
+use std::{str, sync::Arc};
 
 use anyhow::Result;
 use ethers::{
     prelude::LogMeta,
     providers::{Http, Middleware, Provider},
-    types::{Address, Filter, U256},
-    utils::hex,
+    types::{Address, Log, U256},
 };
 use futures::stream::{FuturesUnordered, StreamExt};
 use prisma_client_rust::{
     bigdecimal::ToPrimitive,
     chrono::{DateTime, NaiveDateTime, Utc},
 };
-use regex::Regex;
-use reqwest::{Client, StatusCode};
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use serde::Deserialize;
-use serde_json::Value as JsonValue;
 use tracing::{debug_span, event, instrument, Instrument};
 
 use crate::{
@@ -27,9 +21,10 @@ use crate::{
         aavestrategy,
     },
     daohandler_with_dao,
+    logstore::typed_events,
     prisma::{daohandler, PrismaClient, ProposalState},
     router::chain_proposals::ChainProposal,
-    utils::etherscan::estimate_timestamp,
+    utils::{content::ipfs_title, etherscan::estimate_timestamp},
     Ctx,
 };
 
@@ -41,10 +36,10 @@ struct Decoder {
 }
 
 pub async fn aave_proposals(
+    db: &Arc<PrismaClient>,
     rpc: &Arc<Provider<Http>>,
     dao_handler: &daohandler_with_dao::Data,
-    from_block: &i64,
-    to_block: &i64,
+    logs: &[Log],
 ) -> Result<Vec<ChainProposal>> {
     let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
 
@@ -52,18 +47,21 @@ pub async fn aave_proposals(
 
     let gov_contract = aavegov::aavegov::aavegov::new(address, rpc.clone());
 
-    let events = gov_contract
-        .proposal_created_filter()
-        .from_block(*from_block)
-        .to_block(*to_block);
-
-    let proposals = events.query_with_meta().await?;
+    let proposals = typed_events::<ProposalCreatedFilter>(logs, address);
 
     let mut futures = FuturesUnordered::new();
 
     for p in proposals.iter() {
         futures.push(async {
-            data_for_proposal(p.clone(), rpc, &decoder, dao_handler, gov_contract.clone()).await
+            data_for_proposal(
+                p.clone(),
+                db,
+                rpc,
+                &decoder,
+                dao_handler,
+                gov_contract.clone(),
+            )
+            .await
         });
     }
 
@@ -77,6 +75,7 @@ pub async fn aave_proposals(
 
 async fn data_for_proposal(
     p: (aavegov::aavegov::ProposalCreatedFilter, LogMeta),
+    db: &Arc<PrismaClient>,
     rpc: &Arc<Provider<Http>>,
     decoder: &Decoder,
     dao_handler: &daohandler_with_dao::Data,
@@ -149,7 +148,7 @@ async fn data_for_proposal(
 
     let hash: Vec<u8> = log.ipfs_hash.into();
 
-    let mut title = get_title(hex::encode(hash)).await?;
+    let mut title = ipfs_title(Some(db), &hash).await;
 
     if title.starts_with("# ") {
         title = title.split_off(2);
@@ -186,112 +185,10 @@ async fn data_for_proposal(
         scores: scores.into(),
         scores_total: scores_total.into(),
         quorum: quorum.as_u128().into(),
+        support_threshold: None,
         url: proposal_url,
         state,
     };
 
     Ok(proposal)
 }
-
-async fn get_title(hexhash: String) -> Result<String> {
-    let mut retries = 0;
-    let mut current_gateway = 0;
-
-    let gateways = [
-        "https://senate.infura-ipfs.io/ipfs/",
-        "https://cloudflare-ipfs.com/ipfs/",
-        "https://gateway.pinata.cloud/ipfs/",
-    ];
-
-    let retry_policy = ExponentialBackoff::builder().build_with_max_retries(5);
-    let http_client = ClientBuilder::new(reqwest::Client::new())
-        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-        .build();
-
-    loop {
-        let response = http_client
-            .get(format!("{}f01701220{}", gateways[current_gateway], hexhash))
-            .timeout(Duration::from_secs(5))
-            .send()
-            .await;
-
-        match response {
-            Ok(res) if res.status() == StatusCode::OK => {
-                let text = res.text().await?;
-
-                // Check if the text is JSON
-                if let Ok(json) = serde_json::from_str::<JsonValue>(&text) {
-                    return Ok(json["title"].as_str().unwrap_or("Unknown").to_string());
-                }
-
-                let re = Regex::new(r"title:\s*(.*?)\n")?;
-                if let Some(captures) = re.captures(&text) {
-                    if let Some(matched) = captures.get(1) {
-                        return Ok(matched.as_str().trim().to_string());
-                    }
-                }
-
-                return Ok("Unknown".to_string());
-            }
-            _ if retries % 3 == 0 => {
-                if current_gateway < gateways.len() - 2 {
-                    current_gateway += 1;
-                } else {
-                    current_gateway = 0;
-                }
-            }
-            _ if retries < 12 => {
-                retries += 1;
-                let backoff_duration = Duration::from_millis(2u64.pow(retries as u32));
-                tokio::time::sleep(backoff_duration).await;
-            }
-            _ => return Ok("Unknown".to_string()),
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use std::sync::Arc;
-
-    use reqwest_middleware::ClientBuilder;
-    use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
-
-    use crate::handlers::proposals::aave::get_title;
-
-    #[tokio::test]
-    async fn get_markdown_title() {
-        let result =
-            get_title("f76d79693a81a1c0acd23c6ee151369752142b0d832daeaef9a4dd9f8c4bc7ce".into())
-                .await
-                .unwrap();
-        assert_eq!(result, "Polygon Supply Cap Update");
-
-        let result =
-            get_title("12f2d9c91e4e23ae4009ab9ef5862ee0ae79498937b66252213221f04a5d5b32".into())
-                .await
-                .unwrap();
-        assert_eq!(result, "Add 1INCH to Aave v2 market");
-
-        let result =
-            get_title("e7e93497d3847536f07fe8dba53485cf68a275c7b07ca38b53d2cc2d43fab3b0".into())
-                .await
-                .unwrap();
-        assert_eq!(result, "Unknown");
-
-        let result = get_title("deadbeef".into()).await.unwrap();
-        assert_eq!(result, "Unknown");
-    }
-
-    #[tokio::test]
-    async fn get_json_title() {
-        let result =
-            get_title("8d4f6f42043d8db567d5e733762bb84a6f507997a779a66b2d17fdf9de403c13".into())
-                .await
-                .unwrap();
-        assert_eq!(result, "Add rETH to Arbitrum Aave v3");
-
-        let result = get_title("deadbeef".into()).await.unwrap();
-        assert_eq!(result, "Unknown");
-    }
-}
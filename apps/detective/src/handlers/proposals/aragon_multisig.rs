@@ -0,0 +1,153 @@
+use std::{str, sync::Arc};
+
+use anyhow::Result;
+use ethers::{
+    prelude::LogMeta,
+    providers::{Http, Middleware, Provider},
+    types::{Address, Log},
+};
+use futures::stream::{FuturesUnordered, StreamExt};
+use prisma_client_rust::{
+    bigdecimal::ToPrimitive,
+    chrono::{DateTime, NaiveDateTime, Utc},
+};
+use serde::Deserialize;
+
+use crate::{
+    contracts::aragonmultisig::{self, ProposalCreatedFilter},
+    daohandler_with_dao,
+    logstore::typed_events,
+    prisma::{PrismaClient, ProposalState},
+    router::chain_proposals::ChainProposal,
+    utils::content::uri_title,
+};
+
+type AragonMultisig = aragonmultisig::aragonmultisig::aragonmultisig<Provider<Http>>;
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct Decoder {
+    address: String,
+    proposalUrl: String,
+}
+
+pub async fn aragon_multisig_proposals(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    logs: &[Log],
+) -> Result<Vec<ChainProposal>> {
+    let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
+
+    let address = decoder.address.parse::<Address>().expect("bad address");
+
+    let gov_contract = aragonmultisig::aragonmultisig::aragonmultisig::new(address, rpc.clone());
+
+    let proposals = typed_events::<ProposalCreatedFilter>(logs, address);
+
+    let mut futures = FuturesUnordered::new();
+
+    for p in proposals.iter() {
+        futures.push(async {
+            data_for_proposal(
+                p.clone(),
+                db,
+                rpc,
+                &decoder,
+                dao_handler,
+                gov_contract.clone(),
+            )
+            .await
+        });
+    }
+
+    let mut result = Vec::new();
+    while let Some(proposal) = futures.next().await {
+        result.push(proposal?);
+    }
+
+    Ok(result)
+}
+
+async fn data_for_proposal(
+    p: (ProposalCreatedFilter, LogMeta),
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    decoder: &Decoder,
+    dao_handler: &daohandler_with_dao::Data,
+    gov_contract: AragonMultisig,
+) -> Result<ChainProposal> {
+    let (log, meta): (ProposalCreatedFilter, LogMeta) = p.clone();
+
+    let created_block_number = meta.block_number.as_u64().to_i64().unwrap();
+    let created_block = rpc.get_block(meta.block_number).await?;
+    let created_block_timestamp = created_block.expect("bad block").time()?;
+
+    let (executed, approvals, parameters, _actions, _allow_failure_map) =
+        gov_contract.get_proposal(log.proposal_id).call().await?;
+
+    let voting_starts_timestamp = from_unix(parameters.start_date);
+    let voting_ends_timestamp = from_unix(parameters.end_date);
+
+    let approved = approvals >= parameters.min_approvals;
+
+    let state = if executed {
+        ProposalState::Executed
+    } else if Utc::now() < voting_starts_timestamp {
+        ProposalState::Pending
+    } else if Utc::now() < voting_ends_timestamp {
+        if approved {
+            ProposalState::Succeeded
+        } else {
+            ProposalState::Active
+        }
+    } else if approved {
+        // approved but nobody executed it before the end date
+        ProposalState::Expired
+    } else {
+        ProposalState::Defeated
+    };
+
+    let metadata = str::from_utf8(&log.metadata).unwrap_or_default();
+    let title = uri_title(Some(db), metadata).await;
+
+    let proposal_url = format!("{}{}", decoder.proposalUrl, log.proposal_id);
+
+    let proposal_external_id = log.proposal_id.to_string();
+
+    let choices = vec!["Approve"];
+
+    let scores = vec![approvals as u128];
+
+    let scores_total = approvals as u128;
+
+    let quorum = parameters.min_approvals as u128;
+
+    let proposal = ChainProposal {
+        external_id: proposal_external_id,
+        name: title,
+        dao_id: dao_handler.clone().daoid,
+        dao_handler_id: dao_handler.clone().id,
+        time_start: voting_starts_timestamp,
+        time_end: voting_ends_timestamp,
+        time_created: created_block_timestamp,
+        block_created: created_block_number,
+        choices: choices.into(),
+        scores: scores.into(),
+        scores_total: scores_total.into(),
+        quorum: quorum.into(),
+        support_threshold: None,
+        url: proposal_url,
+        state,
+    };
+
+    Ok(proposal)
+}
+
+fn from_unix(timestamp: u64) -> DateTime<Utc> {
+    DateTime::from_naive_utc_and_offset(
+        NaiveDateTime::from_timestamp_millis(timestamp.to_i64().unwrap() * 1000)
+            .expect("bad timestamp"),
+        Utc,
+    )
+}
@@ -2,7 +2,7 @@ use anyhow::Result;
 use ethers::{
     prelude::LogMeta,
     providers::{Http, Middleware, Provider},
-    types::{Address, Filter, U256},
+    types::{Address, Log, U256},
 };
 use futures::stream::{FuturesUnordered, StreamExt};
 use prisma_client_rust::{
@@ -16,6 +16,7 @@ use tracing::{debug_span, instrument, Instrument};
 use crate::{
     contracts::{arbitrumcore, arbitrumcore::arbitrumcore::ProposalCreatedFilter},
     daohandler_with_dao,
+    logstore::typed_events,
     prisma::{daohandler, PrismaClient, ProposalState},
     router::chain_proposals::ChainProposal,
     utils::etherscan::estimate_timestamp,
@@ -32,8 +33,7 @@ struct Decoder {
 pub async fn arbitrum_core_proposals(
     rpc: &Arc<Provider<Http>>,
     dao_handler: &daohandler_with_dao::Data,
-    from_block: &i64,
-    to_block: &i64,
+    logs: &[Log],
 ) -> Result<Vec<ChainProposal>> {
     let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
 
@@ -41,12 +41,7 @@ pub async fn arbitrum_core_proposals(
 
     let gov_contract = arbitrumcore::arbitrumcore::arbitrumcore::new(address, rpc.clone());
 
-    let events = gov_contract
-        .proposal_created_filter()
-        .from_block(*from_block)
-        .to_block(*to_block);
-
-    let proposals = events.query_with_meta().await?;
+    let proposals = typed_events::<ProposalCreatedFilter>(logs, address);
 
     let mut futures = FuturesUnordered::new();
 
@@ -177,6 +172,7 @@ async fn data_for_proposal(
         scores: scores.into(),
         scores_total: scores_total.into(),
         quorum: quorum.as_u128().into(),
+        support_threshold: None,
         url: proposal_url,
         state,
     };
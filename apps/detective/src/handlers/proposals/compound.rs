@@ -4,7 +4,7 @@ use anyhow::Result;
 use ethers::{
     prelude::LogMeta,
     providers::{Http, Middleware, Provider},
-    types::{Address, Filter},
+    types::{Address, Log},
 };
 use futures::stream::{FuturesUnordered, StreamExt};
 use prisma_client_rust::{
@@ -17,6 +17,7 @@ use tracing::{debug_span, event, instrument, Instrument};
 use crate::{
     contracts::{compoundgov, compoundgov::ProposalCreatedFilter},
     daohandler_with_dao,
+    logstore::typed_events,
     prisma::{daohandler, PrismaClient, ProposalState},
     router::chain_proposals::ChainProposal,
     utils::etherscan::estimate_timestamp,
@@ -33,8 +34,7 @@ struct Decoder {
 pub async fn compound_proposals(
     rpc: &Arc<Provider<Http>>,
     dao_handler: &daohandler_with_dao::Data,
-    from_block: &i64,
-    to_block: &i64,
+    logs: &[Log],
 ) -> Result<Vec<ChainProposal>> {
     let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
 
@@ -42,12 +42,7 @@ pub async fn compound_proposals(
 
     let gov_contract = compoundgov::compoundgov::compoundgov::new(address, rpc.clone());
 
-    let events = gov_contract
-        .proposal_created_filter()
-        .from_block(*from_block)
-        .to_block(*to_block);
-
-    let proposals = events.query_with_meta().await?;
+    let proposals = typed_events::<ProposalCreatedFilter>(logs, address);
 
     let mut futures = FuturesUnordered::new();
 
@@ -171,6 +166,7 @@ async fn data_for_proposal(
         scores: scores.into(),
         scores_total: scores_total.into(),
         quorum: quorum.as_u128().into(),
+        support_threshold: None,
         url: proposal_url,
         state,
     };
@@ -0,0 +1,206 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{bail, Result};
+use ethers::{
+    contract::Contract,
+    providers::{Http, Middleware, Provider},
+    types::{Address, Log, U256},
+};
+use prisma_client_rust::chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Deserialize;
+
+use crate::{
+    abis,
+    daohandler_with_dao,
+    logstore::{
+        decode_logs,
+        derive::{chain_proposal, derive, LogEntry},
+        stored_logs_between,
+    },
+    prisma::{proposal, PrismaClient, ProposalState},
+    router::chain_proposals::ChainProposal,
+};
+
+const BLOCK_SECONDS: i64 = 12;
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct Decoder {
+    address: String,
+    proposalUrl: Option<String>,
+    clock: Option<String>,
+}
+
+/// Proposals of a governor known only by its registered abi.
+///
+/// Proposals are derived from the decoded events of the range plus the stored history
+/// of the proposals still open, the same way a replay does. Where the abi has them, the
+/// OZ `state`, `proposalVotes` and `quorum` getters replace what the events can only
+/// estimate.
+pub async fn generic_proposals(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    logs: &[Log],
+    from_block: &i64,
+    to_block: &i64,
+) -> Result<Vec<ChainProposal>> {
+    let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
+
+    let names = abis::abi_names(dao_handler.r#type, &dao_handler.decoder);
+    let abi = abis::merged(&names);
+
+    if abi.events.is_empty() {
+        bail!("no registered abi in {:?}", names);
+    }
+
+    let address = decoder.address.parse::<Address>()?;
+
+    let existing: HashMap<String, proposal::Data> = db
+        .proposal()
+        .find_many(vec![proposal::daohandlerid::equals(dao_handler.id.clone())])
+        .exec()
+        .await?
+        .into_iter()
+        .map(|p| (p.externalid.clone(), p))
+        .collect();
+
+    let open: Vec<&proposal::Data> = existing
+        .values()
+        .filter(|p| {
+            matches!(
+                p.state,
+                ProposalState::Pending
+                    | ProposalState::Active
+                    | ProposalState::Succeeded
+                    | ProposalState::Queued
+            )
+        })
+        .collect();
+
+    // only the history of the proposals still open is read again, the range itself is
+    // stored by the capture that produced `logs`
+    let earliest_open = open
+        .iter()
+        .filter_map(|p| p.blockcreated)
+        .min()
+        .unwrap_or(*from_block)
+        .min(*from_block);
+
+    let mut entries: Vec<LogEntry> =
+        stored_logs_between(db, &dao_handler.id, earliest_open, *from_block)
+            .await?
+            .into_iter()
+            .map(LogEntry::from)
+            .collect();
+
+    entries.extend(
+        decode_logs(rpc, &abis::events(&names), logs.to_vec())
+            .await?
+            .into_iter()
+            .map(LogEntry::from),
+    );
+    entries.sort_by_key(|e| e.block);
+
+    let (derived_proposals, derived_votes) = derive(&entries);
+
+    let gov_contract = Contract::new(address, abi, rpc.clone());
+    let current_block = rpc.get_block_number().await?.as_u64() as i64;
+    let timestamps = decoder.clock.as_deref() == Some("timestamp");
+
+    let mut result = vec![];
+
+    for p in derived_proposals.iter().filter(|p| {
+        (p.block_created >= *from_block || open.iter().any(|o| o.externalid == p.external_id))
+            && p.block_created <= *to_block
+    }) {
+        let mut proposal = match chain_proposal(
+            dao_handler,
+            p,
+            &derived_votes,
+            existing.get(&p.external_id),
+            &["For", "Against", "Abstain"],
+            &decoder.proposalUrl.clone().unwrap_or_default(),
+        ) {
+            Some(proposal) => proposal,
+            None => continue,
+        };
+
+        proposal.time_start = timepoint(rpc, p.start_block, timestamps, current_block).await?;
+        proposal.time_end = timepoint(rpc, p.end_block, timestamps, current_block).await?;
+
+        let id = U256::from_dec_str(&p.external_id)?;
+
+        if let Ok(method) = gov_contract.method::<_, u8>("state", id) {
+            if let Ok(state) = method.call().await {
+                proposal.state = match state {
+                    0 => ProposalState::Pending,
+                    1 => ProposalState::Active,
+                    2 => ProposalState::Canceled,
+                    3 => ProposalState::Defeated,
+                    4 => ProposalState::Succeeded,
+                    5 => ProposalState::Queued,
+                    6 => ProposalState::Expired,
+                    7 => ProposalState::Executed,
+                    _ => ProposalState::Unknown,
+                };
+            }
+        }
+
+        // GovernorCountingSimple orders the tally against, for, abstain
+        if let Ok(method) = gov_contract.method::<_, (U256, U256, U256)>("proposalVotes", id) {
+            if let Ok((against, votes_for, abstain)) = method.call().await {
+                let scores = vec![votes_for.as_u128(), against.as_u128(), abstain.as_u128()];
+                proposal.scores_total = scores.iter().sum::<u128>().into();
+                proposal.scores = scores.into();
+            }
+        }
+
+        if let Some(quorum) = quorum(&gov_contract, p.start_block).await {
+            proposal.quorum = quorum.as_u128().into();
+        }
+
+        result.push(proposal);
+    }
+
+    Ok(result)
+}
+
+/// Time of a governor clock value, a block number unless the decoder says `"clock":
+/// "timestamp"`. Blocks not mined yet are estimated from the current one.
+async fn timepoint(
+    rpc: &Arc<Provider<Http>>,
+    value: i64,
+    timestamps: bool,
+    current_block: i64,
+) -> Result<DateTime<Utc>> {
+    if timestamps {
+        return Ok(Utc.timestamp_opt(value, 0).unwrap());
+    }
+
+    if value <= current_block {
+        if let Some(block) = rpc.get_block(value as u64).await? {
+            return Ok(Utc
+                .timestamp_opt(block.timestamp.as_u64() as i64, 0)
+                .unwrap());
+        }
+    }
+
+    Ok(Utc::now() + Duration::seconds((value - current_block) * BLOCK_SECONDS))
+}
+
+/// OZ `quorum(timepoint)`, Bravo `quorumVotes()`, whichever the abi has and answers.
+async fn quorum(gov_contract: &Contract<Provider<Http>>, snapshot: i64) -> Option<U256> {
+    if let Ok(method) = gov_contract.method::<_, U256>("quorum", U256::from(snapshot)) {
+        if let Ok(quorum) = method.call().await {
+            return Some(quorum);
+        }
+    }
+
+    gov_contract
+        .method::<_, U256>("quorumVotes", ())
+        .ok()?
+        .call()
+        .await
+        .ok()
+}
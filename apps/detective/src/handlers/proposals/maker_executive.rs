@@ -6,7 +6,7 @@ use ethers::{
     abi::Address,
     prelude::LogMeta,
     providers::{Http, Provider},
-    types::{H256, U256},
+    types::{Log, H256, U256},
     utils::to_checksum,
 };
 use futures::{stream::FuturesUnordered, StreamExt};
@@ -24,6 +24,7 @@ use tracing::{debug_span, instrument, Instrument};
 use crate::{
     contracts::{makerexecutive, makerexecutive::LogNoteFilter},
     daohandler_with_dao,
+    logstore::typed_events,
     prisma::{daohandler, PrismaClient, ProposalState},
     router::chain_proposals::ChainProposal,
     utils::etherscan::estimate_block,
@@ -45,8 +46,7 @@ const VOTE_SINGLE_ACTION_TOPIC: &str =
 pub async fn maker_executive_proposals(
     rpc: &Arc<Provider<Http>>,
     dao_handler: &daohandler_with_dao::Data,
-    from_block: &i64,
-    to_block: &i64,
+    logs: &[Log],
 ) -> Result<Vec<ChainProposal>> {
     let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
 
@@ -54,21 +54,21 @@ pub async fn maker_executive_proposals(
 
     let gov_contract = makerexecutive::makerexecutive::makerexecutive::new(address, rpc.clone());
 
-    let single_spell_events = gov_contract
-        .log_note_filter()
-        .topic0(vec![VOTE_SINGLE_ACTION_TOPIC.parse::<H256>()?])
-        .from_block(*from_block)
-        .to_block(*to_block);
+    let log_notes = typed_events::<LogNoteFilter>(logs, address);
+    let single_topic = VOTE_SINGLE_ACTION_TOPIC.parse::<H256>()?;
+    let multi_topic = VOTE_MULTIPLE_ACTIONS_TOPIC.parse::<H256>()?;
 
-    let single_spell_logs = single_spell_events.query_with_meta().await?;
-
-    let multi_spell_events = gov_contract
-        .log_note_filter()
-        .topic0(vec![VOTE_MULTIPLE_ACTIONS_TOPIC.parse::<H256>()?])
-        .from_block(*from_block)
-        .to_block(*to_block);
+    let single_spell_logs: Vec<(LogNoteFilter, LogMeta)> = log_notes
+        .iter()
+        .filter(|(n, _)| n.sig[..] == single_topic[..4])
+        .cloned()
+        .collect();
 
-    let multi_spell_logs = multi_spell_events.query_with_meta().await?;
+    let multi_spell_logs: Vec<(LogNoteFilter, LogMeta)> = log_notes
+        .iter()
+        .filter(|(n, _)| n.sig[..] == multi_topic[..4])
+        .cloned()
+        .collect();
 
     let single_spells = get_single_spell_addresses(single_spell_logs, gov_contract.clone()).await?;
     let multi_spells = get_multi_spell_addresses(multi_spell_logs, gov_contract.clone()).await?;
@@ -156,6 +156,7 @@ async fn proposal(
         scores: scores.parse::<f64>()?.into(),
         scores_total: scores_total.parse::<f64>()?.into(),
         quorum: 0.into(),
+        support_threshold: None,
         url: proposal_url,
         state,
     };
@@ -5,19 +5,14 @@ use chrono::Duration;
 use ethers::{
     prelude::LogMeta,
     providers::{Http, Middleware, Provider},
-    types::{Address, Filter},
+    types::{Address, Log},
 };
 use futures::stream::{FuturesUnordered, StreamExt};
 use prisma_client_rust::{
     bigdecimal::ToPrimitive,
     chrono::{DateTime, NaiveDateTime, Utc},
 };
-use regex::Regex;
-use reqwest::{
-    header::{ACCEPT, USER_AGENT},
-    Client,
-    StatusCode,
-};
+use reqwest::header::{ACCEPT, USER_AGENT};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use serde::{Deserialize, Serialize};
@@ -27,8 +22,10 @@ use tracing::{debug_span, instrument, Instrument};
 use crate::{
     contracts::{makerpollcreate, makerpollcreate::PollCreatedFilter},
     daohandler_with_dao,
+    logstore::typed_events,
     prisma::{daohandler, PrismaClient, ProposalState},
     router::chain_proposals::ChainProposal,
+    utils::content::uri_title,
     Ctx,
 };
 
@@ -40,10 +37,10 @@ struct Decoder {
 }
 
 pub async fn maker_poll_proposals(
+    db: &Arc<PrismaClient>,
     rpc: &Arc<Provider<Http>>,
     dao_handler: &daohandler_with_dao::Data,
-    from_block: &i64,
-    to_block: &i64,
+    logs: &[Log],
 ) -> Result<Vec<ChainProposal>> {
     let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
 
@@ -52,19 +49,12 @@ pub async fn maker_poll_proposals(
         .parse::<Address>()
         .expect("bad address");
 
-    let gov_contract = makerpollcreate::makerpollcreate::makerpollcreate::new(address, rpc.clone());
-
-    let events = gov_contract
-        .poll_created_filter()
-        .from_block(*from_block)
-        .to_block(*to_block);
-
-    let proposals = events.query_with_meta().await?;
+    let proposals = typed_events::<PollCreatedFilter>(logs, address);
 
     let mut futures = FuturesUnordered::new();
 
     for p in proposals.iter() {
-        futures.push(async { data_for_proposal(p.clone(), rpc, &decoder, dao_handler).await });
+        futures.push(async { data_for_proposal(p.clone(), db, rpc, &decoder, dao_handler).await });
     }
 
     let mut result = Vec::new();
@@ -77,6 +67,7 @@ pub async fn maker_poll_proposals(
 
 async fn data_for_proposal(
     p: (makerpollcreate::makerpollcreate::PollCreatedFilter, LogMeta),
+    db: &Arc<PrismaClient>,
     rpc: &Arc<Provider<Http>>,
     decoder: &Decoder,
     dao_handler: &daohandler_with_dao::Data,
@@ -107,7 +98,7 @@ async fn data_for_proposal(
 
     let proposal_external_id = log.poll_id.to_string();
 
-    let title = get_title(log.url).await?;
+    let title = uri_title(Some(db), &log.url).await;
 
     let mut choices: Vec<String> = vec![];
     let mut scores: Vec<f64> = vec![];
@@ -156,6 +147,7 @@ async fn data_for_proposal(
         scores: scores.into(),
         scores_total: scores_total.into(),
         quorum: quorum.into(),
+        support_threshold: None,
         url: proposal_url,
         state: if voting_ends_timestamp.timestamp() < Utc::now().timestamp() {
             ProposalState::Executed
@@ -225,41 +217,6 @@ async fn get_results_data(poll_id: String) -> Result<ResultsData> {
     }
 }
 
-async fn get_title(url: String) -> Result<String> {
-    let client = Client::new();
-    let mut retries = 0;
-
-    loop {
-        let response = client
-            .get(url.clone())
-            .timeout(std::time::Duration::from_secs(10))
-            .send()
-            .await;
-
-        match response {
-            Ok(res) if res.status() == StatusCode::OK => {
-                let text = match res.text().await {
-                    Ok(r) => r,
-                    Err(_) => "Unknown".to_string(),
-                };
-                let pattern = r"(?m)^title:\s*(.+)$";
-                let re = Regex::new(pattern)?;
-                let result = re
-                    .captures(text.as_str())
-                    .and_then(|cap| cap.get(1).map(|m| m.as_str().to_string()))
-                    .unwrap_or("Unknown".to_string());
-                return Ok(result);
-            }
-            _ if retries < 15 => {
-                retries += 1;
-                let backoff_duration = std::time::Duration::from_millis(2u64.pow(retries as u32));
-                tokio::time::sleep(backoff_duration).await;
-            }
-            _ => return Ok("Unknown".to_string()),
-        }
-    }
-}
-
 // #[cfg(test)]
 // mod tests {
 
@@ -0,0 +1,476 @@
+use std::{str, sync::Arc};
+
+use anyhow::Result;
+use ethers::{
+    prelude::LogMeta,
+    providers::{Http, Middleware, Provider},
+    types::{Address, Log, U256},
+};
+use futures::stream::{FuturesUnordered, StreamExt};
+use prisma_client_rust::{
+    bigdecimal::ToPrimitive,
+    chrono::{DateTime, FixedOffset, NaiveDateTime, Utc},
+    Direction,
+};
+use serde::Deserialize;
+use tracing::{event, instrument, Level};
+
+use crate::{
+    contracts::nounsgov::{
+        self,
+        ProposalCreatedFilter,
+        ProposalDescriptionUpdatedFilter,
+        ProposalTransactionsUpdatedFilter,
+        ProposalUpdatedFilter,
+    },
+    daohandler_with_dao,
+    logstore::typed_events,
+    prisma::{proposal, proposaledit, PrismaClient, ProposalEditType, ProposalState},
+    router::chain_proposals::ChainProposal,
+    utils::etherscan::estimate_timestamp,
+};
+
+type NounsGov = nounsgov::nounsgov::nounsgov<Provider<Http>>;
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct Decoder {
+    address: String,
+    proposalUrl: String,
+}
+
+#[derive(Debug, Clone)]
+struct ProposalEdit {
+    proposal_id: U256,
+    r#type: ProposalEditType,
+    description: Option<String>,
+    update_message: String,
+    meta: LogMeta,
+}
+
+pub async fn nouns_proposals(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    logs: &[Log],
+) -> Result<Vec<ChainProposal>> {
+    let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
+
+    let address = decoder.address.parse::<Address>().expect("bad address");
+
+    let gov_contract = nounsgov::nounsgov::nounsgov::new(address, rpc.clone());
+
+    let proposals = typed_events::<ProposalCreatedFilter>(logs, address);
+    let edits = proposal_edits(logs, address);
+
+    // governors before V3 have no objection period
+    let objection_period = gov_contract
+        .objection_period_duration_in_blocks()
+        .call()
+        .await
+        .unwrap_or_default();
+
+    let mut futures = FuturesUnordered::new();
+
+    for p in proposals.iter() {
+        futures.push(async {
+            data_for_proposal(
+                p.clone(),
+                db,
+                rpc,
+                &decoder,
+                dao_handler,
+                gov_contract.clone(),
+                objection_period,
+                &edits,
+            )
+            .await
+        });
+    }
+
+    let mut result = Vec::new();
+    while let Some(proposal) = futures.next().await {
+        result.push(proposal?);
+    }
+
+    Ok(result)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn data_for_proposal(
+    p: (ProposalCreatedFilter, LogMeta),
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    decoder: &Decoder,
+    dao_handler: &daohandler_with_dao::Data,
+    gov_contract: NounsGov,
+    objection_period: U256,
+    edits: &[ProposalEdit],
+) -> Result<ChainProposal> {
+    let (log, meta): (ProposalCreatedFilter, LogMeta) = p.clone();
+
+    let created_block_number = meta.block_number.as_u64().to_i64().unwrap();
+    let created_block = rpc.get_block(meta.block_number).await?;
+    let created_block_timestamp = created_block.expect("bad block").time()?;
+
+    let onchain_proposal = gov_contract.proposals(log.id).call().await?;
+
+    let proposal_state = gov_contract.state(log.id).call().await?;
+
+    let state = match proposal_state {
+        0 => ProposalState::Pending,
+        1 => ProposalState::Active,
+        2 => ProposalState::Canceled,
+        3 => ProposalState::Defeated,
+        4 => ProposalState::Succeeded,
+        5 => ProposalState::Queued,
+        6 => ProposalState::Expired,
+        7 => ProposalState::Executed,
+        8 => ProposalState::Vetoed,
+        9 => ProposalState::ObjectionPeriod,
+        // the proposer can still edit it, voting has not started yet
+        10 => ProposalState::Pending,
+        _ => ProposalState::Unknown,
+    };
+
+    let voting_start_block_number = onchain_proposal.start_block.as_u64().to_i64().unwrap();
+    let mut voting_end_block_number = onchain_proposal.end_block.as_u64().to_i64().unwrap();
+
+    if state == ProposalState::ObjectionPeriod {
+        voting_end_block_number += objection_period.as_u64().to_i64().unwrap();
+    }
+
+    let voting_starts_timestamp = block_timestamp(
+        voting_start_block_number,
+        created_block_number,
+        created_block_timestamp,
+    )
+    .await;
+
+    let voting_ends_timestamp = block_timestamp(
+        voting_end_block_number,
+        created_block_number,
+        created_block_timestamp,
+    )
+    .await;
+
+    let edited = edits
+        .iter()
+        .rev()
+        .filter(|e| e.proposal_id == log.id)
+        .find_map(|e| e.description.clone());
+
+    let title = match edited {
+        Some(description) => title_from_description(&description),
+        None => stored_title(db, dao_handler, &log.id.to_string())
+            .await?
+            .unwrap_or_else(|| title_from_description(&log.description)),
+    };
+
+    let proposal_url = format!("{}{}", decoder.proposalUrl, log.id);
+
+    let proposal_external_id = log.id.to_string();
+
+    let choices = vec!["For", "Against", "Abstain"];
+
+    let scores = vec![
+        onchain_proposal.for_votes.as_u128(),
+        onchain_proposal.against_votes.as_u128(),
+        onchain_proposal.abstain_votes.as_u128(),
+    ];
+
+    let scores_total = onchain_proposal.for_votes.as_u128()
+        + onchain_proposal.against_votes.as_u128()
+        + onchain_proposal.abstain_votes.as_u128();
+
+    // dynamic quorum depends on the against votes of each proposal
+    let quorum = gov_contract
+        .quorum_votes(log.id)
+        .call()
+        .await
+        .unwrap_or(onchain_proposal.quorum_votes);
+
+    let proposal = ChainProposal {
+        external_id: proposal_external_id,
+        name: title,
+        dao_id: dao_handler.clone().daoid,
+        dao_handler_id: dao_handler.clone().id,
+        time_start: voting_starts_timestamp,
+        time_end: voting_ends_timestamp,
+        time_created: created_block_timestamp,
+        block_created: created_block_number,
+        choices: choices.into(),
+        scores: scores.into(),
+        scores_total: scores_total.into(),
+        quorum: quorum.as_u128().into(),
+        support_threshold: None,
+        url: proposal_url,
+        state,
+    };
+
+    Ok(proposal)
+}
+
+async fn block_timestamp(
+    block_number: i64,
+    created_block_number: i64,
+    created_block_timestamp: DateTime<Utc>,
+) -> DateTime<Utc> {
+    match estimate_timestamp(block_number).await {
+        Ok(r) => r,
+        Err(_) => DateTime::from_naive_utc_and_offset(
+            NaiveDateTime::from_timestamp_millis(
+                created_block_timestamp.timestamp() * 1000
+                    + (block_number - created_block_number) * 12 * 1000,
+            )
+            .expect("bad timestamp"),
+            Utc,
+        ),
+    }
+}
+
+fn title_from_description(description: &str) -> String {
+    let mut title = format!(
+        "{:.120}",
+        description.split('\n').next().unwrap_or("Unknown")
+    );
+
+    if title.starts_with("# ") {
+        title = title.split_off(2);
+    }
+
+    if title.is_empty() {
+        title = "Unknown".into()
+    }
+
+    title
+}
+
+/// Edits to the governor's proposals in `logs`, in chain order.
+fn proposal_edits(logs: &[Log], address: Address) -> Vec<ProposalEdit> {
+    let mut edits = vec![];
+
+    edits.extend(
+        typed_events::<ProposalUpdatedFilter>(logs, address)
+            .into_iter()
+            .map(|(log, meta)| ProposalEdit {
+                proposal_id: log.id,
+                r#type: ProposalEditType::Full,
+                description: Some(log.description),
+                update_message: log.update_message,
+                meta,
+            }),
+    );
+
+    edits.extend(
+        typed_events::<ProposalDescriptionUpdatedFilter>(logs, address)
+            .into_iter()
+            .map(|(log, meta)| ProposalEdit {
+                proposal_id: log.id,
+                r#type: ProposalEditType::Description,
+                description: Some(log.description),
+                update_message: log.update_message,
+                meta,
+            }),
+    );
+
+    edits.extend(
+        typed_events::<ProposalTransactionsUpdatedFilter>(logs, address)
+            .into_iter()
+            .map(|(log, meta)| ProposalEdit {
+                proposal_id: log.id,
+                r#type: ProposalEditType::Transactions,
+                description: None,
+                update_message: log.update_message,
+                meta,
+            }),
+    );
+
+    edits.sort_by_key(|e| (e.meta.block_number, e.meta.log_index));
+
+    edits
+}
+
+/// Title of the last edit recorded for a proposal.
+async fn stored_title(
+    db: &Arc<PrismaClient>,
+    dao_handler: &daohandler_with_dao::Data,
+    external_id: &str,
+) -> Result<Option<String>> {
+    let edit = db
+        .proposaledit()
+        .find_first(vec![proposaledit::proposal::is(vec![
+            proposal::externalid::equals(external_id.to_string()),
+            proposal::daoid::equals(dao_handler.daoid.clone()),
+        ])])
+        .order_by(proposaledit::blockcreated::order(Direction::Desc))
+        .exec()
+        .await?;
+
+    Ok(edit.map(|e| e.name))
+}
+
+/// Records the proposal edits in `logs` and retitles the edited proposals. Runs after
+/// the proposals of the same logs are stored, an edit can also be to a proposal created
+/// in an earlier range.
+#[instrument(skip_all)]
+pub async fn nouns_proposal_edits(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    logs: &[Log],
+) -> Result<()> {
+    let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
+
+    let address = decoder.address.parse::<Address>().expect("bad address");
+
+    let edits = proposal_edits(logs, address);
+
+    let created = typed_events::<ProposalCreatedFilter>(logs, address);
+
+    let mut proposal_ids: Vec<U256> = edits.iter().map(|e| e.proposal_id).collect();
+    proposal_ids.sort();
+    proposal_ids.dedup();
+
+    for proposal_id in proposal_ids {
+        let proposal_edits: Vec<ProposalEdit> = edits
+            .iter()
+            .filter(|e| e.proposal_id == proposal_id)
+            .cloned()
+            .collect();
+
+        record_edits(
+            db,
+            rpc,
+            dao_handler,
+            &proposal_id.to_string(),
+            created
+                .iter()
+                .find(|(log, _)| log.id == proposal_id)
+                .map(|(log, _)| log.description.as_str()),
+            &proposal_edits,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn record_edits(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    external_id: &str,
+    created_description: Option<&str>,
+    edits: &[ProposalEdit],
+) -> Result<()> {
+    let proposal = match db
+        .proposal()
+        .find_unique(proposal::externalid_daoid(
+            external_id.to_string(),
+            dao_handler.daoid.clone(),
+        ))
+        .exec()
+        .await?
+    {
+        Some(p) => p,
+        None => {
+            event!(
+                Level::WARN,
+                proposal_external_id = external_id,
+                dao_handler_id = dao_handler.id,
+                "edit to an unknown proposal"
+            );
+            return Ok(());
+        }
+    };
+
+    let first_block = edits
+        .first()
+        .map(|e| e.meta.block_number.as_u64().to_i64().unwrap())
+        .unwrap_or_default();
+
+    // a transactions edit keeps the title of the edit before it
+    let earlier = db
+        .proposaledit()
+        .find_first(vec![
+            proposaledit::proposalid::equals(proposal.id.clone()),
+            proposaledit::blockcreated::lt(first_block),
+        ])
+        .order_by(proposaledit::blockcreated::order(Direction::Desc))
+        .exec()
+        .await?;
+
+    let mut title = match (earlier, created_description) {
+        (Some(edit), _) => edit.name,
+        (None, Some(description)) => title_from_description(description),
+        (None, None) => proposal.name.clone(),
+    };
+
+    for edit in edits {
+        if let Some(d) = &edit.description {
+            title = title_from_description(d);
+        }
+
+        let txid = format!("{:#x}", edit.meta.transaction_hash);
+
+        let existing = db
+            .proposaledit()
+            .find_unique(proposaledit::txid_proposalid_type(
+                txid.clone(),
+                proposal.id.clone(),
+                edit.r#type,
+            ))
+            .exec()
+            .await?;
+
+        if existing.is_some() {
+            continue;
+        }
+
+        let block = rpc.get_block(edit.meta.block_number).await?;
+        let timestamp = block.expect("bad block").time()?;
+
+        event!(
+            Level::INFO,
+            proposal_id = proposal.id,
+            dao_handler_id = dao_handler.id,
+            edit_type = edit.r#type.to_string(),
+            "proposal edit"
+        );
+
+        db.proposaledit()
+            .create_unchecked(
+                proposal.id.clone(),
+                edit.r#type,
+                title.clone(),
+                edit.update_message.clone(),
+                txid,
+                edit.meta.block_number.as_u64().to_i64().unwrap(),
+                timestamp.with_timezone(&FixedOffset::east_opt(0).unwrap()),
+                vec![],
+            )
+            .exec()
+            .await?;
+    }
+
+    // the range can be older than edits recorded before, the last one names it
+    let latest = db
+        .proposaledit()
+        .find_first(vec![proposaledit::proposalid::equals(proposal.id.clone())])
+        .order_by(proposaledit::blockcreated::order(Direction::Desc))
+        .exec()
+        .await?;
+
+    if let Some(latest) = latest.filter(|e| e.name != proposal.name) {
+        db.proposal()
+            .update(
+                proposal::id::equals(proposal.id.clone()),
+                vec![proposal::name::set(latest.name)],
+            )
+            .exec()
+            .await?;
+    }
+
+    Ok(())
+}
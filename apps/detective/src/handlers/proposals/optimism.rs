@@ -5,7 +5,7 @@ use anyhow::Result;
 use ethers::{
     prelude::LogMeta,
     providers::{Http, Middleware, Provider},
-    types::{Address, Filter},
+    types::{Address, Log},
     utils::hex,
 };
 use futures::{
@@ -26,6 +26,7 @@ use crate::{
         optimismvotemodule_5_4a_8f,
     },
     daohandler_with_dao,
+    logstore::typed_events,
     prisma::{daohandler, ProposalState},
     router::chain_proposals::ChainProposal,
     utils::optimiscan::estimate_timestamp,
@@ -43,8 +44,7 @@ struct Decoder {
 pub async fn optimism_proposals(
     rpc: &Arc<Provider<Http>>,
     dao_handler: &daohandler_with_dao::Data,
-    from_block: &i64,
-    to_block: &i64,
+    logs: &[Log],
 ) -> Result<Vec<ChainProposal>> {
     let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
 
@@ -52,18 +52,8 @@ pub async fn optimism_proposals(
 
     let gov_contract = optimismgov::optimismgov::optimismgov::new(address, rpc.clone());
 
-    let events1 = gov_contract
-        .proposal_created_1_filter()
-        .from_block(*from_block)
-        .to_block(*to_block);
-
-    let events2 = gov_contract
-        .proposal_created_2_filter()
-        .from_block(*from_block)
-        .to_block(*to_block);
-
-    let proposals1 = events1.query_with_meta().await?;
-    let proposals2 = events2.query_with_meta().await?;
+    let proposals1 = typed_events::<ProposalCreated1Filter>(logs, address);
+    let proposals2 = typed_events::<ProposalCreated2Filter>(logs, address);
 
     let mut results = vec![];
 
@@ -222,6 +212,7 @@ async fn data_for_proposal1(
         scores: vec![scores_total].into(),
         scores_total: scores_total.into(),
         quorum: quorum.as_u128().into(),
+        support_threshold: None,
         url: proposal_url,
         state,
     };
@@ -347,6 +338,7 @@ async fn data_for_proposal2(
         .into(),
         scores_total: scores_total.into(),
         quorum: quorum.as_u128().into(),
+        support_threshold: None,
         url: proposal_url,
         state,
     };
@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::Duration;
+use ethers::{
+    contract::EthEvent,
+    providers::{Http, Middleware, Provider},
+    types::{Address, Log, U256},
+};
+use prisma_client_rust::{
+    bigdecimal::ToPrimitive,
+    chrono::{DateTime, Utc},
+};
+use serde::Deserialize;
+use tracing::{event, Level};
+
+use crate::{
+    contracts::safe::{
+        self,
+        AddedOwnerFilter,
+        ChangedThresholdFilter,
+        ExecutionFailureFilter,
+        ExecutionSuccessFilter,
+        RemovedOwnerFilter,
+    },
+    daohandler_with_dao,
+    prisma::ProposalState,
+    router::chain_proposals::ChainProposal,
+    utils::safe::{
+        exec_transaction,
+        first_word,
+        service_transactions,
+        signers,
+        ServiceTransaction,
+    },
+};
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct Decoder {
+    address: String,
+    proposalUrl: String,
+    transactionService: Option<String>,
+}
+
+pub async fn safe_proposals(
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    logs: &[Log],
+    to_block: &i64,
+) -> Result<Vec<ChainProposal>> {
+    let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
+
+    let address = decoder.address.parse::<Address>().expect("bad address");
+
+    let safe_contract = safe::safe::safe::new(address, rpc.clone());
+
+    let threshold = safe_contract.get_threshold().call().await?;
+    let nonce = safe_contract.nonce().call().await?;
+
+    let service_txs = match &decoder.transactionService {
+        Some(url) => match service_transactions(url, address).await {
+            Ok(r) => r,
+            Err(e) => {
+                // executions are still on chain, pending ones get picked up on the next run
+                event!(
+                    Level::WARN,
+                    dao_handler_id = dao_handler.id,
+                    err = e.to_string(),
+                    "transaction service error"
+                );
+                vec![]
+            }
+        },
+        None => vec![],
+    };
+
+    let topics = [
+        ExecutionSuccessFilter::signature(),
+        ExecutionFailureFilter::signature(),
+        AddedOwnerFilter::signature(),
+        RemovedOwnerFilter::signature(),
+        ChangedThresholdFilter::signature(),
+    ];
+
+    let logs: Vec<Log> = logs
+        .iter()
+        .filter(|l| l.address == address)
+        .filter(|l| l.topics.first().map_or(false, |t| topics.contains(t)))
+        .cloned()
+        .collect();
+
+    let mut result = Vec::new();
+
+    for log in logs.iter() {
+        let topic = log.topics[0];
+
+        if topic != ExecutionSuccessFilter::signature()
+            && topic != ExecutionFailureFilter::signature()
+        {
+            continue;
+        }
+
+        result.push(
+            data_for_execution(
+                log,
+                &logs,
+                rpc,
+                &decoder,
+                dao_handler,
+                &service_txs,
+                threshold,
+            )
+            .await?,
+        );
+    }
+
+    for tx in service_txs.iter().filter(|tx| !tx.isExecuted) {
+        result.push(data_for_pending(
+            tx,
+            &decoder,
+            dao_handler,
+            threshold,
+            nonce,
+            *to_block,
+        ));
+    }
+
+    Ok(result)
+}
+
+async fn data_for_execution(
+    log: &Log,
+    logs: &[Log],
+    rpc: &Arc<Provider<Http>>,
+    decoder: &Decoder,
+    dao_handler: &daohandler_with_dao::Data,
+    service_txs: &[ServiceTransaction],
+    threshold: U256,
+) -> Result<ChainProposal> {
+    let safe_tx_hash = first_word(log).unwrap_or_default();
+    let tx_hash = log.transaction_hash.unwrap_or_default();
+
+    let block_number = log.block_number.unwrap_or_default();
+    let created_block_number = block_number.as_u64().to_i64().unwrap();
+    let created_block = rpc.get_block(block_number).await?;
+    let created_block_timestamp = created_block.expect("bad block").time()?;
+
+    let exec_call = exec_transaction(rpc, tx_hash).await?;
+
+    let signatures = exec_call
+        .as_ref()
+        .map(|c| signers(&c.signatures, safe_tx_hash).len() as u128)
+        .unwrap_or(threshold.as_u128());
+
+    let service_tx = service_txs
+        .iter()
+        .find(|tx| tx.safeTxHash.to_lowercase() == format!("{:#x}", safe_tx_hash));
+
+    // owner changes are emitted by the safe itself while the transaction executes
+    let owner_changes: Vec<String> = logs
+        .iter()
+        .filter(|l| l.transaction_hash == log.transaction_hash && l.log_index < log.log_index)
+        .filter_map(owner_change)
+        .collect();
+
+    let title = if let Some(tx) = service_tx {
+        tx.title()
+    } else if !owner_changes.is_empty() {
+        owner_changes.join(", ")
+    } else if let Some(call) = &exec_call {
+        if call.value.is_zero() {
+            format!("Call {:#x}", call.to)
+        } else {
+            format!("Transfer {} wei to {:#x}", call.value, call.to)
+        }
+    } else {
+        "Unknown".to_string()
+    };
+
+    // the nonce is used up either way, a failure means the call itself reverted
+    let state = if log.topics[0] == ExecutionSuccessFilter::signature() {
+        ProposalState::Executed
+    } else {
+        ProposalState::Defeated
+    };
+
+    let proposal = ChainProposal {
+        external_id: format!("{:#x}", safe_tx_hash),
+        name: title,
+        dao_id: dao_handler.clone().daoid,
+        dao_handler_id: dao_handler.clone().id,
+        time_start: service_tx
+            .map(|tx| tx.submitted())
+            .unwrap_or(created_block_timestamp),
+        time_end: created_block_timestamp,
+        time_created: service_tx
+            .map(|tx| tx.submitted())
+            .unwrap_or(created_block_timestamp),
+        block_created: created_block_number,
+        choices: vec!["Confirm"].into(),
+        scores: vec![signatures].into(),
+        scores_total: signatures.into(),
+        quorum: service_tx
+            .and_then(|tx| tx.confirmationsRequired)
+            .map(|c| c as u128)
+            .unwrap_or(threshold.as_u128())
+            .into(),
+        support_threshold: None,
+        url: format!("{}{:#x}", decoder.proposalUrl, safe_tx_hash),
+        state,
+    };
+
+    Ok(proposal)
+}
+
+fn data_for_pending(
+    tx: &ServiceTransaction,
+    decoder: &Decoder,
+    dao_handler: &daohandler_with_dao::Data,
+    threshold: U256,
+    nonce: U256,
+    to_block: i64,
+) -> ChainProposal {
+    let required = tx
+        .confirmationsRequired
+        .map(|c| c as u128)
+        .unwrap_or(threshold.as_u128());
+
+    let confirmations = tx.confirmations.len() as u128;
+
+    // another transaction with the same nonce was executed instead
+    let state = if U256::from(tx.nonce) < nonce {
+        ProposalState::Canceled
+    } else if confirmations >= required {
+        ProposalState::Succeeded
+    } else {
+        ProposalState::Active
+    };
+
+    let submitted = tx.submitted();
+
+    // safe transactions have no deadline, keep open ones around for a while
+    let time_end: DateTime<Utc> = match state {
+        ProposalState::Canceled => submitted,
+        _ => submitted + Duration::days(30),
+    };
+
+    let safe_tx_hash = tx.safeTxHash.to_lowercase();
+
+    ChainProposal {
+        external_id: safe_tx_hash.clone(),
+        name: tx.title(),
+        dao_id: dao_handler.clone().daoid,
+        dao_handler_id: dao_handler.clone().id,
+        time_start: submitted,
+        time_end,
+        time_created: submitted,
+        // not mined yet, the index can move past it
+        block_created: to_block,
+        choices: vec!["Confirm"].into(),
+        scores: vec![confirmations].into(),
+        scores_total: confirmations.into(),
+        quorum: required.into(),
+        support_threshold: None,
+        url: format!("{}{}", decoder.proposalUrl, safe_tx_hash),
+        state,
+    }
+}
+
+fn owner_change(log: &Log) -> Option<String> {
+    let topic = log.topics[0];
+
+    if topic == AddedOwnerFilter::signature() {
+        Some(format!("Add owner {:#x}", Address::from(first_word(log)?)))
+    } else if topic == RemovedOwnerFilter::signature() {
+        Some(format!(
+            "Remove owner {:#x}",
+            Address::from(first_word(log)?)
+        ))
+    } else if topic == ChangedThresholdFilter::signature() {
+        Some(format!(
+            "Change threshold to {}",
+            U256::from_big_endian(first_word(log)?.as_bytes())
+        ))
+    } else {
+        None
+    }
+}
@@ -3,8 +3,7 @@ use std::sync::Arc;
 use anyhow::{bail, Result};
 use ethers::{
     prelude::LogMeta,
-    providers::{Http, Provider},
-    types::{Address, Filter, H160, H256},
+    types::{Address, Log},
 };
 use futures::stream::{FuturesUnordered, StreamExt};
 use prisma_client_rust::{bigdecimal::ToPrimitive, chrono::Utc};
@@ -17,6 +16,7 @@ use crate::{
         {self},
     },
     daohandler_with_dao,
+    logstore::typed_events,
     prisma::{daohandler, proposal, PrismaClient},
     router::chain_votes::{Vote, VoteResult},
     Ctx,
@@ -30,31 +30,15 @@ struct Decoder {
 
 pub async fn dydx_votes(
     db: &Arc<PrismaClient>,
-    rpc: &Arc<Provider<Http>>,
     dao_handler: &daohandler_with_dao::Data,
-    from_block: i64,
-    to_block: i64,
+    logs: &[Log],
     voters: Vec<String>,
 ) -> Result<Vec<VoteResult>> {
     let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
 
     let address = decoder.address.parse::<Address>().expect("bad address");
 
-    let gov_contract = dydxgov::dydxgov::dydxgov::new(address, rpc.clone());
-
-    let voters_addresses: Vec<H256> = voters
-        .clone()
-        .into_iter()
-        .map(|v| H256::from(v.parse::<H160>().unwrap()))
-        .collect();
-
-    let events = gov_contract
-        .event::<dydxgov::VoteEmittedFilter>()
-        .topic1(voters_addresses)
-        .from_block(from_block)
-        .to_block(to_block);
-
-    let logs = events.query_with_meta().await?;
+    let logs = typed_events::<VoteEmittedFilter>(logs, address);
 
     let mut futures = FuturesUnordered::new();
 
@@ -0,0 +1,88 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{bail, Result};
+use ethers::{
+    providers::{Http, Provider},
+    types::Log,
+};
+use prisma_client_rust::chrono::Utc;
+
+use crate::{
+    abis,
+    daohandler_with_dao,
+    logstore::{
+        decode_logs,
+        derive::{derive, LogEntry},
+    },
+    prisma::{proposal, PrismaClient},
+    router::chain_votes::{Vote, VoteResult},
+};
+
+/// Votes on a governor known only by its registered abi, decoded at runtime.
+pub async fn generic_votes(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    logs: &[Log],
+    voters: Vec<String>,
+) -> Result<Vec<VoteResult>> {
+    let names = abis::abi_names(dao_handler.r#type, &dao_handler.decoder);
+    let events = abis::events(&names);
+
+    if events.is_empty() {
+        bail!("no registered abi in {:?}", names);
+    }
+
+    let entries: Vec<LogEntry> = decode_logs(rpc, &events, logs.to_vec())
+        .await?
+        .into_iter()
+        .map(LogEntry::from)
+        .collect();
+
+    let (_, derived_votes) = derive(&entries);
+
+    let proposals: HashMap<String, proposal::Data> = db
+        .proposal()
+        .find_many(vec![proposal::daohandlerid::equals(dao_handler.id.clone())])
+        .exec()
+        .await?
+        .into_iter()
+        .map(|p| (p.externalid.clone(), p))
+        .collect();
+
+    let mut result = vec![];
+
+    for voter_address in voters {
+        let mut votes = vec![];
+
+        for v in derived_votes
+            .iter()
+            .filter(|v| v.voter == voter_address.to_lowercase())
+        {
+            let proposal = match proposals.get(&v.external_id) {
+                Some(p) => p,
+                None => bail!("proposal {} for vote does not exist", v.external_id),
+            };
+
+            votes.push(Vote {
+                block_created: v.block,
+                voter_address: voter_address.clone(),
+                dao_id: dao_handler.daoid.clone(),
+                proposal_id: proposal.id.clone(),
+                dao_handler_id: dao_handler.id.clone(),
+                choice: v.choice.into(),
+                reason: v.reason.clone(),
+                voting_power: v.weight.into(),
+                proposal_active: proposal.timeend > Utc::now(),
+            });
+        }
+
+        result.push(VoteResult {
+            voter_address,
+            success: true,
+            votes,
+        });
+    }
+
+    Ok(result)
+}
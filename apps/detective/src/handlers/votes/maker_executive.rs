@@ -4,7 +4,7 @@ use anyhow::{bail, Result};
 use ethers::{
     prelude::LogMeta,
     providers::{Http, Provider},
-    types::{Address, H160, H256, U256},
+    types::{Address, Log, H256, U256},
     utils::to_checksum,
 };
 use futures::stream::{FuturesUnordered, StreamExt};
@@ -16,6 +16,7 @@ use tracing::{debug_span, instrument, Instrument};
 use crate::{
     contracts::makerexecutive::{self, LogNoteFilter},
     daohandler_with_dao,
+    logstore::typed_events,
     prisma::{daohandler, proposal, PrismaClient},
     router::chain_votes::{Vote, VoteResult},
     Ctx,
@@ -36,39 +37,30 @@ pub async fn makerexecutive_votes(
     db: &Arc<PrismaClient>,
     rpc: &Arc<Provider<Http>>,
     dao_handler: &daohandler_with_dao::Data,
-    from_block: i64,
-    to_block: i64,
+    logs: &[Log],
     voters: Vec<String>,
 ) -> Result<Vec<VoteResult>> {
-    let voters_addresses: Vec<H256> = voters
-        .clone()
-        .into_iter()
-        .map(|v| H256::from(v.parse::<H160>().unwrap()))
-        .collect();
-
     let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
 
     let address = decoder.address.parse::<Address>().expect("bad address");
 
     let gov_contract = makerexecutive::makerexecutive::makerexecutive::new(address, rpc.clone());
 
-    let single_spell_events = gov_contract
-        .log_note_filter()
-        .topic0(vec![VOTE_SINGLE_ACTION_TOPIC.parse::<H256>()?])
-        .topic1(voters_addresses.clone())
-        .from_block(from_block)
-        .to_block(to_block);
-
-    let single_spell_logs = single_spell_events.query_with_meta().await?;
+    let log_notes = typed_events::<LogNoteFilter>(logs, address);
+    let single_topic = VOTE_SINGLE_ACTION_TOPIC.parse::<H256>()?;
+    let multi_topic = VOTE_MULTIPLE_ACTIONS_TOPIC.parse::<H256>()?;
 
-    let multi_spell_events = gov_contract
-        .log_note_filter()
-        .topic0(vec![VOTE_MULTIPLE_ACTIONS_TOPIC.parse::<H256>()?])
-        .topic1(voters_addresses.clone())
-        .from_block(from_block)
-        .to_block(to_block);
+    let single_spell_logs: Vec<(LogNoteFilter, LogMeta)> = log_notes
+        .iter()
+        .filter(|(n, _)| n.sig[..] == single_topic[..4])
+        .cloned()
+        .collect();
 
-    let multi_spell_logs = multi_spell_events.query_with_meta().await?;
+    let multi_spell_logs: Vec<(LogNoteFilter, LogMeta)> = log_notes
+        .iter()
+        .filter(|(n, _)| n.sig[..] == multi_topic[..4])
+        .cloned()
+        .collect();
 
     let single_spells =
         get_single_spell_addresses(voters.clone(), single_spell_logs, gov_contract.clone()).await?;
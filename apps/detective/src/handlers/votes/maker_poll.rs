@@ -3,8 +3,7 @@ use std::{str::FromStr, sync::Arc};
 use anyhow::{bail, Result};
 use ethers::{
     prelude::LogMeta,
-    providers::{Http, Provider},
-    types::{Address, Filter, H160, H256},
+    types::{Address, Log},
 };
 use futures::stream::{FuturesUnordered, StreamExt};
 use num_bigint::BigInt;
@@ -15,6 +14,7 @@ use tracing::{debug_span, instrument, Instrument};
 use crate::{
     contracts::makerpollvote::{self, VotedFilter},
     daohandler_with_dao,
+    logstore::typed_events,
     prisma::{daohandler, proposal, PrismaClient},
     router::chain_votes::{Vote, VoteResult},
     Ctx,
@@ -28,10 +28,8 @@ struct Decoder {
 
 pub async fn makerpoll_votes(
     db: &Arc<PrismaClient>,
-    rpc: &Arc<Provider<Http>>,
     dao_handler: &daohandler_with_dao::Data,
-    from_block: i64,
-    to_block: i64,
+    logs: &[Log],
     voters: Vec<String>,
 ) -> Result<Vec<VoteResult>> {
     let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
@@ -41,21 +39,7 @@ pub async fn makerpoll_votes(
         .parse::<Address>()
         .expect("bad address");
 
-    let gov_contract = makerpollvote::makerpollvote::makerpollvote::new(address, rpc.clone());
-
-    let voters_addresses: Vec<H256> = voters
-        .clone()
-        .into_iter()
-        .map(|v| H256::from(v.parse::<H160>().unwrap()))
-        .collect();
-
-    let events = gov_contract
-        .event::<makerpollvote::VotedFilter>()
-        .topic1(voters_addresses)
-        .from_block(from_block)
-        .to_block(to_block);
-
-    let logs = events.query_with_meta().await?;
+    let logs = typed_events::<VotedFilter>(logs, address);
 
     let mut futures = FuturesUnordered::new();
 
@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use ethers::{
+    contract::EthEvent,
+    providers::{Http, Middleware, Provider},
+    types::{Address, Log, H256},
+};
+use prisma_client_rust::{bigdecimal::ToPrimitive, chrono::Utc};
+use serde::Deserialize;
+use tracing::{event, Level};
+
+use crate::{
+    contracts::safe::{ApproveHashFilter, ExecutionFailureFilter, ExecutionSuccessFilter},
+    daohandler_with_dao,
+    logstore::typed_events,
+    prisma::{proposal, PrismaClient},
+    router::chain_votes::{Vote, VoteResult},
+    utils::safe::{exec_transaction, first_word, service_transactions, signers},
+};
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct Decoder {
+    address: String,
+    transactionService: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Signature {
+    owner: String,
+    safe_tx_hash: String,
+    block_created: i64,
+}
+
+pub async fn safe_votes(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    logs: &[Log],
+    to_block: i64,
+    voters: Vec<String>,
+) -> Result<Vec<VoteResult>> {
+    let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
+
+    let address = decoder.address.parse::<Address>().expect("bad address");
+
+    let mut signatures: Vec<Signature> = vec![];
+
+    // signatures of executed transactions are only in the execTransaction calldata
+    let executions = logs.iter().filter(|l| {
+        l.address == address
+            && l.topics.first().map_or(false, |t| {
+                *t == ExecutionSuccessFilter::signature()
+                    || *t == ExecutionFailureFilter::signature()
+            })
+    });
+
+    for log in executions {
+        let safe_tx_hash = match first_word(log) {
+            Some(h) => h,
+            None => continue,
+        };
+
+        let exec_call =
+            match exec_transaction(rpc, log.transaction_hash.unwrap_or_default()).await? {
+                Some(c) => c,
+                None => continue,
+            };
+
+        for owner in signers(&exec_call.signatures, safe_tx_hash) {
+            signatures.push(Signature {
+                owner: format!("{:#x}", owner),
+                safe_tx_hash: format!("{:#x}", safe_tx_hash),
+                block_created: log
+                    .block_number
+                    .unwrap_or_default()
+                    .as_u64()
+                    .to_i64()
+                    .unwrap(),
+            });
+        }
+    }
+
+    let approvals = typed_events::<ApproveHashFilter>(logs, address);
+
+    signatures.extend(approvals.into_iter().map(|(log, meta)| Signature {
+        owner: format!("{:#x}", log.owner),
+        safe_tx_hash: format!("{:#x}", H256::from(log.approved_hash)),
+        block_created: meta.block_number.as_u64().to_i64().unwrap(),
+    }));
+
+    if let Some(url) = &decoder.transactionService {
+        match service_transactions(url, address).await {
+            Ok(txs) => {
+                for tx in txs {
+                    signatures.extend(tx.confirmations.iter().map(|c| Signature {
+                        owner: c.owner.to_lowercase(),
+                        safe_tx_hash: tx.safeTxHash.to_lowercase(),
+                        block_created: tx.blockNumber.unwrap_or(to_block),
+                    }));
+                }
+            }
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    dao_handler_id = dao_handler.id,
+                    err = e.to_string(),
+                    "transaction service error"
+                );
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+
+    for voter_address in voters.iter() {
+        result.push(get_votes_for_voter(&signatures, dao_handler, voter_address, db).await?);
+    }
+
+    Ok(result)
+}
+
+async fn get_votes_for_voter(
+    signatures: &[Signature],
+    dao_handler: &daohandler_with_dao::Data,
+    voter_address: &str,
+    db: &Arc<PrismaClient>,
+) -> Result<VoteResult> {
+    let mut voter_signatures: Vec<&Signature> = signatures
+        .iter()
+        .filter(|s| s.owner == voter_address.to_lowercase())
+        .collect();
+
+    voter_signatures.sort_by(|a, b| a.safe_tx_hash.cmp(&b.safe_tx_hash));
+    voter_signatures.dedup_by(|a, b| a.safe_tx_hash == b.safe_tx_hash);
+
+    let mut votes: Vec<Vote> = vec![];
+
+    for signature in voter_signatures {
+        let p = db
+            .proposal()
+            .find_first(vec![
+                proposal::externalid::equals(signature.safe_tx_hash.clone()),
+                proposal::daoid::equals(dao_handler.clone().daoid.to_string()),
+                proposal::daohandlerid::equals(dao_handler.clone().id.to_string()),
+            ])
+            .exec()
+            .await?;
+
+        // approvals can come in before the transaction is proposed or executed
+        let proposal = match p {
+            Some(r) => r,
+            None => continue,
+        };
+
+        votes.push(Vote {
+            block_created: signature.block_created,
+            voter_address: voter_address.to_string(),
+            dao_id: dao_handler.clone().daoid.to_string(),
+            proposal_id: proposal.id,
+            dao_handler_id: dao_handler.clone().id.to_string(),
+            choice: 1.into(),
+            reason: "".to_string(),
+            // every owner signature counts the same
+            voting_power: 1.into(),
+            proposal_active: proposal.timeend > Utc::now(),
+        })
+    }
+
+    Ok(VoteResult {
+        voter_address: voter_address.to_string(),
+        success: true,
+        votes,
+    })
+}
@@ -0,0 +1,496 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{bail, Result};
+use prisma_client_rust::chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{event, instrument, Level};
+
+use crate::{
+    daohandler_with_dao,
+    logstore::{stored_logs, DecodedLog},
+    prisma::{chainlog, proposal, voter, DaoHandlerType, PrismaClient, ProposalState},
+    router::{
+        chain_proposals::{upsert_proposals, ChainProposal},
+        chain_votes::{upsert_votes, Vote, VoteResult},
+    },
+};
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+struct Decoder {
+    proposalUrl: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(crate = "rocket::serde")]
+pub struct ReplayReport {
+    pub logs: usize,
+    pub proposals: usize,
+    pub votes: usize,
+    pub skipped: usize,
+}
+
+/// A stored log reduced to what the derivation reads.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub event: String,
+    pub args: Value,
+    pub block: i64,
+    pub timestamp: Option<i64>,
+}
+
+impl From<chainlog::Data> for LogEntry {
+    fn from(l: chainlog::Data) -> Self {
+        LogEntry {
+            event: l.event,
+            args: l.args,
+            block: l.blocknumber,
+            timestamp: l.blocktimestamp.map(|t| t.timestamp()),
+        }
+    }
+}
+
+impl From<DecodedLog> for LogEntry {
+    fn from(l: DecodedLog) -> Self {
+        LogEntry {
+            event: l.event,
+            args: l.args,
+            block: l.block,
+            timestamp: l.timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DerivedProposal {
+    pub external_id: String,
+    pub name: String,
+    pub block_created: i64,
+    pub time_created: Option<i64>,
+    pub start_block: i64,
+    pub end_block: i64,
+    pub canceled: bool,
+    pub queued: bool,
+    pub executed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivedVote {
+    pub external_id: String,
+    pub voter: String,
+    pub choice: i64,
+    pub weight: u128,
+    pub reason: String,
+    pub block: i64,
+}
+
+/// Handler types whose proposals and votes can be rebuilt from events alone.
+pub fn is_replayable(handler_type: DaoHandlerType) -> bool {
+    matches!(
+        handler_type,
+        DaoHandlerType::AaveChain
+            | DaoHandlerType::CompoundChain
+            | DaoHandlerType::UniswapChain
+            | DaoHandlerType::EnsChain
+            | DaoHandlerType::GitcoinChain
+            | DaoHandlerType::HopChain
+            | DaoHandlerType::DydxChain
+            | DaoHandlerType::InterestProtocolChain
+            | DaoHandlerType::ArbitrumCoreChain
+            | DaoHandlerType::ArbitrumTreasuryChain
+            | DaoHandlerType::NounsChain
+            | DaoHandlerType::GenericChain
+    )
+}
+
+fn arg<'a>(args: &'a Value, names: &[&str]) -> Option<&'a Value> {
+    names.iter().find_map(|n| args.get(*n))
+}
+
+fn arg_string(args: &Value, names: &[&str]) -> Option<String> {
+    arg(args, names)?.as_str().map(|s| s.to_string())
+}
+
+fn arg_number(args: &Value, names: &[&str]) -> Option<u128> {
+    arg_string(args, names)?.parse::<u128>().ok()
+}
+
+/// Choice index for a vote, `For` = 1, `Against` = 2, `Abstain` = 3.
+///
+/// Alpha style governors emit a bool, Bravo and OZ emit 0 against, 1 for, 2 abstain.
+pub fn support_choice(support: &Value) -> Option<i64> {
+    match support {
+        Value::Bool(true) => Some(1),
+        Value::Bool(false) => Some(2),
+        Value::String(s) => match s.as_str() {
+            "0" => Some(2),
+            "1" => Some(1),
+            "2" => Some(3),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Proposal title from its description, same rules the handlers use.
+pub fn title_from_description(description: &str) -> String {
+    let mut title = format!(
+        "{:.120}",
+        description.split('\n').next().unwrap_or("Unknown")
+    );
+
+    if title.starts_with("# ") {
+        title = title.split_off(2);
+    }
+
+    if title.is_empty() {
+        title = "Unknown".into()
+    }
+
+    title
+}
+
+/// Folds a handler's logs, in chain order, into its proposals and votes.
+pub fn derive(logs: &[LogEntry]) -> (Vec<DerivedProposal>, Vec<DerivedVote>) {
+    let mut proposals: Vec<DerivedProposal> = vec![];
+    let mut votes: Vec<DerivedVote> = vec![];
+
+    for log in logs {
+        let id = match arg_string(&log.args, &["id", "proposalId"]) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        match log.event.as_str() {
+            "ProposalCreated" => {
+                if proposals.iter().any(|p| p.external_id == id) {
+                    continue;
+                }
+
+                proposals.push(DerivedProposal {
+                    external_id: id,
+                    name: arg_string(&log.args, &["description"])
+                        .map(|d| title_from_description(&d))
+                        .unwrap_or_else(|| "Unknown".into()),
+                    block_created: log.block,
+                    time_created: log.timestamp,
+                    start_block: arg_number(&log.args, &["startBlock", "voteStart"])
+                        .unwrap_or_default() as i64,
+                    end_block: arg_number(&log.args, &["endBlock", "voteEnd"]).unwrap_or_default()
+                        as i64,
+                    ..Default::default()
+                });
+            }
+            "ProposalCanceled" | "ProposalQueued" | "ProposalExecuted" => {
+                if let Some(p) = proposals.iter_mut().find(|p| p.external_id == id) {
+                    match log.event.as_str() {
+                        "ProposalCanceled" => p.canceled = true,
+                        "ProposalQueued" => p.queued = true,
+                        _ => p.executed = true,
+                    }
+                }
+            }
+            "VoteCast" | "VoteCastWithParams" | "VoteEmitted" => {
+                let voter = match arg_string(&log.args, &["voter"]) {
+                    Some(v) => v.to_lowercase(),
+                    None => continue,
+                };
+
+                let choice = match arg(&log.args, &["support"]).and_then(support_choice) {
+                    Some(c) => c,
+                    None => continue,
+                };
+
+                // a later vote on the same proposal replaces the earlier one
+                votes.retain(|v| !(v.voter == voter && v.external_id == id));
+
+                votes.push(DerivedVote {
+                    external_id: id,
+                    voter,
+                    choice,
+                    weight: arg_number(&log.args, &["votes", "weight", "votingPower"])
+                        .unwrap_or_default(),
+                    reason: arg_string(&log.args, &["reason"]).unwrap_or_default(),
+                    block: log.block,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    (proposals, votes)
+}
+
+/// Proposal row for a derived proposal, `None` when neither the logs nor the existing
+/// row know when it was created.
+///
+/// Values events do not carry (quorum, voting times on chains with irregular blocks,
+/// the outcome of a vote that ended without a lifecycle event) are kept from `current`,
+/// and so is its title, which may have been edited since the proposal was created.
+pub fn chain_proposal(
+    dao_handler: &daohandler_with_dao::Data,
+    p: &DerivedProposal,
+    votes: &[DerivedVote],
+    current: Option<&proposal::Data>,
+    choices: &[&str],
+    url_prefix: &str,
+) -> Option<ChainProposal> {
+    let time_created = match (p.time_created, current) {
+        (Some(t), _) => Utc.timestamp_opt(t, 0).unwrap(),
+        (None, Some(c)) => c.timecreated.with_timezone(&Utc),
+        (None, None) => return None,
+    };
+
+    let (time_start, time_end) = match current {
+        Some(c) => (
+            c.timestart.with_timezone(&Utc),
+            c.timeend.with_timezone(&Utc),
+        ),
+        None => (
+            time_created + Duration::seconds((p.start_block - p.block_created) * 12),
+            time_created + Duration::seconds((p.end_block - p.block_created) * 12),
+        ),
+    };
+
+    let mut scores: Vec<u128> = vec![0; choices.len()];
+    for v in votes.iter().filter(|v| v.external_id == p.external_id) {
+        if let Some(score) = scores.get_mut(v.choice as usize - 1) {
+            *score += v.weight;
+        }
+    }
+    let scores_total: u128 = scores.iter().sum();
+
+    let now = Utc::now();
+    let state = if p.canceled {
+        ProposalState::Canceled
+    } else if p.executed {
+        ProposalState::Executed
+    } else if p.queued {
+        ProposalState::Queued
+    } else if now < time_start {
+        ProposalState::Pending
+    } else if now < time_end {
+        ProposalState::Active
+    } else if let Some(c) = current {
+        c.state
+    } else if scores[0] > scores[1] {
+        ProposalState::Succeeded
+    } else {
+        ProposalState::Defeated
+    };
+
+    Some(ChainProposal {
+        external_id: p.external_id.clone(),
+        name: current
+            .map(|c| c.name.clone())
+            .filter(|name| name != "Unknown")
+            .unwrap_or_else(|| p.name.clone()),
+        dao_id: dao_handler.daoid.clone(),
+        dao_handler_id: dao_handler.id.clone(),
+        time_start,
+        time_end,
+        time_created,
+        block_created: p.block_created,
+        choices: choices.to_vec().into(),
+        scores: scores.into(),
+        scores_total: scores_total.into(),
+        quorum: current.map(|c| c.quorum.clone()).unwrap_or(0.into()),
+        support_threshold: current.and_then(|c| c.supportthreshold),
+        url: current
+            .map(|c| c.url.clone())
+            .unwrap_or_else(|| format!("{}{}", url_prefix, p.external_id)),
+        state,
+    })
+}
+
+/// Rebuilds a handler's proposals and votes from the log store, without any rpc call.
+#[instrument(skip_all, fields(dao_handler_id = dao_handler.id))]
+pub async fn replay(
+    db: &Arc<PrismaClient>,
+    dao_handler: &daohandler_with_dao::Data,
+) -> Result<ReplayReport> {
+    if !is_replayable(dao_handler.r#type) {
+        bail!("{} can not be replayed from logs", dao_handler.r#type);
+    }
+
+    let decoder: Decoder = serde_json::from_value(dao_handler.decoder.clone())?;
+
+    let logs: Vec<LogEntry> = stored_logs(db, &dao_handler.id)
+        .await?
+        .into_iter()
+        .map(LogEntry::from)
+        .collect();
+
+    let (derived_proposals, derived_votes) = derive(&logs);
+
+    let existing: HashMap<String, proposal::Data> = db
+        .proposal()
+        .find_many(vec![proposal::daohandlerid::equals(dao_handler.id.clone())])
+        .exec()
+        .await?
+        .into_iter()
+        .map(|p| (p.externalid.clone(), p))
+        .collect();
+
+    let choices: &[&str] = match dao_handler.r#type {
+        DaoHandlerType::AaveChain | DaoHandlerType::DydxChain | DaoHandlerType::GitcoinChain => {
+            &["For", "Against"]
+        }
+        _ => &["For", "Against", "Abstain"],
+    };
+
+    let now = Utc::now();
+    let mut report = ReplayReport {
+        logs: logs.len(),
+        ..Default::default()
+    };
+    let mut chain_proposals = vec![];
+
+    for p in derived_proposals.iter() {
+        match chain_proposal(
+            dao_handler,
+            p,
+            &derived_votes,
+            existing.get(&p.external_id),
+            choices,
+            &decoder.proposalUrl.clone().unwrap_or_default(),
+        ) {
+            Some(proposal) => chain_proposals.push(proposal),
+            None => report.skipped += 1,
+        }
+    }
+
+    // scores can change without the total, a replay rewrites every proposal
+    upsert_proposals(&chain_proposals, db, dao_handler, true).await?;
+    report.proposals = chain_proposals.len();
+
+    // only tracked voters get vote rows, same as the live path
+    let voters = db
+        .voter()
+        .find_many(vec![voter::address::in_vec(
+            derived_votes.iter().map(|v| v.voter.clone()).collect(),
+        )])
+        .exec()
+        .await?;
+
+    let proposal_ids: HashMap<String, proposal::Data> = db
+        .proposal()
+        .find_many(vec![proposal::daohandlerid::equals(dao_handler.id.clone())])
+        .exec()
+        .await?
+        .into_iter()
+        .map(|p| (p.externalid.clone(), p))
+        .collect();
+
+    let mut results = vec![];
+
+    for voter in voters {
+        let votes: Vec<Vote> = derived_votes
+            .iter()
+            .filter(|v| v.voter == voter.address.to_lowercase())
+            .filter_map(|v| {
+                let proposal = proposal_ids.get(&v.external_id)?;
+
+                Some(Vote {
+                    block_created: v.block,
+                    voter_address: voter.address.clone(),
+                    dao_id: dao_handler.daoid.clone(),
+                    proposal_id: proposal.id.clone(),
+                    dao_handler_id: dao_handler.id.clone(),
+                    choice: v.choice.into(),
+                    reason: v.reason.clone(),
+                    voting_power: v.weight.into(),
+                    proposal_active: proposal.timeend > now,
+                })
+            })
+            .collect();
+
+        report.votes += votes.len();
+
+        results.push(VoteResult {
+            voter_address: voter.address.clone(),
+            success: true,
+            votes,
+        });
+    }
+
+    upsert_votes(&results, db, dao_handler).await?;
+
+    event!(
+        Level::INFO,
+        dao_handler_id = dao_handler.id,
+        logs = report.logs,
+        proposals = report.proposals,
+        votes = report.votes,
+        skipped = report.skipped,
+        "replayed logs"
+    );
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{derive, support_choice, title_from_description, LogEntry};
+
+    fn entry(event: &str, args: serde_json::Value, block: i64) -> LogEntry {
+        LogEntry {
+            event: event.into(),
+            args,
+            block,
+            timestamp: Some(1_700_000_000),
+        }
+    }
+
+    #[test]
+    fn keeps_abstain_apart_from_against() {
+        assert_eq!(support_choice(&json!("0")), Some(2));
+        assert_eq!(support_choice(&json!("1")), Some(1));
+        assert_eq!(support_choice(&json!("2")), Some(3));
+        assert_eq!(support_choice(&json!(false)), Some(2));
+        assert_eq!(support_choice(&json!("7")), None);
+    }
+
+    #[test]
+    fn strips_markdown_heading() {
+        assert_eq!(title_from_description("# Upgrade\nbody"), "Upgrade");
+        assert_eq!(title_from_description(""), "Unknown");
+    }
+
+    #[test]
+    fn folds_lifecycle_and_votes() {
+        let logs = vec![
+            entry(
+                "ProposalCreated",
+                json!({"id": "12", "description": "# Fund grants\n...", "startBlock": "110", "endBlock": "200"}),
+                100,
+            ),
+            entry(
+                "VoteCast",
+                json!({"voter": "0xAB", "proposalId": "12", "support": "2", "votes": "5", "reason": ""}),
+                120,
+            ),
+            entry(
+                "VoteCast",
+                json!({"voter": "0xab", "proposalId": "12", "support": "1", "votes": "7", "reason": "changed"}),
+                130,
+            ),
+            entry("ProposalQueued", json!({"id": "12", "eta": "0"}), 210),
+        ];
+
+        let (proposals, votes) = derive(&logs);
+
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].name, "Fund grants");
+        assert_eq!(proposals[0].end_block, 200);
+        assert!(proposals[0].queued && !proposals[0].executed);
+
+        assert_eq!(votes.len(), 1);
+        assert_eq!(votes[0].choice, 1);
+        assert_eq!(votes[0].weight, 7);
+        assert_eq!(votes[0].reason, "changed");
+    }
+}
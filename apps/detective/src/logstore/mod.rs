@@ -0,0 +1,489 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use ethers::{
+    abi::{Event, RawLog, Token},
+    contract::{EthEvent, LogMeta},
+    providers::{Http, Middleware, Provider},
+    types::{Address, Filter, Log, H256},
+};
+use metrics::counter;
+use prisma_client_rust::{
+    chrono::{TimeZone, Utc},
+    Direction,
+};
+use serde_json::{Map, Value};
+use tracing::{event, instrument, Level};
+
+use crate::{
+    abis,
+    daohandler_with_dao,
+    prisma::{chainlog, daohandler, proposal, DaoHandlerType, PrismaClient},
+    Chain,
+    Context,
+};
+
+pub mod derive;
+
+const BACKFILL_INTERVAL_SECONDS: u64 = 30;
+// blocks per backfill step for handlers without a refresh speed
+const BACKFILL_RANGE: i64 = 10_000;
+
+/// Contract addresses of a handler, every decoder field named `address*`.
+pub fn decoder_addresses(decoder: &Value) -> Vec<Address> {
+    match decoder.as_object() {
+        Some(fields) => fields
+            .iter()
+            .filter(|(k, _)| k.starts_with("address"))
+            .filter_map(|(_, v)| v.as_str()?.parse::<Address>().ok())
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Json form of a decoded argument. Integers become decimal strings so nothing loses
+/// precision, addresses and bytes are lowercase hex.
+pub fn token_to_json(token: &Token) -> Value {
+    match token {
+        Token::Address(a) => Value::String(format!("{:#x}", a)),
+        Token::FixedBytes(b) | Token::Bytes(b) => {
+            Value::String(format!("0x{}", ethers::utils::hex::encode(b)))
+        }
+        Token::Int(i) => Value::String(ethers::types::I256::from_raw(*i).to_string()),
+        Token::Uint(u) => Value::String(u.to_string()),
+        Token::Bool(b) => Value::Bool(*b),
+        Token::String(s) => Value::String(s.clone()),
+        Token::FixedArray(t) | Token::Array(t) | Token::Tuple(t) => {
+            Value::Array(t.iter().map(token_to_json).collect())
+        }
+    }
+}
+
+/// Event name and named arguments of a log, `None` for events not in `events`.
+pub fn decode_log(events: &HashMap<H256, Event>, log: &Log) -> Option<(String, Value)> {
+    let event = events.get(log.topics.first()?)?;
+
+    let parsed = event
+        .parse_log(RawLog {
+            topics: log.topics.clone(),
+            data: log.data.to_vec(),
+        })
+        .ok()?;
+
+    let args: Map<String, Value> = parsed
+        .params
+        .into_iter()
+        .map(|p| (p.name, token_to_json(&p.value)))
+        .collect();
+
+    Some((event.name.clone(), Value::Object(args)))
+}
+
+/// A log the handler's abis know, with its decoded arguments.
+#[derive(Debug, Clone)]
+pub struct DecodedLog {
+    pub log: Log,
+    pub event: String,
+    pub args: Value,
+    pub block: i64,
+    pub timestamp: Option<i64>,
+}
+
+/// Decodes `logs` against `events`, dropping the ones it does not know. Proposal and
+/// poll events get their block timestamp, votes are many and only need the block.
+pub async fn decode_logs(
+    rpc: &Arc<Provider<Http>>,
+    events: &HashMap<H256, Event>,
+    logs: Vec<Log>,
+) -> Result<Vec<DecodedLog>> {
+    let mut timestamps: HashMap<u64, Option<i64>> = HashMap::new();
+    let mut decoded = vec![];
+
+    for log in logs {
+        let (event, args) = match decode_log(events, &log) {
+            Some(d) => d,
+            None => continue,
+        };
+
+        let block_number = log.block_number.unwrap_or_default().as_u64();
+
+        let timestamp = if event.starts_with("Proposal") || event.starts_with("Poll") {
+            match timestamps.get(&block_number) {
+                Some(t) => *t,
+                None => {
+                    let block = rpc.get_block(block_number).await?;
+                    let t = block.map(|b| b.timestamp.as_u64() as i64);
+                    timestamps.insert(block_number, t);
+                    t
+                }
+            }
+        } else {
+            None
+        };
+
+        decoded.push(DecodedLog {
+            log,
+            event,
+            args,
+            block: block_number as i64,
+            timestamp,
+        });
+    }
+
+    Ok(decoded)
+}
+
+/// Logs of a handler's contracts in a block range. This is the one `get_logs` a chain
+/// refresh makes, the handlers read their events out of it with `typed_events`.
+pub async fn fetch_logs(
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    from_block: i64,
+    to_block: i64,
+) -> Result<Vec<Log>> {
+    let addresses = decoder_addresses(&dao_handler.decoder);
+
+    if addresses.is_empty() || from_block > to_block {
+        return Ok(vec![]);
+    }
+
+    Ok(rpc
+        .get_logs(
+            &Filter::new()
+                .address(addresses)
+                .from_block(from_block)
+                .to_block(to_block),
+        )
+        .await?)
+}
+
+/// `E` events emitted by `address`, what `query_with_meta` returns for the range `logs`
+/// were fetched over. Anonymous events are not told apart by topic, callers filter
+/// those first.
+pub fn typed_events<E: EthEvent>(logs: &[Log], address: Address) -> Vec<(E, LogMeta)> {
+    logs.iter()
+        .filter(|l| l.address == address)
+        .filter(|l| E::is_anonymous() || l.topics.first() == Some(&E::signature()))
+        .filter_map(|l| {
+            let event = E::decode_log(&RawLog {
+                topics: l.topics.clone(),
+                data: l.data.to_vec(),
+            })
+            .ok()?;
+
+            Some((event, LogMeta::from(l)))
+        })
+        .collect()
+}
+
+/// Stores the governance logs of a handler's `from_block..=to_block` range.
+///
+/// `logindex` only moves when the range continues the stored history, a gap behind it is
+/// left for `backfill` to fill.
+#[instrument(skip_all, fields(dao_handler_id = dao_handler.id))]
+pub async fn store(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    logs: &[Log],
+    from_block: i64,
+    to_block: i64,
+) -> Result<usize> {
+    if dao_handler.r#type == DaoHandlerType::Snapshot {
+        return Ok(0);
+    }
+
+    let events = abis::events(&abis::abi_names(dao_handler.r#type, &dao_handler.decoder));
+    let chain = Chain::of(dao_handler.r#type, &dao_handler.decoder).name();
+
+    let rows = decode_logs(rpc, &events, logs.to_vec())
+        .await?
+        .into_iter()
+        .map(|d| {
+            chainlog::create_unchecked(
+                dao_handler.id.clone(),
+                chain.to_string(),
+                d.block,
+                format!("{:#x}", d.log.transaction_hash.unwrap_or_default()),
+                d.log.log_index.unwrap_or_default().as_u32() as i32,
+                format!("{:#x}", d.log.address),
+                d.event,
+                d.args,
+                vec![chainlog::blocktimestamp::set(
+                    d.timestamp.map(|t| Utc.timestamp_opt(t, 0).unwrap().into()),
+                )],
+            )
+        })
+        .collect();
+
+    let count = db
+        .chainlog()
+        .create_many(rows)
+        .skip_duplicates()
+        .exec()
+        .await?;
+
+    db.daohandler()
+        .update_many(
+            vec![
+                daohandler::id::equals(dao_handler.id.clone()),
+                daohandler::logindex::gte(from_block - 1),
+                daohandler::logindex::lt(to_block),
+            ],
+            vec![daohandler::logindex::set(to_block)],
+        )
+        .exec()
+        .await?;
+
+    if count > 0 {
+        counter!("detective_chainlogs", count as u64);
+    }
+
+    event!(
+        Level::DEBUG,
+        dao_handler_id = dao_handler.id,
+        from_block = from_block,
+        to_block = to_block,
+        count = count,
+        "stored logs"
+    );
+
+    Ok(count as usize)
+}
+
+/// Fetches the logs of a refresh range and stores them. A failed store only leaves a gap
+/// `backfill` fills later, so it does not fail the refresh.
+pub async fn capture(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    from_block: i64,
+    to_block: i64,
+) -> Result<Vec<Log>> {
+    let logs = fetch_logs(rpc, dao_handler, from_block, to_block).await?;
+
+    if let Err(e) = store(db, rpc, dao_handler, &logs, from_block, to_block).await {
+        event!(
+            Level::WARN,
+            dao_handler_id = dao_handler.id,
+            err = e.to_string(),
+            "log store error"
+        );
+    }
+
+    Ok(logs)
+}
+
+/// Stores the next range of a handler's history, from the block of its first proposal up
+/// to the refresh cursor. Returns the range, `None` when the store is caught up.
+#[instrument(skip_all, fields(dao_handler_id = dao_handler.id))]
+pub async fn backfill(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+) -> Result<Option<(i64, i64)>> {
+    let start = if dao_handler.logindex == 0 {
+        let first = db
+            .proposal()
+            .find_first(vec![proposal::daohandlerid::equals(dao_handler.id.clone())])
+            .order_by(proposal::blockcreated::order(Direction::Asc))
+            .exec()
+            .await?
+            .and_then(|p| p.blockcreated);
+
+        match first {
+            Some(block) => block,
+            None => return Ok(None),
+        }
+    } else {
+        dao_handler.logindex + 1
+    };
+
+    let range = dao_handler.refreshspeed.unwrap_or(BACKFILL_RANGE).max(1);
+    let end = dao_handler.chainindex.min(start + range - 1);
+
+    if start > end {
+        return Ok(None);
+    }
+
+    let logs = fetch_logs(rpc, dao_handler, start, end).await?;
+    store(db, rpc, dao_handler, &logs, start, end).await?;
+
+    // the first range starts the history, `store` only extends it
+    if dao_handler.logindex == 0 {
+        db.daohandler()
+            .update_many(
+                vec![
+                    daohandler::id::equals(dao_handler.id.clone()),
+                    daohandler::logindex::equals(0),
+                ],
+                vec![daohandler::logindex::set(end)],
+            )
+            .exec()
+            .await?;
+    }
+
+    Ok(Some((start, end)))
+}
+
+pub async fn run_schedule(ctx: Context) {
+    let mut interval =
+        tokio::time::interval(tokio::time::Duration::from_secs(BACKFILL_INTERVAL_SECONDS));
+
+    loop {
+        interval.tick().await;
+
+        let dao_handlers = match ctx
+            .db
+            .daohandler()
+            .find_many(vec![
+                daohandler::r#type::not(DaoHandlerType::Snapshot),
+                daohandler::refreshpaused::equals(false),
+            ])
+            .include(daohandler_with_dao::include())
+            .exec()
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => {
+                event!(Level::WARN, err = e.to_string(), "log backfill error");
+                continue;
+            }
+        };
+
+        for dao_handler in dao_handlers {
+            let rpc = ctx.rpc_for(dao_handler.r#type, &dao_handler.decoder);
+
+            match backfill(&ctx.db, rpc, &dao_handler).await {
+                Ok(Some((from_block, to_block))) => event!(
+                    Level::DEBUG,
+                    dao_handler_id = dao_handler.id,
+                    from_block = from_block,
+                    to_block = to_block,
+                    "backfilled logs"
+                ),
+                Ok(None) => {}
+                Err(e) => event!(
+                    Level::WARN,
+                    dao_handler_id = dao_handler.id,
+                    err = e.to_string(),
+                    "log backfill error"
+                ),
+            }
+        }
+    }
+}
+
+/// Every stored log of a handler in chain order.
+pub async fn stored_logs(
+    db: &Arc<PrismaClient>,
+    dao_handler_id: &str,
+) -> Result<Vec<chainlog::Data>> {
+    Ok(db
+        .chainlog()
+        .find_many(vec![chainlog::daohandlerid::equals(
+            dao_handler_id.to_string(),
+        )])
+        .order_by(chainlog::blocknumber::order(Direction::Asc))
+        .order_by(chainlog::logindex::order(Direction::Asc))
+        .exec()
+        .await?)
+}
+
+/// Stored logs of a handler in `from_block..to_block`, for reading the history a range
+/// builds on without loading all of it.
+pub async fn stored_logs_between(
+    db: &Arc<PrismaClient>,
+    dao_handler_id: &str,
+    from_block: i64,
+    to_block: i64,
+) -> Result<Vec<chainlog::Data>> {
+    Ok(db
+        .chainlog()
+        .find_many(vec![
+            chainlog::daohandlerid::equals(dao_handler_id.to_string()),
+            chainlog::blocknumber::gte(from_block),
+            chainlog::blocknumber::lt(to_block),
+        ])
+        .order_by(chainlog::blocknumber::order(Direction::Asc))
+        .order_by(chainlog::logindex::order(Direction::Asc))
+        .exec()
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{
+        abi::{encode, Token},
+        contract::EthEvent,
+        types::{Address, Log, H256, U256, U64},
+    };
+    use serde_json::json;
+
+    use super::{decoder_addresses, token_to_json, typed_events};
+    use crate::contracts::compoundgov::compoundgov::VoteCastFilter;
+
+    #[test]
+    fn reads_every_address_field() {
+        let decoder = json!({
+            "address_create": "0xf9be8f0945acddeedaa64dfca5fe9629d0cf8e5d",
+            "address_vote": "0xd3a9fe267852281a1e6307a1c37cdfd76d39b133",
+            "proposalUrl": "https://vote.makerdao.com/polling/",
+        });
+
+        assert_eq!(decoder_addresses(&decoder).len(), 2);
+        assert!(decoder_addresses(&json!("nope")).is_empty());
+    }
+
+    #[test]
+    fn keeps_integer_precision() {
+        let big = U256::from_dec_str("340282366920938463463374607431768211457").unwrap();
+
+        assert_eq!(
+            token_to_json(&Token::Uint(big)),
+            json!("340282366920938463463374607431768211457")
+        );
+        assert_eq!(
+            token_to_json(&Token::Array(vec![
+                Token::Address(Address::zero()),
+                Token::Bool(true)
+            ])),
+            json!(["0x0000000000000000000000000000000000000000", true])
+        );
+    }
+
+    #[test]
+    fn reads_typed_events_of_one_contract() {
+        let governor = Address::repeat_byte(1);
+        let voter = Address::repeat_byte(2);
+
+        let vote = Log {
+            address: governor,
+            topics: vec![VoteCastFilter::signature(), H256::from(voter)],
+            data: encode(&[
+                Token::Uint(U256::from(7)),
+                Token::Uint(U256::from(1)),
+                Token::Uint(U256::from(100)),
+                Token::String("".into()),
+            ])
+            .into(),
+            block_number: Some(U64::from(10)),
+            block_hash: Some(H256::zero()),
+            transaction_hash: Some(H256::zero()),
+            transaction_index: Some(U64::zero()),
+            log_index: Some(U256::zero()),
+            ..Default::default()
+        };
+        let elsewhere = Log {
+            address: Address::repeat_byte(3),
+            ..vote.clone()
+        };
+
+        let votes = typed_events::<VoteCastFilter>(&[vote, elsewhere], governor);
+
+        assert_eq!(votes.len(), 1);
+        assert_eq!(votes[0].0.voter, voter);
+        assert_eq!(votes[0].0.proposal_id, U256::from(7));
+        assert_eq!(votes[0].1.block_number, U64::from(10));
+    }
+}
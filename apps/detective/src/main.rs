@@ -15,32 +15,55 @@ use dotenv::dotenv;
 use ethers::providers::{Http, Provider};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use rocket::{Build, Rocket};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{debug_span, event, instrument, Instrument, Level};
 
-use prisma::{daohandler, proposal, voterhandler, PrismaClient};
-use utils::{maker_polls_sanity::maker_polls_sanity_check, snapshot_sanity::snapshot_sanity_check};
+use prisma::{daohandler, proposal, voterhandler, DaoHandlerType, PrismaClient};
 
 use crate::router::{
+    abis::{get_abi, list_abis, register_abi, reload_abis},
     chain_proposals::update_chain_proposals,
     chain_votes::update_chain_votes,
+    discovery::discover_governor,
+    family::get_proposal_family,
+    logs::replay_dao_handler,
+    proposal_history::get_proposal_history,
+    reconcile::reconcile_dao_handler,
+    simulation::get_proposal_simulation,
     snapshot_proposals::update_snapshot_proposals,
     snapshot_votes::update_snapshot_votes,
+    treasury::get_treasury,
+    voters::get_voter_ens,
 };
 
+mod abis;
 pub mod contracts;
+#[cfg(test)]
+mod devnet;
+mod discovery;
+mod ens;
+mod family;
 pub mod handlers;
+mod logstore;
+mod membership;
 pub mod prisma;
+mod reconcile;
 mod router;
+mod simulation;
 mod telemetry;
+mod treasury;
 
 pub mod utils {
     pub mod arbriscan;
+    pub mod content;
+    pub mod erc20;
     pub mod etherscan;
-    pub mod maker_polls_sanity;
     pub mod optimiscan;
-    pub mod snapshot_sanity;
+    pub mod proposal_history;
+    pub mod safe;
+    pub mod vote_history;
 }
 
 #[derive(Clone, Debug)]
@@ -51,6 +74,69 @@ pub struct Context {
     pub optimism_rpc: Arc<Provider<Http>>,
 }
 
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum Chain {
+    Ethereum,
+    Arbitrum,
+    Optimism,
+}
+
+impl Chain {
+    pub fn parse(name: &str) -> Option<Chain> {
+        match name.to_lowercase().as_str() {
+            "ethereum" | "mainnet" | "eth" => Some(Chain::Ethereum),
+            "arbitrum" | "arb" => Some(Chain::Arbitrum),
+            "optimism" | "op" => Some(Chain::Optimism),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Chain::Ethereum => "ethereum",
+            Chain::Arbitrum => "arbitrum",
+            Chain::Optimism => "optimism",
+        }
+    }
+
+    pub fn for_handler(handler_type: DaoHandlerType) -> Chain {
+        match handler_type {
+            DaoHandlerType::MakerPollArbitrum
+            | DaoHandlerType::ArbitrumCoreChain
+            | DaoHandlerType::ArbitrumTreasuryChain => Chain::Arbitrum,
+            DaoHandlerType::OptimismChain => Chain::Optimism,
+            _ => Chain::Ethereum,
+        }
+    }
+
+    /// Chain a handler reads from. `GENERIC_CHAIN` handlers name it in the decoder.
+    pub fn of(handler_type: DaoHandlerType, decoder: &Value) -> Chain {
+        match handler_type {
+            DaoHandlerType::GenericChain => decoder
+                .get("chain")
+                .and_then(|c| c.as_str())
+                .and_then(Chain::parse)
+                .unwrap_or(Chain::Ethereum),
+            _ => Chain::for_handler(handler_type),
+        }
+    }
+}
+
+impl Context {
+    pub fn rpc_for(&self, handler_type: DaoHandlerType, decoder: &Value) -> &Arc<Provider<Http>> {
+        self.rpc_for_chain(Chain::of(handler_type, decoder))
+    }
+
+    pub fn rpc_for_chain(&self, chain: Chain) -> &Arc<Provider<Http>> {
+        match chain {
+            Chain::Ethereum => &self.eth_rpc,
+            Chain::Arbitrum => &self.arbitrum_rpc,
+            Chain::Optimism => &self.optimism_rpc,
+        }
+    }
+}
+
 pub type Ctx = rocket::State<Context>;
 
 #[allow(non_snake_case)]
@@ -128,23 +214,40 @@ async fn rocket() -> _ {
 
     let context_clone = context.clone();
 
-    tokio::spawn(async move {
-        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60 * 5));
-        loop {
-            interval.tick().await;
+    tokio::spawn(reconcile::run_schedule(context_clone));
+    tokio::spawn(abis::run_schedule(context.clone()));
+    tokio::spawn(ens::run_schedule(context.clone()));
+    tokio::spawn(treasury::run_schedule(context.clone()));
+    tokio::spawn(membership::run_schedule(context.clone()));
+    tokio::spawn(logstore::run_schedule(context.clone()));
 
-            let _ = maker_polls_sanity_check(&context_clone).await;
-            let _ = snapshot_sanity_check(&context_clone).await;
-        }
-    });
+    app(context)
+}
 
+/// Routes and state without the background schedules.
+fn app(context: Context) -> Rocket<Build> {
     rocket::build()
         .manage(context)
         .mount("/", routes![index])
         .mount("/health", routes![health])
         .mount(
             "/proposals",
-            routes![update_snapshot_proposals, update_chain_proposals],
+            routes![
+                update_snapshot_proposals,
+                update_chain_proposals,
+                get_proposal_history,
+                get_proposal_simulation,
+                get_proposal_family
+            ],
         )
         .mount("/votes", routes![update_chain_votes, update_snapshot_votes])
+        .mount("/reconcile", routes![reconcile_dao_handler])
+        .mount("/logs", routes![replay_dao_handler])
+        .mount("/discover", routes![discover_governor])
+        .mount("/voters", routes![get_voter_ens])
+        .mount("/treasury", routes![get_treasury])
+        .mount(
+            "/abis",
+            routes![list_abis, get_abi, register_abi, reload_abis],
+        )
 }
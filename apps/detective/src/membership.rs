@@ -0,0 +1,69 @@
+//! Detective instances announce themselves in `detectiveinstance`, the refresher shards
+//! dao handlers across the ones it has heard from recently.
+//!
+//! An instance registers when `$DETECTIVE_PUBLIC_URL` is set. `$DETECTIVE_POOL` puts it
+//! in a pool: `arbitrum`, `optimism`, `ethereum` or `snapshot` take only that chain's
+//! handlers, `default` takes whatever has no pool of its own.
+
+use std::env;
+
+use anyhow::Result;
+use prisma_client_rust::chrono::{DateTime, FixedOffset, Utc};
+use tracing::{event, Level};
+
+use crate::{prisma::detectiveinstance, Context};
+
+const HEARTBEAT_SECONDS: u64 = 10;
+
+async fn heartbeat(ctx: &Context, id: &str, url: &str, pool: &str) -> Result<()> {
+    let now: DateTime<FixedOffset> = Utc::now().into();
+
+    ctx.db
+        .detectiveinstance()
+        .upsert(
+            detectiveinstance::id::equals(id.to_string()),
+            detectiveinstance::create(
+                id.to_string(),
+                url.to_string(),
+                vec![
+                    detectiveinstance::pool::set(pool.to_string()),
+                    detectiveinstance::lastseen::set(now),
+                ],
+            ),
+            vec![
+                detectiveinstance::url::set(url.to_string()),
+                detectiveinstance::pool::set(pool.to_string()),
+                detectiveinstance::lastseen::set(now),
+            ],
+        )
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+pub async fn run_schedule(ctx: Context) {
+    let Ok(url) = env::var("DETECTIVE_PUBLIC_URL") else {
+        return;
+    };
+    let pool = env::var("DETECTIVE_POOL").unwrap_or_else(|_| "default".to_string());
+    let id = env::var("DETECTIVE_INSTANCE").unwrap_or_else(|_| url.clone());
+
+    event!(
+        Level::INFO,
+        instance = id,
+        url = url,
+        pool = pool,
+        "registering"
+    );
+
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(HEARTBEAT_SECONDS));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = heartbeat(&ctx, &id, &url, &pool).await {
+            event!(Level::WARN, err = e.to_string(), "heartbeat error");
+        }
+    }
+}
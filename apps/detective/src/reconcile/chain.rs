@@ -0,0 +1,260 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use ethers::providers::Middleware;
+use tracing::{event, instrument, Level};
+
+use super::{MissingVote, ReconcileReport, StateMismatch};
+use crate::{
+    daohandler_with_dao,
+    logstore::fetch_logs,
+    prisma::{proposal, vote, voterhandler, DaoHandlerType},
+    router::{
+        chain_proposals::{fetch_proposals, upsert_proposals, ChainProposal},
+        chain_votes::{fetch_votes, upsert_votes, VoteResult},
+    },
+    utils::{arbriscan, etherscan, optimiscan},
+    voterhandler_with_voter,
+    Context,
+};
+
+const VOTERS_BATCH: usize = 100;
+/// Blocks per call for handlers the refresher has not sized a range for yet.
+const FALLBACK_RANGE: i64 = 10_000;
+
+/// `from_block..=to_block` in ranges of at most `range` blocks, the most a live refresh
+/// of the handler asks for.
+fn chunks(from_block: i64, to_block: i64, range: i64) -> Vec<(i64, i64)> {
+    let range = range.max(1);
+
+    (from_block..=to_block)
+        .step_by(range as usize)
+        .map(|start| (start, (start + range - 1).min(to_block)))
+        .collect()
+}
+
+/// Merges the results of one voter batch over consecutive ranges.
+fn merge(results: &mut Vec<VoteResult>, chunk: Vec<VoteResult>) {
+    for result in chunk {
+        match results
+            .iter_mut()
+            .find(|r| r.voter_address == result.voter_address)
+        {
+            Some(merged) => {
+                merged.success &= result.success;
+                merged.votes.extend(result.votes);
+            }
+            None => results.push(result),
+        }
+    }
+}
+
+pub(crate) async fn estimate_block_for(
+    handler_type: DaoHandlerType,
+    timestamp: i64,
+) -> Result<i64> {
+    match handler_type {
+        DaoHandlerType::MakerPollArbitrum
+        | DaoHandlerType::ArbitrumCoreChain
+        | DaoHandlerType::ArbitrumTreasuryChain => arbriscan::estimate_block(timestamp).await,
+        DaoHandlerType::OptimismChain => optimiscan::estimate_block(timestamp).await,
+        _ => etherscan::estimate_block(timestamp).await,
+    }
+}
+
+#[instrument(skip_all)]
+pub async fn reconcile(
+    ctx: &Context,
+    dao_handler: &daohandler_with_dao::Data,
+    window: Duration,
+    repair: bool,
+) -> Result<ReconcileReport> {
+    let rpc = ctx.rpc_for(dao_handler.r#type, &dao_handler.decoder);
+
+    let current_block = rpc.get_block_number().await?.as_u64() as i64;
+
+    let to_block = current_block - 10;
+    let from_block = estimate_block_for(dao_handler.r#type, (Utc::now() - window).timestamp())
+        .await?
+        .min(to_block);
+
+    let mut report = ReconcileReport::new(dao_handler, from_block, to_block);
+
+    if dao_handler.r#type != DaoHandlerType::MakerPollArbitrum {
+        let range = dao_handler.refreshspeed.unwrap_or(FALLBACK_RANGE);
+
+        let mut chain_proposals = vec![];
+        for (from_block, to_block) in chunks(from_block, to_block, range) {
+            let logs = fetch_logs(rpc, dao_handler, from_block, to_block).await?;
+            chain_proposals.extend(
+                fetch_proposals(&ctx.db, rpc, dao_handler, &logs, from_block, to_block).await?,
+            );
+        }
+
+        reconcile_proposals(ctx, dao_handler, chain_proposals, repair, &mut report).await?;
+    }
+
+    reconcile_votes(ctx, dao_handler, from_block, to_block, repair, &mut report).await?;
+
+    Ok(report)
+}
+
+#[instrument(skip_all)]
+async fn reconcile_proposals(
+    ctx: &Context,
+    dao_handler: &daohandler_with_dao::Data,
+    chain_proposals: Vec<ChainProposal>,
+    repair: bool,
+    report: &mut ReconcileReport,
+) -> Result<()> {
+    let stored = ctx
+        .db
+        .proposal()
+        .find_many(vec![
+            proposal::daohandlerid::equals(dao_handler.id.clone()),
+            proposal::externalid::in_vec(
+                chain_proposals
+                    .iter()
+                    .map(|p| p.external_id.clone())
+                    .collect(),
+            ),
+        ])
+        .exec()
+        .await?;
+
+    let mut to_repair = vec![];
+
+    for p in chain_proposals {
+        match stored.iter().find(|s| s.externalid == p.external_id) {
+            None => {
+                report.missing_proposals.push(p.external_id.clone());
+                to_repair.push(p);
+            }
+            Some(s) if s.state != p.state => {
+                report.state_mismatches.push(StateMismatch {
+                    proposal_external_id: p.external_id.clone(),
+                    stored: s.state,
+                    actual: p.state,
+                });
+                to_repair.push(p);
+            }
+            Some(_) => {}
+        }
+    }
+
+    if repair && !to_repair.is_empty() {
+        event!(
+            Level::INFO,
+            dao_handler_id = dao_handler.id,
+            proposals = to_repair.len(),
+            "repair proposals"
+        );
+        upsert_proposals(&to_repair, &ctx.db, dao_handler, false).await?;
+        report.repaired = true;
+    }
+
+    Ok(())
+}
+
+#[instrument(skip_all)]
+async fn reconcile_votes(
+    ctx: &Context,
+    dao_handler: &daohandler_with_dao::Data,
+    from_block: i64,
+    to_block: i64,
+    repair: bool,
+    report: &mut ReconcileReport,
+) -> Result<()> {
+    let rpc = ctx.rpc_for(dao_handler.r#type, &dao_handler.decoder);
+
+    let voters: Vec<String> = ctx
+        .db
+        .voterhandler()
+        .find_many(vec![voterhandler::daohandlerid::equals(
+            dao_handler.id.clone(),
+        )])
+        .include(voterhandler_with_voter::include())
+        .exec()
+        .await?
+        .into_iter()
+        .map(|vh| vh.voter.address)
+        .collect();
+
+    let batches: Vec<&[String]> = voters.chunks(VOTERS_BATCH).collect();
+    let mut batch_results: Vec<Vec<VoteResult>> = vec![vec![]; batches.len()];
+
+    let range = dao_handler.votersrefreshspeed.unwrap_or(FALLBACK_RANGE);
+
+    // logs are not filtered by voter, every batch reads the same fetch of a range
+    for (from_block, to_block) in chunks(from_block, to_block, range) {
+        let logs = fetch_logs(rpc, dao_handler, from_block, to_block).await?;
+
+        for (batch, results) in batches.iter().zip(batch_results.iter_mut()) {
+            merge(
+                results,
+                fetch_votes(&ctx.db, rpc, dao_handler, &logs, to_block, batch.to_vec()).await?,
+            );
+        }
+    }
+
+    for (batch, results) in batches.into_iter().zip(batch_results) {
+        let stored = ctx
+            .db
+            .vote()
+            .find_many(vec![
+                vote::daohandlerid::equals(dao_handler.id.clone()),
+                vote::voteraddress::in_vec(batch.to_vec()),
+            ])
+            .exec()
+            .await?;
+
+        let missing: Vec<MissingVote> = results
+            .iter()
+            .filter(|r| r.success)
+            .flat_map(|r| r.votes.iter())
+            .filter(|v| {
+                !stored.iter().any(|s| {
+                    s.proposalid == v.proposal_id
+                        && s.voteraddress.to_lowercase() == v.voter_address.to_lowercase()
+                })
+            })
+            .map(|v| MissingVote {
+                voter_address: v.voter_address.clone(),
+                proposal_id: v.proposal_id.clone(),
+            })
+            .collect();
+
+        if repair && !missing.is_empty() {
+            event!(
+                Level::INFO,
+                dao_handler_id = dao_handler.id,
+                votes = missing.len(),
+                "repair votes"
+            );
+            upsert_votes(&results, &ctx.db, dao_handler).await?;
+            report.repaired = true;
+        }
+
+        report.missing_votes.extend(missing);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chunks;
+
+    #[test]
+    fn chunks_cover_the_window_once() {
+        assert_eq!(
+            chunks(100, 350, 100),
+            vec![(100, 199), (200, 299), (300, 350)]
+        );
+        assert_eq!(chunks(100, 100, 100), vec![(100, 100)]);
+        assert_eq!(
+            chunks(100, 102, 0),
+            vec![(100, 100), (101, 101), (102, 102)]
+        );
+        assert!(chunks(200, 100, 100).is_empty());
+    }
+}
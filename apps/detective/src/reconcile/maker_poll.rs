@@ -0,0 +1,82 @@
+use anyhow::Result;
+use chrono::Duration;
+use ethers::types::Address;
+use serde::Deserialize;
+use tracing::instrument;
+
+use super::{chain, ReconcileReport};
+use crate::{
+    contracts::makerpollcreate::{self, PollWithdrawnFilter},
+    daohandler_with_dao,
+    prisma::proposal,
+    Context,
+};
+
+#[derive(Debug, Deserialize)]
+struct Decoder {
+    address_create: String,
+}
+
+#[instrument(skip_all)]
+pub async fn reconcile(
+    ctx: &Context,
+    dao_handler: &daohandler_with_dao::Data,
+    window: Duration,
+    repair: bool,
+) -> Result<ReconcileReport> {
+    let mut report = chain::reconcile(ctx, dao_handler, window, repair).await?;
+
+    let decoder: Decoder = serde_json::from_value(dao_handler.decoder.clone())?;
+
+    let address = decoder
+        .address_create
+        .parse::<Address>()
+        .expect("bad address");
+
+    let gov_contract =
+        makerpollcreate::makerpollcreate::makerpollcreate::new(address, ctx.eth_rpc.clone());
+
+    let events = gov_contract
+        .poll_withdrawn_filter()
+        .from_block(report.window_from)
+        .to_block(report.window_to);
+
+    let withdrawn_proposals: Vec<PollWithdrawnFilter> = events.query().await?;
+
+    let hidden = ctx
+        .db
+        .proposal()
+        .find_many(vec![
+            proposal::externalid::in_vec(
+                withdrawn_proposals
+                    .iter()
+                    .map(|p| p.poll_id.to_string())
+                    .collect(),
+            ),
+            proposal::daohandlerid::equals(dao_handler.id.clone()),
+            proposal::visible::equals(true),
+        ])
+        .exec()
+        .await?;
+
+    report
+        .removed_proposals
+        .extend(hidden.iter().map(|p| p.externalid.clone()));
+
+    if repair && !hidden.is_empty() {
+        ctx.db
+            .proposal()
+            .update_many(
+                vec![proposal::id::in_vec(
+                    hidden.iter().map(|p| p.id.clone()).collect(),
+                )],
+                vec![proposal::visible::set(false)],
+            )
+            .exec()
+            .await?;
+
+        report.repaired = true;
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use metrics::counter;
+use serde::Serialize;
+use tracing::{event, instrument, Level};
+
+use crate::{
+    daohandler_with_dao,
+    prisma::{proposal, DaoHandlerType, ProposalState},
+    Context,
+};
+
+pub mod chain;
+pub mod maker_poll;
+pub mod snapshot;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct StateMismatch {
+    pub proposal_external_id: String,
+    pub stored: ProposalState,
+    pub actual: ProposalState,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct MissingVote {
+    pub voter_address: String,
+    pub proposal_id: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct ReconcileReport {
+    pub dao_handler_id: String,
+    pub dao_handler_type: String,
+    pub window_from: i64,
+    pub window_to: i64,
+    pub repaired: bool,
+    pub missing_proposals: Vec<String>,
+    pub removed_proposals: Vec<String>,
+    pub state_mismatches: Vec<StateMismatch>,
+    pub missing_votes: Vec<MissingVote>,
+}
+
+impl ReconcileReport {
+    fn new(dao_handler: &daohandler_with_dao::Data, window_from: i64, window_to: i64) -> Self {
+        ReconcileReport {
+            dao_handler_id: dao_handler.id.clone(),
+            dao_handler_type: dao_handler.r#type.to_string(),
+            window_from,
+            window_to,
+            repaired: false,
+            missing_proposals: vec![],
+            removed_proposals: vec![],
+            state_mismatches: vec![],
+            missing_votes: vec![],
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.missing_proposals.is_empty()
+            && self.removed_proposals.is_empty()
+            && self.state_mismatches.is_empty()
+            && self.missing_votes.is_empty()
+    }
+
+    fn record_metrics(&self) {
+        counter!(
+            "detective_reconcile_missing_proposals",
+            self.missing_proposals.len() as u64,
+            "dao_handler_type" => self.dao_handler_type.clone()
+        );
+        counter!(
+            "detective_reconcile_removed_proposals",
+            self.removed_proposals.len() as u64,
+            "dao_handler_type" => self.dao_handler_type.clone()
+        );
+        counter!(
+            "detective_reconcile_state_mismatches",
+            self.state_mismatches.len() as u64,
+            "dao_handler_type" => self.dao_handler_type.clone()
+        );
+        counter!(
+            "detective_reconcile_missing_votes",
+            self.missing_votes.len() as u64,
+            "dao_handler_type" => self.dao_handler_type.clone()
+        );
+    }
+}
+
+pub fn default_window(handler_type: DaoHandlerType) -> Duration {
+    match handler_type {
+        DaoHandlerType::Snapshot => Duration::days(90),
+        _ => Duration::days(30),
+    }
+}
+
+pub fn default_interval(handler_type: DaoHandlerType) -> Duration {
+    match handler_type {
+        DaoHandlerType::Snapshot | DaoHandlerType::MakerPoll => Duration::minutes(5),
+        _ => Duration::hours(1),
+    }
+}
+
+#[instrument(skip(ctx, dao_handler), fields(dao_handler_id = dao_handler.id))]
+pub async fn reconcile_handler(
+    ctx: &Context,
+    dao_handler: &daohandler_with_dao::Data,
+    window: Duration,
+    repair: bool,
+) -> Result<ReconcileReport> {
+    let report = match dao_handler.r#type {
+        DaoHandlerType::Snapshot => snapshot::reconcile(ctx, dao_handler, window, repair).await?,
+        DaoHandlerType::MakerPoll => {
+            maker_poll::reconcile(ctx, dao_handler, window, repair).await?
+        }
+        _ => chain::reconcile(ctx, dao_handler, window, repair).await?,
+    };
+
+    report.record_metrics();
+
+    event!(
+        Level::INFO,
+        dao_name = dao_handler.dao.name,
+        dao_handler_type = report.dao_handler_type,
+        dao_handler_id = report.dao_handler_id,
+        missing_proposals = report.missing_proposals.len(),
+        removed_proposals = report.removed_proposals.len(),
+        state_mismatches = report.state_mismatches.len(),
+        missing_votes = report.missing_votes.len(),
+        repaired = report.repaired,
+        "reconcile report"
+    );
+
+    Ok(report)
+}
+
+/// Chain handlers are only reconciled on schedule while they have a proposal that is open
+/// or ended within `window`, scanning every block of the window is not worth it for a dao
+/// that has been quiet for a month.
+async fn scheduled(
+    ctx: &Context,
+    dao_handler: &daohandler_with_dao::Data,
+    window: Duration,
+) -> Result<bool> {
+    if dao_handler.r#type == DaoHandlerType::Snapshot {
+        return Ok(true);
+    }
+
+    Ok(ctx
+        .db
+        .proposal()
+        .find_first(vec![
+            proposal::daohandlerid::equals(dao_handler.id.clone()),
+            proposal::timeend::gte((Utc::now() - window).into()),
+        ])
+        .exec()
+        .await?
+        .is_some())
+}
+
+pub async fn run_schedule(ctx: Context) {
+    let mut last_run: HashMap<String, DateTime<Utc>> = HashMap::new();
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60 * 5));
+
+    loop {
+        interval.tick().await;
+
+        let dao_handlers = match ctx
+            .db
+            .daohandler()
+            .find_many(vec![])
+            .include(daohandler_with_dao::include())
+            .exec()
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                event!(Level::WARN, err = e.to_string(), "reconcile schedule error");
+                continue;
+            }
+        };
+
+        for dao_handler in dao_handlers {
+            let due = last_run.get(&dao_handler.id).map_or(true, |t| {
+                Utc::now() - *t >= default_interval(dao_handler.r#type)
+            });
+
+            if !due {
+                continue;
+            }
+
+            last_run.insert(dao_handler.id.clone(), Utc::now());
+
+            let window = default_window(dao_handler.r#type);
+
+            let result = match scheduled(&ctx, &dao_handler, window).await {
+                Ok(true) => reconcile_handler(&ctx, &dao_handler, window, true)
+                    .await
+                    .map(|_| ()),
+                Ok(false) => Ok(()),
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = result {
+                counter!("detective_reconcile_errors", 1);
+                event!(
+                    Level::WARN,
+                    dao_handler_id = dao_handler.id,
+                    err = e.to_string(),
+                    "reconcile error"
+                );
+            }
+        }
+    }
+}
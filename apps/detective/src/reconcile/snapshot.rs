@@ -0,0 +1,430 @@
+use std::env;
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDateTime, Utc};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use serde::Deserialize;
+use tracing::{event, instrument, Level};
+
+use super::{MissingVote, ReconcileReport, StateMismatch};
+use crate::{
+    daohandler_with_dao,
+    prisma::{daohandler, proposal, vote, voterhandler, ProposalState},
+    router::snapshot_proposals::snapshot_state,
+    utils::proposal_history::record_proposal_snapshot,
+    voterhandler_with_voter,
+    Context,
+};
+
+const VOTERS_BATCH: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct Decoder {
+    space: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQLResponse<T> {
+    data: T,
+}
+
+#[derive(Deserialize, Debug)]
+struct GraphQLProposals {
+    proposals: Vec<GraphQLProposal>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GraphQLProposal {
+    id: String,
+    created: i64,
+    state: String,
+    scores_state: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct GraphQLVotes {
+    votes: Vec<GraphQLVote>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GraphQLVote {
+    voter: String,
+    created: i64,
+    proposal: Option<GraphQLVoteProposal>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GraphQLVoteProposal {
+    id: String,
+}
+
+#[instrument(skip_all)]
+pub async fn reconcile(
+    ctx: &Context,
+    dao_handler: &daohandler_with_dao::Data,
+    window: Duration,
+    repair: bool,
+) -> Result<ReconcileReport> {
+    let sanitize_from: DateTime<Utc> = Utc::now() - window;
+    let sanitize_to: DateTime<Utc> = Utc::now() - Duration::minutes(5);
+
+    let decoder: Decoder = serde_json::from_value(dao_handler.decoder.clone())
+        .with_context(|| format!("{:?} decoder not found", dao_handler.id))?;
+
+    let _snapshot_key = env::var("SNAPSHOT_API_KEY").expect("$SNAPSHOT_API_KEY is not set");
+
+    let retry_policy = ExponentialBackoff::builder().build_with_max_retries(5);
+    let http_client = ClientBuilder::new(reqwest::Client::new())
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .build();
+
+    let mut report = ReconcileReport::new(
+        dao_handler,
+        sanitize_from.timestamp(),
+        sanitize_to.timestamp(),
+    );
+
+    reconcile_proposals(
+        ctx,
+        &http_client,
+        dao_handler,
+        &decoder,
+        sanitize_from,
+        sanitize_to,
+        repair,
+        &mut report,
+    )
+    .await?;
+
+    reconcile_votes(
+        ctx,
+        &http_client,
+        dao_handler,
+        &decoder,
+        sanitize_from,
+        sanitize_to,
+        repair,
+        &mut report,
+    )
+    .await?;
+
+    Ok(report)
+}
+
+fn to_db_date(timestamp: i64) -> DateTime<FixedOffset> {
+    DateTime::from_naive_utc_and_offset(
+        NaiveDateTime::from_timestamp_millis(timestamp * 1000).expect("bad timestamp"),
+        FixedOffset::east_opt(0).unwrap(),
+    )
+}
+
+#[instrument(skip_all)]
+async fn reconcile_proposals(
+    ctx: &Context,
+    http_client: &ClientWithMiddleware,
+    dao_handler: &daohandler_with_dao::Data,
+    decoder: &Decoder,
+    sanitize_from: DateTime<Utc>,
+    sanitize_to: DateTime<Utc>,
+    repair: bool,
+    report: &mut ReconcileReport,
+) -> Result<()> {
+    let database_proposals = ctx
+        .db
+        .proposal()
+        .find_many(vec![
+            proposal::daohandlerid::equals(dao_handler.id.clone()),
+            proposal::timecreated::gte(sanitize_from.into()),
+            proposal::timecreated::lte(sanitize_to.into()),
+        ])
+        .exec()
+        .await?;
+
+    let graphql_query = format!(
+        r#"
+        {{
+            proposals (
+                first: 1000,
+                where: {{
+                    space: {:?},
+                    created_gte: {},
+                    created_lte: {},
+                }},
+                orderBy: "created",
+                orderDirection: asc
+            )
+            {{
+                id
+                created
+                state
+                scores_state
+            }}
+        }}
+    "#,
+        decoder.space,
+        sanitize_from.timestamp(),
+        sanitize_to.timestamp()
+    );
+
+    let response_data: GraphQLResponse<GraphQLProposals> = http_client
+        .get("https://hub.snapshot.org/graphql".to_string())
+        .json(&serde_json::json!({ "query": graphql_query }))
+        .send()
+        .await?
+        .json()
+        .await
+        .with_context(|| format!("bad graphql response {}", graphql_query))?;
+
+    let graph_proposals = response_data.data.proposals;
+
+    let removed: Vec<&proposal::Data> = database_proposals
+        .iter()
+        .filter(|p| p.visible && !graph_proposals.iter().any(|g| g.id == p.externalid))
+        .collect();
+
+    report
+        .removed_proposals
+        .extend(removed.iter().map(|p| p.externalid.clone()));
+
+    let missing: Vec<&GraphQLProposal> = graph_proposals
+        .iter()
+        .filter(|g| !database_proposals.iter().any(|p| p.externalid == g.id))
+        .collect();
+
+    report
+        .missing_proposals
+        .extend(missing.iter().map(|g| g.id.clone()));
+
+    let mismatched: Vec<(&proposal::Data, ProposalState)> = database_proposals
+        .iter()
+        .filter_map(|p| {
+            graph_proposals
+                .iter()
+                .find(|g| g.id == p.externalid)
+                .map(|g| (p, snapshot_state(&g.state, &g.scores_state)))
+        })
+        .filter(|(p, state)| p.state != *state)
+        .collect();
+
+    report
+        .state_mismatches
+        .extend(mismatched.iter().map(|(p, state)| StateMismatch {
+            proposal_external_id: p.externalid.clone(),
+            stored: p.state,
+            actual: *state,
+        }));
+
+    if !repair {
+        return Ok(());
+    }
+
+    if !removed.is_empty() {
+        let now: DateTime<FixedOffset> = DateTime::<FixedOffset>::from(Local::now());
+
+        for p in removed.iter() {
+            let updated = ctx
+                .db
+                .proposal()
+                .update(
+                    proposal::id::equals(p.id.clone()),
+                    vec![
+                        proposal::visible::set(false),
+                        proposal::timeend::set(now),
+                        proposal::state::set(ProposalState::Canceled),
+                    ],
+                )
+                .exec()
+                .await?;
+
+            record_proposal_snapshot(&ctx.db, &updated).await?;
+        }
+
+        report.repaired = true;
+    }
+
+    for (p, state) in mismatched {
+        let updated = ctx
+            .db
+            .proposal()
+            .update(
+                proposal::id::equals(p.id.clone()),
+                vec![proposal::state::set(state)],
+            )
+            .exec()
+            .await?;
+
+        record_proposal_snapshot(&ctx.db, &updated).await?;
+
+        report.repaired = true;
+    }
+
+    // rewinding the index lets the regular refresh pick the missing proposals up again
+    if let Some(earliest) = missing.iter().map(|g| g.created).min() {
+        let rewind_to = to_db_date(earliest);
+
+        if rewind_to < dao_handler.snapshotindex {
+            event!(
+                Level::INFO,
+                dao_handler_id = dao_handler.id,
+                rewind_to = earliest,
+                "rewind snapshot index"
+            );
+
+            ctx.db
+                .daohandler()
+                .update(
+                    daohandler::id::equals(dao_handler.id.clone()),
+                    vec![daohandler::snapshotindex::set(rewind_to)],
+                )
+                .exec()
+                .await?;
+
+            report.repaired = true;
+        }
+    }
+
+    Ok(())
+}
+
+#[instrument(skip_all)]
+async fn reconcile_votes(
+    ctx: &Context,
+    http_client: &ClientWithMiddleware,
+    dao_handler: &daohandler_with_dao::Data,
+    decoder: &Decoder,
+    sanitize_from: DateTime<Utc>,
+    sanitize_to: DateTime<Utc>,
+    repair: bool,
+    report: &mut ReconcileReport,
+) -> Result<()> {
+    let voter_handlers = ctx
+        .db
+        .voterhandler()
+        .find_many(vec![voterhandler::daohandlerid::equals(
+            dao_handler.id.clone(),
+        )])
+        .include(voterhandler_with_voter::include())
+        .exec()
+        .await?;
+
+    let proposals = ctx
+        .db
+        .proposal()
+        .find_many(vec![
+            proposal::daohandlerid::equals(dao_handler.id.clone()),
+            proposal::timeend::gte(sanitize_from.into()),
+        ])
+        .exec()
+        .await?;
+
+    for batch in voter_handlers.chunks(VOTERS_BATCH) {
+        let voters: Vec<String> = batch.iter().map(|vh| vh.voter.address.clone()).collect();
+
+        let graphql_query = format!(
+            r#"{{
+        votes(
+            first: 1000,
+            orderBy: "created",
+            orderDirection: asc,
+            where: {{
+                voter_in: {:?},
+                space: "{}",
+                created_gte: {},
+                created_lte: {}
+            }}
+        ) {{
+            voter
+            created
+            proposal {{
+                id
+            }}
+        }}
+    }}"#,
+            voters,
+            decoder.space,
+            sanitize_from.timestamp(),
+            sanitize_to.timestamp()
+        );
+
+        let response_data: GraphQLResponse<GraphQLVotes> = http_client
+            .get("https://hub.snapshot.org/graphql".to_string())
+            .json(&serde_json::json!({ "query": graphql_query }))
+            .send()
+            .await?
+            .json()
+            .await
+            .with_context(|| format!("bad graphql response {}", graphql_query))?;
+
+        let stored = ctx
+            .db
+            .vote()
+            .find_many(vec![
+                vote::daohandlerid::equals(dao_handler.id.clone()),
+                vote::voteraddress::in_vec(voters.clone()),
+            ])
+            .exec()
+            .await?;
+
+        let missing: Vec<(MissingVote, i64)> = response_data
+            .data
+            .votes
+            .iter()
+            .filter_map(|v| {
+                let proposal = proposals
+                    .iter()
+                    .find(|p| Some(&p.externalid) == v.proposal.as_ref().map(|p| &p.id))?;
+
+                let exists = stored.iter().any(|s| {
+                    s.proposalid == proposal.id
+                        && s.voteraddress.to_lowercase() == v.voter.to_lowercase()
+                });
+
+                (!exists).then(|| {
+                    (
+                        MissingVote {
+                            voter_address: v.voter.clone(),
+                            proposal_id: proposal.id.clone(),
+                        },
+                        v.created,
+                    )
+                })
+            })
+            .collect();
+
+        if repair {
+            for vh in batch {
+                let earliest = missing
+                    .iter()
+                    .filter(|(m, _)| {
+                        m.voter_address.to_lowercase() == vh.voter.address.to_lowercase()
+                    })
+                    .map(|(_, created)| *created)
+                    .min();
+
+                if let Some(earliest) = earliest {
+                    let rewind_to = to_db_date(earliest);
+
+                    if rewind_to < vh.snapshotindex {
+                        ctx.db
+                            .voterhandler()
+                            .update(
+                                voterhandler::id::equals(vh.id.clone()),
+                                vec![voterhandler::snapshotindex::set(rewind_to)],
+                            )
+                            .exec()
+                            .await?;
+
+                        report.repaired = true;
+                    }
+                }
+            }
+        }
+
+        report
+            .missing_votes
+            .extend(missing.into_iter().map(|(m, _)| m));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use ethers::abi::EventExt;
+use rocket::serde::json::Json;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{event, info_span, Instrument, Level};
+
+use crate::{abis, Ctx};
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct AbiResponse {
+    name: String,
+    success: bool,
+    events: Vec<String>,
+    functions: Vec<String>,
+}
+
+#[get("/")]
+pub async fn list_abis() -> Json<Vec<String>> {
+    Json(abis::names())
+}
+
+#[get("/<name>")]
+pub async fn get_abi(name: &str) -> Option<Json<AbiResponse>> {
+    let abi = abis::get(name)?;
+
+    Some(Json(AbiResponse {
+        name: name.to_string(),
+        success: true,
+        events: abi.events().map(|e| e.abi_signature()).collect(),
+        functions: abi.functions().map(|f| f.signature()).collect(),
+    }))
+}
+
+/// Registers an abi under `name`, a `GENERIC_CHAIN` handler can use it right away.
+#[put("/<name>", data = "<data>")]
+pub async fn register_abi(ctx: &Ctx, name: &str, data: Json<Value>) -> Json<AbiResponse> {
+    let my_span = info_span!("register_abi", name = name);
+
+    async move {
+        match abis::register(&ctx.db, name, data.into_inner()).await {
+            Ok(abi) => Json(AbiResponse {
+                name: name.to_string(),
+                success: true,
+                events: abi.events().map(|e| e.abi_signature()).collect(),
+                functions: abi.functions().map(|f| f.signature()).collect(),
+            }),
+            Err(e) => {
+                event!(Level::WARN, err = e.to_string(), "abi register error");
+                Json(AbiResponse {
+                    name: name.to_string(),
+                    success: false,
+                    events: vec![],
+                    functions: vec![],
+                })
+            }
+        }
+    }
+    .instrument(my_span)
+    .await
+}
+
+#[post("/reload")]
+pub async fn reload_abis(ctx: &Ctx) -> Json<Vec<String>> {
+    if let Err(e) = abis::reload(&ctx.db).await {
+        event!(Level::WARN, err = e.to_string(), "abi reload error");
+    }
+
+    Json(abis::names())
+}
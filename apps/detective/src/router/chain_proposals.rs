@@ -4,7 +4,7 @@ use anyhow::{bail, Result};
 use ethers::{
     prelude::k256::elliptic_curve::PrimeField,
     providers::{Http, Middleware, Provider},
-    types::U64,
+    types::{Log, U64},
 };
 use prisma_client_rust::chrono::{DateTime, FixedOffset, Utc};
 use reqwest::header::HeaderMap;
@@ -24,23 +24,31 @@ use tracing::{
 
 use crate::{
     daohandler_with_dao,
+    family::link_inserted,
     handlers::proposals::{
         aave::aave_proposals,
+        aragon_multisig::aragon_multisig_proposals,
+        aragon_token_voting::aragon_token_voting_proposals,
         arbitrum_core::{self, arbitrum_core_proposals},
         arbitrum_treasury::arbitrum_treasury_proposals,
         compound::compound_proposals,
         dydx::dydx_proposals,
         ens::ens_proposals,
+        generic::generic_proposals,
         gitcoin::gitcoin_proposals,
         hop::hop_proposals,
         interest_protocol::interest_protocol_proposals,
         maker_executive::maker_executive_proposals,
         maker_poll::maker_poll_proposals,
+        nouns::{nouns_proposal_edits, nouns_proposals},
         optimism::optimism_proposals,
+        safe::safe_proposals,
         uniswap::uniswap_proposals,
         zeroxtreasury::zeroxtreasury_proposals,
     },
+    logstore::capture,
     prisma::{dao, daohandler, proposal, DaoHandlerType, PrismaClient, ProposalState},
+    utils::proposal_history::record_proposal_snapshot,
     Ctx,
     ProposalsRequest,
     ProposalsResponse,
@@ -61,6 +69,9 @@ pub struct ChainProposal {
     pub(crate) scores: Value,
     pub(crate) scores_total: Value,
     pub(crate) quorum: Value,
+    /// Share of the non abstain votes that have to be for, for handlers that decide on
+    /// more than the quorum.
+    pub(crate) support_threshold: Option<f64>,
     pub(crate) url: String,
     pub(crate) state: ProposalState,
 }
@@ -92,16 +103,7 @@ pub async fn update_chain_proposals<'a>(
 
         let mut from_block = min_block;
 
-        let rpc = if dao_handler.r#type == DaoHandlerType::MakerPollArbitrum
-            || dao_handler.r#type == DaoHandlerType::ArbitrumCoreChain
-            || dao_handler.r#type == DaoHandlerType::ArbitrumTreasuryChain
-        {
-            &ctx.arbitrum_rpc
-        } else if dao_handler.r#type == DaoHandlerType::OptimismChain {
-            &ctx.optimism_rpc
-        } else {
-            &ctx.eth_rpc
-        };
+        let rpc = ctx.rpc_for(dao_handler.r#type, &dao_handler.decoder);
 
         let current_block = rpc
             .get_block_number()
@@ -173,188 +175,63 @@ async fn get_results(
     dao_handler: daohandler_with_dao::Data,
     current_block: i64,
 ) -> Result<()> {
+    let logs = capture(db, rpc, &dao_handler, from_block, to_block).await?;
+    let p = fetch_proposals(db, rpc, &dao_handler, &logs, from_block, to_block).await?;
+    let _ = insert_proposals(
+        p,
+        from_block,
+        to_block,
+        db,
+        dao_handler.clone(),
+        current_block,
+    )
+    .await;
+
+    if dao_handler.r#type == DaoHandlerType::NounsChain {
+        nouns_proposal_edits(db, rpc, &dao_handler, &logs).await?;
+    }
+
+    Ok(())
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn fetch_proposals(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    logs: &[Log],
+    from_block: i64,
+    to_block: i64,
+) -> Result<Vec<ChainProposal>> {
     match dao_handler.r#type {
-        DaoHandlerType::AaveChain => {
-            let p = aave_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
-        }
-        DaoHandlerType::CompoundChain => {
-            let p = compound_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
-        }
-        DaoHandlerType::UniswapChain => {
-            let p = uniswap_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
-        }
-        DaoHandlerType::EnsChain => {
-            let p = ens_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
-        }
-        DaoHandlerType::GitcoinChain => {
-            let p = gitcoin_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
-        }
-        DaoHandlerType::HopChain => {
-            let p = hop_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
-        }
-        DaoHandlerType::DydxChain => {
-            let p = dydx_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
-        }
-        DaoHandlerType::MakerPoll => {
-            let p = maker_poll_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
-        }
-        DaoHandlerType::MakerExecutive => {
-            let p = maker_executive_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
-        }
+        DaoHandlerType::AaveChain => aave_proposals(db, rpc, dao_handler, logs).await,
+        DaoHandlerType::CompoundChain => compound_proposals(rpc, dao_handler, logs).await,
+        DaoHandlerType::UniswapChain => uniswap_proposals(rpc, dao_handler, logs).await,
+        DaoHandlerType::EnsChain => ens_proposals(rpc, dao_handler, logs).await,
+        DaoHandlerType::GitcoinChain => gitcoin_proposals(rpc, dao_handler, logs).await,
+        DaoHandlerType::HopChain => hop_proposals(rpc, dao_handler, logs).await,
+        DaoHandlerType::DydxChain => dydx_proposals(db, rpc, dao_handler, logs).await,
+        DaoHandlerType::MakerPoll => maker_poll_proposals(db, rpc, dao_handler, logs).await,
+        DaoHandlerType::MakerExecutive => maker_executive_proposals(rpc, dao_handler, logs).await,
         DaoHandlerType::InterestProtocolChain => {
-            let p = interest_protocol_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
+            interest_protocol_proposals(rpc, dao_handler, logs).await
         }
-        DaoHandlerType::ZeroxProtocolChain => {
-            let p = zeroxtreasury_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
+        DaoHandlerType::ZeroxProtocolChain => zeroxtreasury_proposals(rpc, dao_handler, logs).await,
+        DaoHandlerType::OptimismChain => optimism_proposals(rpc, dao_handler, logs).await,
+        DaoHandlerType::ArbitrumCoreChain => arbitrum_core_proposals(rpc, dao_handler, logs).await,
+        DaoHandlerType::ArbitrumTreasuryChain => {
+            arbitrum_treasury_proposals(rpc, dao_handler, logs).await
         }
-        DaoHandlerType::OptimismChain => {
-            let p = optimism_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
+        DaoHandlerType::NounsChain => nouns_proposals(db, rpc, dao_handler, logs).await,
+        DaoHandlerType::AragonTokenVoting => {
+            aragon_token_voting_proposals(db, rpc, dao_handler, logs).await
         }
-        DaoHandlerType::ArbitrumCoreChain => {
-            let p = arbitrum_core_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
+        DaoHandlerType::AragonMultisig => {
+            aragon_multisig_proposals(db, rpc, dao_handler, logs).await
         }
-        DaoHandlerType::ArbitrumTreasuryChain => {
-            let p = arbitrum_treasury_proposals(rpc, &dao_handler, &from_block, &to_block).await?;
-            let _ = insert_proposals(
-                p,
-                from_block,
-                to_block,
-                db,
-                dao_handler.clone(),
-                current_block,
-            )
-            .await;
-            Ok(())
+        DaoHandlerType::SafeMultisig => safe_proposals(rpc, dao_handler, logs, &to_block).await,
+        DaoHandlerType::GenericChain => {
+            generic_proposals(db, rpc, dao_handler, logs, &from_block, &to_block).await
         }
         DaoHandlerType::MakerPollArbitrum => bail!("not implemeneted"),
         DaoHandlerType::Snapshot => bail!("not implemeneted"),
@@ -370,7 +247,87 @@ async fn insert_proposals(
     dao_handler: daohandler_with_dao::Data,
     current_block: i64,
 ) -> Result<()> {
-    for proposal in proposals.clone() {
+    upsert_proposals(&proposals, db, &dao_handler, false).await?;
+
+    let open_proposals: Vec<ChainProposal> = proposals
+        .iter()
+        .filter(|p| {
+            if dao_handler.r#type == DaoHandlerType::OptimismChain {
+                p.state == ProposalState::Pending
+                    || p.state == ProposalState::Active
+                    || p.state == ProposalState::Queued
+            } else {
+                p.state == ProposalState::Pending
+                    || p.state == ProposalState::Active
+                    || p.state == ProposalState::ObjectionPeriod
+                    || p.state == ProposalState::Succeeded
+                    || p.state == ProposalState::Queued
+            }
+        })
+        .cloned()
+        .collect();
+
+    let new_index = if !open_proposals.is_empty() {
+        open_proposals
+            .iter()
+            .map(|p| p.block_created)
+            .max()
+            .unwrap_or_default()
+    } else {
+        to_block
+    };
+
+    let uptodate = current_block - to_block < 100000;
+
+    event!(
+        Level::INFO,
+        dao_name = dao_handler.dao.name,
+        dao_handler_type = dao_handler.r#type.to_string(),
+        dao_handler_id = dao_handler.id,
+        new_index = new_index,
+        to_block = to_block,
+        uptodate = uptodate,
+        "new index"
+    );
+
+    if (new_index > dao_handler.chainindex && new_index - dao_handler.chainindex > 100)
+        || uptodate != dao_handler.uptodate
+    {
+        event!(
+            Level::INFO,
+            dao_name = dao_handler.dao.name,
+            dao_handler_type = dao_handler.r#type.to_string(),
+            new_index = new_index,
+            dao_handler_id = dao_handler.id,
+            "set new index"
+        );
+        db.daohandler()
+            .update(
+                daohandler::id::equals(dao_handler.id.to_string()),
+                vec![
+                    daohandler::chainindex::set(new_index),
+                    daohandler::uptodate::set(uptodate),
+                ],
+            )
+            .exec()
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Inserts new proposals and updates the ones whose state, total or url moved. With
+/// `overwrite` every existing row is rewritten, for rebuilds that correct the scores.
+#[instrument(skip_all)]
+pub(crate) async fn upsert_proposals(
+    proposals: &[ChainProposal],
+    db: &Arc<PrismaClient>,
+    dao_handler: &daohandler_with_dao::Data,
+    overwrite: bool,
+) -> Result<()> {
+    let mut inserted = false;
+
+    for proposal in proposals.iter().cloned() {
         let existing = db
             .proposal()
             .find_unique(proposal::externalid_daoid(
@@ -382,7 +339,8 @@ async fn insert_proposals(
 
         match existing {
             Some(existing) => {
-                if proposal.state != existing.state
+                if overwrite
+                    || proposal.state != existing.state
                     || proposal.scores_total.as_f64().unwrap().floor()
                         != existing.scorestotal.as_f64().unwrap().floor()
                     || proposal.url != existing.url
@@ -396,7 +354,8 @@ async fn insert_proposals(
                         dao_handler_id = dao_handler.id,
                         "update proposal"
                     );
-                    db.proposal()
+                    let updated = db
+                        .proposal()
                         .update(
                             proposal::externalid_daoid(
                                 proposal.external_id.to_string(),
@@ -416,6 +375,9 @@ async fn insert_proposals(
                                     proposal.clone().scores_total,
                                 ));
                                 update_v.push(proposal::quorum::set(proposal.quorum));
+                                update_v.push(proposal::supportthreshold::set(
+                                    proposal.support_threshold,
+                                ));
                                 update_v.push(proposal::state::set(proposal.state));
                                 update_v.push(proposal::timestart::set(
                                     proposal
@@ -433,6 +395,8 @@ async fn insert_proposals(
                         )
                         .exec()
                         .await?;
+
+                    record_proposal_snapshot(db, &updated).await?;
                 }
             }
             None => {
@@ -446,7 +410,8 @@ async fn insert_proposals(
                     "insert proposal"
                 );
 
-                db.proposal()
+                let created = db
+                    .proposal()
                     .create_unchecked(
                         proposal.name.clone(),
                         proposal.external_id.clone(),
@@ -467,75 +432,22 @@ async fn insert_proposals(
                         proposal.clone().url,
                         dao_handler.id.to_string(),
                         dao_handler.daoid.to_string(),
-                        vec![proposal::blockcreated::set(proposal.block_created.into())],
+                        vec![
+                            proposal::blockcreated::set(proposal.block_created.into()),
+                            proposal::supportthreshold::set(proposal.support_threshold),
+                        ],
                     )
                     .exec()
                     .await?;
+
+                record_proposal_snapshot(db, &created).await?;
+                inserted = true;
             }
         }
     }
 
-    let open_proposals: Vec<ChainProposal> = proposals
-        .iter()
-        .filter(|p| {
-            if dao_handler.r#type == DaoHandlerType::OptimismChain {
-                p.state == ProposalState::Pending
-                    || p.state == ProposalState::Active
-                    || p.state == ProposalState::Queued
-            } else {
-                p.state == ProposalState::Pending
-                    || p.state == ProposalState::Active
-                    || p.state == ProposalState::Succeeded
-                    || p.state == ProposalState::Queued
-            }
-        })
-        .cloned()
-        .collect();
-
-    let new_index = if !open_proposals.is_empty() {
-        open_proposals
-            .iter()
-            .map(|p| p.block_created)
-            .max()
-            .unwrap_or_default()
-    } else {
-        to_block
-    };
-
-    let uptodate = current_block - to_block < 100000;
-
-    event!(
-        Level::INFO,
-        dao_name = dao_handler.dao.name,
-        dao_handler_type = dao_handler.r#type.to_string(),
-        dao_handler_id = dao_handler.id,
-        new_index = new_index,
-        to_block = to_block,
-        uptodate = uptodate,
-        "new index"
-    );
-
-    if (new_index > dao_handler.chainindex && new_index - dao_handler.chainindex > 100)
-        || uptodate != dao_handler.uptodate
-    {
-        event!(
-            Level::INFO,
-            dao_name = dao_handler.dao.name,
-            dao_handler_type = dao_handler.r#type.to_string(),
-            new_index = new_index,
-            dao_handler_id = dao_handler.id,
-            "set new index"
-        );
-        db.daohandler()
-            .update(
-                daohandler::id::equals(dao_handler.id.to_string()),
-                vec![
-                    daohandler::chainindex::set(new_index),
-                    daohandler::uptodate::set(uptodate),
-                ],
-            )
-            .exec()
-            .await?;
+    if inserted {
+        link_inserted(db, &dao_handler.daoid).await;
     }
 
     Ok(())
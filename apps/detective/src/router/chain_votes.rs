@@ -1,24 +1,30 @@
-use std::{cmp, env, ops::Div, sync::Arc};
+use std::{cmp, env, sync::Arc};
 
 use crate::{
     daohandler_with_dao,
     handlers::votes::{
         aave::aave_votes,
+        aragon_multisig::aragon_multisig_votes,
+        aragon_token_voting::aragon_token_voting_votes,
         arbitrum_core::arbitrum_core_votes,
         arbitrum_treasury::arbitrum_treasury_votes,
         compound::compound_votes,
         dydx::dydx_votes,
         ens::ens_votes,
+        generic::generic_votes,
         gitcoin::gitcoin_votes,
         hop::hop_votes,
         interest_protocol::interest_protocol_votes,
         maker_executive::makerexecutive_votes,
         maker_poll::makerpoll_votes,
         maker_poll_arbitrum::makerpollarbitrum_votes,
+        nouns::nouns_votes,
         optimism::optimism_votes,
+        safe::safe_votes,
         uniswap::uniswap_votes,
         zeroxtreasury::zeroxtreasury_votes,
     },
+    logstore::capture,
     prisma::{dao, daohandler, proposal, vote, voter, voterhandler, DaoHandlerType, PrismaClient},
     voterhandler_with_voter,
     Ctx,
@@ -28,7 +34,7 @@ use crate::{
 use anyhow::{bail, Context, Result};
 use ethers::{
     providers::{Http, Middleware, Provider},
-    types::U64,
+    types::{Log, U64},
 };
 use prisma_client_rust::Direction;
 use rocket::serde::json::Json;
@@ -110,16 +116,7 @@ pub async fn update_chain_votes<'a>(
             .min()
             .unwrap_or(0);
 
-        let rpc = if dao_handler.r#type == DaoHandlerType::MakerPollArbitrum
-            || dao_handler.r#type == DaoHandlerType::ArbitrumCoreChain
-            || dao_handler.r#type == DaoHandlerType::ArbitrumTreasuryChain
-        {
-            &ctx.arbitrum_rpc
-        } else if dao_handler.r#type == DaoHandlerType::OptimismChain {
-            &ctx.optimism_rpc
-        } else {
-            &ctx.eth_rpc
-        };
+        let rpc = ctx.rpc_for(dao_handler.r#type, &dao_handler.decoder);
 
         let current_block = rpc
             .get_block_number()
@@ -127,7 +124,7 @@ pub async fn update_chain_votes<'a>(
             .unwrap_or(U64::from(0))
             .as_u64() as i64;
 
-        let batch_size = (data.refreshspeed).div(voters.len() as i64);
+        let batch_size = data.refreshspeed;
 
         let mut from_block = if dao_handler.r#type == DaoHandlerType::MakerPollArbitrum {
             vh_index
@@ -207,113 +204,61 @@ async fn get_results(
     voters: Vec<String>,
     voter_handlers: Vec<voterhandler_with_voter::Data>,
     current_block: i64,
+) -> Result<Vec<VoteResult>> {
+    let logs = capture(db, rpc, dao_handler, from_block, to_block).await?;
+    let r = fetch_votes(db, rpc, dao_handler, &logs, to_block, voters).await?;
+    let ok_v = insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
+    Ok(ok_v)
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn fetch_votes(
+    db: &Arc<PrismaClient>,
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler_with_dao::Data,
+    logs: &[Log],
+    to_block: i64,
+    voters: Vec<String>,
 ) -> Result<Vec<VoteResult>> {
     match dao_handler.r#type {
-        DaoHandlerType::AaveChain => {
-            let r = aave_votes(db, rpc, dao_handler, from_block, to_block, voters.clone()).await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
-        }
-        DaoHandlerType::CompoundChain => {
-            let r =
-                compound_votes(db, rpc, dao_handler, from_block, to_block, voters.clone()).await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
-        }
-        DaoHandlerType::UniswapChain => {
-            let r =
-                uniswap_votes(db, rpc, dao_handler, from_block, to_block, voters.clone()).await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
-        }
-        DaoHandlerType::EnsChain => {
-            let r = ens_votes(db, rpc, dao_handler, from_block, to_block, voters.clone()).await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
-        }
-        DaoHandlerType::GitcoinChain => {
-            let r =
-                gitcoin_votes(db, rpc, dao_handler, from_block, to_block, voters.clone()).await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
-        }
-        DaoHandlerType::HopChain => {
-            let r = hop_votes(db, rpc, dao_handler, from_block, to_block, voters.clone()).await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
-        }
-        DaoHandlerType::DydxChain => {
-            let r = dydx_votes(db, rpc, dao_handler, from_block, to_block, voters.clone()).await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
-        }
+        DaoHandlerType::AaveChain => aave_votes(db, dao_handler, logs, voters).await,
+        DaoHandlerType::CompoundChain => compound_votes(db, dao_handler, logs, voters).await,
+        DaoHandlerType::UniswapChain => uniswap_votes(db, dao_handler, logs, voters).await,
+        DaoHandlerType::EnsChain => ens_votes(db, dao_handler, logs, voters).await,
+        DaoHandlerType::GitcoinChain => gitcoin_votes(db, dao_handler, logs, voters).await,
+        DaoHandlerType::HopChain => hop_votes(db, dao_handler, logs, voters).await,
+        DaoHandlerType::DydxChain => dydx_votes(db, dao_handler, logs, voters).await,
         DaoHandlerType::MakerExecutive => {
-            let r =
-                makerexecutive_votes(db, rpc, dao_handler, from_block, to_block, voters.clone())
-                    .await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
-        }
-        DaoHandlerType::MakerPoll => {
-            let r =
-                makerpoll_votes(db, rpc, dao_handler, from_block, to_block, voters.clone()).await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
+            makerexecutive_votes(db, rpc, dao_handler, logs, voters).await
         }
+        DaoHandlerType::MakerPoll => makerpoll_votes(db, dao_handler, logs, voters).await,
         DaoHandlerType::MakerPollArbitrum => {
-            let r =
-                makerpollarbitrum_votes(db, rpc, dao_handler, from_block, to_block, voters.clone())
-                    .await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
+            makerpollarbitrum_votes(db, dao_handler, logs, voters).await
         }
         DaoHandlerType::InterestProtocolChain => {
-            let r =
-                interest_protocol_votes(db, rpc, dao_handler, from_block, to_block, voters.clone())
-                    .await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
+            interest_protocol_votes(db, dao_handler, logs, voters).await
         }
         DaoHandlerType::ZeroxProtocolChain => {
-            let r = zeroxtreasury_votes(db, rpc, dao_handler, from_block, to_block, voters.clone())
-                .await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
-        }
-        DaoHandlerType::OptimismChain => {
-            let r =
-                optimism_votes(db, rpc, dao_handler, from_block, to_block, voters.clone()).await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
+            zeroxtreasury_votes(db, dao_handler, logs, voters).await
         }
+        DaoHandlerType::OptimismChain => optimism_votes(db, dao_handler, logs, voters).await,
         DaoHandlerType::ArbitrumCoreChain => {
-            let r = arbitrum_core_votes(db, rpc, dao_handler, from_block, to_block, voters.clone())
-                .await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
+            arbitrum_core_votes(db, dao_handler, logs, voters).await
         }
         DaoHandlerType::ArbitrumTreasuryChain => {
-            let r =
-                arbitrum_treasury_votes(db, rpc, dao_handler, from_block, to_block, voters.clone())
-                    .await?;
-            let ok_v =
-                insert_votes(r, to_block, db, dao_handler, voter_handlers, current_block).await?;
-            Ok(ok_v)
+            arbitrum_treasury_votes(db, dao_handler, logs, voters).await
+        }
+        DaoHandlerType::NounsChain => nouns_votes(db, dao_handler, logs, voters).await,
+        DaoHandlerType::AragonTokenVoting => {
+            aragon_token_voting_votes(db, dao_handler, logs, voters).await
+        }
+        DaoHandlerType::AragonMultisig => {
+            aragon_multisig_votes(db, dao_handler, logs, voters).await
         }
+        DaoHandlerType::SafeMultisig => {
+            safe_votes(db, rpc, dao_handler, logs, to_block, voters).await
+        }
+        DaoHandlerType::GenericChain => generic_votes(db, rpc, dao_handler, logs, voters).await,
         DaoHandlerType::Snapshot => bail!("not implemented"),
     }
 }
@@ -327,6 +272,74 @@ async fn insert_votes(
     voter_handlers: Vec<voterhandler_with_voter::Data>,
     current_block: i64,
 ) -> Result<Vec<VoteResult>> {
+    upsert_votes(&votes, db, dao_handler).await?;
+
+    let daochainindex = dao_handler.chainindex;
+
+    let mut new_index = if daochainindex > to_block {
+        to_block
+    } else {
+        daochainindex
+    };
+
+    let mut uptodate = current_block - to_block < 100000 && to_block >= daochainindex;
+
+    if dao_handler.r#type == DaoHandlerType::MakerPollArbitrum {
+        new_index = to_block;
+        uptodate = current_block - to_block < 100000
+    }
+
+    event!(
+        Level::INFO,
+        dao_name = dao_handler.dao.name,
+        dao_handler_type = dao_handler.r#type.to_string(),
+        dao_handler_id = dao_handler.id,
+        daochainindex = daochainindex,
+        new_index = new_index,
+        uptodate = uptodate,
+        to_block = to_block,
+        "new index"
+    );
+
+    for voter_handler in voter_handlers {
+        if (new_index > voter_handler.chainindex && new_index - voter_handler.chainindex > 100)
+            || uptodate != voter_handler.uptodate
+        {
+            event!(
+                Level::INFO,
+                dao_name = dao_handler.dao.name,
+                dao_handler_type = dao_handler.r#type.to_string(),
+                new_index = new_index,
+                voter_handler_id = voter_handler.id,
+                dao_handler_id = dao_handler.id,
+                "set new index"
+            );
+
+            db.voterhandler()
+                .update(
+                    voterhandler::voterid_daohandlerid(
+                        voter_handler.voterid,
+                        dao_handler.clone().id,
+                    ),
+                    vec![
+                        voterhandler::chainindex::set(new_index),
+                        voterhandler::uptodate::set(uptodate),
+                    ],
+                )
+                .exec()
+                .await?;
+        }
+    }
+
+    Ok(votes)
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn upsert_votes(
+    votes: &[VoteResult],
+    db: &Arc<PrismaClient>,
+    dao_handler: &daohandler_with_dao::Data,
+) -> Result<()> {
     let successful_votes: Vec<Vote> = votes
         .iter()
         .filter(|v| v.success)
@@ -406,62 +419,5 @@ async fn insert_votes(
         }
     }
 
-    let daochainindex = dao_handler.chainindex;
-
-    let mut new_index = if daochainindex > to_block {
-        to_block
-    } else {
-        daochainindex
-    };
-
-    let mut uptodate = current_block - to_block < 100000 && to_block >= daochainindex;
-
-    if dao_handler.r#type == DaoHandlerType::MakerPollArbitrum {
-        new_index = to_block;
-        uptodate = current_block - to_block < 100000
-    }
-
-    event!(
-        Level::INFO,
-        dao_name = dao_handler.dao.name,
-        dao_handler_type = dao_handler.r#type.to_string(),
-        dao_handler_id = dao_handler.id,
-        daochainindex = daochainindex,
-        new_index = new_index,
-        uptodate = uptodate,
-        to_block = to_block,
-        "new index"
-    );
-
-    for voter_handler in voter_handlers {
-        if (new_index > voter_handler.chainindex && new_index - voter_handler.chainindex > 100)
-            || uptodate != voter_handler.uptodate
-        {
-            event!(
-                Level::INFO,
-                dao_name = dao_handler.dao.name,
-                dao_handler_type = dao_handler.r#type.to_string(),
-                new_index = new_index,
-                voter_handler_id = voter_handler.id,
-                dao_handler_id = dao_handler.id,
-                "set new index"
-            );
-
-            db.voterhandler()
-                .update(
-                    voterhandler::voterid_daohandlerid(
-                        voter_handler.voterid,
-                        dao_handler.clone().id,
-                    ),
-                    vec![
-                        voterhandler::chainindex::set(new_index),
-                        voterhandler::uptodate::set(uptodate),
-                    ],
-                )
-                .exec()
-                .await?;
-        }
-    }
-
-    Ok(votes)
+    Ok(())
 }
@@ -0,0 +1,50 @@
+use ethers::types::Address;
+use rocket::serde::json::Json;
+use serde::Serialize;
+use tracing::{event, info_span, Instrument, Level};
+
+use crate::{
+    discovery::{discover, DiscoveryReport},
+    Chain,
+    Ctx,
+};
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct DiscoveryResponse {
+    address: String,
+    success: bool,
+    report: Option<DiscoveryReport>,
+}
+
+#[get("/<chain>/<address>")]
+pub async fn discover_governor(
+    ctx: &Ctx,
+    chain: &str,
+    address: &str,
+) -> Option<Json<DiscoveryResponse>> {
+    let my_span = info_span!("discover_governor", chain = chain, address = address);
+
+    async move {
+        let chain = Chain::parse(chain)?;
+        let parsed = address.parse::<Address>().ok()?;
+
+        match discover(ctx.rpc_for_chain(chain), chain, parsed).await {
+            Ok(report) => Some(Json(DiscoveryResponse {
+                address: address.to_string(),
+                success: true,
+                report: Some(report),
+            })),
+            Err(e) => {
+                event!(Level::WARN, err = e.to_string(), "discovery error");
+                Some(Json(DiscoveryResponse {
+                    address: address.to_string(),
+                    success: false,
+                    report: None,
+                }))
+            }
+        }
+    }
+    .instrument(my_span)
+    .await
+}
@@ -0,0 +1,87 @@
+use rocket::serde::json::Json;
+use serde::Serialize;
+use tracing::{info_span, Instrument};
+
+use crate::{
+    prisma::{proposal, DaoHandlerType},
+    proposal_with_dao,
+    Ctx,
+};
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct FamilyMember {
+    proposal_id: String,
+    name: String,
+    offchain: bool,
+    state: String,
+    url: String,
+    matched: Option<String>,
+    time_created: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct FamilyResponse {
+    family_id: Option<String>,
+    members: Vec<FamilyMember>,
+}
+
+impl From<proposal_with_dao::Data> for FamilyMember {
+    fn from(p: proposal_with_dao::Data) -> Self {
+        FamilyMember {
+            proposal_id: p.id,
+            name: p.name,
+            offchain: p.daohandler.r#type == DaoHandlerType::Snapshot,
+            state: p.state.to_string(),
+            url: p.url,
+            matched: p.familymatch,
+            time_created: p.timecreated.timestamp(),
+        }
+    }
+}
+
+/// Every stage of the proposal's lifecycle, from the snapshot vote to the on-chain one.
+#[get("/<proposal_id>/family")]
+pub async fn get_proposal_family(ctx: &Ctx, proposal_id: &str) -> Option<Json<FamilyResponse>> {
+    let my_span = info_span!("get_proposal_family", proposal_id = proposal_id);
+
+    async move {
+        let proposal = ctx
+            .db
+            .proposal()
+            .find_unique(proposal::id::equals(proposal_id.to_string()))
+            .include(proposal_with_dao::include())
+            .exec()
+            .await
+            .expect("bad prisma result")?;
+
+        let family_id = match proposal.familyid.clone() {
+            Some(id) => id,
+            None => {
+                return Some(Json(FamilyResponse {
+                    family_id: None,
+                    members: vec![proposal.into()],
+                }))
+            }
+        };
+
+        let mut members = ctx
+            .db
+            .proposal()
+            .find_many(vec![proposal::familyid::equals(Some(family_id.clone()))])
+            .include(proposal_with_dao::include())
+            .exec()
+            .await
+            .expect("bad prisma result");
+
+        members.sort_by_key(|p| p.timecreated);
+
+        Some(Json(FamilyResponse {
+            family_id: Some(family_id),
+            members: members.into_iter().map(FamilyMember::from).collect(),
+        }))
+    }
+    .instrument(my_span)
+    .await
+}
@@ -0,0 +1,55 @@
+use rocket::serde::json::Json;
+use serde::Serialize;
+use tracing::{event, info_span, Instrument, Level};
+
+use crate::{
+    daohandler_with_dao,
+    logstore::derive::{replay, ReplayReport},
+    prisma::daohandler,
+    Ctx,
+};
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct ReplayResponse {
+    daoHandlerId: String,
+    success: bool,
+    report: Option<ReplayReport>,
+}
+
+/// Rebuilds the proposals and votes of a handler from its stored logs, without rpc calls.
+#[post("/<dao_handler_id>/replay")]
+pub async fn replay_dao_handler(ctx: &Ctx, dao_handler_id: &str) -> Json<ReplayResponse> {
+    let my_span = info_span!("replay_dao_handler", dao_handler_id = dao_handler_id);
+
+    async move {
+        let dao_handler = ctx
+            .db
+            .daohandler()
+            .find_first(vec![daohandler::id::equals(dao_handler_id.to_string())])
+            .include(daohandler_with_dao::include())
+            .exec()
+            .await
+            .expect("bad prisma result")
+            .expect("daoHandlerId not found");
+
+        match replay(&ctx.db, &dao_handler).await {
+            Ok(report) => Json(ReplayResponse {
+                daoHandlerId: dao_handler.id,
+                success: true,
+                report: Some(report),
+            }),
+            Err(e) => {
+                event!(Level::WARN, err = e.to_string(), "replay error");
+                Json(ReplayResponse {
+                    daoHandlerId: dao_handler.id,
+                    success: false,
+                    report: None,
+                })
+            }
+        }
+    }
+    .instrument(my_span)
+    .await
+}
@@ -1,4 +1,13 @@
+pub mod abis;
 pub mod chain_proposals;
 pub mod chain_votes;
+pub mod discovery;
+pub mod family;
+pub mod logs;
+pub mod proposal_history;
+pub mod reconcile;
+pub mod simulation;
 pub mod snapshot_proposals;
 pub mod snapshot_votes;
+pub mod treasury;
+pub mod voters;
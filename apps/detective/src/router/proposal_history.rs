@@ -0,0 +1,155 @@
+use chrono::{Duration, Utc};
+use prisma_client_rust::Direction;
+use rocket::serde::json::Json;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{info_span, Instrument};
+
+use crate::{
+    prisma::{proposal, proposaledit, proposalsnapshot, ProposalEditType, ProposalState},
+    utils::proposal_history::leading_choice,
+    Ctx,
+};
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct HistoryPoint {
+    timestamp: i64,
+    state: ProposalState,
+    scores: Value,
+    scorestotal: Value,
+    quorum: Value,
+    leader: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct StateTransition {
+    timestamp: i64,
+    from: ProposalState,
+    to: ProposalState,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct LeaderChange {
+    timestamp: i64,
+    from: Option<usize>,
+    to: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct ProposalEdit {
+    timestamp: i64,
+    r#type: ProposalEditType,
+    name: String,
+    update_message: String,
+    txid: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct ProposalHistoryResponse {
+    proposal_id: String,
+    points: Vec<HistoryPoint>,
+    transitions: Vec<StateTransition>,
+    leader_changes: Vec<LeaderChange>,
+    edits: Vec<ProposalEdit>,
+}
+
+#[get("/<proposal_id>/history?<since_hours>")]
+pub async fn get_proposal_history(
+    ctx: &Ctx,
+    proposal_id: &str,
+    since_hours: Option<i64>,
+) -> Option<Json<ProposalHistoryResponse>> {
+    let my_span = info_span!("get_proposal_history", proposal_id = proposal_id);
+
+    async move {
+        let proposal = ctx
+            .db
+            .proposal()
+            .find_unique(proposal::id::equals(proposal_id.to_string()))
+            .exec()
+            .await
+            .expect("bad prisma result")?;
+
+        let mut filters = vec![proposalsnapshot::proposalid::equals(proposal.id.clone())];
+
+        if let Some(hours) = since_hours {
+            filters.push(proposalsnapshot::timestamp::gte(
+                (Utc::now() - Duration::hours(hours)).into(),
+            ));
+        }
+
+        let snapshots = ctx
+            .db
+            .proposalsnapshot()
+            .find_many(filters)
+            .order_by(proposalsnapshot::timestamp::order(Direction::Asc))
+            .exec()
+            .await
+            .expect("bad prisma result");
+
+        let points: Vec<HistoryPoint> = snapshots
+            .iter()
+            .map(|s| HistoryPoint {
+                timestamp: s.timestamp.timestamp(),
+                state: s.state,
+                scores: s.scores.clone(),
+                scorestotal: s.scorestotal.clone(),
+                quorum: s.quorum.clone(),
+                leader: leading_choice(&s.scores),
+            })
+            .collect();
+
+        let transitions = points
+            .windows(2)
+            .filter(|w| w[0].state != w[1].state)
+            .map(|w| StateTransition {
+                timestamp: w[1].timestamp,
+                from: w[0].state,
+                to: w[1].state,
+            })
+            .collect();
+
+        let leader_changes = points
+            .windows(2)
+            .filter(|w| w[0].leader != w[1].leader)
+            .map(|w| LeaderChange {
+                timestamp: w[1].timestamp,
+                from: w[0].leader,
+                to: w[1].leader,
+            })
+            .collect();
+
+        let edits = ctx
+            .db
+            .proposaledit()
+            .find_many(vec![proposaledit::proposalid::equals(proposal.id.clone())])
+            .order_by(proposaledit::timecreated::order(Direction::Asc))
+            .exec()
+            .await
+            .expect("bad prisma result")
+            .into_iter()
+            .map(|e| ProposalEdit {
+                timestamp: e.timecreated.timestamp(),
+                r#type: e.r#type,
+                name: e.name,
+                update_message: e.updatemessage,
+                txid: e.txid,
+            })
+            .collect();
+
+        Some(Json(ProposalHistoryResponse {
+            proposal_id: proposal.id,
+            points,
+            transitions,
+            leader_changes,
+            edits,
+        }))
+    }
+    .instrument(my_span)
+    .await
+}
@@ -0,0 +1,67 @@
+use chrono::Duration;
+use rocket::serde::json::Json;
+use serde::Serialize;
+use tracing::{event, info_span, Instrument, Level};
+
+use crate::{
+    daohandler_with_dao,
+    prisma::daohandler,
+    reconcile::{default_window, reconcile_handler, ReconcileReport},
+    Ctx,
+};
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct ReconcileResponse {
+    daoHandlerId: String,
+    success: bool,
+    clean: bool,
+    report: Option<ReconcileReport>,
+}
+
+#[post("/<dao_handler_id>?<days>&<repair>")]
+pub async fn reconcile_dao_handler(
+    ctx: &Ctx,
+    dao_handler_id: &str,
+    days: Option<i64>,
+    repair: Option<bool>,
+) -> Json<ReconcileResponse> {
+    let my_span = info_span!("reconcile_dao_handler", dao_handler_id = dao_handler_id);
+
+    async move {
+        let dao_handler = ctx
+            .db
+            .daohandler()
+            .find_first(vec![daohandler::id::equals(dao_handler_id.to_string())])
+            .include(daohandler_with_dao::include())
+            .exec()
+            .await
+            .expect("bad prisma result")
+            .expect("daoHandlerId not found");
+
+        let window = days
+            .map(Duration::days)
+            .unwrap_or_else(|| default_window(dao_handler.r#type));
+
+        match reconcile_handler(ctx, &dao_handler, window, repair.unwrap_or(false)).await {
+            Ok(report) => Json(ReconcileResponse {
+                daoHandlerId: dao_handler.id,
+                success: true,
+                clean: report.is_clean(),
+                report: Some(report),
+            }),
+            Err(e) => {
+                event!(Level::WARN, err = e.to_string(), "reconcile error");
+                Json(ReconcileResponse {
+                    daoHandlerId: dao_handler.id,
+                    success: false,
+                    clean: false,
+                    report: None,
+                })
+            }
+        }
+    }
+    .instrument(my_span)
+    .await
+}
@@ -0,0 +1,110 @@
+use prisma_client_rust::Direction;
+use rocket::serde::json::Json;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{event, info_span, Instrument, Level};
+
+use crate::{
+    prisma::{proposal, proposalsimulation},
+    proposal_with_dao,
+    simulation::simulate_proposal,
+    Ctx,
+};
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct SimulationResponse {
+    proposal_id: String,
+    success: bool,
+    executor: String,
+    block_number: i64,
+    actions: Value,
+    events: Value,
+    balance_changes: Value,
+    summary: String,
+    timestamp: i64,
+}
+
+impl From<proposalsimulation::Data> for SimulationResponse {
+    fn from(s: proposalsimulation::Data) -> Self {
+        SimulationResponse {
+            proposal_id: s.proposalid,
+            success: s.success,
+            executor: s.executor,
+            block_number: s.blocknumber,
+            actions: s.actions,
+            events: s.events,
+            balance_changes: s.balancechanges,
+            summary: s.summary,
+            timestamp: s.timecreated.timestamp(),
+        }
+    }
+}
+
+/// Latest simulation of a proposal, runs a new one when there is none or `refresh` is set.
+#[get("/<proposal_id>/simulation?<refresh>")]
+pub async fn get_proposal_simulation(
+    ctx: &Ctx,
+    proposal_id: &str,
+    refresh: Option<bool>,
+) -> Option<Json<SimulationResponse>> {
+    let my_span = info_span!("get_proposal_simulation", proposal_id = proposal_id);
+
+    async move {
+        let proposal = ctx
+            .db
+            .proposal()
+            .find_unique(proposal::id::equals(proposal_id.to_string()))
+            .include(proposal_with_dao::include())
+            .exec()
+            .await
+            .expect("bad prisma result")?;
+
+        if !refresh.unwrap_or(false) {
+            let latest = ctx
+                .db
+                .proposalsimulation()
+                .find_first(vec![proposalsimulation::proposalid::equals(
+                    proposal.id.clone(),
+                )])
+                .order_by(proposalsimulation::timecreated::order(Direction::Desc))
+                .exec()
+                .await
+                .expect("bad prisma result");
+
+            if let Some(latest) = latest {
+                return Some(Json(latest.into()));
+            }
+        }
+
+        let report = match simulate_proposal(ctx, &proposal).await {
+            Ok(r) => r,
+            Err(e) => {
+                event!(Level::WARN, err = e.to_string(), "simulation error");
+                return None;
+            }
+        };
+
+        let stored = ctx
+            .db
+            .proposalsimulation()
+            .create_unchecked(
+                proposal.id.clone(),
+                report.executor.clone(),
+                report.block_number,
+                report.success,
+                serde_json::to_value(&report.actions).unwrap(),
+                serde_json::to_value(&report.events).unwrap(),
+                serde_json::to_value(&report.balance_changes).unwrap(),
+                report.summary.clone(),
+                vec![],
+            )
+            .exec()
+            .await
+            .expect("bad prisma result");
+
+        Some(Json(stored.into()))
+    }
+    .instrument(my_span)
+    .await
+}
@@ -21,7 +21,9 @@ use tracing::{
 
 use crate::{
     daohandler_with_dao,
+    family::link_inserted,
     prisma::{dao, daohandler, proposal, ProposalState},
+    utils::proposal_history::record_proposal_snapshot,
     Ctx,
     ProposalsRequest,
     ProposalsResponse,
@@ -51,10 +53,28 @@ struct GraphQLProposal {
     end: i64,
     quorum: f64,
     link: String,
+    discussion: Option<String>,
     state: String,
     flagged: Option<bool>,
 }
 
+/// Our state for a Snapshot proposal `state` and `scores_state`. Closed proposals whose
+/// scores are not final yet stay hidden until they are.
+pub(crate) fn snapshot_state(state: &str, scores_state: &str) -> ProposalState {
+    match state {
+        "active" => ProposalState::Active,
+        "pending" => ProposalState::Pending,
+        "closed" => {
+            if scores_state == "final" {
+                ProposalState::Executed
+            } else {
+                ProposalState::Hidden
+            }
+        }
+        _ => ProposalState::Unknown,
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct Decoder {
     space: String,
@@ -113,6 +133,7 @@ pub async fn update_snapshot_proposals<'a>(
                         end
                         quorum
                         link
+                        discussion
                         state
                         flagged
                     }}
@@ -190,19 +211,10 @@ async fn update_proposals(
 
     let proposals: Vec<GraphQLProposal> = response_data.data.proposals.into_iter().collect();
 
+    let mut inserted = false;
+
     for proposal in proposals.clone() {
-        let state = match proposal.state.as_str() {
-            "active" => ProposalState::Active,
-            "pending" => ProposalState::Pending,
-            "closed" => {
-                if proposal.scores_state == "final" {
-                    ProposalState::Executed
-                } else {
-                    ProposalState::Hidden
-                }
-            }
-            _ => ProposalState::Unknown,
-        };
+        let state = snapshot_state(&proposal.state, &proposal.scores_state);
 
         let existing = ctx
             .db
@@ -230,7 +242,8 @@ async fn update_proposals(
                         dao_handler_id = dao_handler.id,
                         "update proposal"
                     );
-                    ctx.db
+                    let updated = ctx
+                        .db
                         .proposal()
                         .update(
                             proposal::externalid_daoid(
@@ -244,10 +257,13 @@ async fn update_proposals(
                                 proposal::quorum::set(proposal.quorum.into()),
                                 proposal::state::set(state),
                                 proposal::visible::set(!proposal.flagged.is_some_and(|f| f)),
+                                proposal::discussion::set(discussion(&proposal)),
                             ],
                         )
                         .exec()
                         .await?;
+
+                    record_proposal_snapshot(&ctx.db, &updated).await?;
                 }
             }
             None => {
@@ -261,7 +277,8 @@ async fn update_proposals(
                     "insert proposal"
                 );
 
-                ctx.db
+                let created = ctx
+                    .db
                     .proposal()
                     .create_unchecked(
                         proposal.title.clone(),
@@ -289,14 +306,24 @@ async fn update_proposals(
                         proposal.link.clone(),
                         dao_handler.id.to_string(),
                         dao_handler.daoid.to_string(),
-                        vec![proposal::visible::set(!proposal.flagged.is_some_and(|f| f))],
+                        vec![
+                            proposal::visible::set(!proposal.flagged.is_some_and(|f| f)),
+                            proposal::discussion::set(discussion(&proposal)),
+                        ],
                     )
                     .exec()
                     .await?;
+
+                record_proposal_snapshot(&ctx.db, &created).await?;
+                inserted = true;
             }
         }
     }
 
+    if inserted {
+        link_inserted(&ctx.db, &dao_handler.daoid).await;
+    }
+
     let open_proposals: Vec<&GraphQLProposal> = proposals
         .iter()
         .filter(|proposal| {
@@ -378,3 +405,12 @@ async fn update_proposals(
 
     Ok(())
 }
+
+// snapshot returns an empty string when there is no discussion link
+fn discussion(proposal: &GraphQLProposal) -> Option<String> {
+    proposal
+        .discussion
+        .clone()
+        .filter(|d| !d.trim().is_empty())
+        .map(|d| format!("{:.1024}", d.trim()))
+}
@@ -24,6 +24,7 @@ use tracing::{
 use crate::{
     daohandler_with_dao,
     prisma::{dao, daohandler, proposal, vote, voter, voterhandler},
+    utils::vote_history::record_vote_revision,
     voterhandler_with_voter,
     Ctx,
     VotesRequest,
@@ -307,9 +308,19 @@ async fn update_or_create_votes(
             .exec()
             .await?;
 
+        let created: DateTime<FixedOffset> = DateTime::from_naive_utc_and_offset(
+            NaiveDateTime::from_timestamp_millis(vote.created * 1000)
+                .expect("bad created timestamp"),
+            FixedOffset::east_opt(0).unwrap(),
+        );
+
         match existing {
             Some(existing) => {
-                if existing.choice != vote.choice
+                // a new created timestamp means the voter cast the vote again
+                let recast = existing.timecreated != Some(created);
+
+                if recast
+                    || existing.choice != vote.choice
                     || existing.votingpower.as_f64().unwrap().floor() != vote.vp.floor()
                     || existing.reason != vote.reason
                 {
@@ -323,7 +334,13 @@ async fn update_or_create_votes(
                         "update vote"
                     );
 
-                    ctx.db
+                    // votes stored before revisions were tracked become the first revision
+                    if recast {
+                        record_vote_revision(&ctx.db, &existing, "").await?;
+                    }
+
+                    let updated = ctx
+                        .db
                         .vote()
                         .update(
                             vote::voteraddress_daoid_proposalid(
@@ -332,11 +349,7 @@ async fn update_or_create_votes(
                                 proposal_id.clone(),
                             ),
                             vec![
-                                vote::timecreated::set(Some(DateTime::from_naive_utc_and_offset(
-                                    NaiveDateTime::from_timestamp_millis(vote.created * 1000)
-                                        .expect("bad created timestamp"),
-                                    FixedOffset::east_opt(0).unwrap(),
-                                ))),
+                                vote::timecreated::set(Some(created)),
                                 vote::choice::set(vote.choice.clone()),
                                 vote::votingpower::set(vote.vp.into()),
                                 vote::reason::set(vote.reason),
@@ -344,6 +357,10 @@ async fn update_or_create_votes(
                         )
                         .exec()
                         .await?;
+
+                    if recast {
+                        record_vote_revision(&ctx.db, &updated, &vote.id).await?;
+                    }
                 }
             }
             None => {
@@ -357,7 +374,8 @@ async fn update_or_create_votes(
                     "insert vote"
                 );
 
-                ctx.db
+                let inserted = ctx
+                    .db
                     .vote()
                     .create(
                         vote.choice.clone(),
@@ -367,16 +385,12 @@ async fn update_or_create_votes(
                         proposal::id::equals(proposal_id.clone()),
                         dao::id::equals(dao_handler.daoid.clone()),
                         daohandler::id::equals(dao_handler.id.clone()),
-                        vec![vote::timecreated::set(Some(
-                            DateTime::from_naive_utc_and_offset(
-                                NaiveDateTime::from_timestamp_millis(vote.created * 1000)
-                                    .expect("bad created timestamp"),
-                                FixedOffset::east_opt(0).unwrap(),
-                            ),
-                        ))],
+                        vec![vote::timecreated::set(Some(created))],
                     )
                     .exec()
                     .await?;
+
+                record_vote_revision(&ctx.db, &inserted, &vote.id).await?;
             }
         }
     }
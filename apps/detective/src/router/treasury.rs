@@ -0,0 +1,120 @@
+use prisma_client_rust::{
+    chrono::{DateTime, Duration, FixedOffset, Utc},
+    Direction,
+};
+use rocket::serde::json::Json;
+use serde::Serialize;
+use tracing::{info_span, Instrument};
+
+use crate::{
+    prisma::{treasury, treasurybalance, treasurytransfer},
+    Ctx,
+};
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct BalanceResponse {
+    address: String,
+    chain: String,
+    token: String,
+    symbol: String,
+    balance: String,
+    value: f64,
+    block_number: i64,
+    timestamp: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct TransferResponse {
+    address: String,
+    chain: String,
+    proposal_id: Option<String>,
+    token: String,
+    symbol: String,
+    from: String,
+    to: String,
+    amount: String,
+    value: f64,
+    txid: String,
+    timestamp: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct TreasuryResponse {
+    dao_id: String,
+    balances: Vec<BalanceResponse>,
+    transfers: Vec<TransferResponse>,
+}
+
+treasury::include!((since: DateTime<FixedOffset>) => treasury_with_history {
+    balances(vec![treasurybalance::timestamp::gte(since)])
+        .order_by(treasurybalance::timestamp::order(Direction::Asc))
+    transfers(vec![treasurytransfer::timecreated::gte(since)])
+        .order_by(treasurytransfer::timecreated::order(Direction::Asc))
+});
+
+/// Balance history and transfers of every treasury of a dao, defaults to the last 30 days.
+#[get("/<dao_id>?<since_hours>")]
+pub async fn get_treasury(
+    ctx: &Ctx,
+    dao_id: &str,
+    since_hours: Option<i64>,
+) -> Json<TreasuryResponse> {
+    let my_span = info_span!("get_treasury", dao_id = dao_id);
+
+    async move {
+        let since = Utc::now() - Duration::hours(since_hours.unwrap_or(24 * 30));
+
+        let treasuries = ctx
+            .db
+            .treasury()
+            .find_many(vec![treasury::daoid::equals(dao_id.to_string())])
+            .include(treasury_with_history::include(since.into()))
+            .exec()
+            .await
+            .expect("bad prisma result");
+
+        let mut balances = vec![];
+        let mut transfers = vec![];
+
+        for t in treasuries {
+            balances.extend(t.balances.into_iter().map(|b| BalanceResponse {
+                address: t.address.clone(),
+                chain: t.chain.clone(),
+                token: b.token,
+                symbol: b.symbol,
+                balance: b.balance,
+                value: b.value,
+                block_number: b.blocknumber,
+                timestamp: b.timestamp.timestamp(),
+            }));
+
+            transfers.extend(t.transfers.into_iter().map(|tr| TransferResponse {
+                address: t.address.clone(),
+                chain: t.chain.clone(),
+                proposal_id: tr.proposalid,
+                token: tr.token,
+                symbol: tr.symbol,
+                from: tr.fromaddress,
+                to: tr.toaddress,
+                amount: tr.amount,
+                value: tr.value,
+                txid: tr.txid,
+                timestamp: tr.timecreated.timestamp(),
+            }));
+        }
+
+        balances.sort_by_key(|b| b.timestamp);
+        transfers.sort_by_key(|t| t.timestamp);
+
+        Json(TreasuryResponse {
+            dao_id: dao_id.to_string(),
+            balances,
+            transfers,
+        })
+    }
+    .instrument(my_span)
+    .await
+}
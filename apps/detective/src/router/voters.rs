@@ -0,0 +1,51 @@
+use prisma_client_rust::chrono::Utc;
+use rocket::serde::json::Json;
+use serde::Serialize;
+use tracing::{event, info_span, Instrument, Level};
+
+use crate::{
+    ens::{is_stale, refresh_voter},
+    prisma::voter,
+    Ctx,
+};
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct VoterEnsResponse {
+    address: String,
+    name: Option<String>,
+    avatar: Option<String>,
+    updated: Option<i64>,
+}
+
+#[get("/<address>/ens")]
+pub async fn get_voter_ens(ctx: &Ctx, address: &str) -> Option<Json<VoterEnsResponse>> {
+    let my_span = info_span!("get_voter_ens", address = address);
+
+    async move {
+        let mut voter = ctx
+            .db
+            .voter()
+            .find_unique(voter::address::equals(address.to_string()))
+            .exec()
+            .await
+            .expect("bad prisma result")?;
+
+        // serve the cached name if the lookup fails, the schedule retries it
+        if is_stale(voter.ensupdatedat, Utc::now()) {
+            match refresh_voter(&ctx.db, &ctx.eth_rpc, &voter.address).await {
+                Ok(updated) => voter = updated,
+                Err(e) => event!(Level::WARN, err = e.to_string(), "ens lookup error"),
+            }
+        }
+
+        Some(Json(VoterEnsResponse {
+            address: voter.address,
+            name: voter.ensname,
+            avatar: voter.ensavatar,
+            updated: voter.ensupdatedat.map(|t| t.timestamp()),
+        }))
+    }
+    .instrument(my_span)
+    .await
+}
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use ethers::{
+    abi::{self, ParamType, Token},
+    contract::{parse_log, EthEvent},
+    providers::{Http, Middleware, Provider},
+    types::{Address, Bytes, Filter, TransactionRequest, U256},
+    utils::id,
+};
+use serde::Deserialize;
+
+use crate::{
+    contracts::{aavegov, compoundgov},
+    prisma::daohandler,
+};
+
+#[derive(Debug, Deserialize)]
+struct Decoder {
+    address: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Action {
+    pub target: Address,
+    pub value: U256,
+    pub signature: String,
+    pub calldata: Bytes,
+    pub delegatecall: bool,
+}
+
+impl Action {
+    /// The data the timelock sends, the selector is only prepended when a signature is given.
+    pub fn data(&self) -> Bytes {
+        if self.signature.is_empty() {
+            self.calldata.clone()
+        } else {
+            let mut data = id(&self.signature).to_vec();
+            data.extend_from_slice(&self.calldata);
+            data.into()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProposalActions {
+    pub executor: Address,
+    pub actions: Vec<Action>,
+}
+
+/// Actions of a governor proposal, read back from its `ProposalCreated` event.
+///
+/// Bravo, OZ and Nouns governors share the event layout and execute through their
+/// `timelock()`, Aave style governors name the executor in the event.
+pub async fn proposal_actions(
+    rpc: &Arc<Provider<Http>>,
+    dao_handler: &daohandler::Data,
+    external_id: &str,
+    block_created: Option<i64>,
+) -> Result<ProposalActions> {
+    let decoder: Decoder = serde_json::from_value(dao_handler.clone().decoder)?;
+
+    let address = decoder.address.parse::<Address>()?;
+
+    let block = match block_created {
+        Some(b) => b,
+        None => bail!("proposal {} has no creation block", external_id),
+    };
+
+    let logs = rpc
+        .get_logs(
+            &Filter::new()
+                .address(address)
+                .topic0(vec![
+                    compoundgov::ProposalCreatedFilter::signature(),
+                    aavegov::ProposalCreatedFilter::signature(),
+                ])
+                .from_block(block)
+                .to_block(block),
+        )
+        .await?;
+
+    for log in logs {
+        if let Ok(created) = parse_log::<compoundgov::ProposalCreatedFilter>(log.clone()) {
+            if created.id.to_string() != external_id {
+                continue;
+            }
+
+            let executor = timelock(rpc, address).await.unwrap_or(address);
+
+            return Ok(ProposalActions {
+                executor,
+                actions: zip_actions(
+                    created.targets,
+                    created.values,
+                    created.signatures,
+                    created.calldatas,
+                    vec![],
+                ),
+            });
+        }
+
+        if let Ok(created) = parse_log::<aavegov::ProposalCreatedFilter>(log) {
+            if created.id.to_string() != external_id {
+                continue;
+            }
+
+            return Ok(ProposalActions {
+                executor: created.executor,
+                actions: zip_actions(
+                    created.targets,
+                    created.values,
+                    created.signatures,
+                    created.calldatas,
+                    created.with_delegatecalls,
+                ),
+            });
+        }
+    }
+
+    bail!("no ProposalCreated event for {}", external_id)
+}
+
+fn zip_actions(
+    targets: Vec<Address>,
+    values: Vec<U256>,
+    signatures: Vec<String>,
+    calldatas: Vec<Bytes>,
+    delegatecalls: Vec<bool>,
+) -> Vec<Action> {
+    targets
+        .into_iter()
+        .enumerate()
+        .map(|(i, target)| Action {
+            target,
+            value: values.get(i).copied().unwrap_or_default(),
+            signature: signatures.get(i).cloned().unwrap_or_default(),
+            calldata: calldatas.get(i).cloned().unwrap_or_default(),
+            delegatecall: delegatecalls.get(i).copied().unwrap_or(false),
+        })
+        .collect()
+}
+
+async fn timelock(rpc: &Arc<Provider<Http>>, governor: Address) -> Option<Address> {
+    let tx = TransactionRequest::new()
+        .to(governor)
+        .data(id("timelock()").to_vec());
+
+    let result = rpc.call(&tx.into(), None).await.ok()?;
+
+    match abi::decode(&[ParamType::Address], &result).ok()?.pop()? {
+        Token::Address(a) if !a.is_zero() => Some(a),
+        _ => None,
+    }
+}
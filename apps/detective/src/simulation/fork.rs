@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use ethers::{
+    providers::{Http, Middleware, Provider, ProviderError},
+    types::{BlockId, BlockNumber, H160, H256, U256 as EU256},
+};
+use revm::{
+    db::DatabaseRef,
+    primitives::{keccak256, AccountInfo, Bytecode, B160, B256, U256},
+};
+use tokio::runtime::Handle;
+
+/// Chain state at a fixed block, fetched from the RPC the first time the EVM touches it.
+///
+/// Wrapped in a `CacheDB` every account and slot is fetched once and writes stay local.
+/// The EVM is synchronous, so this has to run on a blocking thread.
+pub struct ForkDb {
+    rpc: Arc<Provider<Http>>,
+    block: BlockId,
+    handle: Handle,
+}
+
+impl ForkDb {
+    pub fn new(rpc: Arc<Provider<Http>>, block_number: u64) -> Self {
+        ForkDb {
+            rpc,
+            block: BlockId::Number(BlockNumber::Number(block_number.into())),
+            handle: Handle::current(),
+        }
+    }
+}
+
+impl DatabaseRef for ForkDb {
+    type Error = ProviderError;
+
+    fn basic(&self, address: B160) -> Result<Option<AccountInfo>, Self::Error> {
+        let address = to_h160(address);
+
+        let (balance, nonce, code) = self.handle.block_on(async {
+            tokio::try_join!(
+                self.rpc.get_balance(address, Some(self.block)),
+                self.rpc.get_transaction_count(address, Some(self.block)),
+                self.rpc.get_code(address, Some(self.block)),
+            )
+        })?;
+
+        Ok(Some(AccountInfo::new(
+            to_u256(balance),
+            nonce.as_u64(),
+            keccak256(&code),
+            Bytecode::new_raw(code.0),
+        )))
+    }
+
+    fn code_by_hash(&self, _code_hash: B256) -> Result<Bytecode, Self::Error> {
+        // code always comes with the account in `basic`
+        Ok(Bytecode::new())
+    }
+
+    fn storage(&self, address: B160, index: U256) -> Result<U256, Self::Error> {
+        let slot = H256::from(index.to_be_bytes::<32>());
+
+        let value = self.handle.block_on(self.rpc.get_storage_at(
+            to_h160(address),
+            slot,
+            Some(self.block),
+        ))?;
+
+        Ok(U256::from_be_bytes(value.0))
+    }
+
+    fn block_hash(&self, number: U256) -> Result<B256, Self::Error> {
+        let block = self
+            .handle
+            .block_on(self.rpc.get_block(number.as_limbs()[0]))?;
+
+        Ok(block
+            .and_then(|b| b.hash)
+            .map(|h| B256(h.0))
+            .unwrap_or_default())
+    }
+}
+
+pub fn to_h160(address: B160) -> H160 {
+    H160(address.0)
+}
+
+pub fn to_b160(address: H160) -> B160 {
+    B160(address.0)
+}
+
+pub fn to_u256(value: EU256) -> U256 {
+    U256::from_limbs(value.0)
+}
+
+pub fn to_eu256(value: U256) -> EU256 {
+    EU256(value.into_limbs())
+}
@@ -0,0 +1,520 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{anyhow, bail, Result};
+use ethers::{
+    abi::{self, ParamType, Token},
+    providers::{Http, Middleware, Provider},
+    types::{Address, BlockNumber, H256, I256, U256 as EU256},
+    utils::{format_units, keccak256},
+};
+use revm::{
+    db::CacheDB,
+    primitives::{ExecutionResult, Log, TransactTo, U256},
+    Database,
+    EVM,
+};
+use serde::Serialize;
+use tracing::{event, instrument, Level};
+
+use self::{
+    actions::{proposal_actions, ProposalActions},
+    fork::{to_b160, to_eu256, to_h160, to_u256, ForkDb},
+};
+use crate::{
+    prisma::ProposalState,
+    proposal_with_dao,
+    utils::erc20::{token_metadata, transfer_topic},
+    Context,
+};
+
+pub mod actions;
+pub mod fork;
+
+const ACTION_GAS_LIMIT: u64 = 30_000_000;
+
+// events worth naming in the summary, anything else is listed by its topic
+const KNOWN_EVENTS: [&str; 14] = [
+    "Transfer(address,address,uint256)",
+    "Approval(address,address,uint256)",
+    "OwnershipTransferred(address,address)",
+    "RoleGranted(bytes32,address,address)",
+    "RoleRevoked(bytes32,address,address)",
+    "Upgraded(address)",
+    "AdminChanged(address,address)",
+    "Paused(address)",
+    "Unpaused(address)",
+    "NewAdmin(address)",
+    "NewPendingAdmin(address)",
+    "NewImplementation(address,address)",
+    "NewDelay(uint256)",
+    "Deposit(address,uint256)",
+];
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct ActionResult {
+    pub index: usize,
+    pub target: String,
+    pub signature: String,
+    pub value: String,
+    pub success: bool,
+    pub revert_reason: Option<String>,
+    pub gas_used: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct SimulatedEvent {
+    pub action: usize,
+    pub address: String,
+    pub name: Option<String>,
+    pub topics: Vec<String>,
+    pub data: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct BalanceChange {
+    pub token: Option<String>,
+    pub symbol: String,
+    pub decimals: u8,
+    pub delta: String,
+    pub formatted: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct SimulationReport {
+    pub proposal_id: String,
+    pub executor: String,
+    pub block_number: i64,
+    pub success: bool,
+    pub actions: Vec<ActionResult>,
+    pub events: Vec<SimulatedEvent>,
+    pub balance_changes: Vec<BalanceChange>,
+    pub summary: String,
+}
+
+struct Execution {
+    results: Vec<ActionResult>,
+    logs: Vec<(usize, Log)>,
+    eth_before: EU256,
+    eth_after: EU256,
+}
+
+/// Runs the actions of a proposal the way its timelock would, against the latest block.
+///
+/// Every action is sent from the executor in order and state carries over between them,
+/// like it does inside the single execution transaction. The first revert stops the run.
+#[instrument(skip_all, fields(proposal_id = proposal.id))]
+pub async fn simulate_proposal(
+    ctx: &Context,
+    proposal: &proposal_with_dao::Data,
+) -> Result<SimulationReport> {
+    match proposal.state {
+        ProposalState::Pending
+        | ProposalState::Active
+        | ProposalState::ObjectionPeriod
+        | ProposalState::Succeeded
+        | ProposalState::Queued => {}
+        state => bail!("{:?} proposals can not be executed anymore", state),
+    }
+
+    let rpc = ctx
+        .rpc_for(proposal.daohandler.r#type, &proposal.daohandler.decoder)
+        .clone();
+
+    let actions = proposal_actions(
+        &rpc,
+        &proposal.daohandler,
+        &proposal.externalid,
+        proposal.blockcreated,
+    )
+    .await?;
+
+    let block = rpc
+        .get_block(BlockNumber::Latest)
+        .await?
+        .ok_or_else(|| anyhow!("no latest block"))?;
+    let block_number = block.number.unwrap_or_default().as_u64();
+    let timestamp = block.timestamp.as_u64();
+
+    let executor = actions.executor;
+
+    let execution = {
+        let rpc = rpc.clone();
+        let actions = actions.clone();
+        tokio::task::spawn_blocking(move || execute(rpc, block_number, timestamp, actions))
+            .await??
+    };
+
+    let balance_changes = balance_changes(&rpc, block_number, executor, &execution).await;
+
+    let events: Vec<SimulatedEvent> = execution
+        .logs
+        .iter()
+        .map(|(action, log)| SimulatedEvent {
+            action: *action,
+            address: format!("{:#x}", to_h160(log.address)),
+            name: event_name(log),
+            topics: log
+                .topics
+                .iter()
+                .map(|t| format!("{:#x}", H256(t.0)))
+                .collect(),
+            data: format!("0x{}", hex_string(&log.data)),
+        })
+        .collect();
+
+    let success = execution.results.len() == actions.actions.len()
+        && execution.results.iter().all(|r| r.success);
+
+    let summary = summarize(
+        executor,
+        block_number,
+        actions.actions.len(),
+        &execution.results,
+        &events,
+        &balance_changes,
+    );
+
+    event!(
+        Level::INFO,
+        proposal_id = proposal.id,
+        success = success,
+        events = events.len(),
+        "proposal simulated"
+    );
+
+    Ok(SimulationReport {
+        proposal_id: proposal.id.clone(),
+        executor: format!("{:#x}", executor),
+        block_number: block_number as i64,
+        success,
+        actions: execution.results,
+        events,
+        balance_changes,
+        summary,
+    })
+}
+
+fn execute(
+    rpc: Arc<Provider<Http>>,
+    block_number: u64,
+    timestamp: u64,
+    actions: ProposalActions,
+) -> Result<Execution> {
+    let mut db = CacheDB::new(ForkDb::new(rpc, block_number));
+
+    let executor = to_b160(actions.executor);
+
+    let eth_before = db
+        .basic(executor)?
+        .map(|a| to_eu256(a.balance))
+        .unwrap_or_default();
+
+    let mut evm = EVM::new();
+    evm.database(db);
+
+    evm.env.cfg.disable_eip3607 = true;
+    evm.env.block.number = U256::from(block_number + 1);
+    evm.env.block.timestamp = U256::from(timestamp);
+    evm.env.tx.caller = executor;
+    evm.env.tx.gas_limit = ACTION_GAS_LIMIT;
+    evm.env.tx.gas_price = U256::ZERO;
+
+    let mut results = vec![];
+    let mut logs = vec![];
+
+    for (index, action) in actions.actions.iter().enumerate() {
+        let target = to_b160(action.target);
+
+        // a delegatecall runs the target code on the executor storage, swapping the
+        // executor code for the call gets the same effect from a plain call
+        let original = if action.delegatecall {
+            let db = evm.db().expect("simulation db");
+            let code = db.basic(target)?.unwrap_or_default();
+            let original = db.basic(executor)?.unwrap_or_default();
+
+            let mut swapped = original.clone();
+            swapped.code = code.code;
+            swapped.code_hash = code.code_hash;
+            db.insert_account_info(executor, swapped);
+
+            Some(original)
+        } else {
+            None
+        };
+
+        evm.env.tx.transact_to = TransactTo::Call(if action.delegatecall {
+            executor
+        } else {
+            target
+        });
+        evm.env.tx.value = if action.delegatecall {
+            U256::ZERO
+        } else {
+            to_u256(action.value)
+        };
+        evm.env.tx.data = action.data().0;
+
+        let outcome = evm
+            .transact_commit()
+            .map_err(|e| anyhow!("simulation error {:?}", e))?;
+
+        if let Some(original) = original {
+            let db = evm.db().expect("simulation db");
+            let mut restored = db.basic(executor)?.unwrap_or_default();
+            restored.code = original.code;
+            restored.code_hash = original.code_hash;
+            db.insert_account_info(executor, restored);
+        }
+
+        let (success, revert_reason, gas_used) = match outcome {
+            ExecutionResult::Success {
+                gas_used,
+                logs: action_logs,
+                ..
+            } => {
+                logs.extend(action_logs.into_iter().map(|l| (index, l)));
+                (true, None, gas_used)
+            }
+            ExecutionResult::Revert { gas_used, output } => {
+                (false, Some(revert_reason(&output)), gas_used)
+            }
+            ExecutionResult::Halt { reason, gas_used } => {
+                (false, Some(format!("{:?}", reason)), gas_used)
+            }
+        };
+
+        results.push(ActionResult {
+            index,
+            target: format!("{:#x}", action.target),
+            signature: action.signature.clone(),
+            value: action.value.to_string(),
+            success,
+            revert_reason,
+            gas_used,
+        });
+
+        if !success {
+            break;
+        }
+    }
+
+    let eth_after = evm
+        .db()
+        .expect("simulation db")
+        .basic(executor)?
+        .map(|a| to_eu256(a.balance))
+        .unwrap_or_default();
+
+    Ok(Execution {
+        results,
+        logs,
+        eth_before,
+        eth_after,
+    })
+}
+
+/// ETH and ERC20 balance changes of the executor, tokens are read from `Transfer` events.
+async fn balance_changes(
+    rpc: &Arc<Provider<Http>>,
+    block_number: u64,
+    executor: Address,
+    execution: &Execution,
+) -> Vec<BalanceChange> {
+    let transfer = transfer_topic();
+    let executor_topic = H256::from(executor);
+
+    let mut deltas: BTreeMap<Address, I256> = BTreeMap::new();
+
+    for (_, log) in execution.logs.iter() {
+        // erc721 transfers index the token id as well
+        if log.topics.len() != 3 || log.data.len() != 32 || H256(log.topics[0].0) != transfer {
+            continue;
+        }
+
+        let amount = I256::from_raw(EU256::from_big_endian(&log.data));
+        let token = to_h160(log.address);
+
+        if H256(log.topics[1].0) == executor_topic {
+            *deltas.entry(token).or_default() -= amount;
+        }
+        if H256(log.topics[2].0) == executor_topic {
+            *deltas.entry(token).or_default() += amount;
+        }
+    }
+
+    let mut changes = vec![];
+
+    let eth_delta =
+        I256::from_raw(execution.eth_after).saturating_sub(I256::from_raw(execution.eth_before));
+    if !eth_delta.is_zero() {
+        changes.push(BalanceChange {
+            token: None,
+            symbol: "ETH".to_string(),
+            decimals: 18,
+            delta: eth_delta.to_string(),
+            formatted: format_amount(eth_delta, 18),
+        });
+    }
+
+    for (token, delta) in deltas {
+        if delta.is_zero() {
+            continue;
+        }
+
+        let (symbol, decimals) = token_metadata(rpc, block_number, token).await;
+
+        changes.push(BalanceChange {
+            token: Some(format!("{:#x}", token)),
+            formatted: format_amount(delta, decimals),
+            delta: delta.to_string(),
+            symbol,
+            decimals,
+        });
+    }
+
+    changes
+}
+
+fn event_name(log: &Log) -> Option<String> {
+    let topic0 = H256(log.topics.first()?.0);
+
+    KNOWN_EVENTS
+        .iter()
+        .find(|e| H256::from(keccak256(e)) == topic0)
+        .map(|e| e.split('(').next().unwrap_or(e).to_string())
+}
+
+fn revert_reason(output: &[u8]) -> String {
+    // Error(string)
+    if output.len() > 4 && output[..4] == [0x08, 0xc3, 0x79, 0xa0] {
+        if let Ok(mut tokens) = abi::decode(&[ParamType::String], &output[4..]) {
+            if let Some(Token::String(reason)) = tokens.pop() {
+                return reason;
+            }
+        }
+    }
+
+    if output.is_empty() {
+        "reverted without a reason".to_string()
+    } else {
+        format!("0x{}", hex_string(output))
+    }
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Signed token amount with trailing zeros dropped, `-1.5` rather than `-1.500000000000000000`.
+pub fn format_amount(amount: I256, decimals: u8) -> String {
+    let sign = if amount.is_negative() { "-" } else { "+" };
+
+    let formatted = format_units(amount.unsigned_abs(), decimals as u32)
+        .unwrap_or_else(|_| amount.unsigned_abs().to_string());
+
+    let trimmed = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        formatted.as_str()
+    };
+
+    format!("{}{}", sign, trimmed)
+}
+
+fn summarize(
+    executor: Address,
+    block_number: u64,
+    action_count: usize,
+    results: &[ActionResult],
+    events: &[SimulatedEvent],
+    balance_changes: &[BalanceChange],
+) -> String {
+    let mut lines = vec![];
+
+    let failed = results.iter().find(|r| !r.success);
+
+    lines.push(format!(
+        "Simulated {} action{} from {:#x} at block {}: {}",
+        action_count,
+        if action_count == 1 { "" } else { "s" },
+        executor,
+        block_number,
+        match failed {
+            Some(r) => format!("action {} reverts", r.index + 1),
+            None => "all actions succeed".to_string(),
+        }
+    ));
+
+    for result in results {
+        lines.push(format!(
+            "{}. {} on {}{}",
+            result.index + 1,
+            if result.signature.is_empty() {
+                "call".to_string()
+            } else {
+                result.signature.clone()
+            },
+            result.target,
+            match &result.revert_reason {
+                Some(reason) => format!(" reverted: {}", reason),
+                None => "".to_string(),
+            }
+        ));
+    }
+
+    if balance_changes.is_empty() {
+        lines.push("Treasury balances do not change".to_string());
+    } else {
+        lines.push(format!(
+            "Treasury balance changes: {}",
+            balance_changes
+                .iter()
+                .map(|c| format!("{} {}", c.formatted, c.symbol))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+
+    if !events.is_empty() {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for e in events {
+            *counts
+                .entry(e.name.clone().unwrap_or("unknown".to_string()))
+                .or_default() += 1;
+        }
+
+        lines.push(format!(
+            "{} event{} emitted: {}",
+            events.len(),
+            if events.len() == 1 { "" } else { "s" },
+            counts
+                .iter()
+                .map(|(name, count)| format!("{} {}", count, name))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::I256;
+
+    use super::format_amount;
+
+    #[test]
+    fn formats_signed_amounts() {
+        let one_and_a_half = I256::from(1_500_000_000_000_000_000i128);
+
+        assert_eq!(format_amount(one_and_a_half, 18), "+1.5");
+        assert_eq!(format_amount(-one_and_a_half, 18), "-1.5");
+        assert_eq!(format_amount(I256::from(2_000_000), 6), "+2");
+        assert_eq!(format_amount(I256::from(42), 0), "+42");
+    }
+}
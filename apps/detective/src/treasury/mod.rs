@@ -0,0 +1,498 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{bail, Result};
+use chrono::{Duration, TimeZone, Utc};
+use ethers::{
+    contract::EthEvent,
+    providers::{Http, Middleware, Provider},
+    types::{Address, Filter, Log, H256, U256},
+    utils::format_units,
+};
+use metrics::counter;
+use prisma_client_rust::Direction;
+use serde::Serialize;
+use tracing::{event, instrument, Level};
+
+use crate::{
+    contracts::{aavegov, compoundgov, safe},
+    prisma::{proposal, treasury, treasurybalance, treasurytransfer, PrismaClient},
+    utils::{
+        erc20::{balance_of, token_metadata, transfer_topic},
+        safe::first_word,
+    },
+    Chain,
+    Context,
+};
+
+// balances are sampled, transfers are complete
+const BALANCE_INTERVAL_MINUTES: i64 = 60;
+// how far back a new treasury, or a token added to one, starts scanning transfers
+const INITIAL_WINDOW_HOURS: i64 = 7 * 24;
+// the most one transfer scan covers
+const MAX_RANGE_HOURS: i64 = 32;
+
+pub const NATIVE_TOKEN: &str = "0x0000000000000000000000000000000000000000";
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct TreasuryUpdate {
+    pub treasury_id: String,
+    pub balances: usize,
+    pub transfers: usize,
+    pub chainindex: i64,
+}
+
+#[derive(Debug, Clone)]
+struct TokenInfo {
+    symbol: String,
+    decimals: u8,
+}
+
+/// Blocks a chain produces in an hour, windows are sized in time so they cover the same
+/// history on every chain.
+fn blocks_per_hour(chain: Chain) -> i64 {
+    match chain {
+        Chain::Ethereum => 300,
+        Chain::Optimism => 1_800,
+        Chain::Arbitrum => 14_400,
+    }
+}
+
+fn initial_window(chain: Chain) -> i64 {
+    INITIAL_WINDOW_HOURS * blocks_per_hour(chain)
+}
+
+fn max_range(chain: Chain) -> i64 {
+    MAX_RANGE_HOURS * blocks_per_hour(chain)
+}
+
+/// Next block range to scan for transfers, `None` when the treasury is caught up.
+pub fn scan_range(chain: Chain, chainindex: i64, latest: i64) -> Option<(i64, i64)> {
+    let from = if chainindex == 0 {
+        (latest - initial_window(chain)).max(0)
+    } else {
+        chainindex + 1
+    };
+
+    if from > latest {
+        return None;
+    }
+
+    Some((from, (from + max_range(chain) - 1).min(latest)))
+}
+
+/// Blocks still to scan for each token, by address. A token added after the treasury
+/// started scanning gets the initial window behind the block it was first seen at, the
+/// forward scan covers it from there. `None` once its history is scanned.
+type TokenBackfill = HashMap<String, Option<(i64, i64)>>;
+
+fn token_backfill(
+    treasury: &treasury::Data,
+    chain: Chain,
+    tokens: &[Address],
+) -> Result<TokenBackfill> {
+    let mut backfill: TokenBackfill = match &treasury.tokenbackfill {
+        Some(value) => serde_json::from_value(value.clone())?,
+        // treasuries from before the backfill have scanned every token they list
+        None => tokens.iter().map(|t| (format!("{:#x}", t), None)).collect(),
+    };
+
+    let listed: Vec<String> = tokens.iter().map(|t| format!("{:#x}", t)).collect();
+    backfill.retain(|token, _| listed.contains(token));
+
+    for token in listed {
+        backfill.entry(token).or_insert_with(|| {
+            if treasury.chainindex == 0 {
+                None
+            } else {
+                Some((
+                    (treasury.chainindex - initial_window(chain)).max(0),
+                    treasury.chainindex,
+                ))
+            }
+        });
+    }
+
+    Ok(backfill)
+}
+
+#[instrument(skip_all, fields(treasury_id = treasury.id))]
+pub async fn update_treasury(ctx: &Context, treasury: &treasury::Data) -> Result<TreasuryUpdate> {
+    let chain = match Chain::parse(&treasury.chain) {
+        Some(c) => c,
+        None => bail!("unknown chain {}", treasury.chain),
+    };
+
+    let rpc = ctx.rpc_for_chain(chain);
+
+    let address = treasury.address.parse::<Address>()?;
+
+    let tokens: Vec<Address> = serde_json::from_value::<Vec<String>>(treasury.tokens.clone())?
+        .iter()
+        .filter_map(|t| t.parse::<Address>().ok())
+        .collect();
+
+    let latest = rpc.get_block_number().await?.as_u64();
+
+    let mut token_info: HashMap<Address, TokenInfo> = HashMap::new();
+    for token in tokens.iter() {
+        let (symbol, decimals) = token_metadata(rpc, latest, *token).await;
+        token_info.insert(*token, TokenInfo { symbol, decimals });
+    }
+
+    let balances = record_balances(&ctx.db, rpc, treasury, address, latest, &token_info).await?;
+
+    let mut backfill = token_backfill(treasury, chain, &tokens)?;
+
+    let (mut transfers, chainindex) = match scan_range(chain, treasury.chainindex, latest as i64) {
+        Some((from, to)) if !tokens.is_empty() => {
+            let transfers =
+                record_transfers(&ctx.db, rpc, treasury, address, from, to, &token_info).await?;
+            (transfers, to)
+        }
+        Some((_, to)) => (0, to),
+        None => (0, treasury.chainindex),
+    };
+
+    for token in tokens.iter() {
+        let key = format!("{:#x}", token);
+
+        let Some(Some((from, until))) = backfill.get(&key).copied() else {
+            continue;
+        };
+
+        let to = (from + max_range(chain) - 1).min(until);
+        let info: HashMap<Address, TokenInfo> = token_info
+            .get(token)
+            .map(|i| (*token, i.clone()))
+            .into_iter()
+            .collect();
+
+        transfers += record_transfers(&ctx.db, rpc, treasury, address, from, to, &info).await?;
+
+        backfill.insert(key, (to < until).then_some((to + 1, until)));
+    }
+
+    ctx.db
+        .treasury()
+        .update(
+            treasury::id::equals(treasury.id.clone()),
+            vec![
+                treasury::chainindex::set(chainindex),
+                treasury::tokenbackfill::set(Some(serde_json::to_value(&backfill)?)),
+            ],
+        )
+        .exec()
+        .await?;
+
+    Ok(TreasuryUpdate {
+        treasury_id: treasury.id.clone(),
+        balances,
+        transfers,
+        chainindex,
+    })
+}
+
+async fn record_balances(
+    db: &PrismaClient,
+    rpc: &Arc<Provider<Http>>,
+    treasury: &treasury::Data,
+    address: Address,
+    block_number: u64,
+    token_info: &HashMap<Address, TokenInfo>,
+) -> Result<usize> {
+    let last = db
+        .treasurybalance()
+        .find_first(vec![treasurybalance::treasuryid::equals(
+            treasury.id.clone(),
+        )])
+        .order_by(treasurybalance::timestamp::order(Direction::Desc))
+        .exec()
+        .await?;
+
+    if let Some(last) = last {
+        if Utc::now() - last.timestamp.with_timezone(&Utc)
+            < Duration::minutes(BALANCE_INTERVAL_MINUTES)
+        {
+            return Ok(0);
+        }
+    }
+
+    let eth = rpc.get_balance(address, Some(block_number.into())).await?;
+
+    let mut rows = vec![treasurybalance::create_unchecked(
+        treasury.id.clone(),
+        NATIVE_TOKEN.to_string(),
+        "ETH".to_string(),
+        18,
+        eth.to_string(),
+        token_value(eth, 18),
+        block_number as i64,
+        vec![],
+    )];
+
+    for (token, info) in token_info.iter() {
+        let balance = match balance_of(rpc, block_number, *token, address).await {
+            Ok(b) => b,
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    token = format!("{:#x}", token),
+                    err = e.to_string(),
+                    "treasury balance error"
+                );
+                continue;
+            }
+        };
+
+        rows.push(treasurybalance::create_unchecked(
+            treasury.id.clone(),
+            format!("{:#x}", token),
+            info.symbol.clone(),
+            info.decimals as i32,
+            balance.to_string(),
+            token_value(balance, info.decimals),
+            block_number as i64,
+            vec![],
+        ));
+    }
+
+    let count = db.treasurybalance().create_many(rows).exec().await?;
+
+    Ok(count as usize)
+}
+
+async fn record_transfers(
+    db: &PrismaClient,
+    rpc: &Arc<Provider<Http>>,
+    treasury: &treasury::Data,
+    address: Address,
+    from_block: i64,
+    to_block: i64,
+    token_info: &HashMap<Address, TokenInfo>,
+) -> Result<usize> {
+    let tokens: Vec<Address> = token_info.keys().copied().collect();
+    let treasury_topic = H256::from(address);
+
+    let base = Filter::new()
+        .address(tokens)
+        .topic0(transfer_topic())
+        .from_block(from_block)
+        .to_block(to_block);
+
+    let mut logs = rpc.get_logs(&base.clone().topic1(treasury_topic)).await?;
+    logs.extend(rpc.get_logs(&base.topic2(treasury_topic)).await?);
+
+    let mut timestamps: HashMap<u64, i64> = HashMap::new();
+    let mut proposals: HashMap<H256, Option<String>> = HashMap::new();
+    let mut rows = vec![];
+
+    for log in logs {
+        // erc721 transfers index the token id as well
+        if log.topics.len() != 3 || log.data.len() != 32 {
+            continue;
+        }
+
+        let info = match token_info.get(&log.address) {
+            Some(i) => i,
+            None => continue,
+        };
+
+        let from = Address::from(log.topics[1]);
+        let to = Address::from(log.topics[2]);
+        let amount = U256::from_big_endian(&log.data);
+
+        // transfers to itself do not move anything
+        if from == to {
+            continue;
+        }
+
+        let value = if from == address {
+            -token_value(amount, info.decimals)
+        } else {
+            token_value(amount, info.decimals)
+        };
+
+        let block_number = log.block_number.unwrap_or_default().as_u64();
+        let timestamp = match timestamps.get(&block_number) {
+            Some(t) => *t,
+            None => {
+                let block = rpc.get_block(block_number).await?;
+                let t = block.map(|b| b.timestamp.as_u64() as i64).unwrap_or(0);
+                timestamps.insert(block_number, t);
+                t
+            }
+        };
+
+        let tx_hash = log.transaction_hash.unwrap_or_default();
+        let proposal_id = match proposals.get(&tx_hash) {
+            Some(p) => p.clone(),
+            None => {
+                let p = executed_proposal(db, rpc, &treasury.daoid, tx_hash).await?;
+                proposals.insert(tx_hash, p.clone());
+                p
+            }
+        };
+
+        rows.push(treasurytransfer::create_unchecked(
+            treasury.id.clone(),
+            format!("{:#x}", log.address),
+            info.symbol.clone(),
+            info.decimals as i32,
+            format!("{:#x}", from),
+            format!("{:#x}", to),
+            amount.to_string(),
+            value,
+            format!("{:#x}", tx_hash),
+            log.log_index.unwrap_or_default().as_u32() as i32,
+            block_number as i64,
+            Utc.timestamp_opt(timestamp, 0).unwrap().into(),
+            vec![treasurytransfer::proposalid::set(proposal_id)],
+        ));
+    }
+
+    let count = db
+        .treasurytransfer()
+        .create_many(rows)
+        .skip_duplicates()
+        .exec()
+        .await?;
+
+    if count > 0 {
+        counter!("detective_treasury_transfers", count as u64);
+    }
+
+    Ok(count as usize)
+}
+
+/// The proposal executed by a transaction, read from the execution event in its receipt.
+async fn executed_proposal(
+    db: &PrismaClient,
+    rpc: &Arc<Provider<Http>>,
+    dao_id: &str,
+    tx_hash: H256,
+) -> Result<Option<String>> {
+    let receipt = match rpc.get_transaction_receipt(tx_hash).await? {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+
+    for log in receipt.logs.iter() {
+        let external_id = match execution_id(log) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        let proposal = db
+            .proposal()
+            .find_first(vec![
+                proposal::externalid::equals(external_id),
+                proposal::daoid::equals(dao_id.to_string()),
+            ])
+            .exec()
+            .await?;
+
+        if let Some(p) = proposal {
+            return Ok(Some(p.id));
+        }
+    }
+
+    Ok(None)
+}
+
+fn execution_id(log: &Log) -> Option<String> {
+    let topic = *log.topics.first()?;
+
+    // bravo, oz and nouns share the event, aave adds the executor as an indexed topic
+    if topic == compoundgov::ProposalExecutedFilter::signature()
+        || topic == aavegov::ProposalExecutedFilter::signature()
+    {
+        if log.data.len() < 32 {
+            return None;
+        }
+        return Some(U256::from_big_endian(&log.data[..32]).to_string());
+    }
+
+    if topic == safe::ExecutionSuccessFilter::signature() {
+        return first_word(log).map(|h| format!("{:#x}", h));
+    }
+
+    None
+}
+
+fn token_value(amount: U256, decimals: u8) -> f64 {
+    format_units(amount, decimals as u32)
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(0.0)
+}
+
+pub async fn run_schedule(ctx: Context) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60 * 10));
+
+    loop {
+        interval.tick().await;
+
+        let treasuries = match ctx.db.treasury().find_many(vec![]).exec().await {
+            Ok(r) => r,
+            Err(e) => {
+                event!(Level::WARN, err = e.to_string(), "treasury schedule error");
+                continue;
+            }
+        };
+
+        for treasury in treasuries {
+            match update_treasury(&ctx, &treasury).await {
+                Ok(update) => event!(
+                    Level::INFO,
+                    treasury_id = update.treasury_id,
+                    balances = update.balances,
+                    transfers = update.transfers,
+                    "treasury updated"
+                ),
+                Err(e) => {
+                    counter!("detective_treasury_errors", 1);
+                    event!(
+                        Level::WARN,
+                        treasury_id = treasury.id,
+                        err = e.to_string(),
+                        "treasury error"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{initial_window, max_range, scan_range};
+    use crate::Chain;
+
+    #[test]
+    fn scans_in_bounded_ranges() {
+        let window = initial_window(Chain::Ethereum);
+        let range = max_range(Chain::Ethereum);
+
+        // a new treasury starts a window behind the head
+        assert_eq!(
+            scan_range(Chain::Ethereum, 0, 1_000_000),
+            Some((1_000_000 - window, 1_000_000 - window + range - 1))
+        );
+        // young chains start at genesis
+        assert_eq!(scan_range(Chain::Ethereum, 0, 100), Some((0, 100)));
+        // caught up
+        assert_eq!(scan_range(Chain::Ethereum, 100, 100), None);
+        assert_eq!(scan_range(Chain::Ethereum, 99, 100), Some((100, 100)));
+    }
+
+    #[test]
+    fn windows_cover_the_same_time_on_every_chain() {
+        assert_eq!(initial_window(Chain::Ethereum), 50_400);
+        assert_eq!(
+            initial_window(Chain::Arbitrum),
+            48 * initial_window(Chain::Ethereum)
+        );
+        assert_eq!(max_range(Chain::Optimism), 6 * max_range(Chain::Ethereum));
+    }
+}
@@ -0,0 +1,657 @@
+use std::{env, sync::OnceLock, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use ethers::{
+    prelude::k256::sha2::{Digest, Sha256},
+    utils::hex,
+};
+use regex::Regex;
+use reqwest::{Client, StatusCode};
+use serde_json::Value as JsonValue;
+use tracing::{event, instrument, Level};
+
+use crate::prisma::{contentcache, PrismaClient};
+
+const DEFAULT_IPFS_GATEWAYS: [&str; 4] = [
+    "https://senate.infura-ipfs.io/ipfs/",
+    "https://cloudflare-ipfs.com/ipfs/",
+    "https://gateway.pinata.cloud/ipfs/",
+    "https://ipfs.io/ipfs/",
+];
+
+const DEFAULT_ARWEAVE_GATEWAY: &str = "https://arweave.net/";
+
+const GATEWAY_TIMEOUT: Duration = Duration::from_secs(5);
+const GATEWAY_ROUNDS: u32 = 2;
+
+/// Plain urls can change behind the same address, their content is fetched again once
+/// it is older than this.
+const HTTP_CACHE_TTL_HOURS: i64 = 6;
+
+// files up to one default chunk are stored as a single dag-pb block,
+// bigger ones can't be verified without fetching the whole dag
+const SINGLE_BLOCK_LIMIT: usize = 256 * 1024;
+
+const CODEC_RAW: u64 = 0x55;
+const CODEC_DAG_PB: u64 = 0x70;
+const MULTIHASH_SHA2_256: u64 = 0x12;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cid {
+    pub version: u64,
+    pub codec: u64,
+    pub digest: [u8; 32],
+}
+
+impl Cid {
+    /// Governors store the sha2-256 digest of a dag-pb node as a bare bytes32.
+    pub fn from_bytes32(hash: &[u8]) -> Result<Cid> {
+        let digest: [u8; 32] = hash.try_into().context("ipfs hash is not 32 bytes")?;
+
+        Ok(Cid {
+            version: 1,
+            codec: CODEC_DAG_PB,
+            digest,
+        })
+    }
+
+    pub fn parse(s: &str) -> Result<Cid> {
+        if s.len() == 46 && s.starts_with("Qm") {
+            let bytes = base58_decode(s)?;
+            return Cid::from_multihash(0, CODEC_DAG_PB, &bytes);
+        }
+
+        let bytes = match s.chars().next() {
+            Some('b') => base32_decode(&s[1..])?,
+            Some('f') => hex::decode(&s[1..])?,
+            _ => bail!("unsupported cid encoding {}", s),
+        };
+
+        let (version, rest) = read_varint(&bytes)?;
+        if version != 1 {
+            bail!("unsupported cid version {}", version);
+        }
+        let (codec, rest) = read_varint(rest)?;
+
+        Cid::from_multihash(1, codec, rest)
+    }
+
+    fn from_multihash(version: u64, codec: u64, multihash: &[u8]) -> Result<Cid> {
+        let (hash_fn, rest) = read_varint(multihash)?;
+        let (len, digest) = read_varint(rest)?;
+
+        if hash_fn != MULTIHASH_SHA2_256 || len != 32 || digest.len() != 32 {
+            bail!("unsupported multihash");
+        }
+
+        Ok(Cid {
+            version,
+            codec,
+            digest: digest.try_into()?,
+        })
+    }
+
+    fn multihash(&self) -> Vec<u8> {
+        let mut bytes = vec![MULTIHASH_SHA2_256 as u8, 32];
+        bytes.extend_from_slice(&self.digest);
+        bytes
+    }
+
+    pub fn to_v0(&self) -> Option<String> {
+        (self.codec == CODEC_DAG_PB).then(|| base58_encode(&self.multihash()))
+    }
+
+    pub fn to_v1(&self) -> String {
+        let mut bytes = vec![];
+        write_varint(&mut bytes, 1);
+        write_varint(&mut bytes, self.codec);
+        bytes.extend(self.multihash());
+        format!("b{}", base32_encode(&bytes))
+    }
+
+    /// Checks fetched file content against the digest. Returns None when the
+    /// content is too big to have been stored as a single block.
+    pub fn verify(&self, content: &[u8]) -> Option<bool> {
+        let block = match self.codec {
+            CODEC_RAW => content.to_vec(),
+            CODEC_DAG_PB if content.len() <= SINGLE_BLOCK_LIMIT => dag_pb_file_block(content),
+            _ => return None,
+        };
+
+        Some(Sha256::digest(&block).as_slice() == self.digest)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentRef {
+    Ipfs(Cid),
+    Arweave(String),
+    Http(String),
+}
+
+impl ContentRef {
+    pub fn parse(uri: &str) -> Result<ContentRef> {
+        let uri = uri.trim();
+
+        if let Some(cid) = uri.strip_prefix("ipfs://") {
+            return Ok(ContentRef::Ipfs(Cid::parse(
+                cid.trim_start_matches("ipfs/"),
+            )?));
+        }
+
+        if let Some(id) = uri.strip_prefix("ar://") {
+            return Ok(ContentRef::Arweave(id.to_string()));
+        }
+
+        if uri.starts_with("http://") || uri.starts_with("https://") {
+            // gateway urls get the fallback treatment as well
+            if let Some((_, path)) = uri.split_once("/ipfs/") {
+                let path = path.split(['?', '#']).next().unwrap_or_default();
+                if let Ok(cid) = Cid::parse(path.trim_end_matches('/')) {
+                    return Ok(ContentRef::Ipfs(cid));
+                }
+            }
+
+            return Ok(ContentRef::Http(uri.to_string()));
+        }
+
+        Ok(ContentRef::Ipfs(Cid::parse(uri)?))
+    }
+
+    /// How long cached content stays good. IPFS and Arweave content never changes.
+    fn ttl(&self) -> Option<chrono::Duration> {
+        match self {
+            ContentRef::Http(_) => Some(chrono::Duration::hours(HTTP_CACHE_TTL_HOURS)),
+            _ => None,
+        }
+    }
+
+    fn cache_key(&self) -> String {
+        match self {
+            ContentRef::Ipfs(cid) => format!("ipfs://{}", cid.to_v1()),
+            ContentRef::Arweave(id) => format!("ar://{}", id),
+            ContentRef::Http(url) => url.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedContent {
+    pub text: String,
+    pub verified: bool,
+}
+
+#[instrument(skip(db))]
+pub async fn resolve(db: Option<&PrismaClient>, content: &ContentRef) -> Result<ResolvedContent> {
+    let key = content.cache_key();
+
+    let cached = match db {
+        Some(db) => db
+            .contentcache()
+            .find_unique(contentcache::uri::equals(key.clone()))
+            .exec()
+            .await?
+            .map(|cached| {
+                let fresh = content.ttl().map_or(true, |ttl| {
+                    Utc::now() - cached.fetchedat.with_timezone(&Utc) < ttl
+                });
+
+                (
+                    ResolvedContent {
+                        text: cached.content,
+                        verified: cached.verified,
+                    },
+                    fresh,
+                )
+            }),
+        None => None,
+    };
+
+    let resolved = match cached {
+        Some((cached, true)) => return Ok(cached),
+        // stale content beats none when the url is down
+        Some((cached, false)) => match fetch(content).await {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    uri = key,
+                    err = e.to_string(),
+                    "refetch failed"
+                );
+                return Ok(cached);
+            }
+        },
+        None => fetch(content).await?,
+    };
+
+    if let Some(db) = db {
+        db.contentcache()
+            .upsert(
+                contentcache::uri::equals(key.clone()),
+                contentcache::create(
+                    key,
+                    resolved.text.clone(),
+                    vec![contentcache::verified::set(resolved.verified)],
+                ),
+                vec![
+                    contentcache::content::set(resolved.text.clone()),
+                    contentcache::verified::set(resolved.verified),
+                    contentcache::fetchedat::set(Utc::now().into()),
+                ],
+            )
+            .exec()
+            .await?;
+    }
+
+    Ok(resolved)
+}
+
+async fn fetch(content: &ContentRef) -> Result<ResolvedContent> {
+    let client = Client::new();
+
+    match content {
+        ContentRef::Ipfs(cid) => {
+            let gateways = ipfs_gateways();
+            let path = cid.to_v0().unwrap_or_else(|| cid.to_v1());
+
+            // a file added with non default chunking or hashing never matches the
+            // single block digest, it is kept in case no gateway has a matching one
+            let mut mismatched: Option<Vec<u8>> = None;
+
+            for round in 0..GATEWAY_ROUNDS {
+                for gateway in gateways.iter() {
+                    let url = format!("{}{}", gateway, path);
+
+                    let bytes = match get_bytes(&client, &url).await {
+                        Ok(b) => b,
+                        Err(e) => {
+                            event!(
+                                Level::DEBUG,
+                                url = url,
+                                err = e.to_string(),
+                                "gateway failed"
+                            );
+                            continue;
+                        }
+                    };
+
+                    let verified = match cid.verify(&bytes) {
+                        Some(true) => true,
+                        Some(false) => {
+                            event!(Level::WARN, url = url, "content hash mismatch");
+                            mismatched.get_or_insert(bytes);
+                            continue;
+                        }
+                        None => false,
+                    };
+
+                    return Ok(ResolvedContent {
+                        text: String::from_utf8_lossy(&bytes).to_string(),
+                        verified,
+                    });
+                }
+
+                if let Some(bytes) = &mismatched {
+                    return Ok(ResolvedContent {
+                        text: String::from_utf8_lossy(bytes).to_string(),
+                        verified: false,
+                    });
+                }
+
+                tokio::time::sleep(Duration::from_millis(500 * 2u64.pow(round))).await;
+            }
+
+            bail!("no gateway returned {}", path)
+        }
+        ContentRef::Arweave(id) => {
+            let gateway =
+                env::var("ARWEAVE_GATEWAY_URL").unwrap_or(DEFAULT_ARWEAVE_GATEWAY.to_string());
+            let bytes = get_bytes(&client, &format!("{}{}", gateway, id)).await?;
+
+            Ok(ResolvedContent {
+                text: String::from_utf8_lossy(&bytes).to_string(),
+                verified: false,
+            })
+        }
+        ContentRef::Http(url) => {
+            let mut retries = 0;
+
+            loop {
+                match get_bytes(&client, url).await {
+                    Ok(bytes) => {
+                        return Ok(ResolvedContent {
+                            text: String::from_utf8_lossy(&bytes).to_string(),
+                            verified: false,
+                        })
+                    }
+                    Err(_) if retries < 5 => {
+                        retries += 1;
+                        tokio::time::sleep(Duration::from_millis(2u64.pow(retries + 6))).await;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+}
+
+async fn get_bytes(client: &Client, url: &str) -> Result<Vec<u8>> {
+    let res = client.get(url).timeout(GATEWAY_TIMEOUT).send().await?;
+
+    if res.status() != StatusCode::OK {
+        bail!("bad status {}", res.status());
+    }
+
+    Ok(res.bytes().await?.to_vec())
+}
+
+fn ipfs_gateways() -> Vec<String> {
+    let mut gateways: Vec<String> = env::var("IPFS_GATEWAY_URL")
+        .ok()
+        .filter(|g| !g.is_empty())
+        .map(|g| {
+            format!(
+                "{}/ipfs/",
+                g.trim_end_matches('/').trim_end_matches("/ipfs")
+            )
+        })
+        .into_iter()
+        .collect();
+
+    for gateway in DEFAULT_IPFS_GATEWAYS {
+        if !gateways.iter().any(|g| g == gateway) {
+            gateways.push(gateway.to_string());
+        }
+    }
+
+    gateways
+}
+
+pub fn title_from_content(text: &str) -> Option<String> {
+    if let Ok(json) = serde_json::from_str::<JsonValue>(text) {
+        return json["title"].as_str().map(|t| t.trim().to_string());
+    }
+
+    static FRONT_MATTER_TITLE: OnceLock<Regex> = OnceLock::new();
+
+    FRONT_MATTER_TITLE
+        .get_or_init(|| Regex::new(r"(?m)^\s*title:\s*(.+?)\s*$").unwrap())
+        .captures(text)
+        .and_then(|cap| cap.get(1))
+        .map(|m| m.as_str().trim_matches('"').to_string())
+        .filter(|t| !t.is_empty())
+}
+
+pub async fn ipfs_title(db: Option<&PrismaClient>, hash: &[u8]) -> String {
+    let title = match Cid::from_bytes32(hash) {
+        Ok(cid) => resolve(db, &ContentRef::Ipfs(cid))
+            .await
+            .ok()
+            .and_then(|c| title_from_content(&c.text)),
+        Err(_) => None,
+    };
+
+    title.unwrap_or("Unknown".to_string())
+}
+
+pub async fn uri_title(db: Option<&PrismaClient>, uri: &str) -> String {
+    let title = match ContentRef::parse(uri) {
+        Ok(c) => resolve(db, &c)
+            .await
+            .ok()
+            .and_then(|c| title_from_content(&c.text)),
+        Err(_) => None,
+    };
+
+    title.unwrap_or("Unknown".to_string())
+}
+
+fn dag_pb_file_block(content: &[u8]) -> Vec<u8> {
+    let mut unixfs = vec![0x08, 0x02];
+    if !content.is_empty() {
+        unixfs.push(0x12);
+        write_varint(&mut unixfs, content.len() as u64);
+        unixfs.extend_from_slice(content);
+    }
+    unixfs.push(0x18);
+    write_varint(&mut unixfs, content.len() as u64);
+
+    let mut node = vec![0x0a];
+    write_varint(&mut node, unixfs.len() as u64);
+    node.extend(unixfs);
+    node
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8]) -> Result<(u64, &[u8])> {
+    let mut value: u64 = 0;
+
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, &bytes[i + 1..]));
+        }
+    }
+
+    bail!("bad varint")
+}
+
+fn base58_encode(bytes: &[u8]) -> String {
+    let mut digits: Vec<u8> = vec![];
+
+    for byte in bytes {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+
+    std::iter::repeat('1')
+        .take(zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|d| BASE58_ALPHABET[*d as usize] as char),
+        )
+        .collect()
+}
+
+fn base58_decode(s: &str) -> Result<Vec<u8>> {
+    let mut bytes: Vec<u8> = vec![];
+
+    for c in s.bytes() {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .context("bad base58 character")? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    let zeros = s.bytes().take_while(|c| *c == b'1').count();
+    bytes.extend(std::iter::repeat(0).take(zeros));
+    bytes.reverse();
+
+    Ok(bytes)
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            out.push(BASE32_ALPHABET[((buffer >> (bits - 5)) & 31) as usize] as char);
+            bits -= 5;
+        }
+    }
+
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+
+    out
+}
+
+fn base32_decode(s: &str) -> Result<Vec<u8>> {
+    let mut out = vec![];
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in s.to_lowercase().bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .context("bad base32 character")? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            out.push(((buffer >> (bits - 8)) & 0xff) as u8);
+            bits -= 8;
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::utils::hex;
+
+    use super::{ipfs_title, title_from_content, Cid, ContentRef};
+
+    const HASH: &str = "f76d79693a81a1c0acd23c6ee151369752142b0d832daeaef9a4dd9f8c4bc7ce";
+
+    #[test]
+    fn cid_roundtrip() {
+        let cid = Cid::from_bytes32(&hex::decode(HASH).unwrap()).unwrap();
+
+        let v0 = cid.to_v0().unwrap();
+        assert!(v0.starts_with("Qm"));
+        assert_eq!(Cid::parse(&v0).unwrap().digest, cid.digest);
+
+        let v1 = cid.to_v1();
+        assert!(v1.starts_with("bafy"));
+        assert_eq!(Cid::parse(&v1).unwrap(), cid);
+
+        assert_eq!(Cid::parse(&format!("f01701220{}", HASH)).unwrap(), cid);
+        assert!(Cid::from_bytes32(&hex::decode("deadbeef").unwrap()).is_err());
+    }
+
+    #[test]
+    fn parse_content_refs() {
+        let cid = Cid::from_bytes32(&hex::decode(HASH).unwrap()).unwrap();
+        let v0 = cid.to_v0().unwrap();
+
+        assert_eq!(
+            ContentRef::parse(&format!("ipfs://{}", v0)).unwrap(),
+            ContentRef::Ipfs(Cid::parse(&v0).unwrap())
+        );
+        assert_eq!(
+            ContentRef::parse(&format!("https://ipfs.io/ipfs/{}", v0)).unwrap(),
+            ContentRef::Ipfs(Cid::parse(&v0).unwrap())
+        );
+        assert_eq!(
+            ContentRef::parse("ar://abc").unwrap(),
+            ContentRef::Arweave("abc".into())
+        );
+        assert_eq!(
+            ContentRef::parse("https://example.com/poll.md").unwrap(),
+            ContentRef::Http("https://example.com/poll.md".into())
+        );
+    }
+
+    #[test]
+    fn verify_single_block_file() {
+        // `ipfs add` of "hello world\n" with default settings
+        let cid = Cid::parse("QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o").unwrap();
+        assert_eq!(cid.verify(b"hello world\n"), Some(true));
+        assert_eq!(cid.verify(b"hello world"), Some(false));
+    }
+
+    #[test]
+    fn titles() {
+        assert_eq!(
+            title_from_content(r#"{"title": "Add rETH"}"#),
+            Some("Add rETH".into())
+        );
+        assert_eq!(
+            title_from_content("---\ntitle: Polygon Supply Cap Update\nauthor: x\n---"),
+            Some("Polygon Supply Cap Update".into())
+        );
+        assert_eq!(title_from_content("no front matter"), None);
+    }
+
+    #[tokio::test]
+    async fn invalid_hash_has_no_title() {
+        let result = ipfs_title(None, &hex::decode("deadbeef").unwrap()).await;
+        assert_eq!(result, "Unknown");
+    }
+
+    // the titles below are fetched from the public ipfs gateways
+    #[tokio::test]
+    #[ignore]
+    async fn get_markdown_title() {
+        let result = ipfs_title(None, &hex::decode(HASH).unwrap()).await;
+        assert_eq!(result, "Polygon Supply Cap Update");
+
+        let result = ipfs_title(
+            None,
+            &hex::decode("12f2d9c91e4e23ae4009ab9ef5862ee0ae79498937b66252213221f04a5d5b32")
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(result, "Add 1INCH to Aave v2 market");
+
+        let result = ipfs_title(
+            None,
+            &hex::decode("e7e93497d3847536f07fe8dba53485cf68a275c7b07ca38b53d2cc2d43fab3b0")
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(result, "Unknown");
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn get_json_title() {
+        let result = ipfs_title(
+            None,
+            &hex::decode("8d4f6f42043d8db567d5e733762bb84a6f507997a779a66b2d17fdf9de403c13")
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(result, "Add rETH to Arbitrum Aave v3");
+    }
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use ethers::{
+    abi::{self, ParamType, Token},
+    providers::{Http, Middleware, Provider},
+    types::{Address, BlockId, BlockNumber, Bytes, TransactionRequest, H256, U256},
+    utils::{id, keccak256},
+};
+
+pub fn transfer_topic() -> H256 {
+    H256::from(keccak256("Transfer(address,address,uint256)"))
+}
+
+/// Symbol and decimals of a token, falls back to the address and 18 decimals.
+pub async fn token_metadata(
+    rpc: &Arc<Provider<Http>>,
+    block_number: u64,
+    token: Address,
+) -> (String, u8) {
+    let symbol = match raw_call(rpc, block_number, token, "symbol()", &[]).await {
+        Ok(result) => match abi::decode(&[ParamType::String], &result)
+            .ok()
+            .and_then(|mut t| t.pop())
+        {
+            Some(Token::String(s)) => s,
+            // MKR and a few older tokens return bytes32
+            _ if result.len() == 32 => String::from_utf8_lossy(&result)
+                .trim_end_matches('\0')
+                .to_string(),
+            _ => format!("{:#x}", token),
+        },
+        Err(_) => format!("{:#x}", token),
+    };
+
+    let decimals = match raw_call(rpc, block_number, token, "decimals()", &[]).await {
+        Ok(result) if result.len() == 32 => result[31],
+        _ => 18,
+    };
+
+    (symbol, decimals)
+}
+
+pub async fn balance_of(
+    rpc: &Arc<Provider<Http>>,
+    block_number: u64,
+    token: Address,
+    holder: Address,
+) -> Result<U256> {
+    let result = raw_call(
+        rpc,
+        block_number,
+        token,
+        "balanceOf(address)",
+        &[Token::Address(holder)],
+    )
+    .await?;
+
+    Ok(U256::from_big_endian(&result))
+}
+
+async fn raw_call(
+    rpc: &Arc<Provider<Http>>,
+    block_number: u64,
+    to: Address,
+    signature: &str,
+    args: &[Token],
+) -> Result<Bytes> {
+    let mut data = id(signature).to_vec();
+    data.extend(abi::encode(args));
+
+    let tx = TransactionRequest::new().to(to).data(data);
+    let block = BlockId::Number(BlockNumber::Number(block_number.into()));
+
+    Ok(rpc.call(&tx.into(), Some(block)).await?)
+}
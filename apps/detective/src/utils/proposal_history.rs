@@ -0,0 +1,93 @@
+use anyhow::Result;
+use prisma_client_rust::Direction;
+use serde_json::Value;
+use tracing::{event, instrument, Level};
+
+use crate::prisma::{proposal, proposalsnapshot, PrismaClient};
+
+#[instrument(skip_all)]
+pub async fn record_proposal_snapshot(db: &PrismaClient, proposal: &proposal::Data) -> Result<()> {
+    let latest = db
+        .proposalsnapshot()
+        .find_first(vec![proposalsnapshot::proposalid::equals(
+            proposal.id.clone(),
+        )])
+        .order_by(proposalsnapshot::timestamp::order(Direction::Desc))
+        .exec()
+        .await?;
+
+    if let Some(latest) = latest {
+        if latest.state == proposal.state
+            && latest.scores == proposal.scores
+            && latest.scorestotal == proposal.scorestotal
+        {
+            return Ok(());
+        }
+    }
+
+    event!(
+        Level::INFO,
+        proposal_id = proposal.id,
+        state = proposal.state.to_string(),
+        "record proposal snapshot"
+    );
+
+    db.proposalsnapshot()
+        .create_unchecked(
+            proposal.id.clone(),
+            proposal.state,
+            proposal.scores.clone(),
+            proposal.scorestotal.clone(),
+            proposal.quorum.clone(),
+            vec![],
+        )
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+pub fn leading_choice(scores: &Value) -> Option<usize> {
+    let scores: Vec<f64> = scores
+        .as_array()?
+        .iter()
+        .map(|s| {
+            s.as_f64()
+                .or_else(|| s.as_str().and_then(|s| s.parse::<f64>().ok()))
+                .unwrap_or(0.0)
+        })
+        .collect();
+
+    let (index, max) = scores
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+
+    if *max <= 0.0 || scores.iter().filter(|s| *s == max).count() > 1 {
+        return None;
+    }
+
+    Some(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::leading_choice;
+
+    #[test]
+    fn leader_is_highest_score() {
+        assert_eq!(leading_choice(&json!([10, 30, 5])), Some(1));
+        assert_eq!(leading_choice(&json!([1.5, 0.5])), Some(0));
+        assert_eq!(leading_choice(&json!(["100", "200"])), Some(1));
+    }
+
+    #[test]
+    fn no_leader_on_tie_or_empty() {
+        assert_eq!(leading_choice(&json!([10, 10, 5])), None);
+        assert_eq!(leading_choice(&json!([0, 0])), None);
+        assert_eq!(leading_choice(&json!([])), None);
+        assert_eq!(leading_choice(&json!({})), None);
+    }
+}
@@ -0,0 +1,271 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use ethers::{
+    abi::AbiDecode,
+    providers::{Http, Middleware, Provider},
+    types::{Address, Log, Signature, H256, U256},
+    utils::to_checksum,
+};
+use reqwest_middleware::ClientBuilder;
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use serde::Deserialize;
+
+use crate::contracts::safe::ExecTransactionCall;
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceConfirmation {
+    pub owner: String,
+    pub submissionDate: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceDataDecoded {
+    pub method: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceTransaction {
+    pub safeTxHash: String,
+    pub nonce: u64,
+    pub to: String,
+    pub value: String,
+    pub dataDecoded: Option<ServiceDataDecoded>,
+    pub submissionDate: String,
+    pub blockNumber: Option<i64>,
+    pub isExecuted: bool,
+    pub confirmationsRequired: Option<u64>,
+    #[serde(default)]
+    pub confirmations: Vec<ServiceConfirmation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServiceTransactions {
+    results: Vec<ServiceTransaction>,
+}
+
+impl ServiceTransaction {
+    pub fn title(&self) -> String {
+        match &self.dataDecoded {
+            Some(decoded) => format!("{} on {}", decoded.method, self.to),
+            None if self.value != "0" => format!("Transfer {} wei to {}", self.value, self.to),
+            None => format!("Call {}", self.to),
+        }
+    }
+
+    pub fn submitted(&self) -> DateTime<Utc> {
+        parse_date(&self.submissionDate)
+    }
+}
+
+pub fn parse_date(date: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+/// Latest multisig transactions known to a Safe transaction service, pending and executed.
+pub async fn service_transactions(
+    service_url: &str,
+    safe: Address,
+) -> Result<Vec<ServiceTransaction>> {
+    let retry_policy = ExponentialBackoff::builder().build_with_max_retries(5);
+    let http_client = ClientBuilder::new(reqwest::Client::new())
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .build();
+
+    let url = format!(
+        "{}/api/v1/safes/{}/multisig-transactions/?ordering=-nonce&limit=100",
+        service_url.trim_end_matches('/'),
+        to_checksum(&safe, None)
+    );
+
+    let response = http_client
+        .get(url.clone())
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    let data: ServiceTransactions = response
+        .json()
+        .await
+        .with_context(|| format!("bad transaction service response {}", url))?;
+
+    Ok(data.results)
+}
+
+/// Safe 1.4 indexes the first argument of most events while 1.3 keeps it in the data,
+/// the signatures are the same so both versions show up in the same filter.
+pub fn first_word(log: &Log) -> Option<H256> {
+    match log.topics.get(1) {
+        Some(topic) => Some(*topic),
+        None if log.data.len() >= 32 => Some(H256::from_slice(&log.data[..32])),
+        None => None,
+    }
+}
+
+/// The `execTransaction` call behind an execution, `None` when it went through a module or
+/// a wrapper contract and the signatures can not be read from the calldata.
+pub async fn exec_transaction(
+    rpc: &Provider<Http>,
+    tx_hash: H256,
+) -> Result<Option<ExecTransactionCall>> {
+    let tx = match rpc.get_transaction(tx_hash).await? {
+        Some(tx) => tx,
+        None => return Ok(None),
+    };
+
+    Ok(ExecTransactionCall::decode(&tx.input).ok())
+}
+
+/// Owners that signed a Safe transaction, read from the packed `signatures` of `execTransaction`.
+pub fn signers(signatures: &[u8], safe_tx_hash: H256) -> Vec<Address> {
+    let mut result = vec![];
+
+    // contract signatures point at dynamic data appended after the static parts
+    let mut end = signatures.len();
+    let mut offset = 0;
+
+    while offset + 65 <= end {
+        let r = U256::from_big_endian(&signatures[offset..offset + 32]);
+        let s = U256::from_big_endian(&signatures[offset + 32..offset + 64]);
+        let v = signatures[offset + 64] as u64;
+
+        let signer = match v {
+            // contract signature, r is the owner and s the offset of its data
+            0 => {
+                end = end.min(s.low_u64() as usize);
+                Some(address_from_word(r))
+            }
+            // approved on chain with approveHash or sent by the owner
+            1 => Some(address_from_word(r)),
+            // eth_sign over the prefixed safe tx hash
+            v if v > 30 => Signature { r, s, v: v - 4 }
+                .recover(safe_tx_hash.as_bytes().to_vec())
+                .ok(),
+            v => Signature { r, s, v }.recover(safe_tx_hash).ok(),
+        };
+
+        if let Some(signer) = signer {
+            result.push(signer);
+        }
+
+        offset += 65;
+    }
+
+    result
+}
+
+fn address_from_word(word: U256) -> Address {
+    let mut bytes = [0u8; 32];
+    word.to_big_endian(&mut bytes);
+    Address::from_slice(&bytes[12..])
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{
+        signers::{LocalWallet, Signer},
+        types::{Address, H256, U256},
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::{service_transactions, signers};
+
+    const OWNER_KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    fn word(value: U256) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        value.to_big_endian(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn recovers_signers() {
+        let safe_tx_hash = H256::repeat_byte(0x42);
+        let wallet = OWNER_KEY.parse::<LocalWallet>().unwrap();
+        let approver = "0x00000000000000000000000000000000000000aa"
+            .parse::<Address>()
+            .unwrap();
+
+        let mut signatures = vec![];
+
+        // pre-approved hash
+        signatures.extend(word(U256::from_big_endian(approver.as_bytes())));
+        signatures.extend([0u8; 32]);
+        signatures.push(1);
+
+        // ecdsa over the safe tx hash
+        let signature = wallet.sign_hash(safe_tx_hash).unwrap();
+        signatures.extend(word(signature.r));
+        signatures.extend(word(signature.s));
+        signatures.push(signature.v as u8);
+
+        assert_eq!(
+            signers(&signatures, safe_tx_hash),
+            vec![approver, wallet.address()]
+        );
+
+        // a trailing partial signature is ignored
+        signatures.extend([0u8; 10]);
+        assert_eq!(signers(&signatures, safe_tx_hash).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reads_local_transaction_service() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let service_url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let read = socket.read(&mut request).await.unwrap();
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+
+            assert!(request.starts_with(
+                "GET /api/v1/safes/0x00000000000000000000000000000000000000AA/multisig-transactions/"
+            ));
+
+            let body = r#"{"count":1,"results":[{
+                "safeTxHash":"0x4242424242424242424242424242424242424242424242424242424242424242",
+                "nonce":7,
+                "to":"0x00000000000000000000000000000000000000bB",
+                "value":"0",
+                "dataDecoded":{"method":"addOwnerWithThreshold","parameters":[]},
+                "submissionDate":"2023-08-01T10:00:00.000000Z",
+                "blockNumber":null,
+                "isExecuted":false,
+                "confirmationsRequired":2,
+                "confirmations":[{"owner":"0x00000000000000000000000000000000000000Aa","submissionDate":"2023-08-01T10:00:00.000000Z"}]
+            }]}"#;
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let safe = "0x00000000000000000000000000000000000000aa"
+            .parse::<Address>()
+            .unwrap();
+
+        let transactions = service_transactions(&service_url, safe).await.unwrap();
+
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].nonce, 7);
+        assert_eq!(transactions[0].confirmations.len(), 1);
+        assert_eq!(transactions[0].confirmationsRequired, Some(2));
+        assert_eq!(
+            transactions[0].title(),
+            "addOwnerWithThreshold on 0x00000000000000000000000000000000000000bB"
+        );
+        assert!(!transactions[0].isExecuted);
+    }
+}
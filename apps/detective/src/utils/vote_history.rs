@@ -0,0 +1,59 @@
+use anyhow::Result;
+use prisma_client_rust::{
+    chrono::{DateTime, FixedOffset, Utc},
+    Direction,
+};
+use tracing::{event, instrument, Level};
+
+use crate::prisma::{vote, voterevision, PrismaClient};
+
+/// Stores the vote as a new revision unless its latest revision was cast at the same time.
+/// Returns the revision number when one was recorded.
+#[instrument(skip_all)]
+pub async fn record_vote_revision(
+    db: &PrismaClient,
+    vote: &vote::Data,
+    external_id: &str,
+) -> Result<Option<i32>> {
+    let timecreated: DateTime<FixedOffset> = vote
+        .timecreated
+        .unwrap_or_else(|| Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()));
+
+    let latest = db
+        .voterevision()
+        .find_first(vec![voterevision::voteid::equals(vote.id.clone())])
+        .order_by(voterevision::revision::order(Direction::Desc))
+        .exec()
+        .await?;
+
+    let revision = match latest {
+        Some(latest) if latest.timecreated == timecreated => return Ok(None),
+        Some(latest) => latest.revision + 1,
+        None => 0,
+    };
+
+    if revision > 0 {
+        event!(
+            Level::INFO,
+            voter_address = vote.voteraddress,
+            proposal_id = vote.proposalid,
+            revision = revision,
+            "vote changed"
+        );
+    }
+
+    db.voterevision()
+        .create_unchecked(
+            vote.id.clone(),
+            revision,
+            vote.choice.clone(),
+            vote.votingpower.clone(),
+            vote.reason.clone(),
+            timecreated,
+            vec![voterevision::externalid::set(external_id.to_string())],
+        )
+        .exec()
+        .await?;
+
+    Ok(Some(revision))
+}
@@ -184,6 +184,10 @@ pub async fn dispatch_ending_soon_notifications(client: &Arc<PrismaClient>) -> R
             NotificationType::SecondReminderSlack => todo!(),
             NotificationType::ThirdReminderSlack => todo!(),
             NotificationType::EndedProposalSlack => todo!(),
+            // vote changes have no reminders
+            NotificationType::VoteChangedDiscord
+            | NotificationType::VoteChangedTelegram
+            | NotificationType::VoteChangedSlack => continue,
         };
 
         let message = webhook
@@ -116,20 +116,27 @@ pub async fn dispatch_new_proposal_notifications(client: &Arc<PrismaClient>) ->
                     "https://www.senatelabs.xyz/assets/Discord/placeholder2x.png"
                 };
 
+                // webhooks can not reply, the earlier stage is linked instead
+                let thread = match family_message_link(client, &user.id, &proposal).await? {
+                    Some(link) => format!("\nFollows [the earlier vote]({})", link),
+                    None => String::new(),
+                };
+
                 let message = webhook
                     .clone()
                     .execute(&http, true, |w| {
                         w.embeds(vec![Embed::fake(|e| {
                             e.title(proposal.clone().name)
                                 .description(format!(
-                                    "**{}** {} proposal ending **<t:{}:R>**",
+                                    "**{}** {} proposal ending **<t:{}:R>**{}",
                                     proposal.dao.name,
                                     if proposal.daohandler.r#type == DaoHandlerType::Snapshot {
                                         "offchain"
                                     } else {
                                         "onchain"
                                     },
-                                    proposal.timeend.timestamp()
+                                    proposal.timeend.timestamp(),
+                                    thread
                                 ))
                                 .url(short_url)
                                 .color(Colour(0xFFFFFF))
@@ -246,3 +253,41 @@ pub async fn dispatch_new_proposal_notifications(client: &Arc<PrismaClient>) ->
     }
     Ok(())
 }
+
+/// Message of an earlier stage of the proposal's family the user was already notified about.
+async fn family_message_link(
+    client: &Arc<PrismaClient>,
+    user_id: &str,
+    proposal: &proposal_with_dao::Data,
+) -> Result<Option<String>> {
+    let family_id = match &proposal.familyid {
+        Some(id) => id.clone(),
+        None => return Ok(None),
+    };
+
+    let earlier = client
+        .notification()
+        .find_many(vec![
+            notification::userid::equals(user_id.to_string()),
+            notification::r#type::equals(NotificationType::NewProposalDiscord),
+            notification::dispatchstatus::equals(NotificationDispatchedState::Dispatched),
+            notification::proposal::is(vec![
+                proposal::familyid::equals(Some(family_id)),
+                proposal::id::not(proposal.id.clone()),
+            ]),
+        ])
+        .exec()
+        .await?;
+
+    // message ids are snowflakes, the smallest is the first stage
+    Ok(earlier
+        .into_iter()
+        .filter_map(|n| {
+            Some((
+                n.discordmessageid?.parse::<u64>().ok()?,
+                n.discordmessagelink?,
+            ))
+        })
+        .min_by_key(|(id, _)| *id)
+        .map(|(_, link)| link))
+}
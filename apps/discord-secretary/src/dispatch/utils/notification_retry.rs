@@ -16,6 +16,7 @@ pub async fn update_notification_retry(
                 notification::userid::equals(notification.clone().userid),
                 notification::proposalid::equals(notification.clone().proposalid),
                 notification::r#type::equals(notification.clone().r#type),
+                notification::revision::equals(notification.revision),
             ],
             match notification.dispatchstatus {
                 NotificationDispatchedState::NotDispatched => {
@@ -0,0 +1,217 @@
+use std::{env, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use serenity::{
+    http::Http,
+    model::{prelude::Embed, webhook::Webhook},
+    utils::Colour,
+};
+use tokio::time::sleep;
+use tracing::{event, instrument, Level};
+
+use crate::{
+    prisma::{
+        self,
+        notification,
+        proposal,
+        user,
+        NotificationDispatchedState,
+        NotificationType,
+        PrismaClient,
+    },
+    utils::{posthog::posthog_event, vote::get_vote_change},
+};
+
+use super::utils::notification_retry::update_notification_retry;
+
+prisma::proposal::include!(proposal_with_dao { dao daohandler });
+
+#[instrument(skip_all)]
+pub async fn dispatch_vote_changed_notifications(client: &Arc<PrismaClient>) -> Result<()> {
+    let notifications = client
+        .notification()
+        .find_many(vec![
+            notification::dispatchstatus::in_vec(vec![
+                NotificationDispatchedState::NotDispatched,
+                NotificationDispatchedState::FirstRetry,
+                NotificationDispatchedState::SecondRetry,
+                NotificationDispatchedState::ThirdRetry,
+            ]),
+            notification::r#type::equals(NotificationType::VoteChangedDiscord),
+        ])
+        .exec()
+        .await?;
+
+    for notification in notifications {
+        let user = client
+            .user()
+            .find_first(vec![user::id::equals(notification.clone().userid)])
+            .exec()
+            .await?
+            .unwrap();
+
+        let http = Http::new("");
+
+        let webhook_response = Webhook::from_url(&http, user.discordwebhook.as_str()).await;
+
+        let webhook = match webhook_response {
+            Ok(w) => w,
+            Err(e) => {
+                event!(Level::ERROR, err = e.to_string(), "webhook err");
+                update_notification_retry(client, notification).await;
+                continue;
+            }
+        };
+
+        let proposal = client
+            .proposal()
+            .find_first(vec![proposal::id::equals(
+                notification.clone().proposalid.unwrap(),
+            )])
+            .include(proposal_with_dao::include())
+            .exec()
+            .await?;
+
+        let change = match &proposal {
+            Some(p) => {
+                get_vote_change(user.clone().id, p.clone().id, notification.revision, client)
+                    .await?
+            }
+            None => None,
+        };
+
+        match (proposal, change) {
+            (Some(proposal), Some(change)) => {
+                let shortner_url = match env::var_os("NEXT_PUBLIC_URL_SHORTNER") {
+                    Some(v) => v.into_string().unwrap(),
+                    None => panic!("$NEXT_PUBLIC_URL_SHORTNER is not set"),
+                };
+
+                let short_url = format!(
+                    "{}{}/{}/{}",
+                    shortner_url,
+                    proposal
+                        .id
+                        .chars()
+                        .rev()
+                        .take(7)
+                        .collect::<Vec<char>>()
+                        .into_iter()
+                        .rev()
+                        .collect::<String>(),
+                    "d",
+                    user.clone()
+                        .id
+                        .chars()
+                        .rev()
+                        .take(7)
+                        .collect::<Vec<char>>()
+                        .into_iter()
+                        .rev()
+                        .collect::<String>()
+                );
+
+                let message = webhook
+                    .clone()
+                    .execute(&http, true, |w| {
+                        w.embeds(vec![Embed::fake(|e| {
+                            e.title(proposal.clone().name)
+                                .description(format!(
+                                    "**{}** changed their vote on this **{}** proposal from **{}** to **{}**",
+                                    change.voter_name,
+                                    proposal.dao.name,
+                                    change.previous_choice,
+                                    change.choice
+                                ))
+                                .url(short_url)
+                                .color(Colour(0xFFFFFF))
+                                .thumbnail(format!(
+                                    "https://www.senatelabs.xyz/{}_medium.png",
+                                    proposal.dao.picture
+                                ))
+                        })])
+                        .username("Senate Secretary")
+                        .avatar_url("https://www.senatelabs.xyz/assets/Discord/Profile_picture.gif")
+                    })
+                    .await;
+
+                match message {
+                    Ok(msg) => {
+                        event!(
+                            Level::INFO,
+                            user = user.address.clone().unwrap(),
+                            proposal_name = proposal.name,
+                            dao = proposal.dao.name,
+                            "vote changed notification"
+                        );
+
+                        posthog_event(
+                            "discord_vote_changed_notification",
+                            user.address.unwrap(),
+                            proposal.name,
+                            proposal.dao.name,
+                        );
+
+                        client
+                            .notification()
+                            .update_many(
+                                vec![
+                                    notification::userid::equals(user.id),
+                                    notification::proposalid::equals(proposal.id.into()),
+                                    notification::r#type::equals(
+                                        NotificationType::VoteChangedDiscord,
+                                    ),
+                                    notification::revision::equals(notification.revision),
+                                ],
+                                vec![
+                                    notification::dispatchstatus::set(
+                                        NotificationDispatchedState::Dispatched,
+                                    ),
+                                    notification::discordmessagelink::set(
+                                        msg.clone().unwrap().link().into(),
+                                    ),
+                                    notification::discordmessageid::set(
+                                        msg.clone().unwrap().id.to_string().into(),
+                                    ),
+                                ],
+                            )
+                            .exec()
+                            .await?;
+                    }
+                    Err(e) => {
+                        event!(Level::ERROR, err = e.to_string(), "vote changed err");
+
+                        posthog_event(
+                            "discord_vote_changed_notification_fail",
+                            user.address.unwrap(),
+                            proposal.name,
+                            proposal.dao.name,
+                        );
+
+                        update_notification_retry(client, notification).await;
+                    }
+                }
+
+                sleep(Duration::from_millis(100)).await;
+            }
+            _ => {
+                client
+                    .notification()
+                    .update_many(
+                        vec![
+                            notification::userid::equals(notification.clone().userid),
+                            notification::proposalid::equals(notification.clone().proposalid),
+                            notification::r#type::equals(NotificationType::VoteChangedDiscord),
+                            notification::revision::equals(notification.revision),
+                        ],
+                        vec![notification::dispatchstatus::set(
+                            NotificationDispatchedState::Deleted,
+                        )],
+                    )
+                    .exec()
+                    .await?;
+            }
+        }
+    }
+    Ok(())
+}
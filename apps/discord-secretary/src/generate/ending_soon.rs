@@ -42,6 +42,10 @@ pub async fn generate_ending_soon_notifications(
         NotificationType::SecondReminderSlack => todo!(),
         NotificationType::ThirdReminderSlack => todo!(),
         NotificationType::EndedProposalSlack => todo!(),
+        // vote changes have no reminders
+        NotificationType::VoteChangedDiscord
+        | NotificationType::VoteChangedTelegram
+        | NotificationType::VoteChangedSlack => return Ok(()),
     };
 
     let users = client
@@ -57,7 +61,7 @@ pub async fn generate_ending_soon_notifications(
     let proposals = client
         .proposal()
         .find_many(vec![
-            proposal::state::equals(ProposalState::Active),
+            proposal::state::in_vec(vec![ProposalState::Active, ProposalState::ObjectionPeriod]),
             proposal::timeend::lt((Utc::now() + timeleft).into()),
             proposal::timeend::gt((Utc::now() + timeleft - Duration::minutes(60)).into()),
             proposal::visible::equals(true),
@@ -14,10 +14,12 @@ use dispatch::{
     ending_soon::dispatch_ending_soon_notifications,
     update_active::update_active_proposal_notifications,
     update_hidden::update_hidden_proposal_notifications,
+    vote_changed::dispatch_vote_changed_notifications,
 };
 use generate::{
     ended::generate_ended_proposal_notifications,
     ending_soon::generate_ending_soon_notifications,
+    vote_changed::generate_vote_changed_notifications,
 };
 use prisma::NotificationType;
 
@@ -30,11 +32,13 @@ use crate::{
 mod dispatch;
 mod generate;
 pub mod prisma;
+#[path = "../../../packages/secretary/mod.rs"]
+mod shared;
 mod telemetry;
 
 mod utils {
     pub mod posthog;
-    pub mod vote;
+    pub use crate::shared::{display, vote};
 }
 
 #[tokio::main]
@@ -136,11 +140,36 @@ async fn main() {
         }
     });
 
+    let client_for_vote_changed: Arc<PrismaClient> = Arc::clone(&client);
+    let vote_changed_task = tokio::task::spawn(async move {
+        loop {
+            match generate_vote_changed_notifications(&client_for_vote_changed).await {
+                Ok(_) => event!(Level::INFO, "generate_vote_changed_notifications ok"),
+                Err(e) => event!(
+                    Level::ERROR,
+                    err = e.to_string(),
+                    "failed to generate vote changed"
+                ),
+            };
+            match dispatch_vote_changed_notifications(&client_for_vote_changed).await {
+                Ok(_) => event!(Level::INFO, "dispatch_vote_changed_notifications ok"),
+                Err(e) => event!(
+                    Level::ERROR,
+                    err = e.to_string(),
+                    "failed to dispatch vote changed"
+                ),
+            };
+
+            sleep(std::time::Duration::from_secs(60)).await;
+        }
+    });
+
     try_join!(
         new_proposals_task,
         ending_soon_task,
         ended_proposals_task,
-        active_proposals_task
+        active_proposals_task,
+        vote_changed_task
     )
     .unwrap();
 }
@@ -22,7 +22,13 @@ use crate::{
         NotificationType,
         ProposalState,
     },
-    utils::{countdown::countdown_gif, posthog::posthog_bulletin_event, vote::get_vote},
+    utils::{
+        countdown::countdown_gif,
+        display::voter_display,
+        posthog::posthog_bulletin_event,
+        treasury::treasury_moved,
+        vote::get_vote,
+    },
 };
 
 prisma::proposal::include!(proposal_with_dao { dao daohandler });
@@ -44,6 +50,8 @@ struct BulletinData {
     endingSoonProposals: Vec<EndingSoonProposals>,
     newProposals: Vec<NewProposals>,
     endedProposals: Vec<EndedProposals>,
+    userName: String,
+    userAvatarUrl: Option<String>,
     env: Option<Value>,
 }
 
@@ -84,6 +92,7 @@ struct EndedProposals {
     makerResult: Option<MakerResult>,
     countdownString: String,
     voteStatusIconUrl: String,
+    treasuryMoved: Option<String>,
 }
 
 #[allow(non_snake_case)]
@@ -360,6 +369,7 @@ async fn get_user_bulletin_data(
     let ending_soon_proposals = get_ending_soon_proposals(user.clone(), db).await?;
     let new_proposals = get_new_proposals(user.clone(), db).await?;
     let ended_proposals = get_ended_proposals(user.clone(), db).await?;
+    let user_display = voter_display(&user.address.clone().unwrap_or_default(), db).await?;
     let exec_env = env::var("EXEC_ENV").expect("$EXEC_ENV is not set");
 
     Ok(BulletinData {
@@ -367,6 +377,8 @@ async fn get_user_bulletin_data(
         endingSoonProposals: ending_soon_proposals,
         newProposals: new_proposals,
         endedProposals: ended_proposals,
+        userName: user_display.name,
+        userAvatarUrl: user_display.avatar,
         env: if exec_env == "prod" {
             None
         } else {
@@ -643,6 +655,8 @@ async fn get_ended_proposals(
             None => (0, p.scores.as_f64().unwrap()),
         };
 
+        let treasury_moved = treasury_moved(&p.id, db).await.unwrap_or(None);
+
         EndedProposals {
             daoLogoUrl: format!(
                 "{}/{}{}",
@@ -710,6 +724,7 @@ async fn get_ended_proposals(
             } else {
                 None
             },
+            treasuryMoved: treasury_moved,
         }
     }))
     .await;
@@ -34,7 +34,10 @@ pub mod quorum {
 
 pub mod utils {
     pub mod countdown;
+    #[path = "../../../../packages/secretary/display.rs"]
+    pub mod display;
     pub mod posthog;
+    pub mod treasury;
     pub mod vote;
 }
 
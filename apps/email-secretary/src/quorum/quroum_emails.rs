@@ -25,7 +25,12 @@ use crate::{
         NotificationType,
         ProposalState,
     },
-    utils::{countdown::countdown_gif, posthog::posthog_quorum_event, vote::get_vote},
+    utils::{
+        countdown::countdown_gif,
+        display::voter_display,
+        posthog::posthog_quorum_event,
+        vote::get_vote,
+    },
 };
 
 #[allow(non_snake_case)]
@@ -35,6 +40,15 @@ struct EmailBody {
     From: String,
     TemplateAlias: String,
     TemplateModel: QuorumWarningData,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    Headers: Vec<EmailHeader>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct EmailHeader {
+    Name: String,
+    Value: String,
 }
 
 #[allow(non_snake_case)]
@@ -48,6 +62,8 @@ struct QuorumWarningData {
     voteUrl: String,
     currentQuorum: String,
     requiredQuroum: String,
+    userName: String,
+    userAvatarUrl: Option<String>,
     env: Option<Value>,
 }
 
@@ -61,6 +77,28 @@ struct PostmarkResult {
 prisma::proposal::include!(proposal_with_dao { dao });
 prisma::user::include!(user_with_voters_and_subscriptions { subscriptions voters });
 
+/// Threading headers for the stages of a proposal family. They all reply to the same
+/// made up message, which mail clients group into one conversation.
+fn family_headers(family_id: Option<String>) -> Vec<EmailHeader> {
+    match family_id {
+        Some(id) => {
+            let thread = format!("<family-{}@senatelabs.xyz>", id);
+
+            vec![
+                EmailHeader {
+                    Name: "In-Reply-To".to_string(),
+                    Value: thread.clone(),
+                },
+                EmailHeader {
+                    Name: "References".to_string(),
+                    Value: thread,
+                },
+            ]
+        }
+        None => vec![],
+    }
+}
+
 #[instrument(skip(db))]
 pub async fn send_quorum_email(db: &Arc<prisma::PrismaClient>) -> Result<()> {
     let _ = generate_quorum_notifications(db).await;
@@ -165,6 +203,8 @@ pub async fn dispatch_quorum_notifications(db: &Arc<prisma::PrismaClient>) -> Re
                 .collect::<String>()
         );
 
+        let user_display = voter_display(&user.clone().address.unwrap_or_default(), db).await?;
+
         let exec_env = env::var("EXEC_ENV").expect("$EXEC_ENV is not set");
         let data = QuorumWarningData {
             daoName: proposal.clone().unwrap().dao.name,
@@ -237,6 +277,8 @@ pub async fn dispatch_quorum_notifications(db: &Arc<prisma::PrismaClient>) -> Re
                     / 1000000000000000000)
                     .to_formatted_string(&Locale::en)
             },
+            userName: user_display.name,
+            userAvatarUrl: user_display.avatar,
             env: if exec_env == "prod" {
                 None
             } else {
@@ -249,6 +291,7 @@ pub async fn dispatch_quorum_notifications(db: &Arc<prisma::PrismaClient>) -> Re
             From: "info@senatelabs.xyz".to_string(),
             TemplateAlias: quorum_template.to_string(),
             TemplateModel: data.clone(),
+            Headers: family_headers(proposal.clone().unwrap().familyid),
         };
 
         event!(
@@ -0,0 +1,67 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::Result;
+
+use crate::prisma::{treasurytransfer, PrismaClient};
+
+/// Short amount for bulletins, e.g. `2.5M`.
+pub fn compact_amount(amount: f64) -> String {
+    let (value, suffix) = match amount.abs() {
+        a if a >= 1e9 => (amount / 1e9, "B"),
+        a if a >= 1e6 => (amount / 1e6, "M"),
+        a if a >= 1e3 => (amount / 1e3, "K"),
+        _ => (amount, ""),
+    };
+
+    let formatted = format!("{:.2}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    format!("{}{}", trimmed, suffix)
+}
+
+/// ERC20 tokens an executed proposal moved out of the dao treasuries, e.g. `2.5M USDC, 400K UNI`.
+/// Native transfers are not tracked.
+pub async fn treasury_moved(
+    proposal_id: &str,
+    client: &Arc<PrismaClient>,
+) -> Result<Option<String>> {
+    let transfers = client
+        .treasurytransfer()
+        .find_many(vec![
+            treasurytransfer::proposalid::equals(Some(proposal_id.to_string())),
+            treasurytransfer::value::lt(0.0),
+        ])
+        .exec()
+        .await?;
+
+    let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+    for t in transfers {
+        *totals.entry(t.symbol).or_default() += -t.value;
+    }
+
+    if totals.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        totals
+            .into_iter()
+            .map(|(symbol, amount)| format!("{} {}", compact_amount(amount), symbol))
+            .collect::<Vec<String>>()
+            .join(", "),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compact_amount;
+
+    #[test]
+    fn compacts_amounts() {
+        assert_eq!(compact_amount(2_500_000.0), "2.5M");
+        assert_eq!(compact_amount(1_000.0), "1K");
+        assert_eq!(compact_amount(12.345), "12.35");
+        assert_eq!(compact_amount(3_200_000_000.0), "3.2B");
+        assert_eq!(compact_amount(0.0), "0");
+    }
+}
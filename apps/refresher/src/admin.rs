@@ -0,0 +1,417 @@
+//! Operator api to see why a dao handler is stale and to nudge it along. Rocket reads
+//! `$ROCKET_ADDRESS` and `$ROCKET_PORT` like it does on detective.
+//!
+//! Statuses come from the database, not this refresher's memory, so any replica answers
+//! for all of them.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use prisma_client_rust::{
+    chrono::{DateTime, FixedOffset, TimeZone, Utc},
+    Direction,
+};
+use rocket::{http::Status, serde::json::Json, State};
+use serde::{Deserialize, Serialize};
+use tracing::{event, info_span, Instrument, Level};
+
+use crate::{
+    priority::{weight_of, PRIORITIES},
+    prisma::{
+        daohandler,
+        refreshjob,
+        voterhandler,
+        DaoHandlerType,
+        PrismaClient,
+        RefreshJobStatus,
+    },
+    refresh_status::{
+        db_time,
+        default_refreshspeed,
+        default_votersrefreshspeed,
+        force_refresh,
+        set_paused,
+    },
+    RefreshStatus,
+};
+
+type Client = State<Arc<PrismaClient>>;
+
+daohandler::include!(daohandler_with_dao {
+    dao: select { name }
+});
+voterhandler::include!(voterhandler_with_voter {
+    voter: select { address }
+});
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct HandlerStatus {
+    daoHandlerId: String,
+    dao: String,
+    handlerType: DaoHandlerType,
+    refreshStatus: RefreshStatus,
+    lastRefresh: DateTime<FixedOffset>,
+    refreshSpeed: i64,
+    votersRefreshSpeed: i64,
+    failures: i32,
+    paused: bool,
+    weight: f64,
+    chainIndex: i64,
+    snapshotIndex: DateTime<FixedOffset>,
+    upToDate: bool,
+    queuedJobs: usize,
+    deadJobs: usize,
+    lastError: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct VoterHandlerStatus {
+    voterHandlerId: String,
+    voter: String,
+    refreshStatus: RefreshStatus,
+    lastRefresh: DateTime<FixedOffset>,
+    failures: i32,
+    chainIndex: i64,
+    snapshotIndex: DateTime<FixedOffset>,
+    upToDate: bool,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct ActionResponse {
+    daoHandlerId: String,
+    success: bool,
+}
+
+/// Where to restart indexing from. Leaving an index out keeps it, `voters` resets the
+/// voter handlers of the dao handler too.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct ResetRequest {
+    chainindex: Option<i64>,
+    snapshotindex: Option<DateTime<FixedOffset>>,
+    #[serde(default)]
+    voters: bool,
+}
+
+#[derive(Default)]
+struct JobSummary {
+    queued: usize,
+    dead: usize,
+    last_error: Option<String>,
+}
+
+async fn job_summaries(
+    client: &PrismaClient,
+    filters: Vec<refreshjob::WhereParam>,
+) -> Result<HashMap<String, JobSummary>> {
+    let jobs = client
+        .refreshjob()
+        .find_many(filters)
+        .order_by(refreshjob::createdat::order(Direction::Desc))
+        .select(refreshjob::select!({ daohandlerid status lasterror }))
+        .exec()
+        .await?;
+
+    let mut summaries: HashMap<String, JobSummary> = HashMap::new();
+
+    for job in jobs {
+        let summary = summaries.entry(job.daohandlerid).or_default();
+
+        match job.status {
+            RefreshJobStatus::Dead => summary.dead += 1,
+            _ => summary.queued += 1,
+        }
+
+        if summary.last_error.is_none() {
+            summary.last_error = job.lasterror;
+        }
+    }
+
+    Ok(summaries)
+}
+
+async fn handler_statuses(
+    client: &PrismaClient,
+    filters: Vec<daohandler::WhereParam>,
+) -> Result<Vec<HandlerStatus>> {
+    let dao_handlers = client
+        .daohandler()
+        .find_many(filters.clone())
+        .include(daohandler_with_dao::include())
+        .exec()
+        .await?;
+
+    let mut jobs = job_summaries(client, vec![refreshjob::daohandler::is(filters)]).await?;
+
+    let priorities = PRIORITIES.read().await;
+
+    Ok(dao_handlers
+        .into_iter()
+        .map(|dao_handler| {
+            let jobs = jobs.remove(&dao_handler.id).unwrap_or_default();
+
+            HandlerStatus {
+                weight: weight_of(&priorities, &dao_handler.id),
+                daoHandlerId: dao_handler.id,
+                dao: dao_handler.dao.name,
+                handlerType: dao_handler.r#type,
+                refreshStatus: dao_handler.refreshstatus,
+                lastRefresh: dao_handler.lastrefresh,
+                refreshSpeed: dao_handler
+                    .refreshspeed
+                    .unwrap_or(default_refreshspeed(dao_handler.r#type)),
+                votersRefreshSpeed: dao_handler
+                    .votersrefreshspeed
+                    .unwrap_or(default_votersrefreshspeed(dao_handler.r#type)),
+                failures: dao_handler.refreshfailures,
+                paused: dao_handler.refreshpaused,
+                chainIndex: dao_handler.chainindex,
+                snapshotIndex: dao_handler.snapshotindex,
+                upToDate: dao_handler.uptodate,
+                queuedJobs: jobs.queued,
+                deadJobs: jobs.dead,
+                lastError: jobs.last_error,
+            }
+        })
+        .collect())
+}
+
+/// Moves the indexes of a dao handler, and with `voters` of its voter handlers, back or
+/// forward and refreshes them right away.
+async fn reset(client: &PrismaClient, dao_handler_id: &str, request: &ResetRequest) -> Result<()> {
+    let mut updates = vec![daohandler::uptodate::set(false)];
+    let mut voter_updates = vec![voterhandler::uptodate::set(false)];
+
+    if let Some(chainindex) = request.chainindex {
+        updates.push(daohandler::chainindex::set(chainindex));
+        voter_updates.push(voterhandler::chainindex::set(chainindex));
+    }
+
+    if let Some(snapshotindex) = request.snapshotindex {
+        updates.push(daohandler::snapshotindex::set(snapshotindex));
+        voter_updates.push(voterhandler::snapshotindex::set(snapshotindex));
+    }
+
+    client
+        .daohandler()
+        .update(daohandler::id::equals(dao_handler_id.to_string()), updates)
+        .exec()
+        .await?;
+
+    if request.voters {
+        client
+            .voterhandler()
+            .update_many(
+                vec![voterhandler::daohandlerid::equals(
+                    dao_handler_id.to_string(),
+                )],
+                voter_updates,
+            )
+            .exec()
+            .await?;
+    }
+
+    force_refresh(client, dao_handler_id).await
+}
+
+fn respond(dao_handler_id: &str, action: &str, result: Result<()>) -> Json<ActionResponse> {
+    if let Err(e) = &result {
+        event!(
+            Level::WARN,
+            daohandler = dao_handler_id,
+            action = action,
+            err = e.to_string(),
+            "admin action error"
+        );
+    }
+
+    Json(ActionResponse {
+        daoHandlerId: dao_handler_id.to_string(),
+        success: result.is_ok(),
+    })
+}
+
+#[get("/")]
+pub async fn health() -> &'static str {
+    "ok"
+}
+
+#[get("/")]
+pub async fn list_handlers(client: &Client) -> Result<Json<Vec<HandlerStatus>>, Status> {
+    handler_statuses(client, vec![])
+        .await
+        .map(Json)
+        .map_err(|e| {
+            event!(Level::WARN, err = e.to_string(), "admin list error");
+            Status::InternalServerError
+        })
+}
+
+#[get("/<dao_handler_id>")]
+pub async fn get_handler(
+    client: &Client,
+    dao_handler_id: &str,
+) -> Result<Option<Json<HandlerStatus>>, Status> {
+    handler_statuses(
+        client,
+        vec![daohandler::id::equals(dao_handler_id.to_string())],
+    )
+    .await
+    .map(|statuses| statuses.into_iter().next().map(Json))
+    .map_err(|e| {
+        event!(Level::WARN, err = e.to_string(), "admin get error");
+        Status::InternalServerError
+    })
+}
+
+#[get("/<dao_handler_id>/voters")]
+pub async fn list_voter_handlers(
+    client: &Client,
+    dao_handler_id: &str,
+) -> Result<Json<Vec<VoterHandlerStatus>>, Status> {
+    let voter_handlers = client
+        .voterhandler()
+        .find_many(vec![voterhandler::daohandlerid::equals(
+            dao_handler_id.to_string(),
+        )])
+        .include(voterhandler_with_voter::include())
+        .exec()
+        .await
+        .map_err(|e| {
+            event!(Level::WARN, err = e.to_string(), "admin voters error");
+            Status::InternalServerError
+        })?;
+
+    Ok(Json(
+        voter_handlers
+            .into_iter()
+            .map(|voter_handler| VoterHandlerStatus {
+                voterHandlerId: voter_handler.id,
+                voter: voter_handler.voter.address,
+                refreshStatus: voter_handler.refreshstatus,
+                lastRefresh: voter_handler.lastrefresh,
+                failures: voter_handler.refreshfailures,
+                chainIndex: voter_handler.chainindex,
+                snapshotIndex: voter_handler.snapshotindex,
+                upToDate: voter_handler.uptodate,
+            })
+            .collect(),
+    ))
+}
+
+#[post("/<dao_handler_id>/pause")]
+pub async fn pause_handler(client: &Client, dao_handler_id: &str) -> Json<ActionResponse> {
+    let my_span = info_span!("pause_handler", dao_handler_id = dao_handler_id);
+
+    async move {
+        respond(
+            dao_handler_id,
+            "pause",
+            set_paused(client, dao_handler_id, true).await,
+        )
+    }
+    .instrument(my_span)
+    .await
+}
+
+#[post("/<dao_handler_id>/resume")]
+pub async fn resume_handler(client: &Client, dao_handler_id: &str) -> Json<ActionResponse> {
+    let my_span = info_span!("resume_handler", dao_handler_id = dao_handler_id);
+
+    async move {
+        respond(
+            dao_handler_id,
+            "resume",
+            set_paused(client, dao_handler_id, false).await,
+        )
+    }
+    .instrument(my_span)
+    .await
+}
+
+#[post("/<dao_handler_id>/refresh")]
+pub async fn refresh_handler(client: &Client, dao_handler_id: &str) -> Json<ActionResponse> {
+    let my_span = info_span!("refresh_handler", dao_handler_id = dao_handler_id);
+
+    async move {
+        respond(
+            dao_handler_id,
+            "refresh",
+            force_refresh(client, dao_handler_id).await,
+        )
+    }
+    .instrument(my_span)
+    .await
+}
+
+#[post("/<dao_handler_id>/reset", data = "<data>")]
+pub async fn reset_handler(
+    client: &Client,
+    dao_handler_id: &str,
+    data: Json<ResetRequest>,
+) -> Json<ActionResponse> {
+    let my_span = info_span!("reset_handler", dao_handler_id = dao_handler_id);
+
+    async move {
+        respond(
+            dao_handler_id,
+            "reset",
+            reset(client, dao_handler_id, &data).await,
+        )
+    }
+    .instrument(my_span)
+    .await
+}
+
+/// Reindexes a dao handler and all of its voters from the very start.
+#[post("/<dao_handler_id>/backfill")]
+pub async fn backfill_handler(client: &Client, dao_handler_id: &str) -> Json<ActionResponse> {
+    let my_span = info_span!("backfill_handler", dao_handler_id = dao_handler_id);
+
+    let request = ResetRequest {
+        chainindex: Some(0),
+        snapshotindex: Some(db_time(Utc.timestamp_opt(0, 0).unwrap())),
+        voters: true,
+    };
+
+    async move {
+        respond(
+            dao_handler_id,
+            "backfill",
+            reset(client, dao_handler_id, &request).await,
+        )
+    }
+    .instrument(my_span)
+    .await
+}
+
+pub async fn serve(client: Arc<PrismaClient>) {
+    let result = rocket::build()
+        .manage(client)
+        .mount("/health", routes![health])
+        .mount(
+            "/handlers",
+            routes![
+                list_handlers,
+                get_handler,
+                list_voter_handlers,
+                pause_handler,
+                resume_handler,
+                refresh_handler,
+                reset_handler,
+                backfill_handler
+            ],
+        )
+        .launch()
+        .await;
+
+    if let Err(e) = result {
+        event!(Level::ERROR, err = e.to_string(), "admin api stopped");
+    }
+}
@@ -0,0 +1,220 @@
+//! Paces detective calls against each chain's rpc budget and sizes the ranges handlers
+//! ask for.
+//!
+//! A call is charged an estimate of the compute units it costs: the `eth_blockNumber`
+//! and `eth_getLogs` every refresh makes, a lookup per result and the follow up calls
+//! detective makes for as long as it runs. Every chain has a budget per second in the
+//! config. While several handlers are active on a chain each gets an equal share of it,
+//! so one dao with a deep backlog does not hold back the others.
+
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use metrics::{gauge, histogram};
+use once_cell::sync::Lazy;
+use tokio::time::{sleep_until, Instant};
+
+use crate::{
+    concurrency::Chain,
+    config::{Config, CONFIG},
+    RefreshEntry,
+};
+
+/// Seconds a detective call should take. Ranges grow while calls are faster and shrink
+/// while they are slower.
+const TARGET_LATENCY: f64 = 10.0;
+/// Handlers without a call for this long no longer count toward the shares.
+const ACTIVE_WINDOW: Duration = Duration::from_secs(60);
+/// Weight of the latest call in the cost estimate.
+const COST_SMOOTHING: f64 = 0.1;
+
+/// `eth_blockNumber` and `eth_getLogs`.
+const REQUEST_COST: f64 = 85.0;
+/// A block or transaction lookup.
+const RESULT_COST: f64 = 16.0;
+/// Follow up calls, per second detective spends on a refresh.
+const LATENCY_COST: f64 = 20.0;
+
+fn cost(chain: Chain, latency: Duration, results: usize) -> f64 {
+    match chain {
+        Chain::Snapshot => 1.0,
+        _ => REQUEST_COST + RESULT_COST * results as f64 + LATENCY_COST * latency.as_secs_f64(),
+    }
+}
+
+fn rate(config: &Config, chain: Chain) -> f64 {
+    f64::from(match chain {
+        Chain::Ethereum => config.budget_ethereum,
+        Chain::Arbitrum => config.budget_arbitrum,
+        Chain::Optimism => config.budget_optimism,
+        Chain::Snapshot => config.budget_snapshot,
+    })
+}
+
+struct Share {
+    next: Instant,
+    seen: Instant,
+}
+
+struct ChainBudget {
+    /// Estimated cost of the next call.
+    cost: f64,
+    /// When the budget has room for the next call.
+    next: Instant,
+    shares: HashMap<String, Share>,
+}
+
+static BUDGETS: Lazy<Mutex<HashMap<Chain, ChainBudget>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A call let through by `admit`, `record` settles what it actually cost.
+pub struct Admission {
+    chain: Chain,
+    estimate: f64,
+    rate: f64,
+}
+
+/// Waits until both the handler's share and the chain's budget have room for a call.
+pub async fn admit(dao_handler_id: &str, chain: Chain) -> Admission {
+    let rate = rate(&CONFIG.read().unwrap().global, chain);
+    let requested = Instant::now();
+
+    let (estimate, share_release) = {
+        let mut budgets = BUDGETS.lock().unwrap();
+        let budget = budgets.entry(chain).or_insert_with(|| ChainBudget {
+            cost: cost(chain, Duration::ZERO, 0),
+            next: requested,
+            shares: HashMap::new(),
+        });
+
+        budget
+            .shares
+            .retain(|_, share| requested.duration_since(share.seen) < ACTIVE_WINDOW);
+
+        let active = budget.shares.len() + usize::from(!budget.shares.contains_key(dao_handler_id));
+
+        let share = budget
+            .shares
+            .entry(dao_handler_id.to_string())
+            .or_insert(Share {
+                next: requested,
+                seen: requested,
+            });
+
+        let release = share.next.max(requested);
+        share.next = release + Duration::from_secs_f64(budget.cost * active as f64 / rate);
+        share.seen = requested;
+
+        gauge!("refresher_budget_active_handlers", active as f64, "chain" => chain.name());
+
+        (budget.cost, release)
+    };
+
+    sleep_until(share_release).await;
+
+    let chain_release = {
+        let mut budgets = BUDGETS.lock().unwrap();
+        let budget = budgets.get_mut(&chain).expect("chain budget exists");
+
+        let release = budget.next.max(Instant::now());
+        budget.next = release + Duration::from_secs_f64(estimate / rate);
+
+        release
+    };
+
+    sleep_until(chain_release).await;
+
+    histogram!(
+        "refresher_budget_wait_seconds",
+        requested.elapsed().as_secs_f64(),
+        "chain" => chain.name()
+    );
+
+    Admission {
+        chain,
+        estimate,
+        rate,
+    }
+}
+
+impl Admission {
+    /// Charges a call that cost more than estimated to the chain's budget and moves the
+    /// estimate toward what it cost.
+    pub fn record(self, latency: Duration, results: usize) {
+        let cost = cost(self.chain, latency, results);
+
+        let mut budgets = BUDGETS.lock().unwrap();
+
+        if let Some(budget) = budgets.get_mut(&self.chain) {
+            if cost > self.estimate {
+                budget.next = budget.next.max(Instant::now())
+                    + Duration::from_secs_f64((cost - self.estimate) / self.rate);
+            }
+
+            budget.cost += COST_SMOOTHING * (cost - budget.cost);
+
+            gauge!("refresher_budget_cost", budget.cost, "chain" => self.chain.name());
+        }
+    }
+}
+
+/// Range for a handler's next call, scaled toward what detective gets through in
+/// `TARGET_LATENCY`. A call moves it up by a quarter or down by half at most, a failed
+/// one halves it.
+pub fn next_range(range: i64, latency: Duration, success: bool, min: i64, max: i64) -> i64 {
+    let factor = if success {
+        (TARGET_LATENCY / latency.as_secs_f64().max(0.001)).clamp(0.5, 1.25)
+    } else {
+        0.5
+    };
+
+    ((range as f64 * factor) as i64).clamp(min, max)
+}
+
+/// `next_range` for the handler of `entry`, exported as its allocation.
+pub fn allocate(
+    entry: &RefreshEntry,
+    range: i64,
+    latency: Duration,
+    success: bool,
+    min: i64,
+    max: i64,
+) -> i64 {
+    let range = next_range(range, latency, success, min, max);
+
+    gauge!(
+        "refresher_budget_range",
+        range as f64,
+        "chain" => entry.chain.name(),
+        "daohandler" => entry.handler_id.clone(),
+        "type" => entry.refresh_type.to_string()
+    );
+
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::next_range;
+
+    #[test]
+    fn ranges_follow_latency_within_bounds() {
+        let fast = Duration::from_secs(1);
+        let slow = Duration::from_secs(40);
+
+        assert_eq!(next_range(1000, fast, true, 100, 10_000), 1250);
+        assert_eq!(next_range(1000, slow, true, 100, 10_000), 500);
+        assert_eq!(
+            next_range(1000, Duration::from_millis(12_500), true, 100, 10_000),
+            800
+        );
+        assert_eq!(
+            next_range(1000, Duration::from_secs(20), true, 100, 10_000),
+            500
+        );
+        assert_eq!(next_range(1000, fast, false, 100, 10_000), 500);
+
+        assert_eq!(next_range(9000, fast, true, 100, 10_000), 10_000);
+        assert_eq!(next_range(150, slow, false, 100, 10_000), 100);
+    }
+}
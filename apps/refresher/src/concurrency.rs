@@ -0,0 +1,131 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{
+    config::{Config, CONFIG},
+    prisma::DaoHandlerType,
+};
+
+/// The chain a handler's refreshes run against. Detective calls are limited per chain so
+/// a slow rpc only holds back the handlers that use it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Chain {
+    Ethereum,
+    Arbitrum,
+    Optimism,
+    Snapshot,
+}
+
+impl Chain {
+    fn parse(name: &str) -> Option<Chain> {
+        match name.to_lowercase().as_str() {
+            "ethereum" | "mainnet" | "eth" => Some(Chain::Ethereum),
+            "arbitrum" | "arb" => Some(Chain::Arbitrum),
+            "optimism" | "op" => Some(Chain::Optimism),
+            _ => None,
+        }
+    }
+
+    /// Chain a handler reads from, the one detective picks its rpc by. `GENERIC_CHAIN`
+    /// handlers name it in the decoder.
+    pub fn of(handler_type: DaoHandlerType, decoder: &Value) -> Chain {
+        match handler_type {
+            DaoHandlerType::Snapshot => Chain::Snapshot,
+            DaoHandlerType::MakerPollArbitrum
+            | DaoHandlerType::ArbitrumCoreChain
+            | DaoHandlerType::ArbitrumTreasuryChain => Chain::Arbitrum,
+            DaoHandlerType::OptimismChain => Chain::Optimism,
+            DaoHandlerType::GenericChain => decoder
+                .get("chain")
+                .and_then(|c| c.as_str())
+                .and_then(Chain::parse)
+                .unwrap_or(Chain::Ethereum),
+            _ => Chain::Ethereum,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Chain::Ethereum => "ethereum",
+            Chain::Arbitrum => "arbitrum",
+            Chain::Optimism => "optimism",
+            Chain::Snapshot => "snapshot",
+        }
+    }
+}
+
+struct Limit {
+    semaphore: Arc<Semaphore>,
+    size: AtomicUsize,
+}
+
+static LIMITS: Lazy<HashMap<Chain, Limit>> = Lazy::new(|| {
+    let config = CONFIG.read().unwrap().global;
+
+    HashMap::from([
+        (Chain::Ethereum, config.concurrency_ethereum),
+        (Chain::Arbitrum, config.concurrency_arbitrum),
+        (Chain::Optimism, config.concurrency_optimism),
+        (Chain::Snapshot, config.concurrency_snapshot),
+    ])
+    .into_iter()
+    .map(|(chain, limit)| {
+        let size = limit.max(1) as usize;
+
+        (
+            chain,
+            Limit {
+                semaphore: Arc::new(Semaphore::new(size)),
+                size: AtomicUsize::new(size),
+            },
+        )
+    })
+    .collect()
+});
+
+/// Waits for a free detective call on `chain`. The call holds the permit until it is
+/// dropped.
+pub async fn acquire(chain: Chain) -> OwnedSemaphorePermit {
+    LIMITS[&chain]
+        .semaphore
+        .clone()
+        .acquire_owned()
+        .await
+        .expect("chain limit closed")
+}
+
+/// Applies changed chain limits. A lower limit takes effect as the calls over it finish.
+pub fn resize(config: &Config) {
+    for (chain, limit) in [
+        (Chain::Ethereum, config.concurrency_ethereum),
+        (Chain::Arbitrum, config.concurrency_arbitrum),
+        (Chain::Optimism, config.concurrency_optimism),
+        (Chain::Snapshot, config.concurrency_snapshot),
+    ] {
+        let Limit { semaphore, size } = &LIMITS[&chain];
+        let limit = limit.max(1) as usize;
+        let current = size.swap(limit, Ordering::SeqCst);
+
+        if limit > current {
+            semaphore.add_permits(limit - current);
+        } else if limit < current {
+            let semaphore = semaphore.clone();
+            let excess = (current - limit) as u32;
+
+            tokio::spawn(async move {
+                if let Ok(permits) = semaphore.acquire_many_owned(excess).await {
+                    permits.forget();
+                }
+            });
+        }
+    }
+}
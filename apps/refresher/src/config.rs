@@ -1,13 +1,19 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
 
 use anyhow::Result;
-use tracing::{debug, instrument};
+use tracing::{debug, event, instrument, Level};
 
-use prisma::PrismaClient;
+use prisma::{daohandler, DaoHandlerType, PrismaClient};
 
-use crate::prisma;
+use crate::{concurrency, prisma};
 
-#[derive(Debug, Clone, Copy)]
+const WEEK: i64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Config {
     pub refresh_interval: u32,
 
@@ -28,96 +34,586 @@ pub struct Config {
 
     pub batch_chain_votes: u32,
     pub batch_snapshot_votes: u32,
+
+    pub concurrency_ethereum: u32,
+    pub concurrency_arbitrum: u32,
+    pub concurrency_optimism: u32,
+    pub concurrency_snapshot: u32,
+
+    /// Alchemy compute units per second refreshes may spend on each chain, requests per
+    /// second on snapshot.
+    pub budget_ethereum: u32,
+    pub budget_arbitrum: u32,
+    pub budget_optimism: u32,
+    pub budget_snapshot: u32,
+
+    pub min_chain_proposals_speed: i64,
+    pub max_chain_proposals_speed: i64,
+    pub min_snapshot_proposals_speed: i64,
+    pub max_snapshot_proposals_speed: i64,
+    pub min_chain_votes_speed: i64,
+    pub max_chain_votes_speed: i64,
+    pub min_snapshot_votes_speed: i64,
+    pub max_snapshot_votes_speed: i64,
 }
 
-lazy_static::lazy_static! {
-    pub static ref CONFIG: Arc<RwLock<Config>> = Arc::new(
-        RwLock::new(Config {
+impl Default for Config {
+    fn default() -> Self {
+        Config {
             refresh_interval: 300,
 
-            normal_chain_proposals: 4 * 60 ,
-            normal_chain_votes: 2 * 60 ,
-            normal_snapshot_proposals: 4 * 60 ,
-            normal_snapshot_votes: 2 * 60 ,
+            normal_chain_proposals: 4 * 60,
+            normal_chain_votes: 2 * 60,
+            normal_snapshot_proposals: 4 * 60,
+            normal_snapshot_votes: 2 * 60,
 
-            new_chain_proposals: 5  ,
-            new_chain_votes: 5 ,
-            new_snapshot_proposals: 5  ,
-            new_snapshot_votes: 5  ,
+            new_chain_proposals: 5,
+            new_chain_votes: 5,
+            new_snapshot_proposals: 5,
+            new_snapshot_votes: 5,
 
-            force_chain_proposals: 60 * 60 ,
-            force_chain_votes: 30 * 60 ,
-            force_snapshot_proposals: 60 * 60 ,
-            force_snapshot_votes: 30 * 60 ,
+            force_chain_proposals: 60 * 60,
+            force_chain_votes: 30 * 60,
+            force_snapshot_proposals: 60 * 60,
+            force_snapshot_votes: 30 * 60,
 
             batch_chain_votes: 100,
             batch_snapshot_votes: 100,
+
+            concurrency_ethereum: 10,
+            concurrency_arbitrum: 5,
+            concurrency_optimism: 5,
+            concurrency_snapshot: 10,
+
+            budget_ethereum: 660,
+            budget_arbitrum: 660,
+            budget_optimism: 660,
+            budget_snapshot: 20,
+
+            min_chain_proposals_speed: 100,
+            max_chain_proposals_speed: 10_000_000,
+            min_snapshot_proposals_speed: 10,
+            max_snapshot_proposals_speed: 1000,
+            min_chain_votes_speed: 100_000,
+            max_chain_votes_speed: 10_000_000_000,
+            min_snapshot_votes_speed: 10,
+            max_snapshot_votes_speed: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    UnknownKey(String),
+    UnknownHandler {
+        key: String,
+        dao_handler_id: String,
+    },
+    OutOfRange {
+        key: String,
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+    InvertedSpeeds {
+        scope: String,
+        min_key: &'static str,
+        max_key: &'static str,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::UnknownKey(key) => write!(f, "unknown config key {}", key),
+            ConfigError::UnknownHandler {
+                key,
+                dao_handler_id,
+            } => write!(
+                f,
+                "{} overrides unknown dao handler {}",
+                key, dao_handler_id
+            ),
+            ConfigError::OutOfRange {
+                key,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "{} is {}, must be between {} and {}",
+                key, value, min, max
+            ),
+            ConfigError::InvertedSpeeds {
+                scope,
+                min_key,
+                max_key,
+            } => write!(f, "{} is above {} for {}", min_key, max_key, scope),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn bounded<T: TryFrom<i64>>(key: &str, value: i64, min: i64, max: i64) -> Result<T, ConfigError> {
+    let out_of_range = || ConfigError::OutOfRange {
+        key: key.to_string(),
+        value,
+        min,
+        max,
+    };
+
+    if value < min || value > max {
+        return Err(out_of_range());
+    }
+
+    T::try_from(value).map_err(|_| out_of_range())
+}
+
+fn seconds(key: &str, value: i64) -> Result<u32, ConfigError> {
+    bounded(key, value, 0, WEEK)
+}
+
+fn batch(key: &str, value: i64) -> Result<u32, ConfigError> {
+    bounded(key, value, 1, 10_000)
+}
+
+fn chain_limit(key: &str, value: i64) -> Result<u32, ConfigError> {
+    bounded(key, value, 1, 1000)
+}
+
+fn budget(key: &str, value: i64) -> Result<u32, ConfigError> {
+    bounded(key, value, 1, 1_000_000)
+}
+
+fn speed(key: &str, value: i64) -> Result<i64, ConfigError> {
+    bounded(key, value, 1, 1_000_000_000_000)
+}
+
+impl Config {
+    /// Every key with its value, the keys missing from the `config` table are seeded
+    /// with these.
+    pub fn values(&self) -> Vec<(&'static str, i64)> {
+        vec![
+            ("refresh_interval", self.refresh_interval.into()),
+            ("normal_chain_proposals", self.normal_chain_proposals.into()),
+            ("normal_chain_votes", self.normal_chain_votes.into()),
+            (
+                "normal_snapshot_proposals",
+                self.normal_snapshot_proposals.into(),
+            ),
+            ("normal_snapshot_votes", self.normal_snapshot_votes.into()),
+            ("new_chain_proposals", self.new_chain_proposals.into()),
+            ("new_chain_votes", self.new_chain_votes.into()),
+            ("new_snapshot_proposals", self.new_snapshot_proposals.into()),
+            ("new_snapshot_votes", self.new_snapshot_votes.into()),
+            ("force_chain_proposals", self.force_chain_proposals.into()),
+            ("force_chain_votes", self.force_chain_votes.into()),
+            (
+                "force_snapshot_proposals",
+                self.force_snapshot_proposals.into(),
+            ),
+            ("force_snapshot_votes", self.force_snapshot_votes.into()),
+            ("batch_chain_votes", self.batch_chain_votes.into()),
+            ("batch_snapshot_votes", self.batch_snapshot_votes.into()),
+            ("concurrency_ethereum", self.concurrency_ethereum.into()),
+            ("concurrency_arbitrum", self.concurrency_arbitrum.into()),
+            ("concurrency_optimism", self.concurrency_optimism.into()),
+            ("concurrency_snapshot", self.concurrency_snapshot.into()),
+            ("budget_ethereum", self.budget_ethereum.into()),
+            ("budget_arbitrum", self.budget_arbitrum.into()),
+            ("budget_optimism", self.budget_optimism.into()),
+            ("budget_snapshot", self.budget_snapshot.into()),
+            ("min_chain_proposals_speed", self.min_chain_proposals_speed),
+            ("max_chain_proposals_speed", self.max_chain_proposals_speed),
+            (
+                "min_snapshot_proposals_speed",
+                self.min_snapshot_proposals_speed,
+            ),
+            (
+                "max_snapshot_proposals_speed",
+                self.max_snapshot_proposals_speed,
+            ),
+            ("min_chain_votes_speed", self.min_chain_votes_speed),
+            ("max_chain_votes_speed", self.max_chain_votes_speed),
+            ("min_snapshot_votes_speed", self.min_snapshot_votes_speed),
+            ("max_snapshot_votes_speed", self.max_snapshot_votes_speed),
+        ]
+    }
+
+    pub fn set(&mut self, key: &str, value: i64) -> Result<(), ConfigError> {
+        match key {
+            "refresh_interval" => self.refresh_interval = seconds(key, value)?,
+            "normal_chain_proposals" => self.normal_chain_proposals = seconds(key, value)?,
+            "normal_chain_votes" => self.normal_chain_votes = seconds(key, value)?,
+            "normal_snapshot_proposals" => self.normal_snapshot_proposals = seconds(key, value)?,
+            "normal_snapshot_votes" => self.normal_snapshot_votes = seconds(key, value)?,
+            "new_chain_proposals" => self.new_chain_proposals = seconds(key, value)?,
+            "new_chain_votes" => self.new_chain_votes = seconds(key, value)?,
+            "new_snapshot_proposals" => self.new_snapshot_proposals = seconds(key, value)?,
+            "new_snapshot_votes" => self.new_snapshot_votes = seconds(key, value)?,
+            "force_chain_proposals" => self.force_chain_proposals = seconds(key, value)?,
+            "force_chain_votes" => self.force_chain_votes = seconds(key, value)?,
+            "force_snapshot_proposals" => self.force_snapshot_proposals = seconds(key, value)?,
+            "force_snapshot_votes" => self.force_snapshot_votes = seconds(key, value)?,
+            "batch_chain_votes" => self.batch_chain_votes = batch(key, value)?,
+            "batch_snapshot_votes" => self.batch_snapshot_votes = batch(key, value)?,
+            "concurrency_ethereum" => self.concurrency_ethereum = chain_limit(key, value)?,
+            "concurrency_arbitrum" => self.concurrency_arbitrum = chain_limit(key, value)?,
+            "concurrency_optimism" => self.concurrency_optimism = chain_limit(key, value)?,
+            "concurrency_snapshot" => self.concurrency_snapshot = chain_limit(key, value)?,
+            "budget_ethereum" => self.budget_ethereum = budget(key, value)?,
+            "budget_arbitrum" => self.budget_arbitrum = budget(key, value)?,
+            "budget_optimism" => self.budget_optimism = budget(key, value)?,
+            "budget_snapshot" => self.budget_snapshot = budget(key, value)?,
+            "min_chain_proposals_speed" => self.min_chain_proposals_speed = speed(key, value)?,
+            "max_chain_proposals_speed" => self.max_chain_proposals_speed = speed(key, value)?,
+            "min_snapshot_proposals_speed" => {
+                self.min_snapshot_proposals_speed = speed(key, value)?
+            }
+            "max_snapshot_proposals_speed" => {
+                self.max_snapshot_proposals_speed = speed(key, value)?
+            }
+            "min_chain_votes_speed" => self.min_chain_votes_speed = speed(key, value)?,
+            "max_chain_votes_speed" => self.max_chain_votes_speed = speed(key, value)?,
+            "min_snapshot_votes_speed" => self.min_snapshot_votes_speed = speed(key, value)?,
+            "max_snapshot_votes_speed" => self.max_snapshot_votes_speed = speed(key, value)?,
+            _ => return Err(ConfigError::UnknownKey(key.to_string())),
+        }
+
+        Ok(())
+    }
+
+    fn check(&self, scope: &str) -> Result<(), ConfigError> {
+        let bounds = [
+            (
+                "min_chain_proposals_speed",
+                self.min_chain_proposals_speed,
+                "max_chain_proposals_speed",
+                self.max_chain_proposals_speed,
+            ),
+            (
+                "min_snapshot_proposals_speed",
+                self.min_snapshot_proposals_speed,
+                "max_snapshot_proposals_speed",
+                self.max_snapshot_proposals_speed,
+            ),
+            (
+                "min_chain_votes_speed",
+                self.min_chain_votes_speed,
+                "max_chain_votes_speed",
+                self.max_chain_votes_speed,
+            ),
+            (
+                "min_snapshot_votes_speed",
+                self.min_snapshot_votes_speed,
+                "max_snapshot_votes_speed",
+                self.max_snapshot_votes_speed,
+            ),
+        ];
+
+        for (min_key, min, max_key, max) in bounds {
+            if min > max {
+                return Err(ConfigError::InvertedSpeeds {
+                    scope: scope.to_string(),
+                    min_key,
+                    max_key,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// What a `config` row applies to. `key` is global, `key:AAVE_CHAIN` overrides it for
+/// one dao handler type and `key:<daohandler id>` for one dao handler.
+#[derive(Debug, Clone, PartialEq)]
+enum Scope {
+    Global,
+    Type(DaoHandlerType),
+    Handler(String),
+}
+
+fn parse_key(key: &str) -> (&str, Scope) {
+    match key.split_once(':') {
+        None => (key, Scope::Global),
+        Some((name, scope)) => {
+            match serde_json::from_value(serde_json::Value::String(scope.to_string())) {
+                Ok(handler_type) => (name, Scope::Type(handler_type)),
+                Err(_) => (name, Scope::Handler(scope.to_string())),
+            }
+        }
+    }
+}
+
+/// Global config with the type and handler overrides resolved on top of it. Handler
+/// overrides apply over their type's.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigSet {
+    pub global: Config,
+    by_type: HashMap<DaoHandlerType, Config>,
+    by_handler: HashMap<String, Config>,
+    pub errors: Vec<ConfigError>,
+}
+
+impl ConfigSet {
+    pub fn get(&self, dao_handler_id: &str, handler_type: DaoHandlerType) -> Config {
+        *self
+            .by_handler
+            .get(dao_handler_id)
+            .or_else(|| self.by_type.get(&handler_type))
+            .unwrap_or(&self.global)
+    }
+}
+
+/// Applies `rows` over `base`. Bad values are skipped, a scope that ends up with a
+/// minimum speed above its maximum keeps `base`.
+fn resolve(
+    base: &Config,
+    rows: &[(&str, i64)],
+    scope: &str,
+    errors: &mut Vec<ConfigError>,
+) -> Config {
+    let mut config = *base;
+
+    for (key, value) in rows {
+        if let Err(e) = config.set(key, *value) {
+            errors.push(e);
+        }
+    }
+
+    match config.check(scope) {
+        Ok(()) => config,
+        Err(e) => {
+            errors.push(e);
+            *base
+        }
+    }
+}
+
+/// Builds the config from the `config` rows. `handler_types` has the type of every dao
+/// handler with an override.
+pub fn build(rows: &[(String, i64)], handler_types: &HashMap<String, DaoHandlerType>) -> ConfigSet {
+    let mut errors = Vec::new();
+
+    let mut global = Vec::new();
+    let mut by_type: HashMap<DaoHandlerType, Vec<(&str, i64)>> = HashMap::new();
+    let mut by_handler: HashMap<String, Vec<(&str, i64)>> = HashMap::new();
+
+    for (key, value) in rows {
+        match parse_key(key) {
+            (name, Scope::Global) => global.push((name, *value)),
+            (name, Scope::Type(handler_type)) => by_type
+                .entry(handler_type)
+                .or_default()
+                .push((name, *value)),
+            (name, Scope::Handler(dao_handler_id)) => {
+                if handler_types.contains_key(&dao_handler_id) {
+                    by_handler
+                        .entry(dao_handler_id)
+                        .or_default()
+                        .push((name, *value))
+                } else {
+                    errors.push(ConfigError::UnknownHandler {
+                        key: key.clone(),
+                        dao_handler_id,
+                    })
+                }
+            }
+        }
+    }
+
+    let global = resolve(&Config::default(), &global, "global", &mut errors);
+
+    let by_type: HashMap<DaoHandlerType, Config> = by_type
+        .into_iter()
+        .map(|(handler_type, rows)| {
+            let scope = format!("{:?}", handler_type);
+            (handler_type, resolve(&global, &rows, &scope, &mut errors))
         })
-    );
+        .collect();
+
+    let by_handler = by_handler
+        .into_iter()
+        .map(|(dao_handler_id, rows)| {
+            let base = by_type
+                .get(&handler_types[&dao_handler_id])
+                .unwrap_or(&global);
+            let config = resolve(base, &rows, &dao_handler_id, &mut errors);
+            (dao_handler_id, config)
+        })
+        .collect();
+
+    ConfigSet {
+        global,
+        by_type,
+        by_handler,
+        errors,
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref CONFIG: Arc<RwLock<ConfigSet>> = Arc::new(RwLock::new(ConfigSet::default()));
 }
 
+/// Reloads the `config` table, changes apply to the next produced refreshes and the
+/// chain limits right away.
 #[instrument(skip_all)]
-async fn load_config_value(client: &PrismaClient, key: &str, default_value: u32) -> Result<u32> {
-    if let Some(config_data) = client
+pub(crate) async fn load_config_from_db(client: &PrismaClient) -> Result<()> {
+    let mut rows: Vec<(String, i64)> = client
         .config()
-        .find_first(vec![prisma::config::key::equals(key.to_string())])
+        .find_many(vec![])
         .exec()
         .await?
-    {
-        Ok(config_data.value as u32)
-    } else {
-        let _ = client
+        .into_iter()
+        .map(|row| (row.key, row.value))
+        .collect();
+
+    let missing: Vec<(&str, i64)> = Config::default()
+        .values()
+        .into_iter()
+        .filter(|(key, _)| !rows.iter().any(|(row_key, _)| row_key == key))
+        .collect();
+
+    if !missing.is_empty() {
+        client
             .config()
-            .create(key.to_string(), default_value as i32, vec![])
+            .create_many(
+                missing
+                    .iter()
+                    .map(|(key, value)| prisma::config::create(key.to_string(), *value, vec![]))
+                    .collect(),
+            )
             .exec()
             .await?;
 
-        Ok(default_value)
+        rows.extend(missing.iter().map(|(key, value)| (key.to_string(), *value)));
     }
-}
 
-#[instrument(skip_all)]
-pub(crate) async fn load_config_from_db(client: &PrismaClient) -> Result<()> {
-    let refresh_interval = load_config_value(client, "refresh_interval", 300).await?;
-
-    let normal_chain_proposals =
-        load_config_value(client, "normal_chain_proposals", 4 * 60).await?;
-    let normal_chain_votes = load_config_value(client, "normal_chain_votes", 2 * 60).await?;
-    let normal_snapshot_proposals =
-        load_config_value(client, "normal_snapshot_proposals", 4 * 60).await?;
-    let normal_snapshot_votes = load_config_value(client, "normal_snapshot_votes", 2 * 60).await?;
-
-    let force_chain_proposals = load_config_value(client, "force_chain_proposals", 60 * 60).await?;
-    let force_chain_votes = load_config_value(client, "force_chain_votes", 30 * 60).await?;
-    let force_snapshot_proposals =
-        load_config_value(client, "force_snapshot_proposals", 60 * 60).await?;
-    let force_snapshot_votes = load_config_value(client, "force_snapshot_votes", 30 * 60).await?;
-
-    let new_chain_proposals = load_config_value(client, "new_chain_proposals", 5).await?;
-    let new_chain_votes = load_config_value(client, "new_chain_votes", 5).await?;
-    let new_snapshot_proposals = load_config_value(client, "new_snapshot_proposals", 5).await?;
-    let new_snapshot_votes = load_config_value(client, "new_snapshot_votes", 5).await?;
-
-    let batch_chain_votes = load_config_value(client, "batch_chain_votes", 100).await?;
-    let batch_snapshot_votes = load_config_value(client, "batch_snapshot_votes", 100).await?;
-
-    let mut config = CONFIG.write().expect("can not write lock config struct");
-
-    config.refresh_interval = refresh_interval;
-    config.normal_chain_proposals = normal_chain_proposals;
-    config.normal_chain_votes = normal_chain_votes;
-    config.normal_snapshot_proposals = normal_snapshot_proposals;
-    config.normal_snapshot_votes = normal_snapshot_votes;
-    config.force_chain_proposals = force_chain_proposals;
-    config.force_chain_votes = force_chain_votes;
-    config.force_snapshot_proposals = force_snapshot_proposals;
-    config.force_snapshot_votes = force_snapshot_votes;
-    config.new_chain_proposals = new_chain_proposals;
-    config.new_chain_votes = new_chain_votes;
-    config.new_snapshot_proposals = new_snapshot_proposals;
-    config.new_snapshot_votes = new_snapshot_votes;
-    config.batch_chain_votes = batch_chain_votes;
-    config.batch_snapshot_votes = batch_snapshot_votes;
+    let handler_ids: Vec<String> = rows
+        .iter()
+        .filter_map(|(key, _)| match parse_key(key) {
+            (_, Scope::Handler(dao_handler_id)) => Some(dao_handler_id),
+            _ => None,
+        })
+        .collect();
+
+    let handler_types: HashMap<String, DaoHandlerType> = if handler_ids.is_empty() {
+        HashMap::new()
+    } else {
+        client
+            .daohandler()
+            .find_many(vec![daohandler::id::in_vec(handler_ids)])
+            .exec()
+            .await?
+            .into_iter()
+            .map(|dao_handler| (dao_handler.id, dao_handler.r#type))
+            .collect()
+    };
+
+    let config = build(&rows, &handler_types);
+
+    {
+        let mut current = CONFIG.write().expect("can not write lock config struct");
+
+        if *current == config {
+            debug!("config unchanged");
+            return Ok(());
+        }
+
+        for e in &config.errors {
+            event!(Level::WARN, err = e.to_string(), "invalid config");
+        }
+
+        event!(
+            Level::INFO,
+            types = config.by_type.len(),
+            handlers = config.by_handler.len(),
+            errors = config.errors.len(),
+            "config changed"
+        );
+
+        *current = config.clone();
+    }
+
+    concurrency::resize(&config.global);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{build, Config, ConfigError};
+    use crate::prisma::DaoHandlerType;
+
+    fn rows(rows: &[(&str, i64)]) -> Vec<(String, i64)> {
+        rows.iter()
+            .map(|(key, value)| (key.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn handler_overrides_apply_over_type_overrides() {
+        let config = build(
+            &rows(&[
+                ("normal_chain_votes", 60),
+                ("normal_chain_votes:AAVE_CHAIN", 30),
+                ("batch_chain_votes:aave", 10),
+            ]),
+            &HashMap::from([("aave".to_string(), DaoHandlerType::AaveChain)]),
+        );
+
+        assert!(config.errors.is_empty(), "{:?}", config.errors);
+
+        let aave = config.get("aave", DaoHandlerType::AaveChain);
+        assert_eq!((aave.normal_chain_votes, aave.batch_chain_votes), (30, 10));
+
+        let other_aave = config.get("other", DaoHandlerType::AaveChain);
+        assert_eq!(
+            (other_aave.normal_chain_votes, other_aave.batch_chain_votes),
+            (30, 100)
+        );
+
+        let compound = config.get("compound", DaoHandlerType::CompoundChain);
+        assert_eq!(compound.normal_chain_votes, 60);
+    }
+
+    #[test]
+    fn invalid_values_are_reported_and_skipped() {
+        let config = build(
+            &rows(&[
+                ("batch_chain_votes", 0),
+                ("normal_chain_vote", 60),
+                ("batch_chain_votes:gone", 10),
+                ("min_snapshot_votes_speed", 2000),
+            ]),
+            &HashMap::new(),
+        );
+
+        assert_eq!(config.global, Config::default());
+        assert_eq!(
+            config.errors,
+            vec![
+                ConfigError::UnknownHandler {
+                    key: "batch_chain_votes:gone".to_string(),
+                    dao_handler_id: "gone".to_string(),
+                },
+                ConfigError::OutOfRange {
+                    key: "batch_chain_votes".to_string(),
+                    value: 0,
+                    min: 1,
+                    max: 10_000,
+                },
+                ConfigError::UnknownKey("normal_chain_vote".to_string()),
+                ConfigError::InvertedSpeeds {
+                    scope: "global".to_string(),
+                    min_key: "min_snapshot_votes_speed",
+                    max_key: "max_snapshot_votes_speed",
+                },
+            ]
+        );
+    }
+}
@@ -1,30 +1,19 @@
-use std::{cmp, collections::HashMap, env, sync::Arc};
+use std::{sync::Arc, time::Instant};
 
-use anyhow::Result;
-use log::warn;
+use anyhow::{anyhow, Result};
 use metrics::increment_counter;
-use prisma_client_rust::chrono::{DateTime, Utc};
-use reqwest::{
-    header::{HeaderName, HeaderValue},
-    Client,
-};
+use prisma_client_rust::chrono::Utc;
+use reqwest::Client;
 use serde::Deserialize;
-use tokio::task;
-use tracing::{
-    debug,
-    debug_span,
-    error_span,
-    event,
-    info_span,
-    instrument,
-    warn_span,
-    Instrument,
-    Level,
-};
+use tracing::{event, instrument, Level};
 
 use crate::{
-    prisma::{self, daohandler, PrismaClient},
-    refresh_status::DAOS_REFRESH_STATUS,
+    budget,
+    concurrency,
+    config::CONFIG,
+    prisma::PrismaClient,
+    refresh_status::{dao_state, persist_dao_status},
+    sharding,
     RefreshEntry,
     RefreshStatus,
 };
@@ -35,114 +24,137 @@ struct ProposalsResponse {
     success: bool,
 }
 
-#[instrument]
-pub(crate) async fn consume_snapshot_proposals(entry: RefreshEntry) -> Result<()> {
-    let detective_url = env::var("DETECTIVE_URL").expect("$DETECTIVE_URL is not set");
+/// Runs one proposals refresh. A failed refresh stays `PENDING`, the job queue retries
+/// it and gives it back to the producer once it is dead lettered.
+#[instrument(skip(client))]
+pub(crate) async fn consume_snapshot_proposals(
+    client: Arc<PrismaClient>,
+    entry: RefreshEntry,
+) -> Result<()> {
+    let config = CONFIG
+        .read()
+        .unwrap()
+        .get(&entry.handler_id, entry.handler_type);
+
+    let detective_url = sharding::detective_url(&entry.handler_id, entry.chain).await;
 
     let post_url = format!("{}/proposals/snapshot_proposals", detective_url);
 
     let http_client = Client::builder().build().unwrap();
 
-    task::spawn(async move {
-        let mut daos_refresh_status = DAOS_REFRESH_STATUS.lock().await;
-        let dao_handler_position = daos_refresh_status
-            .iter()
-            .position(|r| r.dao_handler_id == entry.handler_id)
-            .expect("DaoHandler not found in refresh status array");
-        let dao_handler = daos_refresh_status.get_mut(dao_handler_position).unwrap();
-
-        event!(
-            Level::INFO,
-            daoHandlerId = entry.handler_id,
-            votersrefreshspeed = dao_handler.refreshspeed,
-            "refresh item"
-        );
-
-        let response = http_client
-                .post(&post_url)
-                .json(&serde_json::json!({ "daoHandlerId": entry.handler_id, "refreshspeed":dao_handler.refreshspeed}))
-                .send()
-                .await;
-
-        match response {
-            Ok(res) => {
-                let data = res.json::<ProposalsResponse>().await;
-                match data {
-                    Ok(data) => {
-                        match data.success {
-                            true => {
-                                dao_handler.refresh_status = RefreshStatus::DONE;
-                                dao_handler.last_refresh = Utc::now();
-                                dao_handler.refreshspeed = cmp::min(
-                                    dao_handler.refreshspeed
-                                        + (dao_handler.refreshspeed * 10 / 100),
-                                    1000,
-                                );
-
-                                event!(
-                                    Level::INFO,
-                                    daohandler = dao_handler.dao_handler_id,
-                                    lastrefresh = dao_handler.last_refresh.to_string(),
-                                    refreshspeed = dao_handler.refreshspeed,
-                                    "updated ok"
-                                );
-                            }
-                            false => {
-                                dao_handler.refresh_status = RefreshStatus::NEW;
-                                dao_handler.refreshspeed = cmp::max(
-                                    dao_handler.refreshspeed
-                                        - (dao_handler.refreshspeed * 25 / 100),
-                                    10,
-                                );
-
-                                event!(
-                                    Level::WARN,
-                                    daohandler = dao_handler.dao_handler_id,
-                                    lastrefresh = dao_handler.last_refresh.to_string(),
-                                    refreshspeed = dao_handler.refreshspeed,
-                                    "updated nok"
-                                );
-                            }
-                        };
-                    }
-                    Err(e) => {
-                        dao_handler.refresh_status = RefreshStatus::NEW;
-                        dao_handler.refreshspeed = cmp::max(
-                            dao_handler.refreshspeed - (dao_handler.refreshspeed * 25 / 100),
-                            10,
-                        );
-
-                        increment_counter!("refresher_snapshot_proposals_errors");
-                        event!(
-                            Level::ERROR,
-                            daohandler = dao_handler.dao_handler_id,
-                            lastrefresh = dao_handler.last_refresh.to_string(),
-                            refreshspeed = dao_handler.refreshspeed,
-                            err = e.to_string(),
-                            "failed to update"
-                        );
-                    }
-                }
+    let state = dao_state(&entry.handler_id)
+        .await
+        .ok_or_else(|| anyhow!("dao handler has no refresh status"))?;
+
+    let refreshspeed = state.lock().await.refreshspeed.clamp(
+        config.min_snapshot_proposals_speed,
+        config.max_snapshot_proposals_speed,
+    );
+
+    event!(
+        Level::INFO,
+        daoHandlerId = entry.handler_id,
+        refreshspeed = refreshspeed,
+        "refresh item"
+    );
+
+    let admission = budget::admit(&entry.handler_id, entry.chain).await;
+    let permit = concurrency::acquire(entry.chain).await;
+    let started = Instant::now();
+
+    let response = match http_client
+        .post(&post_url)
+        .json(&serde_json::json!({
+            "daoHandlerId": entry.handler_id,
+            "refreshspeed": refreshspeed
+        }))
+        .send()
+        .await
+    {
+        Ok(res) => res.json::<ProposalsResponse>().await,
+        Err(e) => Err(e),
+    };
+
+    let latency = started.elapsed();
+    drop(permit);
+
+    admission.record(latency, 0);
+
+    let mut dao_handler = state.lock().await;
+
+    let result = match response {
+        Ok(data) => match data.success {
+            true => {
+                dao_handler.refresh_status = RefreshStatus::Done;
+                dao_handler.failures = 0;
+                dao_handler.last_refresh = Utc::now();
+                dao_handler.refreshspeed = budget::allocate(
+                    &entry,
+                    dao_handler.refreshspeed,
+                    latency,
+                    true,
+                    config.min_snapshot_proposals_speed,
+                    config.max_snapshot_proposals_speed,
+                );
+
+                event!(
+                    Level::INFO,
+                    daohandler = dao_handler.dao_handler_id,
+                    lastrefresh = dao_handler.last_refresh.to_string(),
+                    refreshspeed = dao_handler.refreshspeed,
+                    "updated ok"
+                );
+
+                Ok(())
             }
-            Err(e) => {
-                dao_handler.refresh_status = RefreshStatus::NEW;
-                dao_handler.refreshspeed = cmp::max(
-                    dao_handler.refreshspeed - (dao_handler.refreshspeed * 25 / 100),
-                    10,
+            false => {
+                dao_handler.failures += 1;
+                dao_handler.refreshspeed = budget::allocate(
+                    &entry,
+                    dao_handler.refreshspeed,
+                    latency,
+                    false,
+                    config.min_snapshot_proposals_speed,
+                    config.max_snapshot_proposals_speed,
                 );
 
-                increment_counter!("refresher_snapshot_proposals_errors");
                 event!(
-                    Level::ERROR,
+                    Level::WARN,
                     daohandler = dao_handler.dao_handler_id,
                     lastrefresh = dao_handler.last_refresh.to_string(),
                     refreshspeed = dao_handler.refreshspeed,
-                    err = e.to_string(),
-                    "failed to update"
+                    "updated nok"
                 );
+
+                Err(anyhow!("detective refresh failed"))
             }
+        },
+        Err(e) => {
+            dao_handler.failures += 1;
+            dao_handler.refreshspeed = budget::allocate(
+                &entry,
+                dao_handler.refreshspeed,
+                latency,
+                false,
+                config.min_snapshot_proposals_speed,
+                config.max_snapshot_proposals_speed,
+            );
+
+            increment_counter!("refresher_snapshot_proposals_errors");
+            event!(
+                Level::ERROR,
+                daohandler = dao_handler.dao_handler_id,
+                lastrefresh = dao_handler.last_refresh.to_string(),
+                refreshspeed = dao_handler.refreshspeed,
+                err = e.to_string(),
+                "failed to update"
+            );
+
+            Err(e.into())
         }
-    }.instrument(info_span!("consume_snapshot_proposals_task")));
+    };
+
+    persist_dao_status(&client, &dao_handler).await;
 
-    Ok(())
+    result
 }
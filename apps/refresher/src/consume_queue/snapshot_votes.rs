@@ -1,169 +1,191 @@
-use std::{cmp, collections::HashMap, env, sync::Arc};
+use std::{sync::Arc, time::Instant};
 
-use anyhow::Result;
-use log::warn;
+use anyhow::{anyhow, Result};
 use metrics::increment_counter;
-use prisma_client_rust::chrono::{DateTime, Utc};
-use reqwest::{
-    header::{HeaderName, HeaderValue},
-    Client,
-};
+use prisma_client_rust::chrono::Utc;
+use reqwest::Client;
 use serde::Deserialize;
-use tokio::task;
-use tracing::{
-    debug,
-    debug_span,
-    error_span,
-    event,
-    info_span,
-    instrument,
-    warn_span,
-    Instrument,
-    Level,
-};
+use tracing::{event, instrument, Level};
 
 use crate::{
-    prisma::{self, daohandler, PrismaClient},
-    refresh_status::{DAOS_REFRESH_STATUS, VOTERS_REFRESH_STATUS},
+    budget,
+    concurrency,
+    config::CONFIG,
+    prisma::PrismaClient,
+    refresh_status::{dao_state, persist_dao_status, persist_voters_result, voters_state},
+    sharding,
     RefreshEntry,
     RefreshStatus,
 };
 
 #[allow(non_snake_case)]
-#[derive(Deserialize)]
+#[derive(Deserialize, Debug)]
 struct ApiResponse {
     voter_address: String,
     success: bool,
 }
 
-#[instrument]
-pub(crate) async fn consume_snapshot_votes(entry: RefreshEntry) -> Result<()> {
-    let detective_url = env::var("DETECTIVE_URL").expect("$DETECTIVE_URL is not set");
+/// Runs one votes refresh. Voters detective reports as failed go back to `NEW`. When
+/// the call itself fails they stay `PENDING` for the job queue to retry.
+#[instrument(skip(client))]
+pub(crate) async fn consume_snapshot_votes(
+    client: Arc<PrismaClient>,
+    entry: RefreshEntry,
+) -> Result<()> {
+    let config = CONFIG
+        .read()
+        .unwrap()
+        .get(&entry.handler_id, entry.handler_type);
+
+    let detective_url = sharding::detective_url(&entry.handler_id, entry.chain).await;
 
     let post_url = format!("{}/votes/snapshot_votes", detective_url);
 
     let http_client = Client::builder().build().unwrap();
 
-    task::spawn(async move {
-        let mut daos_refresh_status = DAOS_REFRESH_STATUS.lock().await;
-        let mut voter_refresh_status = VOTERS_REFRESH_STATUS.lock().await;
-        let dao_handler_position = daos_refresh_status
-            .iter()
-            .position(|r| r.dao_handler_id == entry.handler_id)
-            .expect("DaoHandler not found in refresh status array");
-        let dao_handler_r = daos_refresh_status.get_mut(dao_handler_position).unwrap();
-
-        event!(
-            Level::INFO,
-            daoHandlerId = entry.handler_id,
-            votersrefreshspeed = dao_handler_r.votersrefreshspeed,
-            "refresh item"
-        );
-
-        let response = http_client
-                .post(&post_url)
-                .json(&serde_json::json!({ "daoHandlerId": entry.handler_id, "voters": entry.voters, "refreshspeed": dao_handler_r.votersrefreshspeed}))
-                .send()
-                .await;
-
-        match response {
-            Ok(res) => {
-                let data = res.json::<Vec<ApiResponse>>().await;
-
-                match data {
-                    Ok(data) => {
-                        let ok_voters_response: Vec<String> = data
-                            .iter()
-                            .filter(|result| result.success)
-                            .map(|result| result.voter_address.clone())
-                            .collect();
-
-                        let nok_voters_response: Vec<String> = data
-                            .iter()
-                            .filter(|result| !result.success)
-                            .map(|result| result.voter_address.clone())
-                            .collect();
-
-                        if !ok_voters_response.is_empty() {
-                            dao_handler_r.votersrefreshspeed = cmp::min(
-                                dao_handler_r.votersrefreshspeed
-                                    + (dao_handler_r.votersrefreshspeed * 10 / 100),
-                                1000,
-                            );
-
-                            event!(
-                                Level::INFO,
-                                daohandler = dao_handler_r.dao_handler_id,
-                                "updated ok"
-                            );
-                        }
-                        if !nok_voters_response.is_empty() {
-                            dao_handler_r.votersrefreshspeed = cmp::max(
-                                dao_handler_r.votersrefreshspeed
-                                    - (dao_handler_r.votersrefreshspeed * 25 / 100),
-                                10,
-                            );
-
-                            event!(
-                                Level::INFO,
-                                daohandler = dao_handler_r.dao_handler_id,
-                                "updated nok"
-                            );
-                        }
-
-                        for vh in voter_refresh_status.iter_mut() {
-                            if ok_voters_response.contains(&vh.voter_address) {
-                                vh.refresh_status = RefreshStatus::DONE;
-                                vh.last_refresh = Utc::now();
-                            }
-
-                            if nok_voters_response.contains(&vh.voter_address) {
-                                vh.refresh_status = RefreshStatus::NEW;
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        for vh in voter_refresh_status.iter_mut() {
-                            vh.refresh_status = RefreshStatus::NEW;
-
-                            increment_counter!("refresher_snapshot_votes_errors");
-                            event!(
-                                Level::ERROR,
-                                daohandler = dao_handler_r.dao_handler_id,
-                                voteraddress = vh.voter_address,
-                                err = e.to_string(),
-                                "failed to update"
-                            );
-                        }
-                        dao_handler_r.votersrefreshspeed = cmp::max(
-                            dao_handler_r.votersrefreshspeed
-                                - (dao_handler_r.votersrefreshspeed * 25 / 100),
-                            10,
-                        );
-                    }
-                }
-            }
-            Err(e) => {
-                for vh in voter_refresh_status.iter_mut() {
-                    vh.refresh_status = RefreshStatus::NEW;
-
-                    increment_counter!("refresher_snapshot_votes_errors");
-                    event!(
-                        Level::ERROR,
-                        daohandler = dao_handler_r.dao_handler_id,
-                        voteraddress = vh.voter_address,
-                        err = e.to_string(),
-                        "failed to update"
-                    );
-                }
-                dao_handler_r.votersrefreshspeed = cmp::max(
-                    dao_handler_r.votersrefreshspeed
-                        - (dao_handler_r.votersrefreshspeed * 25 / 100),
-                    10,
-                );
-            }
+    let (Some(state), Some(voters)) = (
+        dao_state(&entry.handler_id).await,
+        voters_state(&entry.handler_id).await,
+    ) else {
+        return Err(anyhow!("dao handler has no refresh status"));
+    };
+
+    let votersrefreshspeed = state.lock().await.votersrefreshspeed.clamp(
+        config.min_snapshot_votes_speed,
+        config.max_snapshot_votes_speed,
+    );
+
+    event!(
+        Level::INFO,
+        daoHandlerId = entry.handler_id,
+        votersrefreshspeed = votersrefreshspeed,
+        "refresh item"
+    );
+
+    let admission = budget::admit(&entry.handler_id, entry.chain).await;
+    let permit = concurrency::acquire(entry.chain).await;
+    let started = Instant::now();
+
+    let response = match http_client
+        .post(&post_url)
+        .json(&serde_json::json!({
+            "daoHandlerId": entry.handler_id,
+            "voters": entry.voters,
+            "refreshspeed": votersrefreshspeed
+        }))
+        .send()
+        .await
+    {
+        Ok(res) => res.json::<Vec<ApiResponse>>().await,
+        Err(e) => Err(e),
+    };
+
+    let latency = started.elapsed();
+    drop(permit);
+
+    admission.record(
+        latency,
+        response.as_ref().map(|data| data.len()).unwrap_or(0),
+    );
+
+    let data = match response {
+        Ok(data) => data,
+        Err(e) => {
+            let mut dao_handler_r = state.lock().await;
+
+            dao_handler_r.votersrefreshspeed = budget::allocate(
+                &entry,
+                dao_handler_r.votersrefreshspeed,
+                latency,
+                false,
+                config.min_snapshot_votes_speed,
+                config.max_snapshot_votes_speed,
+            );
+
+            increment_counter!("refresher_snapshot_votes_errors");
+            event!(
+                Level::ERROR,
+                daohandler = dao_handler_r.dao_handler_id,
+                voters = entry.voters.len(),
+                err = e.to_string(),
+                "failed to update"
+            );
+
+            persist_dao_status(&client, &dao_handler_r).await;
+
+            return Err(e.into());
+        }
+    };
+
+    let ok_voters_response: Vec<String> = data
+        .iter()
+        .filter(|result| result.success)
+        .map(|result| result.voter_address.clone())
+        .collect();
+
+    let nok_voters_response: Vec<String> = data
+        .iter()
+        .filter(|result| !result.success)
+        .map(|result| result.voter_address.clone())
+        .collect();
+
+    {
+        let mut dao_handler_r = state.lock().await;
+
+        if !ok_voters_response.is_empty() {
+            dao_handler_r.votersrefreshspeed = budget::allocate(
+                &entry,
+                dao_handler_r.votersrefreshspeed,
+                latency,
+                true,
+                config.min_snapshot_votes_speed,
+                config.max_snapshot_votes_speed,
+            );
+
+            event!(
+                Level::INFO,
+                daohandler = dao_handler_r.dao_handler_id,
+                "updated ok"
+            );
         }
-    }.instrument(info_span!("consume_snapshot_votes_task")));
+
+        if !nok_voters_response.is_empty() {
+            dao_handler_r.votersrefreshspeed = budget::allocate(
+                &entry,
+                dao_handler_r.votersrefreshspeed,
+                latency,
+                false,
+                config.min_snapshot_votes_speed,
+                config.max_snapshot_votes_speed,
+            );
+
+            event!(
+                Level::INFO,
+                daohandler = dao_handler_r.dao_handler_id,
+                "updated nok"
+            );
+        }
+
+        persist_dao_status(&client, &dao_handler_r).await;
+    }
+
+    let mut voters = voters.lock().await;
+
+    for vh in voters.values_mut() {
+        if ok_voters_response.contains(&vh.voter_address) {
+            vh.refresh_status = RefreshStatus::Done;
+            vh.last_refresh = Utc::now();
+            vh.failures = 0;
+        }
+
+        if nok_voters_response.contains(&vh.voter_address) {
+            vh.refresh_status = RefreshStatus::New;
+            vh.failures += 1;
+        }
+    }
+
+    persist_voters_result(&client, &entry.handler_id, ok_voters_response, true).await;
+    persist_voters_result(&client, &entry.handler_id, nok_voters_response, false).await;
 
     Ok(())
 }
@@ -1,100 +1,246 @@
+use std::collections::{BTreeSet, HashMap};
+
 use anyhow::Result;
+use once_cell::sync::Lazy;
+use tokio::sync::Mutex;
 use tracing::{debug, event, instrument, Level};
 
 use crate::{
-    prisma::{self, voter, voterhandler, PrismaClient},
-    RefreshStatus,
+    prisma::{self, subscription, voter, voterhandler, PrismaClient},
+    refresh_status::forget_voter_handlers,
 };
 
-#[instrument(skip_all)]
-pub(crate) async fn create_voter_handlers(client: &PrismaClient) -> Result<()> {
-    // remove_orphan_voters(client);
+/// Rows per statement when creating or removing voter handlers.
+const CHUNK_SIZE: usize = 1000;
 
-    let voters_count = client.voter().count(vec![]).exec().await.unwrap();
+/// A dao handler id and voter id a voter handler exists for.
+type Pair = (String, String);
 
-    let daohandlers_count = client.daohandler().count(vec![]).exec().await.unwrap();
+/// The pairs the last sync left in `voterhandler`, a sync with the same pairs is a no-op.
+static SYNCED: Lazy<Mutex<Option<BTreeSet<Pair>>>> = Lazy::new(|| Mutex::new(None));
 
-    let voterhandler_count = client.voterhandler().count(vec![]).exec().await.unwrap();
+/// Voter handlers reachable through a subscription: every voter linked to a subscribed
+/// user, on every handler of the dao. `subscriptions` are dao ids with the voter ids of
+/// the subscriber, `dao_handlers` are handler ids by dao id.
+fn reachable(
+    subscriptions: &[(String, Vec<String>)],
+    dao_handlers: &HashMap<String, Vec<String>>,
+) -> BTreeSet<Pair> {
+    let mut pairs = BTreeSet::new();
 
-    if voters_count * daohandlers_count > voterhandler_count {
-        let daohandlers = client.daohandler().find_many(vec![]).exec().await.unwrap();
-        let voters = client
-            .voter()
-            .find_many(vec![voter::voterhandlers::none(vec![])])
-            .exec()
-            .await
-            .unwrap();
-
-        for voter in voters {
-            let _result = client
-                .voterhandler()
-                .create_many(
-                    daohandlers
-                        .iter()
-                        .map(|daohandler| {
-                            voterhandler::create_unchecked(
-                                daohandler.id.clone(),
-                                voter.id.clone(),
-                                vec![],
-                            )
-                        })
-                        .collect(),
-                )
-                .skip_duplicates()
-                .exec()
-                .await?;
+    for (dao_id, voter_ids) in subscriptions {
+        for dao_handler_id in dao_handlers.get(dao_id).into_iter().flatten() {
+            for voter_id in voter_ids {
+                pairs.insert((dao_handler_id.clone(), voter_id.clone()));
+            }
         }
     }
 
-    Ok(())
+    pairs
 }
 
-async fn _remove_orphan_voters(client: &PrismaClient) {
-    prisma::voter::include!(voter_with_users { users });
+/// Pairs to create and ids of the voter handlers to remove to get from `existing`, voter
+/// handler ids with their pair, to `desired`.
+fn diff(desired: &BTreeSet<Pair>, existing: Vec<(String, Pair)>) -> (Vec<Pair>, Vec<String>) {
+    let mut missing = desired.clone();
+    let mut removed = Vec::new();
 
-    let all_voters = client
-        .voter()
+    for (voter_handler_id, pair) in existing {
+        if desired.contains(&pair) {
+            missing.remove(&pair);
+        } else {
+            removed.push(voter_handler_id);
+        }
+    }
+
+    (missing.into_iter().collect(), removed)
+}
+
+/// Keeps `voterhandler` to the (dao handler, voter) pairs someone is subscribed to.
+/// Subscriptions and linked voters are checked on every call, the table is only read and
+/// changed when they moved.
+#[instrument(skip_all)]
+pub(crate) async fn sync_voter_handlers(client: &PrismaClient) -> Result<()> {
+    remove_orphan_voters(client).await?;
+
+    let subscriptions: Vec<(String, Vec<String>)> = client
+        .subscription()
         .find_many(vec![])
-        .include(voter_with_users::include())
+        .include(subscription::include!({
+            user: select { voters: select { id } }
+        }))
         .exec()
-        .await
-        .expect("Failed to fetch voters");
+        .await?
+        .into_iter()
+        .map(|subscription| {
+            (
+                subscription.daoid,
+                subscription.user.voters.into_iter().map(|v| v.id).collect(),
+            )
+        })
+        .collect();
+
+    let mut dao_handlers: HashMap<String, Vec<String>> = HashMap::new();
+    for dao_handler in client.daohandler().find_many(vec![]).exec().await? {
+        dao_handlers
+            .entry(dao_handler.daoid)
+            .or_default()
+            .push(dao_handler.id);
+    }
 
-    let orphan_voters: Vec<voter_with_users::Data> = all_voters
+    let desired = reachable(&subscriptions, &dao_handlers);
+
+    let mut synced = SYNCED.lock().await;
+
+    if synced.as_ref() == Some(&desired) {
+        debug!("voter handlers unchanged");
+        return Ok(());
+    }
+
+    let existing = client
+        .voterhandler()
+        .find_many(vec![])
+        .select(voterhandler::select!({ id daohandlerid voterid }))
+        .exec()
+        .await?
         .into_iter()
-        .filter(|v| v.users.is_empty())
+        .map(|vh| (vh.id, (vh.daohandlerid, vh.voterid)))
         .collect();
 
-    if !orphan_voters.is_empty() {
+    let (missing, removed) = diff(&desired, existing);
+
+    for chunk in missing.chunks(CHUNK_SIZE) {
         client
-            .vote()
-            .delete_many(vec![prisma::vote::voteraddress::in_vec(
-                orphan_voters
-                    .clone()
-                    .into_iter()
-                    .map(|v| v.address)
+            .voterhandler()
+            .create_many(
+                chunk
+                    .iter()
+                    .map(|(dao_handler_id, voter_id)| {
+                        voterhandler::create_unchecked(
+                            dao_handler_id.clone(),
+                            voter_id.clone(),
+                            vec![],
+                        )
+                    })
                     .collect(),
-            )])
+            )
+            .skip_duplicates()
             .exec()
-            .await
-            .unwrap();
+            .await?;
+    }
 
+    for chunk in removed.chunks(CHUNK_SIZE) {
         client
             .voterhandler()
-            .delete_many(vec![prisma::voterhandler::voterid::in_vec(
-                orphan_voters.clone().into_iter().map(|v| v.id).collect(),
-            )])
+            .delete_many(vec![voterhandler::id::in_vec(chunk.to_vec())])
             .exec()
-            .await
-            .unwrap();
+            .await?;
+    }
 
-        client
-            .voter()
-            .delete_many(vec![prisma::voter::id::in_vec(
-                orphan_voters.clone().into_iter().map(|v| v.id).collect(),
-            )])
-            .exec()
-            .await
-            .unwrap();
+    forget_voter_handlers(&removed).await;
+
+    event!(
+        Level::INFO,
+        voter_handlers = desired.len(),
+        created = missing.len(),
+        removed = removed.len(),
+        "synced voter handlers"
+    );
+
+    *synced = Some(desired);
+
+    Ok(())
+}
+
+/// Drops voters no user links to anymore, with their votes and voter handlers.
+#[instrument(skip_all)]
+async fn remove_orphan_voters(client: &PrismaClient) -> Result<()> {
+    let orphan_voters = client
+        .voter()
+        .find_many(vec![voter::users::none(vec![])])
+        .exec()
+        .await?;
+
+    if orphan_voters.is_empty() {
+        return Ok(());
+    }
+
+    client
+        .vote()
+        .delete_many(vec![prisma::vote::voteraddress::in_vec(
+            orphan_voters.iter().map(|v| v.address.clone()).collect(),
+        )])
+        .exec()
+        .await?;
+
+    client
+        .voterhandler()
+        .delete_many(vec![prisma::voterhandler::voterid::in_vec(
+            orphan_voters.iter().map(|v| v.id.clone()).collect(),
+        )])
+        .exec()
+        .await?;
+
+    client
+        .voter()
+        .delete_many(vec![prisma::voter::id::in_vec(
+            orphan_voters.iter().map(|v| v.id.clone()).collect(),
+        )])
+        .exec()
+        .await?;
+
+    event!(
+        Level::INFO,
+        voters = orphan_voters.len(),
+        "removed orphan voters"
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashMap};
+
+    use super::{diff, reachable};
+
+    fn pair(dao_handler_id: &str, voter_id: &str) -> (String, String) {
+        (dao_handler_id.to_string(), voter_id.to_string())
+    }
+
+    #[test]
+    fn only_subscribed_pairs_are_reachable() {
+        let dao_handlers = HashMap::from([
+            (
+                "aave".to_string(),
+                vec!["aave-chain".to_string(), "aave-snapshot".to_string()],
+            ),
+            ("ens".to_string(), vec!["ens-chain".to_string()]),
+        ]);
+
+        let subscriptions = vec![
+            ("aave".to_string(), vec!["alice".to_string()]),
+            ("uniswap".to_string(), vec!["bob".to_string()]),
+        ];
+
+        assert_eq!(
+            reachable(&subscriptions, &dao_handlers),
+            BTreeSet::from([pair("aave-chain", "alice"), pair("aave-snapshot", "alice")])
+        );
+    }
+
+    #[test]
+    fn diff_creates_missing_and_removes_unreachable() {
+        let desired = BTreeSet::from([pair("aave-chain", "alice"), pair("ens-chain", "alice")]);
+
+        let existing = vec![
+            ("vh1".to_string(), pair("aave-chain", "alice")),
+            ("vh2".to_string(), pair("aave-chain", "bob")),
+        ];
+
+        assert_eq!(
+            diff(&desired, existing),
+            (vec![pair("ens-chain", "alice")], vec!["vh2".to_string()])
+        );
     }
 }
@@ -2,8 +2,10 @@
 #![allow(unused_imports)]
 #![allow(unused_parens)]
 
+#[macro_use]
+extern crate rocket;
+
 use dotenv::dotenv;
-use flume as _;
 use log::{info, warn};
 use opentelemetry::{
     sdk::{trace, Resource},
@@ -20,58 +22,49 @@ use tracing_opentelemetry as _;
 use tracing_subscriber::{fmt, prelude::*};
 
 use config::{load_config_from_db, CONFIG};
-use handlers::create_voter_handlers;
+use handlers::sync_voter_handlers;
 use prisma::PrismaClient;
 use tracing_subscriber::{prelude::__tracing_subscriber_SubscriberExt, EnvFilter};
 
 use crate::{
-    consume_queue::{
-        chain_proposals::consume_chain_proposals,
-        chain_votes::consume_chain_votes,
-        snapshot_proposals::consume_snapshot_proposals,
-        snapshot_votes::consume_snapshot_votes,
-    },
+    priority::update_priorities,
     produce_queue::{
         chain_proposals::produce_chain_proposals_queue,
         chain_votes::produce_chain_votes_queue,
         snapshot_proposals::produce_snapshot_proposals_queue,
         snapshot_votes::produce_snapshot_votes_queue,
     },
-    refresh_status::{create_refresh_statuses, DAOS_REFRESH_STATUS},
+    queue::{enqueue, lead, work},
+    refresh_status::{create_refresh_statuses, sync_paused, sync_refresh_statuses},
+    sharding::update_membership,
 };
 
+mod admin;
+mod budget;
+mod concurrency;
 mod consume_queue;
+mod priority;
 pub mod prisma;
 mod produce_queue;
+mod queue;
 mod refresh_status;
+mod sharding;
 mod telemetry;
 
 pub mod config;
 pub mod handlers;
 
-#[derive(Debug)]
-enum RefreshType {
-    Daochainproposals,
-    Daosnapshotproposals,
-    Daochainvotes,
-    Daosnapshotvotes,
-}
-
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RefreshEntry {
     handler_id: String,
     handler_type: prisma::DaoHandlerType,
+    chain: concurrency::Chain,
     refresh_type: RefreshType,
     voters: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum RefreshStatus {
-    NEW,
-    DONE,
-    PENDING,
-}
+pub use prisma::{RefreshStatus, RefreshType};
 
 #[tokio::main]
 async fn main() {
@@ -79,131 +72,127 @@ async fn main() {
     telemetry::setup();
 
     let client = Arc::new(PrismaClient::_builder().build().await.unwrap());
-    let config = *CONFIG.read().unwrap();
-
     //initial load
     let _ = load_config_from_db(&client).await;
-    let _ = create_voter_handlers(&client).await;
+    let _ = sync_voter_handlers(&client).await;
 
     let _ = create_refresh_statuses(&client).await;
 
-    let (tx_snapshot_proposals, rx_snapshot_proposals) = flume::unbounded();
-    let (tx_snapshot_votes, rx_snapshot_votes) = flume::unbounded();
-    let (tx_chain_proposals, rx_chain_proposals) = flume::unbounded();
-    let (tx_chain_votes, rx_chain_votes) = flume::unbounded();
-
     let slow_task_client_clone = client.clone();
     let slow_task = tokio::task::spawn(async move {
         loop {
-            let _ = create_voter_handlers(&slow_task_client_clone).await;
+            if let Err(e) = load_config_from_db(&slow_task_client_clone).await {
+                event!(Level::WARN, err = e.to_string(), "failed to reload config");
+            }
+            let _ = sync_voter_handlers(&slow_task_client_clone).await;
             let _ = create_refresh_statuses(&slow_task_client_clone).await;
+            let _ = sync_paused(&slow_task_client_clone).await;
             sleep(Duration::from_secs(5)).await;
         }
     });
 
-    let producer_client_clone = client.clone();
-    let producer_task = tokio::task::spawn_blocking(move || async move {
+    let priority_client_clone = client.clone();
+    let priority_task = tokio::task::spawn(async move {
         loop {
-            if let Ok(queue) = produce_snapshot_proposals_queue(&config).await {
-                for item in queue {
-                    tx_snapshot_proposals.try_send(item).unwrap();
-                }
+            if let Err(e) = update_priorities(&priority_client_clone).await {
+                event!(
+                    Level::WARN,
+                    err = e.to_string(),
+                    "failed to update priorities"
+                );
             }
+            sleep(Duration::from_secs(30)).await;
+        }
+    });
 
-            if let Ok(queue) = produce_chain_proposals_queue(&config).await {
-                for item in queue {
-                    tx_chain_proposals.try_send(item).unwrap();
-                }
+    let membership_client_clone = client.clone();
+    let membership_task = tokio::task::spawn(async move {
+        loop {
+            if let Err(e) = update_membership(&membership_client_clone).await {
+                event!(
+                    Level::WARN,
+                    err = e.to_string(),
+                    "failed to update detective membership"
+                );
             }
+            sleep(Duration::from_secs(10)).await;
+        }
+    });
 
-            if let Ok(queue) = produce_snapshot_votes_queue(&producer_client_clone, &config).await {
-                for item in queue {
-                    tx_snapshot_votes.try_send(item).unwrap();
+    let producer_client_clone = client.clone();
+    let producer_task = tokio::task::spawn(async move {
+        loop {
+            match lead(&producer_client_clone).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    sleep(Duration::from_secs(1)).await;
+                    continue;
                 }
-            }
-
-            if let Ok(queue) = produce_chain_votes_queue(&producer_client_clone, &config).await {
-                for item in queue {
-                    tx_chain_votes.try_send(item).unwrap();
+                Err(e) => {
+                    event!(
+                        Level::WARN,
+                        err = e.to_string(),
+                        "failed to take the producer lease"
+                    );
+                    sleep(Duration::from_secs(1)).await;
+                    continue;
                 }
             }
 
-            let _daos_refresh_status = DAOS_REFRESH_STATUS.lock().await;
-
-            sleep(Duration::from_secs(1)).await;
-        }
-    })
-    .await
-    .unwrap();
-
-    let consumer_snapshot_proposals_task = tokio::spawn(async move {
-        loop {
-            if let Ok(item) = rx_snapshot_proposals.recv_async().await {
-                tokio::spawn(async move {
-                    match consume_snapshot_proposals(item).await {
-                        Ok(_) => {}
-                        Err(_e) => {}
-                    }
-                });
+            if let Err(e) = sync_refresh_statuses(&producer_client_clone).await {
+                event!(
+                    Level::WARN,
+                    err = e.to_string(),
+                    "failed to sync refresh statuses"
+                );
+                sleep(Duration::from_secs(1)).await;
+                continue;
             }
 
-            sleep(Duration::from_millis(100)).await;
-        }
-    });
+            let config = CONFIG.read().unwrap().clone();
+            let mut queue = Vec::new();
 
-    let consumer_chain_proposals_task = tokio::spawn(async move {
-        loop {
-            if let Ok(item) = rx_chain_proposals.recv_async().await {
-                tokio::spawn(async move {
-                    match consume_chain_proposals(item).await {
-                        Ok(_) => {}
-                        Err(_e) => {}
-                    }
-                });
+            if let Ok(items) =
+                produce_snapshot_proposals_queue(&producer_client_clone, &config).await
+            {
+                queue.extend(items);
             }
 
-            sleep(Duration::from_millis(100)).await;
-        }
-    });
+            if let Ok(items) = produce_chain_proposals_queue(&producer_client_clone, &config).await
+            {
+                queue.extend(items);
+            }
 
-    let consumer_snapshot_votes_task = tokio::spawn(async move {
-        loop {
-            if let Ok(item) = rx_snapshot_votes.recv_async().await {
-                tokio::spawn(async move {
-                    match consume_snapshot_votes(item).await {
-                        Ok(_) => {}
-                        Err(_e) => {}
-                    }
-                });
+            if let Ok(items) = produce_snapshot_votes_queue(&producer_client_clone, &config).await {
+                queue.extend(items);
             }
 
-            sleep(Duration::from_millis(100)).await;
-        }
-    });
+            if let Ok(items) = produce_chain_votes_queue(&producer_client_clone, &config).await {
+                queue.extend(items);
+            }
 
-    let consumer_chain_votes_task = tokio::spawn(async move {
-        loop {
-            if let Ok(item) = rx_chain_votes.recv_async().await {
-                tokio::spawn(async move {
-                    match consume_chain_votes(item).await {
-                        Ok(_) => {}
-                        Err(_e) => {}
-                    }
-                });
+            if let Err(e) = enqueue(&producer_client_clone, queue).await {
+                event!(
+                    Level::WARN,
+                    err = e.to_string(),
+                    "failed to enqueue refreshes"
+                );
             }
 
-            sleep(Duration::from_millis(100)).await;
+            sleep(Duration::from_secs(1)).await;
         }
     });
 
-    producer_task.await;
+    let worker_task = tokio::task::spawn(work(client.clone()));
+    let admin_task = tokio::task::spawn(admin::serve(client.clone()));
 
     try_join!(
         slow_task,
-        consumer_snapshot_proposals_task,
-        consumer_snapshot_votes_task,
-        consumer_chain_proposals_task,
-        consumer_chain_votes_task
+        priority_task,
+        membership_task,
+        producer_task,
+        worker_task,
+        admin_task
     )
     .unwrap();
 }
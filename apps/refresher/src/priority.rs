@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prisma_client_rust::{
+    chrono::{DateTime, Duration, FixedOffset, Utc},
+    Direction,
+};
+use tokio::sync::RwLock;
+use tracing::{event, instrument, Level};
+
+use crate::prisma::{daohandler, proposal, subscription, vote, PrismaClient, ProposalState};
+
+/// A handler with an active proposal ending within this many seconds is closing.
+const CLOSING_WINDOW: i64 = 60 * 60;
+/// Longest time between refreshes of a closing handler, in seconds.
+const CLOSING_INTERVAL: i64 = 60;
+/// Votes newer than this many seconds count as recent activity.
+const RECENT_VOTES_WINDOW: i64 = 60 * 60;
+/// How much slower than configured a handler with nothing going on is refreshed.
+const IDLE_BACKOFF: f64 = 4.0;
+
+/// What a dao handler has going on, the normal refresh interval of the handler and its
+/// voter handlers is scaled by it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Priority {
+    pub active_proposals: i64,
+    pub next_deadline: Option<DateTime<Utc>>,
+    pub subscribers: i64,
+    pub recent_votes: i64,
+}
+
+impl Priority {
+    /// Active proposals and recent votes make a handler more urgent, so do more
+    /// subscribers. A handler with neither backs off.
+    pub fn weight(&self) -> f64 {
+        if self.active_proposals == 0 && self.recent_votes == 0 {
+            return 1.0 / IDLE_BACKOFF;
+        }
+
+        let mut weight = 1.0;
+
+        if self.active_proposals > 0 {
+            weight *= 2.0;
+        }
+
+        if self.recent_votes > 0 {
+            weight *= 1.5;
+        }
+
+        weight * (1.0 + ((self.subscribers.max(0) + 1) as f64).log10())
+    }
+
+    pub fn closing(&self, now: DateTime<Utc>) -> bool {
+        self.next_deadline
+            .map(|deadline| deadline - now < Duration::seconds(CLOSING_WINDOW))
+            .unwrap_or(false)
+    }
+
+    /// The configured `base` interval in seconds scaled by the weight, and at most
+    /// `CLOSING_INTERVAL` while a proposal is about to end.
+    pub fn interval(&self, base: u32, now: DateTime<Utc>) -> Duration {
+        let mut seconds = (base as f64 / self.weight()).round() as i64;
+
+        if self.closing(now) {
+            seconds = seconds.min(CLOSING_INTERVAL);
+        }
+
+        Duration::seconds(seconds.max(1))
+    }
+}
+
+/// Keyed by dao handler id. Handlers not in here yet run at the configured intervals.
+pub static PRIORITIES: Lazy<RwLock<HashMap<String, Priority>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Cutoff for a `DONE` handler: last refreshed before it, the handler is due again.
+pub fn normal_cutoff(
+    priorities: &HashMap<String, Priority>,
+    dao_handler_id: &str,
+    base: u32,
+    now: DateTime<Utc>,
+) -> DateTime<Utc> {
+    match priorities.get(dao_handler_id) {
+        Some(priority) => now - priority.interval(base, now),
+        None => now - Duration::seconds(base.into()),
+    }
+}
+
+pub fn weight_of(priorities: &HashMap<String, Priority>, dao_handler_id: &str) -> f64 {
+    priorities
+        .get(dao_handler_id)
+        .map(|priority| priority.weight())
+        .unwrap_or(1.0)
+}
+
+#[instrument(skip_all)]
+pub async fn update_priorities(client: &PrismaClient) -> Result<()> {
+    let now = Utc::now();
+    let db_now = now.with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+    let dao_handlers = client
+        .daohandler()
+        .find_many(vec![])
+        .select(daohandler::select!({ id daoid }))
+        .exec()
+        .await?;
+
+    let mut priorities = HashMap::new();
+
+    for dao_handler in dao_handlers {
+        let active = client
+            .proposal()
+            .find_many(vec![
+                proposal::daohandlerid::equals(dao_handler.id.clone()),
+                proposal::state::equals(ProposalState::Active),
+                proposal::timeend::gt(db_now),
+            ])
+            .order_by(proposal::timeend::order(Direction::Asc))
+            .select(proposal::select!({ timeend }))
+            .exec()
+            .await?;
+
+        let subscribers = client
+            .subscription()
+            .count(vec![subscription::daoid::equals(dao_handler.daoid.clone())])
+            .exec()
+            .await?;
+
+        let recent_votes = client
+            .vote()
+            .count(vec![
+                vote::daohandlerid::equals(dao_handler.id.clone()),
+                vote::timecreated::gt(db_now - Duration::seconds(RECENT_VOTES_WINDOW)),
+            ])
+            .exec()
+            .await?;
+
+        priorities.insert(
+            dao_handler.id,
+            Priority {
+                active_proposals: active.len() as i64,
+                next_deadline: active.first().map(|p| p.timeend.with_timezone(&Utc)),
+                subscribers,
+                recent_votes,
+            },
+        );
+    }
+
+    event!(
+        Level::DEBUG,
+        dao_handlers = priorities.len(),
+        closing = priorities.values().filter(|p| p.closing(now)).count(),
+        "updated priorities"
+    );
+
+    *PRIORITIES.write().await = priorities;
+
+    Ok(())
+}
@@ -8,19 +8,22 @@ use tracing::{debug, debug_span, event, instrument, Instrument, Level};
 use prisma::{daohandler, PrismaClient};
 
 use crate::{
-    config::Config,
+    config::ConfigSet,
+    priority::{normal_cutoff, weight_of, PRIORITIES},
     prisma,
-    refresh_status::{DaoHandlerRefreshStatus, DAOS_REFRESH_STATUS},
+    refresh_status::{persist_daos_pending, DAOS_REFRESH_STATUS},
     RefreshEntry,
     RefreshStatus,
     RefreshType,
 };
 
 #[instrument(skip_all)]
-pub async fn produce_chain_proposals_queue(config: &Config) -> Result<Vec<RefreshEntry>> {
-    let normal_refresh = Utc::now() - Duration::seconds(config.normal_chain_proposals.into());
-    let force_refresh = Utc::now() - Duration::seconds(config.force_chain_proposals.into());
-    let new_refresh = Utc::now() - Duration::seconds(config.new_chain_proposals.into());
+pub async fn produce_chain_proposals_queue(
+    client: &PrismaClient,
+    configs: &ConfigSet,
+) -> Result<Vec<RefreshEntry>> {
+    let now = Utc::now();
+    let priorities = PRIORITIES.read().await.clone();
 
     let handler_types = [
         prisma::DaoHandlerType::AaveChain,
@@ -37,35 +40,65 @@ pub async fn produce_chain_proposals_queue(config: &Config) -> Result<Vec<Refres
         prisma::DaoHandlerType::OptimismChain,
         prisma::DaoHandlerType::ArbitrumCoreChain,
         prisma::DaoHandlerType::ArbitrumTreasuryChain,
+        prisma::DaoHandlerType::NounsChain,
+        prisma::DaoHandlerType::AragonTokenVoting,
+        prisma::DaoHandlerType::AragonMultisig,
+        prisma::DaoHandlerType::SafeMultisig,
+        prisma::DaoHandlerType::GenericChain,
     ];
 
-    let mut daos_refresh_status = DAOS_REFRESH_STATUS.lock().await;
+    let daos_refresh_status = DAOS_REFRESH_STATUS.read().await;
 
-    let mut dao_handlers: Vec<_> = daos_refresh_status
-        .iter_mut()
-        .filter(|r| {
-            handler_types.contains(&r.r#type)
-                && ((r.refresh_status == RefreshStatus::DONE && r.last_refresh < normal_refresh)
-                    || (r.refresh_status == RefreshStatus::PENDING
-                        && r.last_refresh < force_refresh)
-                    || (r.refresh_status == RefreshStatus::NEW && r.last_refresh < new_refresh))
-        })
-        .collect();
+    let mut refresh_queue = Vec::new();
 
-    let refresh_queue: Vec<RefreshEntry> = dao_handlers
-        .iter()
-        .map(|dao_handler| RefreshEntry {
-            handler_id: dao_handler.dao_handler_id.clone(),
-            handler_type: dao_handler.r#type,
-            refresh_type: RefreshType::Daochainproposals,
-            voters: vec![],
-        })
-        .collect();
+    for state in daos_refresh_status.values() {
+        let mut dhr = state.lock().await;
+        let config = configs.get(&dhr.dao_handler_id, dhr.r#type);
 
-    for dhr in &mut *dao_handlers {
-        dhr.refresh_status = RefreshStatus::PENDING;
-        dhr.last_refresh = Utc::now();
+        let force_refresh = now - Duration::seconds(config.force_chain_proposals.into());
+        let new_refresh = now - Duration::seconds(config.new_chain_proposals.into());
+
+        if handler_types.contains(&dhr.r#type)
+            && !dhr.paused
+            && ((dhr.refresh_status == RefreshStatus::Done
+                && dhr.last_refresh
+                    < normal_cutoff(
+                        &priorities,
+                        &dhr.dao_handler_id,
+                        config.normal_chain_proposals,
+                        now,
+                    ))
+                || (dhr.refresh_status == RefreshStatus::Pending
+                    && dhr.last_refresh < force_refresh)
+                || (dhr.refresh_status == RefreshStatus::New && dhr.last_refresh < new_refresh))
+        {
+            refresh_queue.push(RefreshEntry {
+                handler_id: dhr.dao_handler_id.clone(),
+                handler_type: dhr.r#type,
+                chain: dhr.chain,
+                refresh_type: RefreshType::Daochainproposals,
+                voters: vec![],
+            });
+
+            dhr.refresh_status = RefreshStatus::Pending;
+            dhr.last_refresh = Utc::now();
+        }
     }
 
+    drop(daos_refresh_status);
+
+    persist_daos_pending(
+        client,
+        refresh_queue
+            .iter()
+            .map(|entry| entry.handler_id.clone())
+            .collect(),
+    )
+    .await;
+
+    refresh_queue.sort_by(|a, b| {
+        weight_of(&priorities, &b.handler_id).total_cmp(&weight_of(&priorities, &a.handler_id))
+    });
+
     Ok(refresh_queue)
 }
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use anyhow::Result;
 use prisma_client_rust::{
     chrono::{Duration, Utc},
@@ -11,9 +9,10 @@ use tracing::{debug, debug_span, event, instrument, Instrument, Level};
 use prisma::{daohandler, PrismaClient};
 
 use crate::{
-    config::Config,
+    config::ConfigSet,
+    priority::{normal_cutoff, weight_of, PRIORITIES},
     prisma::{self, voterhandler},
-    refresh_status::{DAOS_REFRESH_STATUS, VOTERS_REFRESH_STATUS},
+    refresh_status::{dao_state, persist_voters_pending, voters_state, DAOS_REFRESH_STATUS},
     RefreshEntry,
     RefreshStatus,
     RefreshType,
@@ -22,11 +21,10 @@ use crate::{
 #[instrument(skip_all)]
 pub async fn produce_chain_votes_queue(
     client: &PrismaClient,
-    config: &Config,
+    configs: &ConfigSet,
 ) -> Result<Vec<RefreshEntry>> {
-    let normal_refresh = Utc::now() - Duration::seconds(config.normal_chain_votes.into());
-    let force_refresh = Utc::now() - Duration::seconds(config.force_chain_votes.into());
-    let new_refresh = Utc::now() - Duration::seconds(config.new_chain_votes.into());
+    let now = Utc::now();
+    let priorities = PRIORITIES.read().await.clone();
 
     let handler_types = [
         prisma::DaoHandlerType::AaveChain,
@@ -44,142 +42,207 @@ pub async fn produce_chain_votes_queue(
         prisma::DaoHandlerType::OptimismChain,
         prisma::DaoHandlerType::ArbitrumCoreChain,
         prisma::DaoHandlerType::ArbitrumTreasuryChain,
+        prisma::DaoHandlerType::NounsChain,
+        prisma::DaoHandlerType::AragonTokenVoting,
+        prisma::DaoHandlerType::AragonMultisig,
+        prisma::DaoHandlerType::SafeMultisig,
+        prisma::DaoHandlerType::GenericChain,
     ];
 
-    let mut daos_refresh_status = DAOS_REFRESH_STATUS.lock().await;
-    let mut voters_refresh_status = VOTERS_REFRESH_STATUS.lock().await;
+    let mut dao_handlers = Vec::new();
 
-    let dao_handlers: Vec<_> = daos_refresh_status
-        .iter_mut()
-        .filter(|r| handler_types.contains(&r.r#type))
-        .collect();
+    for state in DAOS_REFRESH_STATUS.read().await.values() {
+        let dhr = state.lock().await;
+
+        if handler_types.contains(&dhr.r#type) && !dhr.paused {
+            dao_handlers.push((dhr.dao_handler_id.clone(), dhr.r#type, dhr.chain));
+        }
+    }
 
-    let mut voter_handler_to_refresh = Vec::new();
     let mut refresh_queue = Vec::new();
 
-    for dao_handler in dao_handlers {
-        let mut voter_handlers_r: Vec<_> = voters_refresh_status
-            .iter_mut()
+    for (dao_handler_id, dao_handler_type, chain) in dao_handlers {
+        let Some(voters) = voters_state(&dao_handler_id).await else {
+            continue;
+        };
+
+        let config = configs.get(&dao_handler_id, dao_handler_type);
+
+        let force_refresh = now - Duration::seconds(config.force_chain_votes.into());
+        let new_refresh = now - Duration::seconds(config.new_chain_votes.into());
+
+        let normal_refresh =
+            normal_cutoff(&priorities, &dao_handler_id, config.normal_chain_votes, now);
+
+        let voter_handler_ids: Vec<String> = voters
+            .lock()
+            .await
+            .values_mut()
             .filter(|r| {
-                r.dao_handler_id == dao_handler.dao_handler_id
-                    && ((r.refresh_status == RefreshStatus::DONE
-                        && r.last_refresh < normal_refresh)
-                        || (r.refresh_status == RefreshStatus::PENDING
-                            && r.last_refresh < force_refresh)
-                        || (r.refresh_status == RefreshStatus::NEW && r.last_refresh < new_refresh))
+                (r.refresh_status == RefreshStatus::Done && r.last_refresh < normal_refresh)
+                    || (r.refresh_status == RefreshStatus::Pending
+                        && r.last_refresh < force_refresh)
+                    || (r.refresh_status == RefreshStatus::New && r.last_refresh < new_refresh)
+            })
+            .map(|r| {
+                r.refresh_status = RefreshStatus::Pending;
+                r.last_refresh = Utc::now();
+                r.voter_handler_id.clone()
             })
             .collect();
 
+        if voter_handler_ids.is_empty() {
+            continue;
+        }
+
+        persist_voters_pending(client, voter_handler_ids.clone()).await;
+
         let voter_handlers = client
             .voterhandler()
-            .find_many(vec![voterhandler::id::in_vec(
-                voter_handlers_r
-                    .iter()
-                    .map(|r| r.voter_handler_id.clone())
-                    .collect(),
-            )])
+            .find_many(vec![voterhandler::id::in_vec(voter_handler_ids)])
             .include(voterhandler::include!({
                 voter: select
                 { address }
             }))
             .exec()
-            .await
-            .unwrap();
+            .await?;
 
-        let vote_indexes: Vec<i64> = voter_handlers
-            .iter()
-            .map(|voter_handler| voter_handler.chainindex)
-            .collect();
-
-        let domain_limit = if dao_handler.r#type == prisma::DaoHandlerType::MakerPollArbitrum
-            || dao_handler.r#type == prisma::DaoHandlerType::OptimismChain
-            || dao_handler.r#type == prisma::DaoHandlerType::ArbitrumCoreChain
-            || dao_handler.r#type == prisma::DaoHandlerType::ArbitrumTreasuryChain
-        {
-            200_000_000
+        let head = if dao_handler_type == prisma::DaoHandlerType::MakerPollArbitrum {
+            i64::MAX
         } else {
-            20_000_000
+            client
+                .daohandler()
+                .find_unique(daohandler::id::equals(dao_handler_id.clone()))
+                .exec()
+                .await?
+                .map(|dao_handler| dao_handler.chainindex)
+                .unwrap_or(i64::MAX)
         };
 
-        let vote_indexes_buckets = bin(vote_indexes, 0, domain_limit, 10);
-
-        let items: Vec<RefreshEntry> = vote_indexes_buckets
-            .iter()
-            .filter_map(|bucket| {
-                let bucket_vh: Vec<_> = voter_handlers
-                    .iter()
-                    .filter(|&voter_handler| {
-                        let index = voter_handler.chainindex;
-                        bucket.min <= index && index < bucket.max
-                    })
-                    .take(config.batch_chain_votes.try_into().unwrap())
-                    .cloned()
-                    .collect();
-
-                voter_handler_to_refresh.extend(bucket_vh.clone());
-
-                if bucket_vh.is_empty() {
-                    None
-                } else {
-                    Some(RefreshEntry {
-                        handler_id: dao_handler.dao_handler_id.clone(),
-                        refresh_type: RefreshType::Daochainvotes,
-                        handler_type: dao_handler.r#type,
-                        voters: bucket_vh
-                            .iter()
-                            .map(|vhandler| vhandler.voter.address.clone())
-                            .collect(),
-                    })
-                }
-            })
-            .collect();
+        let range = match dao_state(&dao_handler_id).await {
+            Some(state) => state.lock().await.votersrefreshspeed,
+            None => config.min_chain_votes_speed,
+        };
 
-        for vhr in &mut *voter_handlers_r {
-            vhr.refresh_status = RefreshStatus::PENDING;
-            vhr.last_refresh = Utc::now();
-        }
+        let items: Vec<RefreshEntry> = batches(
+            voter_handlers
+                .into_iter()
+                .map(|voter_handler| (voter_handler.chainindex, voter_handler.voter.address))
+                .collect(),
+            head,
+            range,
+            config.batch_chain_votes.try_into()?,
+        )
+        .into_iter()
+        .map(|voters| RefreshEntry {
+            handler_id: dao_handler_id.clone(),
+            refresh_type: RefreshType::Daochainvotes,
+            handler_type: dao_handler_type,
+            chain,
+            voters,
+        })
+        .collect();
 
         refresh_queue.extend(items)
     }
 
+    refresh_queue.sort_by(|a, b| {
+        weight_of(&priorities, &b.handler_id).total_cmp(&weight_of(&priorities, &a.handler_id))
+    });
+
     Ok(refresh_queue)
 }
 
-#[derive(Debug)]
-struct Bucket {
-    min: i64,
-    max: i64,
+/// Groups voters, by chain index and address, into the batches of one detective call
+/// each. Detective starts no later than the dao handler's `head`, so voters past it
+/// count as at `head`. A call reads `range` blocks once for all of its voters, a voter
+/// joins a batch while its index is within `range` of the lowest one in the batch.
+fn batches(
+    mut voters: Vec<(i64, String)>,
+    head: i64,
+    range: i64,
+    batch_size: usize,
+) -> Vec<Vec<String>> {
+    for (index, _) in voters.iter_mut() {
+        *index = (*index).min(head);
+    }
+
+    voters.sort();
+
+    let mut batches: Vec<Vec<String>> = Vec::new();
+    let mut start = 0;
+
+    for (index, address) in voters {
+        match batches.last_mut() {
+            Some(batch) if batch.len() < batch_size && index - start <= range => {
+                batch.push(address)
+            }
+            _ => {
+                start = index;
+                batches.push(vec![address]);
+            }
+        }
+    }
+
+    batches
 }
 
-fn bin(data: Vec<i64>, min: i64, max: i64, num_bins: i64) -> Vec<Bucket> {
-    let bin_width = ((max - min) as f64) / (num_bins as f64);
-    let mut bins: Vec<Bucket> = Vec::with_capacity(num_bins.try_into().unwrap());
+#[cfg(test)]
+mod tests {
+    use super::batches;
+
+    fn voters(indexes: &[i64]) -> Vec<(i64, String)> {
+        indexes
+            .iter()
+            .enumerate()
+            .map(|(i, index)| (*index, format!("voter-{}", i)))
+            .collect()
+    }
 
-    for i in 0..num_bins {
-        bins.push(Bucket {
-            min: min + (((i as f64) * bin_width).round() as i64),
-            max: min + ((((i + 1) as f64) * bin_width).round() as i64),
-        });
+    fn sizes(batches: Vec<Vec<String>>) -> Vec<usize> {
+        batches.iter().map(Vec::len).collect()
     }
 
-    let mut data_counts: HashMap<usize, u64> = HashMap::new();
+    #[test]
+    fn new_voters_at_zero_fill_batches() {
+        assert_eq!(
+            sizes(batches(voters(&[0, 0, 0, 0, 0]), 1_000_000, 1000, 2)),
+            vec![2, 2, 1]
+        );
+    }
 
-    for value in data {
-        let bin_index = (((value - min) as f64) / bin_width).floor() as usize;
-        let count = data_counts.entry(bin_index).or_insert(0);
-        *count += 1;
+    #[test]
+    fn new_voters_are_not_batched_with_caught_up_ones() {
+        assert_eq!(
+            batches(voters(&[0, 999_990, 0]), 1_000_000, 1000, 100),
+            vec![
+                vec!["voter-0".to_string(), "voter-2".to_string()],
+                vec!["voter-1".to_string()],
+            ]
+        );
     }
 
-    let mut filled_bins: Vec<Bucket> = Vec::with_capacity(num_bins.try_into().unwrap());
+    #[test]
+    fn voters_past_head_count_as_head() {
+        assert_eq!(
+            sizes(batches(voters(&[995, 1200, 50_000_000]), 1000, 100, 10)),
+            vec![3]
+        );
+    }
 
-    for (bin_index, count) in data_counts.iter() {
-        if *count > 0 {
-            filled_bins.push(Bucket {
-                min: bins[*bin_index].min,
-                max: bins[*bin_index].max,
-            });
-        }
+    #[test]
+    fn batches_reach_one_range_from_their_lowest_index() {
+        assert_eq!(
+            batches(voters(&[1200, 0, 1000]), i64::MAX, 1000, 10),
+            vec![
+                vec!["voter-1".to_string(), "voter-2".to_string()],
+                vec!["voter-0".to_string()],
+            ]
+        );
     }
 
-    filled_bins
+    #[test]
+    fn no_voters_no_batches() {
+        assert!(batches(vec![], 1000, 1000, 10).is_empty());
+    }
 }
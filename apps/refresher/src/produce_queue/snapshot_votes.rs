@@ -12,9 +12,10 @@ use crate::RefreshStatus;
 use prisma::{daohandler, PrismaClient};
 
 use crate::{
-    config::Config,
+    config::ConfigSet,
+    priority::{normal_cutoff, weight_of, PRIORITIES},
     prisma::{self, voterhandler},
-    refresh_status::{DAOS_REFRESH_STATUS, VOTERS_REFRESH_STATUS},
+    refresh_status::{persist_voters_pending, voters_state, DAOS_REFRESH_STATUS},
     RefreshEntry,
     RefreshType,
 };
@@ -22,46 +23,69 @@ use crate::{
 #[instrument(skip_all)]
 pub async fn produce_snapshot_votes_queue(
     client: &PrismaClient,
-    config: &Config,
+    configs: &ConfigSet,
 ) -> Result<Vec<RefreshEntry>> {
-    let normal_refresh = Utc::now() - Duration::seconds(config.normal_snapshot_votes.into());
-    let force_refresh = Utc::now() - Duration::seconds(config.force_snapshot_votes.into());
-    let new_refresh = Utc::now() - Duration::seconds(config.new_snapshot_votes.into());
+    let now = Utc::now();
+    let priorities = PRIORITIES.read().await.clone();
 
     let handler_types = [prisma::DaoHandlerType::Snapshot];
 
-    let mut daos_refresh_status = DAOS_REFRESH_STATUS.lock().await;
-    let mut voters_refresh_status = VOTERS_REFRESH_STATUS.lock().await;
+    let mut dao_handlers = Vec::new();
 
-    let dao_handlers: Vec<_> = daos_refresh_status
-        .iter_mut()
-        .filter(|r| handler_types.contains(&r.r#type))
-        .collect();
+    for state in DAOS_REFRESH_STATUS.read().await.values() {
+        let dhr = state.lock().await;
+
+        if handler_types.contains(&dhr.r#type) && !dhr.paused {
+            dao_handlers.push((dhr.dao_handler_id.clone(), dhr.r#type, dhr.chain));
+        }
+    }
 
     let mut voter_handler_to_refresh = Vec::new();
     let mut refresh_queue = Vec::new();
 
-    for dao_handler in dao_handlers {
-        let mut voter_handlers_r: Vec<_> = voters_refresh_status
-            .iter_mut()
+    for (dao_handler_id, dao_handler_type, chain) in dao_handlers {
+        let Some(voters) = voters_state(&dao_handler_id).await else {
+            continue;
+        };
+
+        let config = configs.get(&dao_handler_id, dao_handler_type);
+
+        let force_refresh = now - Duration::seconds(config.force_snapshot_votes.into());
+        let new_refresh = now - Duration::seconds(config.new_snapshot_votes.into());
+
+        let normal_refresh = normal_cutoff(
+            &priorities,
+            &dao_handler_id,
+            config.normal_snapshot_votes,
+            now,
+        );
+
+        let voter_handler_ids: Vec<String> = voters
+            .lock()
+            .await
+            .values_mut()
             .filter(|r| {
-                r.dao_handler_id == dao_handler.dao_handler_id
-                    && ((r.refresh_status == RefreshStatus::DONE
-                        && r.last_refresh < normal_refresh)
-                        || (r.refresh_status == RefreshStatus::PENDING
-                            && r.last_refresh < force_refresh)
-                        || (r.refresh_status == RefreshStatus::NEW && r.last_refresh < new_refresh))
+                (r.refresh_status == RefreshStatus::Done && r.last_refresh < normal_refresh)
+                    || (r.refresh_status == RefreshStatus::Pending
+                        && r.last_refresh < force_refresh)
+                    || (r.refresh_status == RefreshStatus::New && r.last_refresh < new_refresh)
+            })
+            .map(|r| {
+                r.refresh_status = RefreshStatus::Pending;
+                r.last_refresh = Utc::now();
+                r.voter_handler_id.clone()
             })
             .collect();
 
+        if voter_handler_ids.is_empty() {
+            continue;
+        }
+
+        persist_voters_pending(client, voter_handler_ids.clone()).await;
+
         let voter_handlers = client
             .voterhandler()
-            .find_many(vec![voterhandler::id::in_vec(
-                voter_handlers_r
-                    .iter()
-                    .map(|r| r.voter_handler_id.clone())
-                    .collect(),
-            )])
+            .find_many(vec![voterhandler::id::in_vec(voter_handler_ids)])
             .include(voterhandler::include!({
                 voter: select
                 { address }
@@ -96,8 +120,9 @@ pub async fn produce_snapshot_votes_queue(
                     None
                 } else {
                     Some(RefreshEntry {
-                        handler_id: dao_handler.dao_handler_id.clone(),
-                        handler_type: dao_handler.r#type,
+                        handler_id: dao_handler_id.clone(),
+                        handler_type: dao_handler_type,
+                        chain,
                         refresh_type: RefreshType::Daosnapshotvotes,
                         voters: bucket_vh
                             .iter()
@@ -108,14 +133,13 @@ pub async fn produce_snapshot_votes_queue(
             })
             .collect();
 
-        for vhr in &mut *voter_handlers_r {
-            vhr.refresh_status = RefreshStatus::PENDING;
-            vhr.last_refresh = Utc::now();
-        }
-
         refresh_queue.extend(items)
     }
 
+    refresh_queue.sort_by(|a, b| {
+        weight_of(&priorities, &b.handler_id).total_cmp(&weight_of(&priorities, &a.handler_id))
+    });
+
     Ok(refresh_queue)
 }
 
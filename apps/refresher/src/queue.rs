@@ -0,0 +1,456 @@
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    sync::Arc,
+    time::Duration as StdDuration,
+};
+
+use anyhow::{anyhow, Result};
+use metrics::{counter, increment_counter};
+use once_cell::sync::Lazy;
+use prisma_client_rust::{
+    chrono::{DateTime, Duration, FixedOffset, Utc},
+    operator::{and, or},
+    Direction,
+};
+use tokio::{
+    sync::Semaphore,
+    time::{sleep, timeout},
+};
+use tracing::{event, instrument, Level};
+
+use crate::{
+    consume_queue::{
+        chain_proposals::consume_chain_proposals,
+        chain_votes::consume_chain_votes,
+        snapshot_proposals::consume_snapshot_proposals,
+        snapshot_votes::consume_snapshot_votes,
+    },
+    priority::{weight_of, PRIORITIES},
+    prisma::{refresherlease, refreshjob, PrismaClient, RefreshJobStatus},
+    refresh_status::{dao_state, db_time, release},
+    RefreshEntry,
+    RefreshType,
+};
+
+/// Seconds a leased job stays invisible to other workers. A refresher that dies with the
+/// lease leaves the job to whoever leases it after that.
+const VISIBILITY_TIMEOUT: i64 = 10 * 60;
+/// How long a leased job may run, admission and permit waits included. Well inside the
+/// visibility timeout, a job is given up before another refresher can lease it again.
+const JOB_TIMEOUT: StdDuration = StdDuration::from_secs(VISIBILITY_TIMEOUT as u64 / 2);
+/// Leases after which a failing job is dead lettered instead of retried.
+const MAX_ATTEMPTS: i32 = 5;
+/// Seconds before the first retry, doubled on every attempt up to `MAX_BACKOFF`.
+const BASE_BACKOFF: i64 = 10;
+const MAX_BACKOFF: i64 = 10 * 60;
+/// Jobs one refresher runs at once.
+const MAX_IN_FLIGHT: usize = 200;
+
+/// Producer lease row and the seconds it lasts without a renewal.
+const PRODUCER: &str = "producer";
+const PRODUCER_LEASE: i64 = 30;
+
+/// Who holds a lease, `$REFRESHER_INSTANCE` or the host and pid.
+pub static INSTANCE: Lazy<String> = Lazy::new(|| {
+    env::var("REFRESHER_INSTANCE").unwrap_or_else(|_| {
+        format!(
+            "{}-{}",
+            env::var("HOSTNAME").unwrap_or_else(|_| "refresher".to_string()),
+            std::process::id()
+        )
+    })
+});
+
+static IN_FLIGHT: Lazy<Arc<Semaphore>> = Lazy::new(|| Arc::new(Semaphore::new(MAX_IN_FLIGHT)));
+
+pub fn backoff(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+
+    Duration::seconds((BASE_BACKOFF * 2i64.pow(exponent)).min(MAX_BACKOFF))
+}
+
+/// Queued jobs that are due and leased jobs whose lease ran out.
+fn leasable(now: DateTime<FixedOffset>) -> refreshjob::WhereParam {
+    or(vec![
+        and(vec![
+            refreshjob::status::equals(RefreshJobStatus::Queued),
+            refreshjob::runafter::lte(now),
+        ]),
+        and(vec![
+            refreshjob::status::equals(RefreshJobStatus::Leased),
+            refreshjob::leaseduntil::lt(now),
+        ]),
+    ])
+}
+
+/// Takes or renews the producer lease. Only the refresher holding it produces, the
+/// others just run jobs, and a producer that stops hands over once its lease ran out.
+#[instrument(skip_all)]
+pub async fn lead(client: &PrismaClient) -> Result<bool> {
+    let now = db_time(Utc::now());
+    let until = now + Duration::seconds(PRODUCER_LEASE);
+
+    let renewed = client
+        .refresherlease()
+        .update_many(
+            vec![
+                refresherlease::id::equals(PRODUCER.to_string()),
+                or(vec![
+                    refresherlease::instance::equals(INSTANCE.clone()),
+                    refresherlease::leaseduntil::lt(now),
+                ]),
+            ],
+            vec![
+                refresherlease::instance::set(INSTANCE.clone()),
+                refresherlease::leaseduntil::set(until),
+            ],
+        )
+        .exec()
+        .await?;
+
+    if renewed == 1 {
+        return Ok(true);
+    }
+
+    // The first refresher to start creates the lease, the others lose on the key.
+    let created = client
+        .refresherlease()
+        .create(PRODUCER.to_string(), INSTANCE.clone(), until, vec![])
+        .exec()
+        .await;
+
+    Ok(created.is_ok())
+}
+
+/// Queues the refreshes that are not covered by a queued or leased job yet: a handler
+/// has one live proposals job and a voter is in one live votes job at a time. The check
+/// and the insert share a transaction.
+#[instrument(skip_all)]
+pub async fn enqueue(client: &PrismaClient, entries: Vec<RefreshEntry>) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let priorities = PRIORITIES.read().await.clone();
+
+    let skipped = client
+        ._transaction()
+        .run(|tx| async move {
+            let handler_ids: Vec<String> = entries
+                .iter()
+                .map(|entry| entry.handler_id.clone())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+
+            let live_jobs = tx
+                .refreshjob()
+                .find_many(vec![
+                    refreshjob::daohandlerid::in_vec(handler_ids),
+                    refreshjob::status::in_vec(vec![
+                        RefreshJobStatus::Queued,
+                        RefreshJobStatus::Leased,
+                    ]),
+                ])
+                .exec()
+                .await?;
+
+            let mut live: HashMap<(String, RefreshType), HashSet<String>> = HashMap::new();
+
+            for job in live_jobs {
+                let voters: Vec<String> = serde_json::from_value(job.voters)?;
+
+                live.entry((job.daohandlerid, job.r#type))
+                    .or_default()
+                    .extend(voters);
+            }
+
+            let mut jobs = Vec::new();
+            let mut skipped: u64 = 0;
+
+            for mut entry in entries {
+                if let Some(voters) = live.get(&(entry.handler_id.clone(), entry.refresh_type)) {
+                    match entry.refresh_type {
+                        RefreshType::Daochainproposals | RefreshType::Daosnapshotproposals => {
+                            skipped += 1;
+                            continue;
+                        }
+                        RefreshType::Daochainvotes | RefreshType::Daosnapshotvotes => {
+                            entry.voters.retain(|voter| !voters.contains(voter));
+
+                            if entry.voters.is_empty() {
+                                skipped += 1;
+                                continue;
+                            }
+                        }
+                    }
+                }
+
+                jobs.push(refreshjob::create_unchecked(
+                    entry.refresh_type,
+                    entry.handler_id.clone(),
+                    serde_json::to_value(&entry.voters)?,
+                    vec![refreshjob::priority::set(weight_of(
+                        &priorities,
+                        &entry.handler_id,
+                    ))],
+                ));
+            }
+
+            tx.refreshjob().create_many(jobs).exec().await?;
+
+            Ok::<_, anyhow::Error>(skipped)
+        })
+        .await?;
+
+    if skipped > 0 {
+        counter!("refresher_jobs_skipped", skipped);
+        event!(
+            Level::DEBUG,
+            skipped = skipped,
+            "skipped refreshes with a live job"
+        );
+    }
+
+    Ok(())
+}
+
+/// Leases up to `limit` jobs, most urgent first. Each job is claimed with a conditional
+/// update, when another refresher got there first the update matches nothing and the
+/// job is skipped.
+#[instrument(skip_all)]
+pub async fn lease(client: &PrismaClient, limit: i64) -> Result<Vec<refreshjob::Data>> {
+    let now = db_time(Utc::now());
+
+    let candidates = client
+        .refreshjob()
+        .find_many(vec![leasable(now)])
+        .order_by(refreshjob::priority::order(Direction::Desc))
+        .order_by(refreshjob::runafter::order(Direction::Asc))
+        .take(limit)
+        .exec()
+        .await?;
+
+    let mut leased = Vec::new();
+
+    for job in candidates {
+        let claimed = client
+            .refreshjob()
+            .update_many(
+                vec![refreshjob::id::equals(job.id.clone()), leasable(now)],
+                vec![
+                    refreshjob::status::set(RefreshJobStatus::Leased),
+                    refreshjob::leasedby::set(Some(INSTANCE.clone())),
+                    refreshjob::leaseduntil::set(Some(now + Duration::seconds(VISIBILITY_TIMEOUT))),
+                    refreshjob::attempts::increment(1),
+                ],
+            )
+            .exec()
+            .await?;
+
+        if claimed == 1 {
+            leased.push(refreshjob::Data {
+                attempts: job.attempts + 1,
+                ..job
+            });
+        }
+    }
+
+    Ok(leased)
+}
+
+async fn entry(job: &refreshjob::Data) -> Result<RefreshEntry> {
+    let state = dao_state(&job.daohandlerid)
+        .await
+        .ok_or_else(|| anyhow!("dao handler has no refresh status"))?;
+
+    let (handler_type, chain) = {
+        let state = state.lock().await;
+        (state.r#type, state.chain)
+    };
+
+    Ok(RefreshEntry {
+        handler_id: job.daohandlerid.clone(),
+        handler_type,
+        chain,
+        refresh_type: job.r#type,
+        voters: serde_json::from_value(job.voters.clone())?,
+    })
+}
+
+/// Runs a leased job and acks it: done jobs are deleted, failed ones go back to the
+/// queue with a backoff or, out of attempts, to the dead letters.
+#[instrument(skip_all, fields(job = job.id, attempts = job.attempts))]
+pub async fn run(client: Arc<PrismaClient>, job: refreshjob::Data) {
+    let result = match entry(&job).await {
+        Ok(entry) => {
+            let consume = async {
+                match entry.refresh_type {
+                    RefreshType::Daochainproposals => {
+                        consume_chain_proposals(client.clone(), entry.clone()).await
+                    }
+                    RefreshType::Daosnapshotproposals => {
+                        consume_snapshot_proposals(client.clone(), entry.clone()).await
+                    }
+                    RefreshType::Daochainvotes => {
+                        consume_chain_votes(client.clone(), entry.clone()).await
+                    }
+                    RefreshType::Daosnapshotvotes => {
+                        consume_snapshot_votes(client.clone(), entry.clone()).await
+                    }
+                }
+            };
+
+            let result = match timeout(JOB_TIMEOUT, consume).await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow!(
+                    "refresh timed out after {}s",
+                    JOB_TIMEOUT.as_secs()
+                )),
+            };
+
+            result.map_err(|e| (e, Some(entry)))
+        }
+        Err(e) => Err((e, None)),
+    };
+
+    let acked = match result {
+        Ok(()) => complete(&client, &job).await,
+        Err((e, entry)) => fail(&client, &job, entry, e).await,
+    };
+
+    if let Err(e) = acked {
+        event!(
+            Level::ERROR,
+            job = job.id,
+            err = e.to_string(),
+            "failed to ack job"
+        );
+    }
+}
+
+/// The job as this refresher leased it. The attempts go up on every lease, so a job
+/// that was leased again after our lease ran out no longer matches, even by us.
+fn held(job: &refreshjob::Data) -> Vec<refreshjob::WhereParam> {
+    vec![
+        refreshjob::id::equals(job.id.clone()),
+        refreshjob::status::equals(RefreshJobStatus::Leased),
+        refreshjob::leasedby::equals(Some(INSTANCE.clone())),
+        refreshjob::attempts::equals(job.attempts),
+    ]
+}
+
+/// The lease ran out while the job ran and another lease owns it now, that one acks it.
+fn lease_lost(job: &refreshjob::Data) {
+    increment_counter!("refresher_jobs_lease_lost");
+    event!(
+        Level::WARN,
+        job = job.id,
+        daohandler = job.daohandlerid,
+        "lease lost before ack"
+    );
+}
+
+async fn complete(client: &PrismaClient, job: &refreshjob::Data) -> Result<()> {
+    let deleted = client.refreshjob().delete_many(held(job)).exec().await?;
+
+    if deleted == 0 {
+        lease_lost(job);
+    }
+
+    Ok(())
+}
+
+async fn fail(
+    client: &PrismaClient,
+    job: &refreshjob::Data,
+    entry: Option<RefreshEntry>,
+    err: anyhow::Error,
+) -> Result<()> {
+    if job.attempts >= MAX_ATTEMPTS {
+        let updated = client
+            .refreshjob()
+            .update_many(
+                held(job),
+                vec![
+                    refreshjob::status::set(RefreshJobStatus::Dead),
+                    refreshjob::leasedby::set(None),
+                    refreshjob::leaseduntil::set(None),
+                    refreshjob::lasterror::set(Some(err.to_string())),
+                ],
+            )
+            .exec()
+            .await?;
+
+        if updated == 0 {
+            lease_lost(job);
+            return Ok(());
+        }
+
+        if let Some(entry) = entry {
+            release(client, &entry).await;
+        }
+
+        increment_counter!("refresher_jobs_dead");
+        event!(
+            Level::ERROR,
+            job = job.id,
+            daohandler = job.daohandlerid,
+            err = err.to_string(),
+            "job dead lettered"
+        );
+    } else {
+        let updated = client
+            .refreshjob()
+            .update_many(
+                held(job),
+                vec![
+                    refreshjob::status::set(RefreshJobStatus::Queued),
+                    refreshjob::runafter::set(db_time(Utc::now() + backoff(job.attempts))),
+                    refreshjob::leasedby::set(None),
+                    refreshjob::leaseduntil::set(None),
+                    refreshjob::lasterror::set(Some(err.to_string())),
+                ],
+            )
+            .exec()
+            .await?;
+
+        if updated == 0 {
+            lease_lost(job);
+            return Ok(());
+        }
+
+        increment_counter!("refresher_jobs_retried");
+    }
+
+    Ok(())
+}
+
+/// Leases jobs whenever this refresher has room for more and runs each on its own task.
+pub async fn work(client: Arc<PrismaClient>) {
+    loop {
+        let available = IN_FLIGHT.available_permits();
+
+        if available > 0 {
+            match lease(&client, available as i64).await {
+                Ok(jobs) => {
+                    for job in jobs {
+                        let permit = IN_FLIGHT.clone().acquire_owned().await.unwrap();
+                        let client = client.clone();
+
+                        tokio::spawn(async move {
+                            run(client, job).await;
+                            drop(permit);
+                        });
+                    }
+                }
+                Err(e) => {
+                    event!(Level::WARN, err = e.to_string(), "failed to lease jobs");
+                }
+            }
+        }
+
+        sleep(StdDuration::from_millis(500)).await;
+    }
+}
@@ -1,13 +1,29 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
-use chrono::{DateTime, Utc};
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
 use once_cell::sync::Lazy;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{event, instrument, Level};
 
 use crate::{
-    prisma::{self, dao, voterhandler, DaoHandlerType, PrismaClient},
+    concurrency::Chain,
+    prisma::{
+        self,
+        daohandler,
+        refreshjob,
+        voter,
+        voterhandler,
+        DaoHandlerType,
+        PrismaClient,
+        RefreshJobStatus,
+    },
+    RefreshEntry,
     RefreshStatus,
+    RefreshType,
 };
 
 #[derive(Debug, Clone)]
@@ -16,8 +32,11 @@ pub struct DaoHandlerRefreshStatus {
     pub refresh_status: RefreshStatus,
     pub last_refresh: DateTime<Utc>,
     pub r#type: prisma::DaoHandlerType,
+    pub chain: Chain,
     pub refreshspeed: i64,
     pub votersrefreshspeed: i64,
+    pub failures: i32,
+    pub paused: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -27,13 +46,72 @@ pub struct VoterHandlerRefreshStatus {
     pub voter_handler_id: String,
     pub refresh_status: RefreshStatus,
     pub last_refresh: DateTime<Utc>,
+    pub failures: i32,
+}
+
+pub type DaoState = Arc<Mutex<DaoHandlerRefreshStatus>>;
+
+/// Voter handlers of one dao handler, by voter address.
+pub type VotersState = Arc<Mutex<HashMap<String, VoterHandlerRefreshStatus>>>;
+
+/// In memory view of the scheduling state on `daohandler` and `voterhandler`. Every
+/// change is written back, a restart picks up where the last run stopped.
+///
+/// Each dao handler has its own locks, the maps are only write locked to add handlers.
+/// Consumers lock their handler to read the speed and again to record the result, never
+/// across the detective call.
+pub static DAOS_REFRESH_STATUS: Lazy<RwLock<HashMap<String, DaoState>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Keyed by dao handler id.
+pub static VOTERS_REFRESH_STATUS: Lazy<RwLock<HashMap<String, VotersState>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+pub async fn dao_state(dao_handler_id: &str) -> Option<DaoState> {
+    DAOS_REFRESH_STATUS
+        .read()
+        .await
+        .get(dao_handler_id)
+        .cloned()
 }
 
-pub static DAOS_REFRESH_STATUS: Lazy<Arc<Mutex<Vec<DaoHandlerRefreshStatus>>>> =
-    Lazy::new(|| Arc::new(Mutex::new(Vec::new())));
+pub async fn voters_state(dao_handler_id: &str) -> Option<VotersState> {
+    VOTERS_REFRESH_STATUS
+        .read()
+        .await
+        .get(dao_handler_id)
+        .cloned()
+}
 
-pub static VOTERS_REFRESH_STATUS: Lazy<Arc<Mutex<Vec<VoterHandlerRefreshStatus>>>> =
-    Lazy::new(|| Arc::new(Mutex::new(Vec::new())));
+pub fn default_refreshspeed(handler_type: DaoHandlerType) -> i64 {
+    if handler_type == DaoHandlerType::Snapshot {
+        1000
+    } else {
+        1000000
+    }
+}
+
+pub fn default_votersrefreshspeed(handler_type: DaoHandlerType) -> i64 {
+    if handler_type == DaoHandlerType::Snapshot {
+        1000
+    } else {
+        10000000000
+    }
+}
+
+/// A refresh that was in flight when the last run stopped never reported back. It is
+/// treated as done at the time it was sent, so it comes up again after the normal
+/// interval instead of all at once on startup.
+fn loaded_status(status: RefreshStatus) -> RefreshStatus {
+    match status {
+        RefreshStatus::Pending => RefreshStatus::Done,
+        status => status,
+    }
+}
+
+pub(crate) fn db_time(time: DateTime<Utc>) -> DateTime<FixedOffset> {
+    time.with_timezone(&FixedOffset::east_opt(0).unwrap())
+}
 
 pub async fn create_refresh_statuses(client: &PrismaClient) {
     create_daos_refresh_statuses(client).await;
@@ -43,7 +121,7 @@ pub async fn create_refresh_statuses(client: &PrismaClient) {
 #[instrument(skip_all)]
 pub async fn create_daos_refresh_statuses(client: &PrismaClient) {
     let dao_handlers_count = client.daohandler().count(vec![]).exec().await.unwrap();
-    let mut daos_refresh_status = DAOS_REFRESH_STATUS.lock().await;
+    let mut daos_refresh_status = DAOS_REFRESH_STATUS.write().await;
 
     if dao_handlers_count <= daos_refresh_status.len().try_into().unwrap() {
         return;
@@ -51,40 +129,55 @@ pub async fn create_daos_refresh_statuses(client: &PrismaClient) {
 
     let dao_handlers = client.daohandler().find_many(vec![]).exec().await.unwrap();
 
+    let mut loaded = Vec::new();
+
     dao_handlers.iter().for_each(|daohandler| {
-        if !daos_refresh_status
-            .iter()
-            .any(|refresh_status| refresh_status.dao_handler_id == daohandler.id)
-        {
+        if !daos_refresh_status.contains_key(&daohandler.id) {
             let item = DaoHandlerRefreshStatus {
                 dao_handler_id: daohandler.clone().id,
-                refresh_status: RefreshStatus::NEW,
-                last_refresh: Utc::now(),
+                refresh_status: loaded_status(daohandler.refreshstatus),
+                last_refresh: daohandler.lastrefresh.with_timezone(&Utc),
                 r#type: daohandler.clone().r#type,
-                refreshspeed: if daohandler.r#type == DaoHandlerType::Snapshot {
-                    1000
-                } else {
-                    1000000
-                },
-                votersrefreshspeed: if daohandler.r#type == DaoHandlerType::Snapshot {
-                    1000
-                } else {
-                    10000000000
-                },
+                chain: Chain::of(daohandler.r#type, &daohandler.decoder),
+                refreshspeed: daohandler
+                    .refreshspeed
+                    .unwrap_or(default_refreshspeed(daohandler.r#type)),
+                votersrefreshspeed: daohandler
+                    .votersrefreshspeed
+                    .unwrap_or(default_votersrefreshspeed(daohandler.r#type)),
+                failures: daohandler.refreshfailures,
+                paused: daohandler.refreshpaused,
             };
 
-            daos_refresh_status.push(item);
+            loaded.push((item.refresh_status, item.failures));
+            daos_refresh_status.insert(item.dao_handler_id.clone(), Arc::new(Mutex::new(item)));
         }
     });
+
+    event!(
+        Level::INFO,
+        dao_handlers = loaded.len(),
+        new = loaded
+            .iter()
+            .filter(|(status, _)| *status == RefreshStatus::New)
+            .count(),
+        failing = loaded.iter().filter(|(_, failures)| *failures > 0).count(),
+        "loaded dao handler refresh statuses"
+    );
 }
 
 #[instrument(skip_all)]
 pub async fn create_voters_refresh_statuses(client: &PrismaClient) {
     let voter_handlers_count = client.voterhandler().count(vec![]).exec().await.unwrap();
 
-    let mut voters_refresh_status = VOTERS_REFRESH_STATUS.lock().await;
+    let mut voters_refresh_status = VOTERS_REFRESH_STATUS.write().await;
+
+    let mut known = 0;
+    for voters in voters_refresh_status.values() {
+        known += voters.lock().await.len();
+    }
 
-    if voter_handlers_count <= voters_refresh_status.len().try_into().unwrap() {
+    if voter_handlers_count == known as i64 {
         return;
     }
 
@@ -96,21 +189,398 @@ pub async fn create_voters_refresh_statuses(client: &PrismaClient) {
         .await;
 
     if let Ok(voter_handlers) = voter_handlers {
-        voter_handlers.iter().for_each(|voterhandler| {
-            if !voters_refresh_status
-                .iter()
-                .any(|refresh_status| refresh_status.voter_handler_id == voterhandler.id)
-            {
+        let ids: HashSet<&str> = voter_handlers.iter().map(|vh| vh.id.as_str()).collect();
+
+        for voters in voters_refresh_status.values() {
+            voters
+                .lock()
+                .await
+                .retain(|_, vh| ids.contains(vh.voter_handler_id.as_str()));
+        }
+
+        for voterhandler in voter_handlers {
+            let voters = voters_refresh_status
+                .entry(voterhandler.daohandlerid.clone())
+                .or_default()
+                .clone();
+            let mut voters = voters.lock().await;
+
+            if !voters.contains_key(&voterhandler.voter.address) {
                 let item = VoterHandlerRefreshStatus {
                     voter_handler_id: voterhandler.clone().id,
-                    refresh_status: RefreshStatus::NEW,
-                    last_refresh: Utc::now(),
+                    refresh_status: loaded_status(voterhandler.refreshstatus),
+                    last_refresh: voterhandler.lastrefresh.with_timezone(&Utc),
                     dao_handler_id: voterhandler.clone().daohandlerid,
                     voter_address: voterhandler.clone().voter.address,
+                    failures: voterhandler.refreshfailures,
                 };
 
-                voters_refresh_status.push(item);
+                voters.insert(item.voter_address.clone(), item);
             }
-        })
+        }
     }
 }
+
+/// Stops scheduling removed voter handlers.
+pub async fn forget_voter_handlers(voter_handler_ids: &[String]) {
+    if voter_handler_ids.is_empty() {
+        return;
+    }
+
+    let ids: HashSet<&str> = voter_handler_ids.iter().map(String::as_str).collect();
+
+    for voters in VOTERS_REFRESH_STATUS.read().await.values() {
+        voters
+            .lock()
+            .await
+            .retain(|_, vh| !ids.contains(vh.voter_handler_id.as_str()));
+    }
+}
+
+/// Writes the scheduling state of a dao handler back to its row.
+#[instrument(skip_all)]
+pub async fn persist_dao_status(client: &PrismaClient, status: &DaoHandlerRefreshStatus) {
+    let result = client
+        .daohandler()
+        .update(
+            daohandler::id::equals(status.dao_handler_id.clone()),
+            vec![
+                daohandler::refreshstatus::set(status.refresh_status),
+                daohandler::lastrefresh::set(db_time(status.last_refresh)),
+                daohandler::refreshspeed::set(Some(status.refreshspeed)),
+                daohandler::votersrefreshspeed::set(Some(status.votersrefreshspeed)),
+                daohandler::refreshfailures::set(status.failures),
+            ],
+        )
+        .exec()
+        .await;
+
+    if let Err(e) = result {
+        event!(
+            Level::WARN,
+            daohandler = status.dao_handler_id,
+            err = e.to_string(),
+            "failed to persist refresh status"
+        );
+    }
+}
+
+/// Marks dao handlers as sent to detective.
+#[instrument(skip_all)]
+pub async fn persist_daos_pending(client: &PrismaClient, dao_handler_ids: Vec<String>) {
+    if dao_handler_ids.is_empty() {
+        return;
+    }
+
+    let result = client
+        .daohandler()
+        .update_many(
+            vec![daohandler::id::in_vec(dao_handler_ids)],
+            vec![
+                daohandler::refreshstatus::set(RefreshStatus::Pending),
+                daohandler::lastrefresh::set(db_time(Utc::now())),
+            ],
+        )
+        .exec()
+        .await;
+
+    if let Err(e) = result {
+        event!(
+            Level::WARN,
+            err = e.to_string(),
+            "failed to persist refresh status"
+        );
+    }
+}
+
+/// Marks voter handlers as sent to detective.
+#[instrument(skip_all)]
+pub async fn persist_voters_pending(client: &PrismaClient, voter_handler_ids: Vec<String>) {
+    if voter_handler_ids.is_empty() {
+        return;
+    }
+
+    let result = client
+        .voterhandler()
+        .update_many(
+            vec![voterhandler::id::in_vec(voter_handler_ids)],
+            vec![
+                voterhandler::refreshstatus::set(RefreshStatus::Pending),
+                voterhandler::lastrefresh::set(db_time(Utc::now())),
+            ],
+        )
+        .exec()
+        .await;
+
+    if let Err(e) = result {
+        event!(
+            Level::WARN,
+            err = e.to_string(),
+            "failed to persist refresh status"
+        );
+    }
+}
+
+/// Records the outcome of a votes refresh for the voters of one dao handler. A success
+/// is `DONE` now and clears the failures, a failure goes back to `NEW` and counts.
+#[instrument(skip_all)]
+pub async fn persist_voters_result(
+    client: &PrismaClient,
+    dao_handler_id: &str,
+    voters: Vec<String>,
+    success: bool,
+) {
+    if voters.is_empty() {
+        return;
+    }
+
+    let updates = if success {
+        vec![
+            voterhandler::refreshstatus::set(RefreshStatus::Done),
+            voterhandler::lastrefresh::set(db_time(Utc::now())),
+            voterhandler::refreshfailures::set(0),
+        ]
+    } else {
+        vec![
+            voterhandler::refreshstatus::set(RefreshStatus::New),
+            voterhandler::refreshfailures::increment(1),
+        ]
+    };
+
+    let result = client
+        .voterhandler()
+        .update_many(
+            vec![
+                voterhandler::daohandlerid::equals(dao_handler_id.to_string()),
+                voterhandler::voter::is(vec![voter::address::in_vec(voters)]),
+            ],
+            updates,
+        )
+        .exec()
+        .await;
+
+    if let Err(e) = result {
+        event!(
+            Level::WARN,
+            daohandler = dao_handler_id,
+            err = e.to_string(),
+            "failed to persist refresh status"
+        );
+    }
+}
+
+/// Hands a dead lettered refresh back to the producers, it is `NEW` again.
+#[instrument(skip_all)]
+pub async fn release(client: &PrismaClient, entry: &RefreshEntry) {
+    match entry.refresh_type {
+        RefreshType::Daochainproposals | RefreshType::Daosnapshotproposals => {
+            if let Some(state) = dao_state(&entry.handler_id).await {
+                let mut dao_handler = state.lock().await;
+
+                dao_handler.refresh_status = RefreshStatus::New;
+
+                persist_dao_status(client, &dao_handler).await;
+            }
+        }
+        RefreshType::Daochainvotes | RefreshType::Daosnapshotvotes => {
+            if let Some(voters) = voters_state(&entry.handler_id).await {
+                let mut voters = voters.lock().await;
+
+                for vh in voters.values_mut() {
+                    if entry.voters.contains(&vh.voter_address) {
+                        vh.refresh_status = RefreshStatus::New;
+                        vh.failures += 1;
+                    }
+                }
+            }
+
+            persist_voters_result(client, &entry.handler_id, entry.voters.clone(), false).await;
+        }
+    }
+}
+
+/// Picks up handlers paused or resumed through another refresher's admin api.
+#[instrument(skip_all)]
+pub async fn sync_paused(client: &PrismaClient) -> Result<()> {
+    let dao_handlers = client
+        .daohandler()
+        .find_many(vec![])
+        .select(daohandler::select!({ id refreshpaused }))
+        .exec()
+        .await?;
+
+    let daos_refresh_status = DAOS_REFRESH_STATUS.read().await;
+
+    for daohandler in dao_handlers {
+        if let Some(state) = daos_refresh_status.get(&daohandler.id) {
+            state.lock().await.paused = daohandler.refreshpaused;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reloads the scheduling state of the known handlers from their rows. Refreshes run on
+/// every refresher and each one writes its results back, the producer reads them here
+/// before every pass instead of trusting its own memory.
+#[instrument(skip_all)]
+pub async fn sync_refresh_statuses(client: &PrismaClient) -> Result<()> {
+    let dao_handlers = client
+        .daohandler()
+        .find_many(vec![])
+        .select(daohandler::select!({
+            id
+            refreshstatus
+            lastrefresh
+            refreshspeed
+            votersrefreshspeed
+            refreshfailures
+            refreshpaused
+        }))
+        .exec()
+        .await?;
+
+    let voter_handlers = client
+        .voterhandler()
+        .find_many(vec![])
+        .select(voterhandler::select!({
+            id
+            daohandlerid
+            refreshstatus
+            lastrefresh
+            refreshfailures
+        }))
+        .exec()
+        .await?;
+
+    let daos_refresh_status = DAOS_REFRESH_STATUS.read().await;
+
+    for daohandler in dao_handlers {
+        if let Some(state) = daos_refresh_status.get(&daohandler.id) {
+            let mut state = state.lock().await;
+
+            state.refresh_status = daohandler.refreshstatus;
+            state.last_refresh = daohandler.lastrefresh.with_timezone(&Utc);
+            state.failures = daohandler.refreshfailures;
+            state.paused = daohandler.refreshpaused;
+
+            if let Some(refreshspeed) = daohandler.refreshspeed {
+                state.refreshspeed = refreshspeed;
+            }
+            if let Some(votersrefreshspeed) = daohandler.votersrefreshspeed {
+                state.votersrefreshspeed = votersrefreshspeed;
+            }
+        }
+    }
+
+    drop(daos_refresh_status);
+
+    let mut by_dao_handler: HashMap<String, HashMap<String, _>> = HashMap::new();
+
+    for voterhandler in voter_handlers {
+        by_dao_handler
+            .entry(voterhandler.daohandlerid.clone())
+            .or_default()
+            .insert(voterhandler.id.clone(), voterhandler);
+    }
+
+    let voters_refresh_status = VOTERS_REFRESH_STATUS.read().await;
+
+    for (dao_handler_id, rows) in by_dao_handler {
+        let Some(voters) = voters_refresh_status.get(&dao_handler_id) else {
+            continue;
+        };
+
+        for vh in voters.lock().await.values_mut() {
+            if let Some(row) = rows.get(&vh.voter_handler_id) {
+                vh.refresh_status = row.refreshstatus;
+                vh.last_refresh = row.lastrefresh.with_timezone(&Utc);
+                vh.failures = row.refreshfailures;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Pauses or resumes a dao handler and its voters. Pausing drops the refreshes still
+/// waiting in the queue, the ones already leased run to completion.
+#[instrument(skip(client))]
+pub async fn set_paused(client: &PrismaClient, dao_handler_id: &str, paused: bool) -> Result<()> {
+    client
+        .daohandler()
+        .update(
+            daohandler::id::equals(dao_handler_id.to_string()),
+            vec![daohandler::refreshpaused::set(paused)],
+        )
+        .exec()
+        .await?;
+
+    if let Some(state) = dao_state(dao_handler_id).await {
+        state.lock().await.paused = paused;
+    }
+
+    if paused {
+        client
+            .refreshjob()
+            .delete_many(vec![
+                refreshjob::daohandlerid::equals(dao_handler_id.to_string()),
+                refreshjob::status::equals(RefreshJobStatus::Queued),
+            ])
+            .exec()
+            .await?;
+    } else {
+        force_refresh(client, dao_handler_id).await?;
+    }
+
+    Ok(())
+}
+
+/// Makes a dao handler and all of its voters `NEW` and long overdue, the producers send
+/// them on their next pass unless the handler is paused.
+#[instrument(skip(client))]
+pub async fn force_refresh(client: &PrismaClient, dao_handler_id: &str) -> Result<()> {
+    let overdue = Utc.timestamp_opt(0, 0).unwrap();
+
+    if let Some(state) = dao_state(dao_handler_id).await {
+        let mut dao_handler = state.lock().await;
+
+        dao_handler.refresh_status = RefreshStatus::New;
+        dao_handler.last_refresh = overdue;
+    }
+
+    if let Some(voters) = voters_state(dao_handler_id).await {
+        for vh in voters.lock().await.values_mut() {
+            vh.refresh_status = RefreshStatus::New;
+            vh.last_refresh = overdue;
+        }
+    }
+
+    client
+        .daohandler()
+        .update(
+            daohandler::id::equals(dao_handler_id.to_string()),
+            vec![
+                daohandler::refreshstatus::set(RefreshStatus::New),
+                daohandler::lastrefresh::set(db_time(overdue)),
+            ],
+        )
+        .exec()
+        .await?;
+
+    client
+        .voterhandler()
+        .update_many(
+            vec![voterhandler::daohandlerid::equals(
+                dao_handler_id.to_string(),
+            )],
+            vec![
+                voterhandler::refreshstatus::set(RefreshStatus::New),
+                voterhandler::lastrefresh::set(db_time(overdue)),
+            ],
+        )
+        .exec()
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use std::{collections::HashMap, env};
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prisma_client_rust::chrono::{Duration, Utc};
+use tokio::sync::RwLock;
+use tracing::{event, instrument, Level};
+
+use crate::{
+    concurrency::Chain,
+    prisma::{detectiveinstance, PrismaClient},
+    refresh_status::{db_time, DAOS_REFRESH_STATUS},
+};
+
+/// Points each detective instance gets on the ring, more spread handlers more evenly.
+const VIRTUAL_NODES: u32 = 64;
+/// Seconds after its last heartbeat an instance is taken off the rings.
+const STALE_AFTER: i64 = 30;
+/// Instances in this pool take the handlers of chains without a pool of their own.
+pub const DEFAULT_POOL: &str = "default";
+
+/// FNV-1a, every refresher routes a handler to the same instance.
+fn hash(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Consistent hash ring over detective urls. A member joining or leaving only moves the
+/// handlers between it and its neighbours.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HashRing {
+    points: Vec<(u64, String)>,
+}
+
+impl HashRing {
+    pub fn new<I: IntoIterator<Item = String>>(members: I) -> HashRing {
+        let mut points: Vec<(u64, String)> = members
+            .into_iter()
+            .flat_map(|member| {
+                (0..VIRTUAL_NODES)
+                    .map(move |i| (hash(&format!("{}#{}", member, i)), member.clone()))
+            })
+            .collect();
+
+        points.sort();
+        points.dedup();
+
+        HashRing { points }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn route(&self, key: &str) -> Option<&str> {
+        if self.points.is_empty() {
+            return None;
+        }
+
+        let hash = hash(key);
+        let index = self.points.partition_point(|(point, _)| *point < hash);
+
+        Some(&self.points[index % self.points.len()].1)
+    }
+}
+
+/// Rings by pool over the live detective instances.
+static RINGS: Lazy<RwLock<HashMap<String, HashRing>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+fn route<'a>(
+    rings: &'a HashMap<String, HashRing>,
+    dao_handler_id: &str,
+    chain: Chain,
+) -> Option<&'a str> {
+    rings
+        .get(chain.name())
+        .filter(|ring| !ring.is_empty())
+        .or_else(|| rings.get(DEFAULT_POOL))
+        .and_then(|ring| ring.route(dao_handler_id))
+}
+
+/// Detective instance a dao handler is refreshed on. With no instances registered
+/// everything goes to `$DETECTIVE_URL`.
+pub async fn detective_url(dao_handler_id: &str, chain: Chain) -> String {
+    match route(&*RINGS.read().await, dao_handler_id, chain) {
+        Some(url) => url.to_string(),
+        None => env::var("DETECTIVE_URL").expect("$DETECTIVE_URL is not set"),
+    }
+}
+
+/// Rebuilds the rings from the instances with a recent heartbeat.
+#[instrument(skip_all)]
+pub async fn update_membership(client: &PrismaClient) -> Result<()> {
+    let instances = client
+        .detectiveinstance()
+        .find_many(vec![detectiveinstance::lastseen::gt(db_time(
+            Utc::now() - Duration::seconds(STALE_AFTER),
+        ))])
+        .exec()
+        .await?;
+
+    let mut pools: HashMap<String, Vec<String>> = HashMap::new();
+    for instance in &instances {
+        pools
+            .entry(instance.pool.clone())
+            .or_default()
+            .push(instance.url.clone());
+    }
+
+    let rings: HashMap<String, HashRing> = pools
+        .into_iter()
+        .map(|(pool, urls)| (pool, HashRing::new(urls)))
+        .collect();
+
+    let mut current = RINGS.write().await;
+
+    if *current == rings {
+        return Ok(());
+    }
+
+    let mut moved = 0;
+    for state in DAOS_REFRESH_STATUS.read().await.values() {
+        let dao_handler = state.lock().await;
+
+        if route(&current, &dao_handler.dao_handler_id, dao_handler.r#type)
+            != route(&rings, &dao_handler.dao_handler_id, dao_handler.r#type)
+        {
+            moved += 1;
+        }
+    }
+
+    event!(
+        Level::INFO,
+        instances = instances.len(),
+        pools = rings.len(),
+        moved = moved,
+        "detective membership changed"
+    );
+
+    *current = rings;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashRing;
+
+    fn members(count: usize) -> Vec<String> {
+        (0..count)
+            .map(|i| format!("http://detective-{}", i))
+            .collect()
+    }
+
+    #[test]
+    fn routes_every_key_to_a_member() {
+        let ring = HashRing::new(members(3));
+
+        for key in 0..100 {
+            let member = ring.route(&key.to_string()).unwrap();
+            assert!(members(3).iter().any(|m| m == member));
+        }
+
+        assert_eq!(HashRing::new(vec![]).route("handler"), None);
+    }
+
+    #[test]
+    fn joining_member_only_takes_keys() {
+        let before = HashRing::new(members(4));
+        let after = HashRing::new(members(5));
+        let joined = &members(5)[4];
+
+        let mut moved = 0;
+        for key in 0..1000 {
+            let key = format!("handler-{}", key);
+
+            if before.route(&key) != after.route(&key) {
+                assert_eq!(after.route(&key), Some(joined.as_str()));
+                moved += 1;
+            }
+        }
+
+        assert!(moved > 0 && moved < 400, "moved {}", moved);
+    }
+}
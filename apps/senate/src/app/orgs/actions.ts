@@ -4,6 +4,7 @@ import {
   db,
   sql,
   eq,
+  inArray,
   dao,
   user,
   subscription,
@@ -108,7 +109,10 @@ const getSubscribedDAOs = async () => {
         .select({ count: sql<number>`count(*)` })
         .from(proposal)
         .where(
-          and(eq(proposal.daoid, cur.dao.id), eq(proposal.state, "ACTIVE")),
+          and(
+            eq(proposal.daoid, cur.dao.id),
+            inArray(proposal.state, ["ACTIVE", "OBJECTION_PERIOD"]),
+          ),
         );
 
       if (cur.daohandler)
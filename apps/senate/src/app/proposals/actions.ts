@@ -194,13 +194,14 @@ export async function fetchItems(
         inArray(
           proposal.state,
           active
-            ? ["ACTIVE"]
+            ? ["ACTIVE", "OBJECTION_PERIOD"]
             : [
                 "QUEUED",
                 "DEFEATED",
                 "EXECUTED",
                 "EXPIRED",
                 "SUCCEEDED",
+                "VETOED",
                 "HIDDEN",
                 "UNKNOWN",
               ],
@@ -3,6 +3,7 @@ pub mod ending_soon;
 pub mod new_proposals;
 pub mod update_active;
 pub mod update_hidden;
+pub mod vote_changed;
 
 mod utils {
     pub mod notification_retry;
@@ -2,7 +2,7 @@ use std::{env, sync::Arc, time::Duration};
 
 use anyhow::Result;
 
-use prisma_client_rust::serde_json;
+use prisma_client_rust::{serde_json, Direction};
 use tokio::time::sleep;
 use tracing::{debug_span, event, instrument, warn, Instrument, Level};
 
@@ -89,7 +89,7 @@ pub async fn dispatch_new_proposal_notifications(client: &Arc<PrismaClient>) ->
                         .collect::<String>()
                 );
 
-                let payload = serde_json::json!({
+                let mut payload = serde_json::json!({
                     "blocks": [
                         {
                             "type": "section",
@@ -130,6 +130,22 @@ pub async fn dispatch_new_proposal_notifications(client: &Arc<PrismaClient>) ->
                     ]
                 });
 
+                // incoming webhooks can not reply, the earlier stage is linked instead
+                if let Some(earlier) = family_predecessor(client, &user.id, &proposal).await? {
+                    payload["blocks"].as_array_mut().unwrap().insert(
+                        1,
+                        serde_json::json!({
+                            "type": "context",
+                            "elements": [
+                                {
+                                    "type": "mrkdwn",
+                                    "text": format!("Follows *<{}|{}>*", earlier.url, earlier.name)
+                                }
+                            ]
+                        }),
+                    );
+                }
+
                 let repsonse = reqwest_client
                     .post(user.clone().slackwebhook)
                     .json(&payload)
@@ -225,3 +241,30 @@ pub async fn dispatch_new_proposal_notifications(client: &Arc<PrismaClient>) ->
     }
     Ok(())
 }
+
+/// Earlier stage of the proposal's family the user was already notified about.
+async fn family_predecessor(
+    client: &Arc<PrismaClient>,
+    user_id: &str,
+    proposal: &proposal_with_dao::Data,
+) -> Result<Option<proposal::Data>> {
+    let family_id = match &proposal.familyid {
+        Some(id) => id.clone(),
+        None => return Ok(None),
+    };
+
+    Ok(client
+        .proposal()
+        .find_first(vec![
+            proposal::familyid::equals(Some(family_id)),
+            proposal::id::not(proposal.id.clone()),
+            proposal::notification::some(vec![
+                notification::userid::equals(user_id.to_string()),
+                notification::r#type::equals(NotificationType::NewProposalSlack),
+                notification::dispatchstatus::equals(NotificationDispatchedState::Dispatched),
+            ]),
+        ])
+        .order_by(proposal::timecreated::order(Direction::Asc))
+        .exec()
+        .await?)
+}
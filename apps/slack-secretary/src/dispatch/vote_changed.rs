@@ -0,0 +1,194 @@
+use std::{env, sync::Arc, time::Duration};
+
+use anyhow::Result;
+
+use prisma_client_rust::serde_json;
+use tokio::time::sleep;
+use tracing::{event, instrument, Level};
+
+use crate::{
+    prisma::{
+        self,
+        notification,
+        proposal,
+        user,
+        NotificationDispatchedState,
+        NotificationType,
+        PrismaClient,
+    },
+    utils::vote::get_vote_change,
+};
+
+use super::utils::notification_retry::update_notification_retry;
+
+prisma::proposal::include!(proposal_with_dao { dao daohandler });
+
+#[instrument(skip_all)]
+pub async fn dispatch_vote_changed_notifications(client: &Arc<PrismaClient>) -> Result<()> {
+    let reqwest_client = reqwest::Client::new();
+    let notifications = client
+        .notification()
+        .find_many(vec![
+            notification::dispatchstatus::in_vec(vec![
+                NotificationDispatchedState::NotDispatched,
+                NotificationDispatchedState::FirstRetry,
+                NotificationDispatchedState::SecondRetry,
+                NotificationDispatchedState::ThirdRetry,
+            ]),
+            notification::r#type::equals(NotificationType::VoteChangedSlack),
+        ])
+        .exec()
+        .await?;
+
+    for notification in notifications {
+        let user = client
+            .user()
+            .find_first(vec![user::id::equals(notification.clone().userid)])
+            .exec()
+            .await?
+            .unwrap();
+
+        let proposal = client
+            .proposal()
+            .find_first(vec![proposal::id::equals(
+                notification.clone().proposalid.unwrap(),
+            )])
+            .include(proposal_with_dao::include())
+            .exec()
+            .await?;
+
+        let change = match &proposal {
+            Some(p) => {
+                get_vote_change(user.clone().id, p.clone().id, notification.revision, client)
+                    .await?
+            }
+            None => None,
+        };
+
+        let shortner_url = match env::var_os("NEXT_PUBLIC_URL_SHORTNER") {
+            Some(v) => v.into_string().unwrap(),
+            None => panic!("$NEXT_PUBLIC_URL_SHORTNER is not set"),
+        };
+
+        match (proposal, change) {
+            (Some(proposal), Some(change)) => {
+                let short_url = format!(
+                    "{}{}/{}/{}",
+                    shortner_url,
+                    proposal
+                        .id
+                        .chars()
+                        .rev()
+                        .take(7)
+                        .collect::<Vec<char>>()
+                        .into_iter()
+                        .rev()
+                        .collect::<String>(),
+                    "s",
+                    user.clone()
+                        .id
+                        .chars()
+                        .rev()
+                        .take(7)
+                        .collect::<Vec<char>>()
+                        .into_iter()
+                        .rev()
+                        .collect::<String>()
+                );
+
+                let payload = serde_json::json!({
+                    "blocks": [
+                        {
+                            "type": "section",
+                            "text": {
+                                "type": "mrkdwn",
+                                "text": format!("*<{}|{}>*\n🔄 *{}* changed their vote on this *{}* proposal from *{}* to *{}*", short_url, proposal.name, change.voter_name, proposal.dao.name, change.previous_choice, change.choice)
+                            },
+                            "accessory": {
+                                "type": "image",
+                                "image_url": format!(
+                                    "https://www.senatelabs.xyz/{}_medium.png",
+                                    proposal.dao.picture
+                                ),
+                                "alt_text": proposal.dao.name
+                            }
+                        },
+                        {
+                            "type": "divider"
+                        }
+                    ]
+                });
+
+                let repsonse = reqwest_client
+                    .post(user.clone().slackwebhook)
+                    .json(&payload)
+                    .header("Content-Type", "application/json")
+                    .send()
+                    .await?
+                    .text()
+                    .await?;
+
+                match repsonse.as_str() {
+                    "ok" => {
+                        event!(
+                            Level::INFO,
+                            user = user.address.clone().unwrap(),
+                            proposal_name = proposal.name,
+                            dao = proposal.dao.name,
+                            "vote changed notification"
+                        );
+
+                        client
+                            .notification()
+                            .update_many(
+                                vec![
+                                    notification::userid::equals(user.id),
+                                    notification::proposalid::equals(proposal.id.into()),
+                                    notification::r#type::equals(
+                                        NotificationType::VoteChangedSlack,
+                                    ),
+                                    notification::revision::equals(notification.revision),
+                                ],
+                                vec![notification::dispatchstatus::set(
+                                    NotificationDispatchedState::Dispatched,
+                                )],
+                            )
+                            .exec()
+                            .await?;
+                    }
+                    _ => {
+                        event!(
+                            Level::WARN,
+                            user = user.address.clone().unwrap(),
+                            proposal_name = proposal.name,
+                            dao = proposal.dao.name,
+                            "vote changed notification"
+                        );
+
+                        update_notification_retry(client, notification).await;
+                    }
+                };
+
+                sleep(Duration::from_millis(100)).await;
+            }
+            _ => {
+                client
+                    .notification()
+                    .update_many(
+                        vec![
+                            notification::userid::equals(notification.clone().userid),
+                            notification::proposalid::equals(notification.clone().proposalid),
+                            notification::r#type::equals(NotificationType::VoteChangedSlack),
+                            notification::revision::equals(notification.revision),
+                        ],
+                        vec![notification::dispatchstatus::set(
+                            NotificationDispatchedState::Deleted,
+                        )],
+                    )
+                    .exec()
+                    .await?;
+            }
+        }
+    }
+    Ok(())
+}
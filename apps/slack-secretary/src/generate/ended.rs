@@ -36,6 +36,7 @@ pub async fn generate_ended_proposal_notifications(client: &Arc<PrismaClient>) -
                 ProposalState::Queued,
                 ProposalState::Expired,
                 ProposalState::Executed,
+                ProposalState::Vetoed,
             ]),
             proposal::timeend::lt((Utc::now()).into()),
             proposal::timeend::gt((Utc::now() - Duration::minutes(60)).into()),
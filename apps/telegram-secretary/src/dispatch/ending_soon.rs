@@ -179,6 +179,10 @@ pub async fn dispatch_ending_soon_notifications(
                     NotificationType::SecondReminderSlack => todo!(),
                     NotificationType::ThirdReminderSlack => todo!(),
                     NotificationType::EndedProposalSlack => todo!(),
+                    // vote changes have no reminders
+                    NotificationType::VoteChangedDiscord
+                    | NotificationType::VoteChangedTelegram
+                    | NotificationType::VoteChangedSlack => continue,
                 };
 
                 let message = bot
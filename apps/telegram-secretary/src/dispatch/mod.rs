@@ -3,3 +3,4 @@ pub mod ending_soon;
 pub mod new_proposals;
 pub mod update_active;
 pub mod update_ended;
+pub mod vote_changed;
@@ -7,7 +7,7 @@ use teloxide::{
     payloads::SendMessageSetters,
     prelude::OnError,
     requests::Requester,
-    types::{ChatId, InlineKeyboardButton, InlineKeyboardMarkup},
+    types::{ChatId, InlineKeyboardButton, InlineKeyboardMarkup, MessageId},
     Bot,
 };
 use tokio::time::sleep;
@@ -111,7 +111,9 @@ pub async fn dispatch_new_proposal_notifications(
                     },
                 };
 
-                let message = bot
+                let thread = family_message(client, &user.id, &proposal).await?;
+
+                let mut request = bot
                     .send_message(
                         ChatId(user.telegramchatid.parse().unwrap()),
                         format!(
@@ -139,8 +141,16 @@ pub async fn dispatch_new_proposal_notifications(
                             url::Url::parse(short_url.as_str()).unwrap(),
                         ),
                     ]))
-                    .disable_web_page_preview(true)
-                    .await;
+                    .disable_web_page_preview(true);
+
+                // the snapshot vote and its on-chain proposal read as one conversation
+                if let Some(message_id) = thread {
+                    request = request
+                        .reply_to_message_id(MessageId(message_id))
+                        .allow_sending_without_reply(true);
+                }
+
+                let message = request.await;
 
                 let update_data = match message {
                     Ok(msg) => {
@@ -219,3 +229,34 @@ pub async fn dispatch_new_proposal_notifications(
 
     Ok(())
 }
+
+/// Message of an earlier stage of the proposal's family the user was already notified about.
+async fn family_message(
+    client: &Arc<PrismaClient>,
+    user_id: &str,
+    proposal: &proposal_with_dao::Data,
+) -> Result<Option<i32>> {
+    let family_id = match &proposal.familyid {
+        Some(id) => id.clone(),
+        None => return Ok(None),
+    };
+
+    let earlier = client
+        .notification()
+        .find_many(vec![
+            notification::userid::equals(user_id.to_string()),
+            notification::r#type::equals(NotificationType::NewProposalTelegram),
+            notification::dispatchstatus::equals(NotificationDispatchedState::Dispatched),
+            notification::proposal::is(vec![
+                proposal::familyid::equals(Some(family_id)),
+                proposal::id::not(proposal.id.clone()),
+            ]),
+        ])
+        .exec()
+        .await?;
+
+    Ok(earlier
+        .into_iter()
+        .filter_map(|n| n.telegrammessageid?.parse::<i32>().ok())
+        .min())
+}
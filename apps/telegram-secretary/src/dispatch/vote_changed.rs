@@ -0,0 +1,210 @@
+use std::{env, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use teloxide::{
+    adaptors::{DefaultParseMode, Throttle},
+    payloads::SendMessageSetters,
+    requests::Requester,
+    types::ChatId,
+};
+use tokio::time::sleep;
+use tracing::{event, instrument, Level};
+
+use crate::{
+    prisma::{
+        self,
+        notification,
+        proposal,
+        user,
+        NotificationDispatchedState,
+        NotificationType,
+        PrismaClient,
+    },
+    utils::vote::get_vote_change,
+};
+
+prisma::proposal::include!(proposal_with_dao { dao daohandler });
+
+#[instrument(skip(client))]
+pub async fn dispatch_vote_changed_notifications(
+    client: &Arc<PrismaClient>,
+    bot: &Arc<DefaultParseMode<Throttle<teloxide::Bot>>>,
+) -> Result<()> {
+    let notifications = client
+        .notification()
+        .find_many(vec![
+            notification::dispatchstatus::in_vec(vec![
+                NotificationDispatchedState::NotDispatched,
+                NotificationDispatchedState::FirstRetry,
+                NotificationDispatchedState::SecondRetry,
+                NotificationDispatchedState::ThirdRetry,
+            ]),
+            notification::r#type::equals(NotificationType::VoteChangedTelegram),
+        ])
+        .exec()
+        .await?;
+
+    for notification in notifications {
+        let user = client
+            .user()
+            .find_first(vec![user::id::equals(notification.clone().userid)])
+            .exec()
+            .await?
+            .unwrap();
+
+        let proposal = client
+            .proposal()
+            .find_first(vec![proposal::id::equals(
+                notification.clone().proposalid.unwrap(),
+            )])
+            .include(proposal_with_dao::include())
+            .exec()
+            .await?;
+
+        let change = match &proposal {
+            Some(p) => {
+                get_vote_change(user.clone().id, p.clone().id, notification.revision, client)
+                    .await?
+            }
+            None => None,
+        };
+
+        match (proposal, change) {
+            (Some(proposal), Some(change)) => {
+                let shortner_url = match env::var_os("NEXT_PUBLIC_URL_SHORTNER") {
+                    Some(v) => v.into_string().unwrap(),
+                    None => panic!("$NEXT_PUBLIC_URL_SHORTNER is not set"),
+                };
+
+                let short_url = format!(
+                    "{}{}/{}/{}",
+                    shortner_url,
+                    proposal
+                        .id
+                        .chars()
+                        .rev()
+                        .take(7)
+                        .collect::<Vec<char>>()
+                        .into_iter()
+                        .rev()
+                        .collect::<String>(),
+                    "t",
+                    user.clone()
+                        .id
+                        .chars()
+                        .rev()
+                        .take(7)
+                        .collect::<Vec<char>>()
+                        .into_iter()
+                        .rev()
+                        .collect::<String>()
+                );
+
+                let message = bot
+                    .send_message(
+                        ChatId(user.telegramchatid.parse().unwrap()),
+                        format!(
+                            "🔄 <b>{}</b> changed their vote on <b>{}</b> proposal <a href=\"{}\"><i>{}</i></a> from <b>{}</b> to <b>{}</b>\nIf you did not expect this, check who can vote with this address.",
+                            escape(&change.voter_name),
+                            proposal.dao.name,
+                            short_url,
+                            escape(&proposal.name),
+                            escape(&change.previous_choice),
+                            escape(&change.choice),
+                        ),
+                    )
+                    .disable_web_page_preview(true)
+                    .await;
+
+                let update_data = match message {
+                    Ok(msg) => {
+                        event!(Level::INFO, "vote changed notification");
+                        vec![
+                            notification::dispatchstatus::set(
+                                NotificationDispatchedState::Dispatched,
+                            ),
+                            notification::telegramchatid::set(msg.chat.id.to_string().into()),
+                            notification::telegrammessageid::set(msg.id.to_string().into()),
+                        ]
+                    }
+                    Err(e) => {
+                        event!(
+                            Level::ERROR,
+                            err = e.to_string(),
+                            "failed vote changed notification"
+                        );
+                        match notification.dispatchstatus {
+                            NotificationDispatchedState::NotDispatched => {
+                                vec![notification::dispatchstatus::set(
+                                    NotificationDispatchedState::FirstRetry,
+                                )]
+                            }
+                            NotificationDispatchedState::FirstRetry => {
+                                vec![notification::dispatchstatus::set(
+                                    NotificationDispatchedState::SecondRetry,
+                                )]
+                            }
+                            NotificationDispatchedState::SecondRetry => {
+                                vec![notification::dispatchstatus::set(
+                                    NotificationDispatchedState::ThirdRetry,
+                                )]
+                            }
+                            NotificationDispatchedState::ThirdRetry => {
+                                vec![notification::dispatchstatus::set(
+                                    NotificationDispatchedState::Failed,
+                                )]
+                            }
+                            // only pending notifications are picked up
+                            NotificationDispatchedState::Dispatched
+                            | NotificationDispatchedState::Deleted
+                            | NotificationDispatchedState::Failed => vec![],
+                        }
+                    }
+                };
+
+                client
+                    .notification()
+                    .update_many(
+                        vec![
+                            notification::userid::equals(notification.clone().userid),
+                            notification::proposalid::equals(notification.clone().proposalid),
+                            notification::r#type::equals(notification.clone().r#type),
+                            notification::revision::equals(notification.revision),
+                        ],
+                        update_data,
+                    )
+                    .exec()
+                    .await?;
+            }
+            _ => {
+                client
+                    .notification()
+                    .update_many(
+                        vec![
+                            notification::userid::equals(notification.clone().userid),
+                            notification::proposalid::equals(notification.clone().proposalid),
+                            notification::r#type::equals(notification.clone().r#type),
+                            notification::revision::equals(notification.revision),
+                        ],
+                        vec![notification::dispatchstatus::set(
+                            NotificationDispatchedState::Deleted,
+                        )],
+                    )
+                    .exec()
+                    .await?;
+            }
+        }
+
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    Ok(())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\"', "&quot;")
+        .replace('\'', "&#39;")
+}
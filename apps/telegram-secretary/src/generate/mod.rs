@@ -1,3 +1,4 @@
 pub mod ended;
 pub mod ending_soon;
 pub mod new_proposals;
+pub mod vote_changed;
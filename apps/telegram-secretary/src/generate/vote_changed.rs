@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::{
+    prisma::{user, NotificationType, PrismaClient},
+    shared::vote_changed::generate_vote_changed,
+};
+
+pub async fn generate_vote_changed_notifications(client: &Arc<PrismaClient>) -> Result<()> {
+    generate_vote_changed(
+        client,
+        vec![
+            user::telegramnotifications::equals(true),
+            user::telegramincludevotes::equals(true),
+        ],
+        NotificationType::VoteChangedTelegram,
+    )
+    .await
+}
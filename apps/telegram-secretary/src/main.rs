@@ -24,22 +24,27 @@ use crate::{
         ended::dispatch_ended_proposal_notifications,
         ending_soon::dispatch_ending_soon_notifications,
         new_proposals::dispatch_new_proposal_notifications,
+        vote_changed::dispatch_vote_changed_notifications,
     },
     generate::{
         ended::generate_ended_proposal_notifications,
         ending_soon::generate_ending_soon_notifications,
         new_proposals::generate_new_proposal_notifications,
+        vote_changed::generate_vote_changed_notifications,
     },
     prisma::{NotificationType, PrismaClient},
+    utils::display::voter_display,
 };
 
 mod dispatch;
 mod generate;
 pub mod prisma;
+#[path = "../../../packages/secretary/mod.rs"]
+mod shared;
 mod telemetry;
 
 mod utils {
-    pub mod vote;
+    pub use crate::shared::{display, vote};
 }
 
 #[tokio::main]
@@ -155,6 +160,37 @@ async fn main() {
         }
     });
 
+    let client_for_vote_changed: Arc<PrismaClient> = Arc::clone(&client);
+    let bot_for_vote_changed: Arc<DefaultParseMode<Throttle<teloxide::Bot>>> =
+        Arc::clone(&botwrapper);
+    let vote_changed_task = tokio::task::spawn(async move {
+        loop {
+            match generate_vote_changed_notifications(&client_for_vote_changed).await {
+                Ok(_) => event!(Level::INFO, "generate_vote_changed_notifications ok"),
+                Err(e) => event!(
+                    Level::ERROR,
+                    err = e.to_string(),
+                    "failed to generate vote changed"
+                ),
+            };
+            match dispatch_vote_changed_notifications(
+                &client_for_vote_changed,
+                &bot_for_vote_changed,
+            )
+            .await
+            {
+                Ok(_) => event!(Level::INFO, "dispatch_vote_changed_notifications ok"),
+                Err(e) => event!(
+                    Level::ERROR,
+                    err = e.to_string(),
+                    "failed to dispatch vote changed"
+                ),
+            };
+
+            sleep(std::time::Duration::from_secs(60)).await;
+        }
+    });
+
     // let client_for_active_proposals: Arc<PrismaClient> = Arc::clone(&client);
     // let bot_for_active_proposals: Arc<DefaultParseMode<Throttle<teloxide::Bot>>> =
     //     Arc::clone(&botwrapper);
@@ -184,6 +220,7 @@ async fn main() {
         new_proposals_task,
         ending_soon_task,
         ended_proposals_task,
+        vote_changed_task,
         //active_proposals_task
     )
     .unwrap();
@@ -271,11 +308,13 @@ async fn answer(bot: Bot, msg: Message, cmd: Command) -> ResponseResult<()> {
                                     .await
                                     .unwrap();
 
-                                bot.send_message(
-                                    msg.chat.id,
-                                    format!("Hello {}!", user.address.unwrap()),
-                                )
-                                .await?;
+                                let greeting =
+                                    voter_display(&user.address.clone().unwrap(), &prisma_client)
+                                        .await
+                                        .unwrap();
+
+                                bot.send_message(msg.chat.id, format!("Hello {}!", greeting.name))
+                                    .await?;
                                 bot.send_message(
                                     msg.chat.id,
                                     "You are now subscribed to Senate Telegram Notifications!",
@@ -8,6 +8,9 @@ import {
   json,
   bigint,
   boolean,
+  longtext,
+  text,
+  double,
   index,
   primaryKey,
 } from "drizzle-orm/mysql-core";
@@ -41,11 +44,43 @@ export const userTovoterRelations = relations(userTovoter, ({ one }) => ({
   }),
 }));
 
+export const detectiveinstance = mysqlTable(
+  "detectiveinstance",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    url: varchar("url", { length: 1024 }).notNull(),
+    pool: varchar("pool", { length: 191 }).default("default").notNull(),
+    lastseen: datetime("lastseen", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+  },
+  (table) => {
+    return {
+      lastseenIdx: index("detectiveinstance_lastseen_idx").on(table.lastseen),
+      detectiveinstanceId: primaryKey(table.id),
+    };
+  },
+);
+
+export const refresherlease = mysqlTable(
+  "refresherlease",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    instance: varchar("instance", { length: 191 }).notNull(),
+    leaseduntil: datetime("leaseduntil", { mode: "date", fsp: 3 }).notNull(),
+  },
+  (table) => {
+    return {
+      refresherleaseId: primaryKey(table.id),
+    };
+  },
+);
+
 export const config = mysqlTable(
   "config",
   {
     key: varchar("key", { length: 191 }).notNull(),
-    value: int("value").notNull(),
+    value: bigint("value", { mode: "number" }).notNull(),
   },
   (table) => {
     return {
@@ -55,6 +90,46 @@ export const config = mysqlTable(
   },
 );
 
+export const contentcache = mysqlTable(
+  "contentcache",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    uri: varchar("uri", { length: 768 }).notNull(),
+    content: longtext("content").notNull(),
+    verified: boolean("verified").default(false).notNull(),
+    fetchedat: datetime("fetchedat", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+  },
+  (table) => {
+    return {
+      contentcacheId: primaryKey(table.id),
+      contentcacheUriKey: unique("contentcache_uri_key").on(table.uri),
+    };
+  },
+);
+
+export const contractabi = mysqlTable(
+  "contractabi",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    name: varchar("name", { length: 191 }).notNull(),
+    abi: json("abi").notNull(),
+    timecreated: datetime("timecreated", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+    timeupdated: datetime("timeupdated", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+  },
+  (table) => {
+    return {
+      contractabiId: primaryKey(table.id),
+      contractabiNameKey: unique("contractabi_name_key").on(table.name),
+    };
+  },
+);
+
 export const dao = mysqlTable(
   "dao",
   {
@@ -80,6 +155,8 @@ export const daoRelations = relations(dao, ({ many }) => ({
   proposals: many(proposal),
   subscriptions: many(subscription),
   votes: many(vote),
+  treasuries: many(treasury),
+  families: many(proposalfamily),
 }));
 
 export const daohandler = mysqlTable(
@@ -102,10 +179,16 @@ export const daohandler = mysqlTable(
       "OPTIMISM_CHAIN",
       "ARBITRUM_CORE_CHAIN",
       "ARBITRUM_TREASURY_CHAIN",
+      "NOUNS_CHAIN",
+      "ARAGON_TOKEN_VOTING",
+      "ARAGON_MULTISIG",
+      "SAFE_MULTISIG",
+      "GENERIC_CHAIN",
       "SNAPSHOT",
     ]).notNull(),
     decoder: json("decoder").notNull(),
     chainindex: bigint("chainindex", { mode: "number" }).default(0).notNull(),
+    logindex: bigint("logindex", { mode: "number" }).default(0).notNull(),
     snapshotindex: datetime("snapshotindex", {
       mode: "date",
       fsp: 3,
@@ -113,6 +196,16 @@ export const daohandler = mysqlTable(
       .default(new Date(0))
       .notNull(),
     uptodate: boolean("uptodate").default(false).notNull(),
+    refreshstatus: mysqlEnum("refreshstatus", ["NEW", "PENDING", "DONE"])
+      .default("NEW")
+      .notNull(),
+    lastrefresh: datetime("lastrefresh", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+    refreshspeed: bigint("refreshspeed", { mode: "number" }),
+    votersrefreshspeed: bigint("votersrefreshspeed", { mode: "number" }),
+    refreshfailures: int("refreshfailures").default(0).notNull(),
+    refreshpaused: boolean("refreshpaused").default(false).notNull(),
     daoid: varchar("daoid", { length: 191 }).notNull(),
   },
   (table) => {
@@ -135,6 +228,94 @@ export const daohandlerRelations = relations(daohandler, ({ many, one }) => ({
   proposals: many(proposal),
   voterhandlers: many(voterhandler),
   votes: many(vote),
+  chainlogs: many(chainlog),
+  refreshjobs: many(refreshjob),
+}));
+
+export const refreshjob = mysqlTable(
+  "refreshjob",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    type: mysqlEnum("type", [
+      "DAOCHAINPROPOSALS",
+      "DAOSNAPSHOTPROPOSALS",
+      "DAOCHAINVOTES",
+      "DAOSNAPSHOTVOTES",
+    ]).notNull(),
+    daohandlerid: varchar("daohandlerid", { length: 191 }).notNull(),
+    voters: json("voters").notNull(),
+    status: mysqlEnum("status", ["QUEUED", "LEASED", "DEAD"])
+      .default("QUEUED")
+      .notNull(),
+    priority: double("priority").default(1).notNull(),
+    attempts: int("attempts").default(0).notNull(),
+    runafter: datetime("runafter", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+    leasedby: varchar("leasedby", { length: 191 }),
+    leaseduntil: datetime("leaseduntil", { mode: "date", fsp: 3 }),
+    lasterror: text("lasterror"),
+    createdat: datetime("createdat", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+  },
+  (table) => {
+    return {
+      statusRunafterIdx: index("refreshjob_status_runafter_idx").on(
+        table.status,
+        table.runafter,
+      ),
+      daohandleridIdx: index("refreshjob_daohandlerid_idx").on(
+        table.daohandlerid,
+      ),
+      refreshjobId: primaryKey(table.id),
+    };
+  },
+);
+
+export const refreshjobRelations = relations(refreshjob, ({ one }) => ({
+  daohandler: one(daohandler, {
+    fields: [refreshjob.daohandlerid],
+    references: [daohandler.id],
+  }),
+}));
+
+export const chainlog = mysqlTable(
+  "chainlog",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    daohandlerid: varchar("daohandlerid", { length: 191 }).notNull(),
+    chain: varchar("chain", { length: 191 }).notNull(),
+    blocknumber: bigint("blocknumber", { mode: "number" }).notNull(),
+    blocktimestamp: datetime("blocktimestamp", { mode: "date", fsp: 3 }),
+    txid: varchar("txid", { length: 191 }).notNull(),
+    logindex: int("logindex").notNull(),
+    address: varchar("address", { length: 191 }).notNull(),
+    event: varchar("event", { length: 191 }).notNull(),
+    args: json("args").notNull(),
+    timecreated: datetime("timecreated", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+  },
+  (table) => {
+    return {
+      daohandleridBlocknumberIdx: index(
+        "chainlog_daohandlerid_blocknumber_idx",
+      ).on(table.daohandlerid, table.blocknumber),
+      eventIdx: index("chainlog_event_idx").on(table.event),
+      chainlogId: primaryKey(table.id),
+      chainlogChainTxidLogindexKey: unique(
+        "chainlog_chain_txid_logindex_key",
+      ).on(table.chain, table.txid, table.logindex),
+    };
+  },
+);
+
+export const chainlogRelations = relations(chainlog, ({ one }) => ({
+  daohandler: one(daohandler, {
+    fields: [chainlog.daohandlerid],
+    references: [daohandler.id],
+  }),
 }));
 
 export const notification = mysqlTable(
@@ -151,17 +332,21 @@ export const notification = mysqlTable(
       "SECOND_REMINDER_DISCORD",
       "THIRD_REMINDER_DISCORD",
       "ENDED_PROPOSAL_DISCORD",
+      "VOTE_CHANGED_DISCORD",
       "NEW_PROPOSAL_TELEGRAM",
       "FIRST_REMINDER_TELEGRAM",
       "SECOND_REMINDER_TELEGRAM",
       "THIRD_REMINDER_TELEGRAM",
       "ENDED_PROPOSAL_TELEGRAM",
+      "VOTE_CHANGED_TELEGRAM",
       "NEW_PROPOSAL_SLACK",
       "FIRST_REMINDER_SLACK",
       "SECOND_REMINDER_SLACK",
       "THIRD_REMINDER_SLACK",
       "ENDED_PROPOSAL_SLACK",
+      "VOTE_CHANGED_SLACK",
     ]).notNull(),
+    revision: int("revision").default(0).notNull(),
     dispatchstatus: mysqlEnum("dispatchstatus", [
       "NOT_DISPATCHED",
       "FIRST_RETRY",
@@ -190,9 +375,9 @@ export const notification = mysqlTable(
         table.dispatchstatus,
       ),
       notificationId: primaryKey(table.id),
-      notificationUseridProposalidTypeKey: unique(
-        "notification_userid_proposalid_type_key",
-      ).on(table.userid, table.proposalid, table.type),
+      notificationUseridProposalidTypeRevisionKey: unique(
+        "notification_userid_proposalid_type_revision_key",
+      ).on(table.userid, table.proposalid, table.type, table.revision),
     };
   },
 );
@@ -218,6 +403,7 @@ export const proposal = mysqlTable(
     scores: json("scores").notNull(),
     scorestotal: json("scorestotal").notNull(),
     quorum: json("quorum").notNull(),
+    supportthreshold: double("supportthreshold"),
     state: mysqlEnum("state", [
       "PENDING",
       "ACTIVE",
@@ -227,6 +413,8 @@ export const proposal = mysqlTable(
       "QUEUED",
       "EXPIRED",
       "EXECUTED",
+      "VETOED",
+      "OBJECTION_PERIOD",
       "HIDDEN",
       "UNKNOWN",
     ]).notNull(),
@@ -235,12 +423,16 @@ export const proposal = mysqlTable(
     timestart: datetime("timestart", { mode: "date", fsp: 3 }).notNull(),
     timeend: datetime("timeend", { mode: "date", fsp: 3 }).notNull(),
     url: varchar("url", { length: 1024 }).notNull(),
+    discussion: varchar("discussion", { length: 1024 }),
     daohandlerid: varchar("daohandlerid", { length: 191 }).notNull(),
     daoid: varchar("daoid", { length: 191 }).notNull(),
     visible: boolean("visible").default(true).notNull(),
+    familyid: varchar("familyid", { length: 191 }),
+    familymatch: varchar("familymatch", { length: 191 }),
   },
   (table) => {
     return {
+      familyidIdx: index("proposal_familyid_idx").on(table.familyid),
       timeendIdx: index("proposal_timeend_idx").on(table.timeend),
       visibleIdx: index("proposal_visible_idx").on(table.visible),
       stateIdx: index("proposal_state_idx").on(table.state),
@@ -260,6 +452,10 @@ export const proposal = mysqlTable(
 export const proposalRelations = relations(proposal, ({ many, one }) => ({
   votes: many(vote),
   notifications: many(notification),
+  snapshots: many(proposalsnapshot),
+  edits: many(proposaledit),
+  simulations: many(proposalsimulation),
+  transfers: many(treasurytransfer),
   dao: one(dao, {
     fields: [proposal.daoid],
     references: [dao.id],
@@ -268,8 +464,275 @@ export const proposalRelations = relations(proposal, ({ many, one }) => ({
     fields: [proposal.daohandlerid],
     references: [daohandler.id],
   }),
+  family: one(proposalfamily, {
+    fields: [proposal.familyid],
+    references: [proposalfamily.id],
+  }),
 }));
 
+export const proposalfamily = mysqlTable(
+  "proposalfamily",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    daoid: varchar("daoid", { length: 191 }).notNull(),
+    name: varchar("name", { length: 2048 }).notNull(),
+    timecreated: datetime("timecreated", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+  },
+  (table) => {
+    return {
+      daoidIdx: index("proposalfamily_daoid_idx").on(table.daoid),
+      proposalfamilyId: primaryKey(table.id),
+    };
+  },
+);
+
+export const proposalfamilyRelations = relations(
+  proposalfamily,
+  ({ many, one }) => ({
+    dao: one(dao, {
+      fields: [proposalfamily.daoid],
+      references: [dao.id],
+    }),
+    proposals: many(proposal),
+  }),
+);
+
+export const proposaledit = mysqlTable(
+  "proposaledit",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    proposalid: varchar("proposalid", { length: 191 }).notNull(),
+    type: mysqlEnum("type", ["DESCRIPTION", "TRANSACTIONS", "FULL"]).notNull(),
+    name: varchar("name", { length: 2048 }).notNull(),
+    updatemessage: varchar("updatemessage", { length: 2048 }).notNull(),
+    txid: varchar("txid", { length: 191 }).notNull(),
+    blockcreated: bigint("blockcreated", { mode: "number" }).notNull(),
+    timecreated: datetime("timecreated", { mode: "date", fsp: 3 }).notNull(),
+  },
+  (table) => {
+    return {
+      proposalidIdx: index("proposaledit_proposalid_idx").on(table.proposalid),
+      proposaleditId: primaryKey(table.id),
+      proposaleditTxidProposalidTypeKey: unique(
+        "proposaledit_txid_proposalid_type_key",
+      ).on(table.txid, table.proposalid, table.type),
+    };
+  },
+);
+
+export const proposaleditRelations = relations(proposaledit, ({ one }) => ({
+  proposal: one(proposal, {
+    fields: [proposaledit.proposalid],
+    references: [proposal.id],
+  }),
+}));
+
+export const proposalsnapshot = mysqlTable(
+  "proposalsnapshot",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    proposalid: varchar("proposalid", { length: 191 }).notNull(),
+    state: mysqlEnum("state", [
+      "PENDING",
+      "ACTIVE",
+      "CANCELED",
+      "DEFEATED",
+      "SUCCEEDED",
+      "QUEUED",
+      "EXPIRED",
+      "EXECUTED",
+      "VETOED",
+      "OBJECTION_PERIOD",
+      "HIDDEN",
+      "UNKNOWN",
+    ]).notNull(),
+    scores: json("scores").notNull(),
+    scorestotal: json("scorestotal").notNull(),
+    quorum: json("quorum").notNull(),
+    timestamp: datetime("timestamp", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+  },
+  (table) => {
+    return {
+      proposalidIdx: index("proposalsnapshot_proposalid_idx").on(
+        table.proposalid,
+      ),
+      timestampIdx: index("proposalsnapshot_timestamp_idx").on(table.timestamp),
+      proposalsnapshotId: primaryKey(table.id),
+    };
+  },
+);
+
+export const proposalsnapshotRelations = relations(
+  proposalsnapshot,
+  ({ one }) => ({
+    proposal: one(proposal, {
+      fields: [proposalsnapshot.proposalid],
+      references: [proposal.id],
+    }),
+  }),
+);
+
+export const proposalsimulation = mysqlTable(
+  "proposalsimulation",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    proposalid: varchar("proposalid", { length: 191 }).notNull(),
+    executor: varchar("executor", { length: 191 }).notNull(),
+    blocknumber: bigint("blocknumber", { mode: "number" }).notNull(),
+    success: boolean("success").notNull(),
+    actions: json("actions").notNull(),
+    events: json("events").notNull(),
+    balancechanges: json("balancechanges").notNull(),
+    summary: longtext("summary").notNull(),
+    timecreated: datetime("timecreated", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+  },
+  (table) => {
+    return {
+      proposalidIdx: index("proposalsimulation_proposalid_idx").on(
+        table.proposalid,
+      ),
+      timecreatedIdx: index("proposalsimulation_timecreated_idx").on(
+        table.timecreated,
+      ),
+      proposalsimulationId: primaryKey(table.id),
+    };
+  },
+);
+
+export const proposalsimulationRelations = relations(
+  proposalsimulation,
+  ({ one }) => ({
+    proposal: one(proposal, {
+      fields: [proposalsimulation.proposalid],
+      references: [proposal.id],
+    }),
+  }),
+);
+
+export const treasury = mysqlTable(
+  "treasury",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    daoid: varchar("daoid", { length: 191 }).notNull(),
+    address: varchar("address", { length: 191 }).notNull(),
+    chain: varchar("chain", { length: 191 }).default("ethereum").notNull(),
+    tokens: json("tokens").notNull(),
+    chainindex: bigint("chainindex", { mode: "number" }).default(0).notNull(),
+    tokenbackfill: json("tokenbackfill"),
+  },
+  (table) => {
+    return {
+      daoidIdx: index("treasury_daoid_idx").on(table.daoid),
+      treasuryId: primaryKey(table.id),
+      treasuryDaoidAddressChainKey: unique(
+        "treasury_daoid_address_chain_key",
+      ).on(table.daoid, table.address, table.chain),
+    };
+  },
+);
+
+export const treasuryRelations = relations(treasury, ({ one, many }) => ({
+  dao: one(dao, {
+    fields: [treasury.daoid],
+    references: [dao.id],
+  }),
+  balances: many(treasurybalance),
+  transfers: many(treasurytransfer),
+}));
+
+export const treasurybalance = mysqlTable(
+  "treasurybalance",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    treasuryid: varchar("treasuryid", { length: 191 }).notNull(),
+    token: varchar("token", { length: 191 }).notNull(),
+    symbol: varchar("symbol", { length: 191 }).notNull(),
+    decimals: int("decimals").notNull(),
+    balance: varchar("balance", { length: 191 }).notNull(),
+    value: double("value").notNull(),
+    blocknumber: bigint("blocknumber", { mode: "number" }).notNull(),
+    timestamp: datetime("timestamp", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+  },
+  (table) => {
+    return {
+      treasuryidIdx: index("treasurybalance_treasuryid_idx").on(
+        table.treasuryid,
+      ),
+      timestampIdx: index("treasurybalance_timestamp_idx").on(table.timestamp),
+      treasurybalanceId: primaryKey(table.id),
+    };
+  },
+);
+
+export const treasurybalanceRelations = relations(
+  treasurybalance,
+  ({ one }) => ({
+    treasury: one(treasury, {
+      fields: [treasurybalance.treasuryid],
+      references: [treasury.id],
+    }),
+  }),
+);
+
+export const treasurytransfer = mysqlTable(
+  "treasurytransfer",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    treasuryid: varchar("treasuryid", { length: 191 }).notNull(),
+    proposalid: varchar("proposalid", { length: 191 }),
+    token: varchar("token", { length: 191 }).notNull(),
+    symbol: varchar("symbol", { length: 191 }).notNull(),
+    decimals: int("decimals").notNull(),
+    fromaddress: varchar("fromaddress", { length: 191 }).notNull(),
+    toaddress: varchar("toaddress", { length: 191 }).notNull(),
+    amount: varchar("amount", { length: 191 }).notNull(),
+    value: double("value").notNull(),
+    txid: varchar("txid", { length: 191 }).notNull(),
+    logindex: int("logindex").notNull(),
+    blocknumber: bigint("blocknumber", { mode: "number" }).notNull(),
+    timecreated: datetime("timecreated", { mode: "date", fsp: 3 }).notNull(),
+  },
+  (table) => {
+    return {
+      treasuryidIdx: index("treasurytransfer_treasuryid_idx").on(
+        table.treasuryid,
+      ),
+      proposalidIdx: index("treasurytransfer_proposalid_idx").on(
+        table.proposalid,
+      ),
+      timecreatedIdx: index("treasurytransfer_timecreated_idx").on(
+        table.timecreated,
+      ),
+      treasurytransferId: primaryKey(table.id),
+      treasurytransferTxidLogindexTreasuryidKey: unique(
+        "treasurytransfer_txid_logindex_treasuryid_key",
+      ).on(table.txid, table.logindex, table.treasuryid),
+    };
+  },
+);
+
+export const treasurytransferRelations = relations(
+  treasurytransfer,
+  ({ one }) => ({
+    treasury: one(treasury, {
+      fields: [treasurytransfer.treasuryid],
+      references: [treasury.id],
+    }),
+    proposal: one(proposal, {
+      fields: [treasurytransfer.proposalid],
+      references: [proposal.id],
+    }),
+  }),
+);
+
 export const subscription = mysqlTable(
   "subscription",
   {
@@ -417,7 +880,7 @@ export const vote = mysqlTable(
   },
 );
 
-export const voteRelations = relations(vote, ({ one }) => ({
+export const voteRelations = relations(vote, ({ one, many }) => ({
   voteraddress: one(voter, {
     fields: [vote.voteraddress],
     references: [voter.address],
@@ -434,6 +897,40 @@ export const voteRelations = relations(vote, ({ one }) => ({
     fields: [vote.daohandlerid],
     references: [daohandler.id],
   }),
+  revisions: many(voterevision),
+}));
+
+export const voterevision = mysqlTable(
+  "voterevision",
+  {
+    id: varchar("id", { length: 191 }).notNull(),
+    voteid: varchar("voteid", { length: 191 }).notNull(),
+    revision: int("revision").notNull(),
+    externalid: varchar("externalid", { length: 191 }).default("").notNull(),
+    choice: json("choice").notNull(),
+    votingpower: json("votingpower").notNull(),
+    reason: varchar("reason", { length: 2048 }).notNull(),
+    timecreated: datetime("timecreated", { mode: "date", fsp: 3 }).notNull(),
+  },
+  (table) => {
+    return {
+      voteidIdx: index("voterevision_voteid_idx").on(table.voteid),
+      timecreatedIdx: index("voterevision_timecreated_idx").on(
+        table.timecreated,
+      ),
+      voterevisionId: primaryKey(table.id),
+      voterevisionVoteidRevisionKey: unique(
+        "voterevision_voteid_revision_key",
+      ).on(table.voteid, table.revision),
+    };
+  },
+);
+
+export const voterevisionRelations = relations(voterevision, ({ one }) => ({
+  vote: one(vote, {
+    fields: [voterevision.voteid],
+    references: [vote.id],
+  }),
 }));
 
 export const voter = mysqlTable(
@@ -441,10 +938,14 @@ export const voter = mysqlTable(
   {
     id: varchar("id", { length: 191 }).notNull(),
     address: varchar("address", { length: 191 }).notNull(),
+    ensname: varchar("ensname", { length: 191 }),
+    ensavatar: varchar("ensavatar", { length: 2048 }),
+    ensupdatedat: datetime("ensupdatedat", { mode: "date", fsp: 3 }),
   },
   (table) => {
     return {
       addressIdx: index("voter_address_idx").on(table.address),
+      ensupdatedatIdx: index("voter_ensupdatedat_idx").on(table.ensupdatedat),
       voterId: primaryKey(table.id),
       voterAddressKey: unique("voter_address_key").on(table.address),
     };
@@ -467,6 +968,13 @@ export const voterhandler = mysqlTable(
       .default(new Date(0))
       .notNull(),
     uptodate: boolean("uptodate").default(false).notNull(),
+    refreshstatus: mysqlEnum("refreshstatus", ["NEW", "PENDING", "DONE"])
+      .default("NEW")
+      .notNull(),
+    lastrefresh: datetime("lastrefresh", { mode: "date", fsp: 3 })
+      .default(sql`CURRENT_TIMESTAMP(3)`)
+      .notNull(),
+    refreshfailures: int("refreshfailures").default(0).notNull(),
     daohandlerid: varchar("daohandlerid", { length: 191 }).notNull(),
     voterid: varchar("voterid", { length: 191 }).notNull(),
   },
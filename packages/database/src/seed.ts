@@ -859,6 +859,31 @@ const seedData = async () => {
       },
     ]);
 
+  const nouns_id = cuid();
+  await db.insert(dao).ignore().values({
+    id: nouns_id,
+    name: "Nouns DAO",
+    picture: "/assets/Project_Icons/nouns-dao",
+    quorumwarningemailsupport: true,
+    backgroundcolor: "#d5d7e1",
+  });
+  const nouns = await db.select().from(dao).where(eq(dao.name, "Nouns DAO"));
+  await db
+    .insert(daohandler)
+    .ignore()
+    .values([
+      {
+        id: cuid(),
+        daoid: nouns[0].id,
+        type: "NOUNS_CHAIN",
+        decoder: {
+          address: "0x6f3E6272A167e8AcCb32072d08E0957F9c79223d",
+          proposalUrl: "https://nouns.wtf/vote/",
+          governancePortal: "https://nouns.wtf/vote",
+        },
+      },
+    ]);
+
   const dorg_id = cuid();
   await db.insert(dao).ignore().values({
     id: dorg_id,
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::prisma::{voter, PrismaClient};
+
+#[derive(Debug, Clone)]
+pub struct VoterDisplay {
+    pub name: String,
+    pub avatar: Option<String>,
+}
+
+/// ENS name resolved by detective, or the shortened address when there is none.
+pub fn display_name(address: &str, ensname: Option<&str>) -> String {
+    match ensname {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ if address.len() > 10 => format!("{}...{}", &address[..6], &address[address.len() - 4..]),
+        _ => address.to_string(),
+    }
+}
+
+pub async fn voter_display(address: &str, client: &Arc<PrismaClient>) -> Result<VoterDisplay> {
+    let voter = client
+        .voter()
+        .find_unique(voter::address::equals(address.to_string()))
+        .exec()
+        .await?;
+
+    Ok(match voter {
+        Some(v) => VoterDisplay {
+            name: display_name(&v.address, v.ensname.as_deref()),
+            avatar: v.ensavatar,
+        },
+        None => VoterDisplay {
+            name: display_name(address, None),
+            avatar: None,
+        },
+    })
+}
@@ -0,0 +1,8 @@
+//! Code the secretaries share. The discord, slack and telegram secretaries include this
+//! module with `#[path]` and build it against their own prisma client, so `crate::prisma`
+//! and `crate::utils` have to exist in every crate that uses it. The email secretary only
+//! includes `display.rs`.
+
+pub mod display;
+pub mod vote;
+pub mod vote_changed;
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use prisma_client_rust::{serde_json::Value, Direction};
+use tracing::instrument;
+
+use crate::{
+    prisma::{self, PrismaClient},
+    utils::display::display_name,
+};
+
+#[derive(Debug, Clone)]
+pub struct VoteChange {
+    pub voter_address: String,
+    pub voter_name: String,
+    pub previous_choice: String,
+    pub choice: String,
+}
+
+#[instrument(skip(client))]
+pub async fn get_vote(
+    user_id: String,
+    proposal_id: String,
+    client: &Arc<PrismaClient>,
+) -> Result<bool> {
+    let user = client
+        .user()
+        .find_first(vec![prisma::user::id::equals(user_id)])
+        .include(prisma::user::include!({ voters }))
+        .exec()
+        .await?
+        .unwrap();
+
+    let mut voted = false;
+
+    for voter in user.voters {
+        let vote = client
+            .vote()
+            .find_first(vec![
+                prisma::vote::proposalid::equals(proposal_id.to_string()),
+                prisma::vote::voteraddress::equals(voter.address),
+            ])
+            .exec()
+            .await?;
+
+        if vote.is_some() {
+            voted = true
+        }
+    }
+
+    Ok(voted)
+}
+
+/// `revision` of the user's vote on a proposal against the revision it replaced.
+#[instrument(skip(client))]
+pub async fn get_vote_change(
+    user_id: String,
+    proposal_id: String,
+    revision: i32,
+    client: &Arc<PrismaClient>,
+) -> Result<Option<VoteChange>> {
+    let user = client
+        .user()
+        .find_first(vec![prisma::user::id::equals(user_id)])
+        .include(prisma::user::include!({ voters }))
+        .exec()
+        .await?
+        .unwrap();
+
+    let proposal = match client
+        .proposal()
+        .find_first(vec![prisma::proposal::id::equals(proposal_id.to_string())])
+        .exec()
+        .await?
+    {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+
+    for voter in user.voters {
+        let vote = client
+            .vote()
+            .find_first(vec![
+                prisma::vote::proposalid::equals(proposal_id.to_string()),
+                prisma::vote::voteraddress::equals(voter.address.clone()),
+            ])
+            .exec()
+            .await?;
+
+        let vote = match vote {
+            Some(v) => v,
+            None => continue,
+        };
+
+        let revisions = client
+            .voterevision()
+            .find_many(vec![
+                prisma::voterevision::voteid::equals(vote.id),
+                prisma::voterevision::revision::in_vec(vec![revision - 1, revision]),
+            ])
+            .order_by(prisma::voterevision::revision::order(Direction::Desc))
+            .exec()
+            .await?;
+
+        if let [latest, previous] = revisions.as_slice() {
+            return Ok(Some(VoteChange {
+                voter_name: display_name(&voter.address, voter.ensname.as_deref()),
+                voter_address: voter.address,
+                previous_choice: choice_label(&previous.choice, &proposal.choices),
+                choice: choice_label(&latest.choice, &proposal.choices),
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+// snapshot choices are 1-indexed, ranked and weighted votes list several of them
+fn choice_label(choice: &Value, choices: &Value) -> String {
+    let label = |index: &Value| -> Option<String> {
+        let index = index
+            .as_u64()
+            .or_else(|| index.as_str().and_then(|i| i.parse::<u64>().ok()))?;
+
+        choices
+            .as_array()?
+            .get(index.checked_sub(1)? as usize)?
+            .as_str()
+            .map(|c| c.to_string())
+    };
+
+    let labels: Option<Vec<String>> = match choice {
+        Value::Array(indexes) => indexes.iter().map(label).collect(),
+        Value::Object(weights) => weights
+            .keys()
+            .map(|k| label(&Value::String(k.clone())))
+            .collect(),
+        index => label(index).map(|l| vec![l]),
+    };
+
+    labels.map_or(choice.to_string(), |l| l.join(", "))
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use prisma_client_rust::chrono::{Duration, Utc};
+use tracing::instrument;
+
+use crate::prisma::{self, notification, user, voterevision, NotificationType, PrismaClient};
+
+prisma::user::include!(user_with_voters { voters subscriptions });
+prisma::voterevision::include!(voterevision_with_vote { vote });
+
+/// Creates a `notification_type` notification for every vote change of the last day
+/// made by a voter the users matching `users` follow, in a dao they are subscribed to.
+#[instrument(skip(client, users))]
+pub async fn generate_vote_changed(
+    client: &Arc<PrismaClient>,
+    users: Vec<user::WhereParam>,
+    notification_type: NotificationType,
+) -> Result<()> {
+    let users = client
+        .user()
+        .find_many(users)
+        .include(user_with_voters::include())
+        .exec()
+        .await?;
+
+    // the first revision is the original vote, anything after it is a change
+    let revisions = client
+        .voterevision()
+        .find_many(vec![
+            voterevision::revision::gt(0),
+            voterevision::timecreated::gt((Utc::now() - Duration::days(1)).into()),
+        ])
+        .include(voterevision_with_vote::include())
+        .exec()
+        .await?;
+
+    for user in users {
+        let voters: Vec<String> = user
+            .voters
+            .iter()
+            .map(|v| v.address.to_lowercase())
+            .collect();
+
+        let subscribed_dao_ids: Vec<String> =
+            user.subscriptions.iter().map(|s| s.daoid.clone()).collect();
+
+        // one notification per revision, regenerating the same revision is a no-op
+        client
+            .notification()
+            .create_many(
+                revisions
+                    .iter()
+                    .filter(|r| voters.contains(&r.vote.voteraddress.to_lowercase()))
+                    .filter(|r| subscribed_dao_ids.contains(&r.vote.daoid))
+                    .map(|r| {
+                        notification::create_unchecked(
+                            user.clone().id,
+                            notification_type,
+                            vec![
+                                notification::proposalid::set(r.vote.proposalid.clone().into()),
+                                notification::revision::set(r.revision),
+                            ],
+                        )
+                    })
+                    .collect(),
+            )
+            .skip_duplicates()
+            .exec()
+            .await?;
+    }
+
+    Ok(())
+}